@@ -0,0 +1,42 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use cryptopals::crypto::hash::{HashFunction, Sha1, Sha256};
+
+const SIZES: &[usize] = &[64, 1024, 1 << 20];
+
+fn sha1(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("sha1");
+    for &size in SIZES {
+        let buffer = vec![0x5a; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("digest", size), &buffer, |bencher, buffer| {
+            bencher.iter(|| Sha1::digest(buffer))
+        });
+        // Small unaligned updates exercise the partial chunk path.
+        group.bench_with_input(BenchmarkId::new("chunked_update", size), &buffer, |bencher, buffer| {
+            bencher.iter(|| {
+                let mut hash = Sha1::new();
+                for chunk in buffer.chunks(13) {
+                    hash.update(chunk);
+                }
+                hash.finalize()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn sha256(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("sha256");
+    for &size in SIZES {
+        let buffer = vec![0x5a; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("digest", size), &buffer, |bencher, buffer| {
+            bencher.iter(|| Sha256::digest(buffer))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, sha1, sha256);
+criterion_main!(benches);
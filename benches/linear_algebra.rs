@@ -0,0 +1,52 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use cryptopals::crypto::random::{Mt19337, RandomGenerator, SeedableGenerator};
+use cryptopals::math::linear_algebra::{GaussElimination, Vector};
+use cryptopals::math::sparse::SparseMatrix;
+
+const SIZES: &[usize] = &[256, 1024, 2048];
+
+/// Returns a sparse invertible system `L * U`, where `U` is upper triangular with three
+/// additional elements in each row, and `L` is lower triangular with one additional element in
+/// each row. This causes fill-in during elimination, unlike a triangular system.
+fn get_system(size: usize) -> (SparseMatrix, Vector) {
+    let mut random = Mt19337::new(5489);
+    let upper: Vec<Vec<usize>> = (0..size)
+        .map(|i| {
+            let mut row = vec![i];
+            if i + 1 < size {
+                row.extend((0..3).map(|_| i + 1 + (random.next_u32() as usize) % (size - i - 1)));
+            }
+            row
+        })
+        .collect();
+    let rows = (0..size)
+        .map(|i| match i {
+            0 => upper[0].clone(),
+            _ => [&upper[i][..], &upper[(random.next_u32() as usize) % i][..]].concat()
+        })
+        .collect();
+    let matrix = SparseMatrix::from_rows(size, rows).unwrap();
+    let solution = Vector::from_bits((0..size).map(|_| random.next_u8() & 1 == 1));
+    let rhs = &matrix * &solution;
+    (matrix, rhs)
+}
+
+fn gauss_elimination(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("gauss_elimination");
+    group.sample_size(10);
+    for &size in SIZES {
+        let (matrix, rhs) = get_system(size);
+        let dense = matrix.to_dense();
+        group.bench_with_input(BenchmarkId::new("sparse", size), &size, |bencher, _| {
+            bencher.iter(|| matrix.solve(&rhs).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("dense", size), &size, |bencher, _| {
+            bencher.iter(|| GaussElimination::new(dense.clone(), rhs.clone()).solve().unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, gauss_elimination);
+criterion_main!(benches);
@@ -0,0 +1,21 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use cryptopals::attacks::random::mersenne_twister::recover_state_from;
+use cryptopals::crypto::random::{Mt19337, RandomGenerator, SeedableGenerator};
+
+fn untemper(criterion: &mut Criterion) {
+    let mut random = Mt19337::new(5489);
+    let outputs: Vec<u32> = (0..624).map(|_| random.next_u32()).collect();
+    let mut group = criterion.benchmark_group("untemper");
+    group.bench_function("untemper", |bencher| {
+        bencher.iter(|| outputs.iter().map(|&output| Mt19337::untemper(output)).collect::<Vec<u32>>())
+    });
+    // Solves a 32x32 linear system over GF(2) for each output.
+    group.bench_function("recover_state_from", |bencher| {
+        bencher.iter(|| outputs.iter().map(|&output| recover_state_from(output).unwrap()).collect::<Vec<u32>>())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, untemper);
+criterion_main!(benches);
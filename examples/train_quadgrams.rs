@@ -0,0 +1,29 @@
+//! Trains a quadgram model on one or more corpus files and writes it to disk
+//! using the binary format from `math::statistics::quadgrams`.
+//!
+//! Usage: cargo run --example train_quadgrams -- OUTPUT CORPUS...
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
+
+use cryptopals::math::statistics::quadgrams::QuadgramModel;
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.len() < 2 {
+        eprintln!("usage: train_quadgrams OUTPUT CORPUS...");
+        process::exit(1);
+    }
+
+    // The corpora are streamed, so they never have to fit in memory.
+    let mut corpus: Box<dyn Read> = Box::new(io::empty());
+    for path in &args[1..] {
+        corpus = Box::new(corpus.chain(File::open(path)?));
+    }
+    let model = QuadgramModel::train_from_reader(corpus)?;
+    model.save(&args[0])?;
+    println!("trained on {} quadgrams", model.sample_size());
+    Ok(())
+}
@@ -0,0 +1,238 @@
+//! Asynchronous versions of the attacks requiring many oracle queries. Independent queries
+//! are issued concurrently, which is useful against remote oracles.
+//!
+//! # Note
+//!
+//! The oracles are passed as `Arc`s to the concurrent attacks since each query runs as a
+//! separate task. The timing attack issues its queries one at a time, and borrows the oracle.
+
+pub mod simple_ecb_decryption {
+    use std::sync::Arc;
+    use tokio::task::JoinSet;
+
+    use crate::crypto::symmetric::Error;
+    use crate::oracles::asynchronous::AsyncOracle;
+    use crate::attacks::symmetric::simple_ecb_decryption::{get_query, find_candidate};
+
+    pub async fn get_block_size<O>(oracle: &O) -> Result<usize, Error> 
+        where O: AsyncOracle<Output=Vec<u8>, Error=Error>
+    {
+        for block_size in 8..=256 {
+            let result = oracle.query(&vec![0; 2 * block_size]).await?;
+            let mut blocks = result.chunks(block_size);
+            if blocks.next() == blocks.next() {
+                return Ok(block_size);
+            }
+        }
+        Err(Error::CipherError)
+    }
+
+    /// Recovers the unknown data as `simple_ecb_decryption::get_unknown_data`, but splits the
+    /// candidate bytes into the given number of concurrent dictionary queries.
+    pub async fn get_unknown_data<O>(oracle: Arc<O>, concurrency: usize) -> Result<Vec<u8>, Error>
+        where O: AsyncOracle<Output=Vec<u8>, Error=Error> + 'static
+    {
+        let block_size = get_block_size(&*oracle).await?;
+        let candidates: Vec<u8> = (0..=255).collect();
+        let chunk_size = candidates.len().div_ceil(concurrency.max(1));
+
+        let mut unknown_data = Vec::new();
+        loop {
+            let mut tasks = JoinSet::new();
+            for chunk in candidates.chunks(chunk_size) {
+                let (oracle, chunk) = (oracle.clone(), chunk.to_vec());
+                let query = get_query(&unknown_data, &chunk, block_size);
+                let unknown_data = unknown_data.clone();
+                tasks.spawn(async move {
+                    let result = oracle.query(&query).await?;
+                    Ok::<_, Error>(find_candidate(&result, &unknown_data, &chunk, block_size))
+                });
+            }
+            let mut byte = None;
+            while let Some(result) = tasks.join_next().await {
+                byte = byte.or(result.map_err(|_| Error::CipherError)??);
+            }
+            match byte {
+                Some(byte) => unknown_data.push(byte),
+                None => {
+                    // We have reached the padding bytes.
+                    unknown_data.pop();
+                    return Ok(unknown_data);
+                }
+            }
+        }
+    }
+}
+
+pub mod cbc_padding_oracle {
+    use std::sync::Arc;
+    use tokio::task::JoinSet;
+
+    use crate::crypto::symmetric;
+    use symmetric::{Cipher, PaddingMode};
+    use crate::oracles::asynchronous::AsyncOracle;
+    use crate::attacks::symmetric::cbc_padding_oracle::{Error, get_padding};
+    use crate::utils::bytes;
+
+    /// Returns all guesses for the byte at the given index which result in valid padding.
+    async fn get_valid_guesses<O>(oracle: &Arc<O>, edited_buffer: &[u8], index: usize) -> Result<Vec<u8>, Error>
+        where O: AsyncOracle<Output=bool, Error=symmetric::Error> + 'static
+    {
+        let mut tasks = JoinSet::new();
+        for guess in 0..=255 {
+            let oracle = oracle.clone();
+            let mut edited_buffer = edited_buffer.to_vec();
+            edited_buffer[index] = guess;
+            tasks.spawn(async move {
+                oracle.query(&edited_buffer).await.map(|valid| (guess, valid))
+            });
+        }
+        let mut result = Vec::new();
+        while let Some(task) = tasks.join_next().await {
+            let (guess, valid) = task.map_err(|_| Error::CipherError)??;
+            if valid {
+                result.push(guess);
+            }
+        }
+        result.sort_unstable();
+        Ok(result)
+    }
+
+    /// Recovers the intermediate state of a single block as
+    /// `cbc_padding_oracle::get_intermediate_block`, testing all guesses for each byte
+    /// concurrently.
+    async fn get_intermediate_block<C, P, O>(
+        oracle: Arc<O>, 
+        block: Vec<u8>
+    ) -> Result<Vec<u8>, Error> where
+        C: Cipher,
+        P: PaddingMode,
+        O: AsyncOracle<Output=bool, Error=symmetric::Error> + 'static
+    {
+        // The padding mode is not required to be `Send`, so we compute all paddings up front.
+        let paddings = {
+            let padding_mode = P::new(C::BLOCK_SIZE);
+            (1..=C::BLOCK_SIZE)
+                .map(|size| get_padding(&padding_mode, size))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        let mut intermediate_block = vec![0; C::BLOCK_SIZE];
+        let mut edited_buffer = [vec![0; C::BLOCK_SIZE], block].concat();
+        for index in (0..C::BLOCK_SIZE).rev() {
+            let padding = &paddings[C::BLOCK_SIZE - index - 1];
+            for (offset, byte) in padding.iter().enumerate().skip(1) {
+                edited_buffer[index + offset] = intermediate_block[index + offset] ^ byte;
+            }
+            let mut recovered = None;
+            for guess in get_valid_guesses(&oracle, &edited_buffer, index).await? {
+                edited_buffer[index] = guess;
+                // Rule out guesses resulting in a longer padding than intended.
+                if index > 0 {
+                    edited_buffer[index - 1] ^= 0xff;
+                    let verified = oracle.query(&edited_buffer).await?;
+                    edited_buffer[index - 1] ^= 0xff;
+                    if !verified {
+                        continue;
+                    }
+                }
+                recovered = Some(guess);
+                break;
+            }
+            let guess = recovered.ok_or(Error::RecoveryError)?;
+            intermediate_block[index] = guess ^ padding[0];
+        }
+        Ok(intermediate_block)
+    }
+
+    /// Recovers the plaintext as `cbc_padding_oracle::get_plaintext_buffer_with`. All blocks
+    /// are recovered concurrently, and all guesses for each byte are tested concurrently.
+    pub async fn get_plaintext_buffer<C, P, O>(
+        encrypted_buffer: &[u8],
+        oracle: Arc<O>
+    ) -> Result<Vec<u8>, Error> where
+        C: Cipher,
+        P: PaddingMode,
+        O: AsyncOracle<Output=bool, Error=symmetric::Error> + 'static
+    {
+        let size = encrypted_buffer.len();
+        if size < 2 * C::BLOCK_SIZE || !size.is_multiple_of(C::BLOCK_SIZE) {
+            return Err(Error::RecoveryError);
+        }
+        let blocks: Vec<&[u8]> = encrypted_buffer.chunks(C::BLOCK_SIZE).collect();
+        
+        let mut tasks = JoinSet::new();
+        for (index, block) in blocks.iter().enumerate().skip(1) {
+            let (oracle, block) = (oracle.clone(), block.to_vec());
+            tasks.spawn(async move {
+                (index, get_intermediate_block::<C, P, O>(oracle, block).await)
+            });
+        }
+        let mut intermediate_blocks = vec![Vec::new(); blocks.len()];
+        while let Some(task) = tasks.join_next().await {
+            let (index, result) = task.map_err(|_| Error::CipherError)?;
+            intermediate_blocks[index] = result?;
+        }
+
+        let mut solution = Vec::with_capacity(size - C::BLOCK_SIZE);
+        for (index, intermediate_block) in intermediate_blocks.iter().enumerate().skip(1) {
+            solution.extend(bytes::xor_slices(intermediate_block, blocks[index - 1]));
+        }
+        let length = P::new(C::BLOCK_SIZE).unpad_mut(&solution)?;
+        solution.truncate(length);
+        Ok(solution)
+    }
+}
+
+pub mod hmac_timing_leak {
+    use std::time::Duration;
+
+    use crate::crypto::hash::{Mac, Sha1Hmac};
+    use crate::oracles::timing::Clock;
+    use crate::oracles::asynchronous::AsyncOracle;
+    use crate::attacks::timing::hmac_timing_leak::Error;
+    use crate::trace::{self, Event};
+
+    /// Returns the total time taken to verify the signature the given number of times.
+    async fn get_timing<C, O>(oracle: &O, clock: &C, query: &[u8], samples: usize) -> Result<Duration, Error>
+        where C: Clock, O: AsyncOracle<Output=bool>
+    {
+        let start = clock.now();
+        for _ in 0..samples {
+            oracle.query(query).await.map_err(|_| Error::RecoveryError)?;
+        }
+        Ok(clock.now() - start)
+    }
+
+    /// Recovers the HMAC-SHA1 signature of the given file as
+    /// `timing::hmac_timing_leak::get_signature`, using an oracle which takes a buffer on the
+    /// form `file || signature`. Unlike the other asynchronous attacks, queries are issued one
+    /// at a time since concurrent queries would distort the timing.
+    pub async fn get_signature<C, O>(oracle: &O, clock: &C, file: &[u8], samples: usize) -> Result<Vec<u8>, Error>
+        where C: Clock, O: AsyncOracle<Output=bool>
+    {
+        let mut query = [file, &[0; Sha1Hmac::TAG_SIZE]].concat();
+        let offset = file.len();
+        for index in 0..Sha1Hmac::TAG_SIZE {
+            let mut best_timing = Duration::from_secs(0);
+            let mut best_byte = 0;
+            for byte in 0..=255 {
+                query[offset + index] = byte;
+                if oracle.query(&query).await.map_err(|_| Error::RecoveryError)? {
+                    return Ok(query[offset..].to_vec());
+                }
+                let timing = get_timing(oracle, clock, &query, samples).await?;
+                if timing > best_timing {
+                    best_timing = timing;
+                    best_byte = byte;
+                }
+            }
+            query[offset + index] = best_byte;
+            trace::emit(|| Event::Candidate {
+                attack: "hmac_timing_leak",
+                position: index,
+                value: best_byte
+            });
+        }
+        Err(Error::RecoveryError)
+    }
+}
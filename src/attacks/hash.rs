@@ -0,0 +1,54 @@
+pub mod length_extension {
+    use crate::crypto::hash::{HashFunction, MessageDigest};
+
+    #[derive(Debug)]
+    pub enum Error {
+        /// The tag has the wrong size.
+        LengthError,
+        /// No key size up to the given maximum produced a valid tag.
+        RecoveryError,
+    }
+
+    /// Returns the padding appended by the hash function to a message of the given size.
+    pub fn get_glue_padding<H: HashFunction>(message_size: usize) -> Vec<u8> {
+        H::padding(message_size)
+    }
+
+    /// Returns the digest of `input || glue_padding || suffix`, given only the digest and the
+    /// size of `input`.
+    pub fn extend_digest<H: HashFunction>(
+        digest: &MessageDigest,
+        input_size: usize,
+        suffix: &[u8]
+    ) -> Result<MessageDigest, Error> {
+        if digest.len() != H::DIGEST_SIZE {
+            return Err(Error::LengthError);
+        }
+        Ok(H::from_digest(digest, input_size).update(suffix).finalize())
+    }
+
+    /// Forges a `NaiveMac<H>` tag for `message || glue_padding || suffix`, given the tag for
+    /// `message`. Since the glue padding depends on the size of the key, each key size up to
+    /// `max_key_size` is tried in turn until `verify_tag` accepts the forgery. Returns the
+    /// forged message together with its tag.
+    pub fn forge_tag<H, Oracle>(
+        message: &[u8],
+        tag: &MessageDigest,
+        suffix: &[u8],
+        max_key_size: usize,
+        verify_tag: &mut Oracle
+    ) -> Result<(Vec<u8>, MessageDigest), Error> where
+        H: HashFunction,
+        Oracle: FnMut(&[u8], &[u8]) -> bool
+    {
+        for key_size in 0..=max_key_size {
+            let input_size = key_size + message.len();
+            let forged_message = [message, &get_glue_padding::<H>(input_size), suffix].concat();
+            let forged_tag = extend_digest::<H>(tag, input_size, suffix)?;
+            if verify_tag(&forged_message, forged_tag.as_ref()) {
+                return Ok((forged_message, forged_tag));
+            }
+        }
+        Err(Error::RecoveryError)
+    }
+}
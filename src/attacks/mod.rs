@@ -1,3 +1,8 @@
 pub mod statistics;
 pub mod symmetric;
 pub mod random;
+pub mod protocol;
+pub mod timing;
+pub mod hash;
+#[cfg(feature = "async")]
+pub mod asynchronous;
@@ -0,0 +1,44 @@
+/// Set 5, problem 34. Mallory replaces both public keys with `p`, which forces the shared
+/// secret computed by both parties to be `p^x mod p = 0`. The attacker can then decrypt every
+/// message while relaying it unchanged.
+pub mod parameter_injection {
+    use crate::oracles::protocol::{Channel, Direction};
+    use crate::oracles::protocol::diffie_hellman::{Message, derive_key, decrypt};
+
+    /// A channel which injects `p` as the public key of both parties and records the
+    /// decrypted messages.
+    #[derive(Default)]
+    pub struct Mallory {
+        p: Option<u64>,
+        plaintexts: Vec<(Direction, Vec<u8>)>,
+    }
+
+    impl Mallory {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Returns the decrypted messages together with their direction.
+        pub fn plaintexts(&self) -> &[(Direction, Vec<u8>)] { &self.plaintexts }
+    }
+
+    impl Channel<Message> for Mallory {
+        fn transmit(&mut self, direction: Direction, message: Message) -> Option<Message> {
+            match message {
+                Message::Parameters { group, .. } => {
+                    self.p = Some(group.p);
+                    Some(Message::Parameters { group, public_key: group.p })
+                },
+                Message::PublicKey(public_key) => {
+                    Some(Message::PublicKey(self.p.unwrap_or(public_key)))
+                },
+                Message::Ciphertext(ciphertext) => {
+                    if let Ok(plaintext) = decrypt(&derive_key(0), &ciphertext) {
+                        self.plaintexts.push((direction, plaintext));
+                    }
+                    Some(Message::Ciphertext(ciphertext))
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,5 @@
 pub mod  mersenne_twister {
     use std::convert::{From, TryInto};
-    use std::time::{SystemTime, SystemTimeError};
 
     use crate::crypto::symmetric;
     use symmetric::cipher_modes::StreamCipherMode;
@@ -8,25 +7,21 @@ pub mod  mersenne_twister {
     use crate::crypto::random::mersenne_twister::Mt19337;
     
     use crate::math::linear_algebra;
-    use linear_algebra::{Matrix, Vector, GaussElimination};
+    use linear_algebra::{LinearMap, Vector};
+    use crate::oracles::timing::Clock;
+    use crate::utils::search;
 
+    /// The default search window (in seconds) used when recovering time-based seeds.
     pub const MAXIMUM_DELTA: u64 = 1000;
     const FIRST_MASK: u32 = 0x9d2c_5680;
     const SECOND_MASK: u32 = 0xefc6_0000;
 
     #[derive(Debug)]
     pub enum Error {
-        UnixTimeError,
         RecoveryError,
         CipherError
     }
 
-    impl From<SystemTimeError> for Error {
-        fn from(_: SystemTimeError) -> Self {
-            Error::UnixTimeError
-        }
-    }
-
     impl From<symmetric::Error> for Error {
         fn from(_: symmetric::Error) -> Self {
             Error::CipherError
@@ -39,52 +34,74 @@ pub mod  mersenne_twister {
         }
     }
 
-    fn get_unix_time() -> Result<u64, Error> {
-        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map(
-            |duration| duration.as_secs()
-        ).map_err(|error| error.into())
-    }
-
     fn verify_u64_seed(seed: u64, output: u32) -> bool {
         Mt19337::new(seed as u32).next_u32() == output
     }
 
-    pub fn recover_timestamp_from(output: u32) -> Result<u64, Error> {
-        let now = get_unix_time()?;
-        for delta in 0..=MAXIMUM_DELTA {
-            if verify_u64_seed(now - delta, output) { return Ok(now - delta) }
+    /// Returns the Unix time used to seed the `Mt19337` instance which produced the given
+    /// output, assuming that it was seeded at most `window` seconds before the current time.
+    /// The clock is expected to measure time since the Unix epoch (like `SystemClock`).
+    pub fn recover_timestamp_from<C: Clock>(clock: &C, output: u32, window: u64) -> Result<u64, Error> {
+        let now = clock.now().as_secs();
+        for seed in (now.saturating_sub(window)..=now).rev() {
+            if verify_u64_seed(seed, output) { return Ok(seed) }
         }
         Err(Error::RecoveryError)
     }
 
+    /// Recovers the state word which produced the given output by solving the tempering
+    /// transform as a linear system over GF(2). `Mt19337::untemper` computes the same inverse
+    /// directly and is considerably faster (see `benches/random.rs`).
     pub fn recover_state_from(output: u32) -> Result<u32, Error> {
-        let rhs = Vector::from(output);
-        let mut lhs = Matrix::diagonal(32);
-        
-        // x ^= x >> 11;
-        lhs += &lhs >> 11;
-
-        // x ^= (x << 7) & Mt19337::FIRST_MASK;
-        lhs += (&lhs << 7) & Vector::from(FIRST_MASK);
-        
-        // x ^= (x << 15) & Mt19337::SECOND_MASK;
-        lhs += (&lhs << 15) & Vector::from(SECOND_MASK);
-        
-        // x ^= x >> 18;
-        lhs += &lhs >> 18;
+        // This is the tempering function from `Mt19337::next_u32`.
+        let mut x = LinearMap::identity(32);
+        x ^= &x >> 11;
+        x ^= (&x << 7) & FIRST_MASK;
+        x ^= (&x << 15) & SECOND_MASK;
+        x ^= &x >> 18;
         
-        GaussElimination::new(lhs, rhs)
-            .solve()
+        x.solve(&Vector::from(output))
             .and_then(|solution| solution.try_into())
             .map_err(Error::from)
     }
 
-    pub fn recover_key_from(input: &[u8], output: &[u8]) -> Result<u16, Error> {
-        for key in 0..=0xffff {
-            if Mt19337::new(key).encrypt_buffer(&input)? == output {
-                return Ok(key as u16)
+    /// Clones a `Mt19337` instance from its next 624 outputs, by untempering each output to
+    /// recover the corresponding state word. The clone then produces the same outputs as the
+    /// original.
+    pub fn clone_generator<R: RandomGenerator>(random: &mut R) -> Mt19337 {
+        let mut state = [0; 624];
+        for word in state.iter_mut() {
+            *word = Mt19337::untemper(random.next_u32());
+        }
+        Mt19337::from_state(state, 624)
+    }
+
+    /// Returns the Unix time used to seed the `Mt19337` instance which generated the given
+    /// token, assuming that the token was generated at most `MAXIMUM_DELTA` seconds before
+    /// `unix_time`.
+    pub fn recover_token_seed(token: &[u8], unix_time: u64) -> Result<u64, Error> {
+        for seed in (unix_time.saturating_sub(MAXIMUM_DELTA)..=unix_time).rev() {
+            let mut random = Mt19337::new(seed as u32);
+            if token.iter().all(|&byte| byte == random.next_u8()) {
+                return Ok(seed);
             }
         }
         Err(Error::RecoveryError)
     }
+
+    /// Returns true if the token was generated by a `Mt19337` instance seeded with the Unix
+    /// time at most `MAXIMUM_DELTA` seconds before `unix_time`.
+    pub fn is_time_seeded_token(token: &[u8], unix_time: u64) -> bool {
+        recover_token_seed(token, unix_time).is_ok()
+    }
+
+    /// Recovers the 16-bit key by searching the entire key space. The search is parallel
+    /// when the `parallel` feature is enabled.
+    pub fn recover_key_from(input: &[u8], output: &[u8]) -> Result<u16, Error> {
+        search::find_first(0..0x10000, |key| {
+            matches!(Mt19337::new(key as u32).encrypt_buffer(input), Ok(result) if result == output)
+        })
+        .map(|key| key as u16)
+        .ok_or(Error::RecoveryError)
+    }
 }
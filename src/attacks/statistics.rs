@@ -20,44 +20,197 @@ impl std::convert::From<symmetric::Error> for Error {
     }
 }
 
+pub mod scoring {
+    use std::io::{self, Read};
+
+    use crate::math::statistics::{english, Distribution};
+    use crate::math::statistics::quadgrams::QuadgramModel;
+
+    /// A scorer used to rank candidate plaintexts. Lower scores are better.
+    pub trait PlaintextScorer {
+        fn score(&self, plaintext: &[u8]) -> f64;
+
+        /// Scores the plaintext read from `reader` in chunks of `chunk_size` bytes, and returns
+        /// the average of the chunk scores weighted by the size of each chunk. This allows
+        /// large plaintexts to be scored in constant memory, at the cost of ignoring any n-grams
+        /// which span a chunk boundary.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if reading from the underlying reader fails.
+        fn score_reader<R: Read>(&self, mut reader: R, chunk_size: usize) -> io::Result<f64>
+        where
+            Self: Sized
+        {
+            let mut buffer = Vec::with_capacity(chunk_size);
+            let mut total_score = 0.0;
+            let mut total_size = 0;
+            loop {
+                buffer.clear();
+                let size = reader.by_ref().take(chunk_size as u64).read_to_end(&mut buffer)?;
+                if size == 0 {
+                    break;
+                }
+                total_score += (size as f64) * self.score(&buffer);
+                total_size += size;
+            }
+            if total_size == 0 {
+                return Ok(self.score(&[]));
+            }
+            Ok(total_score / (total_size as f64))
+        }
+    }
+
+    /// Scores plaintexts by the total variation distance between the observed
+    /// byte distribution and a given model.
+    pub struct MonogramScorer {
+        distribution: Distribution<u8>,
+    }
+
+    impl MonogramScorer {
+        pub fn new(distribution: Distribution<u8>) -> Self {
+            MonogramScorer { distribution }
+        }
+    }
+
+    /// Returns a monogram scorer for English.
+    impl Default for MonogramScorer {
+        fn default() -> Self {
+            MonogramScorer::new(english::monograms())
+        }
+    }
+
+    impl PlaintextScorer for MonogramScorer {
+        fn score(&self, plaintext: &[u8]) -> f64 {
+            plaintext
+                .iter()
+                .collect::<Distribution<u8>>()
+                .distance_from(&self.distribution)
+        }
+    }
+
+    /// Scores plaintexts using English monogram, bigram, and trigram statistics.
+    impl PlaintextScorer for english::Scorer {
+        fn score(&self, plaintext: &[u8]) -> f64 {
+            english::Scorer::score(self, plaintext)
+        }
+    }
+
+    /// Scores plaintexts by the negated average log probability of their quadgrams.
+    impl PlaintextScorer for QuadgramModel {
+        fn score(&self, plaintext: &[u8]) -> f64 {
+            -self.average_log_likelihood(plaintext)
+        }
+    }
+
+    /// A configurable preprocessing pipeline applied to plaintexts before scoring.
+    ///
+    /// The steps are applied in the following order: punctuation stripping,
+    /// lowercasing, and whitespace folding.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Normalizer {
+        lowercase: bool,
+        fold_whitespace: bool,
+        strip_punctuation: bool,
+    }
+
+    impl Normalizer {
+        /// Returns a normalizer which leaves the input unchanged.
+        pub fn new() -> Self {
+            Normalizer::default()
+        }
+
+        /// Converts ASCII uppercase letters to lowercase.
+        pub fn lowercase(mut self) -> Self {
+            self.lowercase = true;
+            self
+        }
+
+        /// Replaces each run of ASCII whitespace by a single space.
+        pub fn fold_whitespace(mut self) -> Self {
+            self.fold_whitespace = true;
+            self
+        }
+
+        /// Removes ASCII punctuation.
+        pub fn strip_punctuation(mut self) -> Self {
+            self.strip_punctuation = true;
+            self
+        }
+
+        /// Returns the normalized plaintext.
+        pub fn normalize(&self, plaintext: &[u8]) -> Vec<u8> {
+            let mut result = Vec::with_capacity(plaintext.len());
+            for &byte in plaintext {
+                if self.strip_punctuation && byte.is_ascii_punctuation() {
+                    continue;
+                }
+                if self.fold_whitespace && byte.is_ascii_whitespace() {
+                    if result.last() != Some(&b' ') {
+                        result.push(b' ');
+                    }
+                    continue;
+                }
+                result.push(if self.lowercase { byte.to_ascii_lowercase() } else { byte });
+            }
+            result
+        }
+    }
+
+    /// A scorer which normalizes plaintexts before passing them to the underlying scorer.
+    pub struct Normalized<S> {
+        normalizer: Normalizer,
+        scorer: S,
+    }
+
+    impl<S: PlaintextScorer> Normalized<S> {
+        pub fn new(normalizer: Normalizer, scorer: S) -> Self {
+            Normalized { normalizer, scorer }
+        }
+    }
+
+    impl<S: PlaintextScorer> PlaintextScorer for Normalized<S> {
+        fn score(&self, plaintext: &[u8]) -> f64 {
+            self.scorer.score(&self.normalizer.normalize(plaintext))
+        }
+    }
+
+    /// Returns the scorer used by default by the statistics attacks, which lowercases the
+    /// plaintext before comparing it with English monogram, bigram, and trigram statistics.
+    pub fn default_scorer() -> Normalized<english::Scorer> {
+        Normalized::new(Normalizer::new().lowercase(), english::Scorer::new())
+    }
+
+    /// Scores plaintexts by the fraction of bytes which are not printable
+    /// ASCII (or ASCII whitespace). This makes no assumptions about the
+    /// language of the plaintext.
+    #[derive(Default)]
+    pub struct PrintableScorer;
+
+    impl PlaintextScorer for PrintableScorer {
+        fn score(&self, plaintext: &[u8]) -> f64 {
+            if plaintext.is_empty() {
+                return 0.0;
+            }
+            let printable = plaintext
+                .iter()
+                .filter(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace())
+                .count();
+            1.0 - (printable as f64) / (plaintext.len() as f64)
+        }
+    }
+}
+
 pub mod single_byte_xor {
     use super::Error;
+    use super::scoring::{self, PlaintextScorer};
 
-    use crate::dist;
     use crate::math::optimization::Minimize;
-    use crate::math::statistics::Distribution;
-    
+    use crate::math::statistics::{english, Distribution};
+
     // English lowercase monogram statistics.
     pub fn get_monogram_statistics() -> Distribution<u8> {
-        dist!(
-            b'a' => 0.065_173_8,
-            b'b' => 0.012_424_8,
-            b'c' => 0.021_733_9,
-            b'd' => 0.034_983_5,
-            b'e' => 0.104_144_2,
-            b'f' => 0.019_788_1,
-            b'g' => 0.015_861_0,
-            b'h' => 0.049_288_8,
-            b'i' => 0.055_809_4,
-            b'j' => 0.000_903_3,
-            b'k' => 0.005_052_9,
-            b'l' => 0.033_149_0,
-            b'm' => 0.020_212_4,
-            b'n' => 0.056_451_3,
-            b'o' => 0.059_630_2,
-            b'p' => 0.013_764_5,
-            b'q' => 0.000_860_6,
-            b'r' => 0.049_756_3,
-            b's' => 0.051_576_0,
-            b't' => 0.072_935_7,
-            b'u' => 0.022_513_4,
-            b'v' => 0.008_290_3,
-            b'w' => 0.017_127_2,
-            b'x' => 0.001_369_2,
-            b'y' => 0.014_598_4,
-            b'x' => 0.000_783_6,
-            b' ' => 0.191_818_2
-        )
+        english::monograms()
     }
 
     pub fn decrypt_ciphertext(key: u8, ciphertext: &[u8]) -> Vec<u8> {
@@ -71,25 +224,88 @@ pub mod single_byte_xor {
             .distance_from(distribution)
     }
     
-    pub fn recover_plaintext(ciphertext: &[u8]) -> Result<String, Error> {
-        let distribution = get_monogram_statistics();
-        let result = (0..=255)
-            .map(|key|
-                decrypt_ciphertext(key, &ciphertext)
-            )
-            .minimize(|plaintext|
-                // We should really convert the plaintext to lowercase before scoring, but YOLO.
-                score_plaintext(&plaintext, &distribution)
-            );
-       
-        Ok(String::from_utf8(result.0)?)
+    /// The number of ranked candidates returned by `recover_plaintext`.
+    pub const CANDIDATES: usize = 5;
+
+    /// A candidate key together with the corresponding plaintext and its score.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Candidate {
+        pub key: u8,
+        pub plaintext: Vec<u8>,
+        pub score: f64,
+    }
+
+    /// The result of a single byte XOR attack. The `candidates` are ranked from best to worst,
+    /// and the recovered key and plaintext correspond to the best candidate which decrypts to
+    /// valid UTF-8.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Recovery {
+        pub key: u8,
+        pub plaintext: String,
+        pub candidates: Vec<Candidate>,
+    }
+
+    impl Recovery {
+        /// Returns the score of the recovered plaintext.
+        pub fn score(&self) -> f64 {
+            self.candidates
+                .iter()
+                .find(|candidate| candidate.key == self.key)
+                .map_or(f64::INFINITY, |candidate| candidate.score)
+        }
+    }
+
+    pub fn recover_plaintext(ciphertext: &[u8]) -> Result<Recovery, Error> {
+        recover_plaintext_with(ciphertext, CANDIDATES, &scoring::default_scorer())
+    }
+
+    /// Returns the `k` most likely keys together with the score of the corresponding plaintexts,
+    /// ranked from best to worst.
+    pub fn rank_keys<S: PlaintextScorer>(ciphertext: &[u8], k: usize, scorer: &S) -> Vec<(u8, f64)> {
+        (0..=255).k_smallest(k, |&key| scorer.score(&decrypt_ciphertext(key, ciphertext)))
+    }
+
+    /// Recover the plaintext from a `ciphertext` encrypted using single byte XOR, ranking keys by
+    /// the score of the corresponding plaintext. The `candidates` best keys are returned
+    /// together with the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if none of the candidates decrypts to valid UTF-8.
+    pub fn recover_plaintext_with<S: PlaintextScorer>(
+        ciphertext: &[u8],
+        candidates: usize,
+        scorer: &S
+    ) -> Result<Recovery, Error> {
+        let candidates: Vec<Candidate> = (0..=255)
+            .map(|key| (key, decrypt_ciphertext(key, ciphertext)))
+            .k_smallest(candidates, |(_, plaintext)| scorer.score(plaintext))
+            .into_iter()
+            .map(|((key, plaintext), score)| Candidate { key, plaintext, score })
+            .collect();
+
+        let mut result = Err(Error::RecoveryError);
+        for candidate in &candidates {
+            match String::from_utf8(candidate.plaintext.clone()) {
+                Ok(plaintext) => {
+                    return Ok(Recovery { key: candidate.key, plaintext, candidates });
+                }
+                Err(error) => result = Err(Error::from(error)),
+            }
+        }
+        result
     }
 }
 
 pub mod detect_single_byte_xor {
     use super::{single_byte_xor, Error};
+    use super::scoring::{self, PlaintextScorer};
     use crate::math::optimization::Minimize;
     use crate::math::statistics::Distribution;
+    use crate::utils::search;
+
+    /// The number of ciphertexts with the lowest entropy which are decrypted and cross-checked.
+    pub const CIPHERTEXT_CANDIDATES: usize = 3;
 
     fn score_ciphertext(ciphertext: &[u8])  -> f64 {
         ciphertext
@@ -98,30 +314,59 @@ pub mod detect_single_byte_xor {
             .entropy()
     }
 
-    pub fn recover_plaintext(ciphertexts: &[Vec<u8>]) -> Result<String, Error> {
-        let result = ciphertexts
-            .iter()
-            .minimize(|ciphertext|
-                score_ciphertext(ciphertext)
-            );
-        single_byte_xor::recover_plaintext(result.0)
+    pub fn recover_plaintext(ciphertexts: &[Vec<u8>]) -> Result<single_byte_xor::Recovery, Error> {
+        recover_plaintext_with(ciphertexts, &scoring::default_scorer())
+    }
+
+    /// Detect the ciphertext encrypted using single byte XOR and recover the plaintext using the
+    /// given scorer. The `CIPHERTEXT_CANDIDATES` ciphertexts with the lowest entropy are
+    /// decrypted, and the recovery with the best plaintext score is returned.
+    pub fn recover_plaintext_with<S: PlaintextScorer>(
+        ciphertexts: &[Vec<u8>],
+        scorer: &S
+    ) -> Result<single_byte_xor::Recovery, Error> {
+        let mut result: Result<single_byte_xor::Recovery, Error> = Err(Error::RecoveryError);
+        let scores = search::map(ciphertexts, |ciphertext| score_ciphertext(ciphertext));
+        let candidates = ciphertexts.iter().zip(scores).k_smallest(CIPHERTEXT_CANDIDATES, |(_, score)| *score);
+        for ((ciphertext, _), _) in candidates {
+            let recovery = match single_byte_xor::recover_plaintext_with(
+                ciphertext,
+                single_byte_xor::CANDIDATES,
+                scorer
+            ) {
+                Ok(recovery) => recovery,
+                Err(error) => {
+                    if result.is_err() {
+                        result = Err(error);
+                    }
+                    continue;
+                }
+            };
+            let better = match &result {
+                Ok(best) => recovery.score() < best.score(),
+                Err(_) => true,
+            };
+            if better {
+                result = Ok(recovery);
+            }
+        }
+        result
     }
 }
 
 pub mod repeating_key_xor {
+    use std::collections::HashMap;
+
     use super::{single_byte_xor, Error};
+    use super::scoring::{MonogramScorer, PlaintextScorer};
     
-    use crate::math::optimization::Minimize;
-    use crate::math::statistics::Distribution;
+    use crate::math::optimization::{Maximize, Minimize, OptimizeByCachedKey};
+    use crate::math::statistics::periodic_index_of_coincidence;
     
     use crate::crypto::symmetric;
+    use crate::utils::bytes;
     use symmetric::{RepeatingKeyXor, StreamCipherMode};
 
-    fn hamming_distance(lhs: &[u8], rhs: &[u8]) -> u32 {
-        lhs.iter().zip(rhs)
-            .fold(0, |sum, (x, y)| sum + (x ^ y).count_ones())
-    }
-
     /// Returns the average hamming distance per byte for the given key size.
     fn score_key_size(key_size: usize, ciphertext: &[u8]) -> f64 {
         let mut sum = 0;
@@ -129,7 +374,7 @@ pub mod repeating_key_xor {
         for (i, lhs) in ciphertext.chunks(key_size).enumerate() {
             for (j, rhs) in ciphertext.chunks(key_size).enumerate() {
                 if i < j {
-                    sum += hamming_distance(lhs, rhs);
+                    sum += bytes::hamming_distance(lhs, rhs);
                     total += 1;
                 }
             }
@@ -137,74 +382,530 @@ pub mod repeating_key_xor {
         (sum as f64) / ((total * key_size) as f64)
     }
 
-    fn recover_key_byte(ciphertext: &[u8], distribution: &Distribution<u8>) -> u8 {
+    /// Key size detectors used to recover the size of an unknown repeating key.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum KeySizeDetector {
+        /// Choose the size which minimizes the average Hamming distance per byte between blocks.
+        HammingDistance,
+        /// Choose the smallest size whose average index of coincidence per column is close to
+        /// the maximum over all candidate sizes.
+        IndexOfCoincidence,
+        /// Choose the largest size whose Kasiski confidence is close to the maximum. (For details,
+        /// see `kasiski_examination`.) Falls back to `HammingDistance` if the ciphertext contains
+        /// no repeated substrings.
+        Kasiski,
+    }
+
+    impl KeySizeDetector {
+        /// Returns the most likely key size up to `max_key_size` for the given ciphertext.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `max_key_size` is 0.
+        pub fn detect(&self, ciphertext: &[u8], max_key_size: usize) -> usize {
+            match self {
+                KeySizeDetector::HammingDistance => {
+                    (1..=max_key_size).minimize(|&key_size| score_key_size(key_size, ciphertext)).0
+                }
+                KeySizeDetector::IndexOfCoincidence => {
+                    // Multiples of the key size score as well as the key size itself, so we
+                    // return the smallest candidate within 10% of the maximum.
+                    let scores: Vec<(usize, f64)> = (1..=max_key_size)
+                        .map(|key_size| (key_size, periodic_index_of_coincidence(ciphertext, key_size)))
+                        .collect();
+                    let maximum = scores.iter().maximize(|(_, score)| *score).1;
+                    scores
+                        .iter()
+                        .find(|(_, score)| *score >= 0.9 * maximum)
+                        .map_or(1, |(key_size, _)| *key_size)
+                }
+                KeySizeDetector::Kasiski => {
+                    // Factors of the key size score as well as the key size itself, so we
+                    // return the largest candidate within 10% of the maximum.
+                    let candidates = kasiski_examination(ciphertext, 3, max_key_size);
+                    match candidates.first() {
+                        Some(&(_, maximum)) => candidates
+                            .iter()
+                            .filter(|(_, confidence)| *confidence >= 0.9 * maximum)
+                            .maximize(|(key_size, _)| *key_size).1,
+                        None => KeySizeDetector::HammingDistance.detect(ciphertext, max_key_size)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Performs a Kasiski examination of the ciphertext, returning candidate key sizes up to
+    /// `max_key_size` ranked by confidence (highest first).
+    ///
+    /// Repeated substrings of length `length` in the ciphertext are likely to be encryptions of
+    /// the same plaintext under the same part of the key, so the distance between them is likely
+    /// to be a multiple of the key size. The confidence of a candidate key size is the fraction
+    /// of these distances divisible by it, normalized so that 0 corresponds to what we would
+    /// expect by chance, and 1 to every distance being divisible by the candidate.
+    ///
+    /// # Note
+    ///
+    /// Returns an empty vector if `length` is 0, or if the ciphertext contains no repeated
+    /// substrings.
+    pub fn kasiski_examination(ciphertext: &[u8], length: usize, max_key_size: usize) -> Vec<(usize, f64)> {
+        if length == 0 {
+            return Vec::new();
+        }
+        let mut positions: HashMap<&[u8], usize> = HashMap::new();
+        let mut distances = Vec::new();
+        for (position, substring) in ciphertext.windows(length).enumerate() {
+            if let Some(previous) = positions.insert(substring, position) {
+                distances.push(position - previous);
+            }
+        }
+        if distances.is_empty() {
+            return Vec::new();
+        }
+
+        let mut candidates: Vec<(usize, f64)> = (2..=max_key_size)
+            .map(|key_size| {
+                let divisible = distances.iter().filter(|&distance| distance % key_size == 0).count();
+                let observed = (divisible as f64) / (distances.len() as f64);
+                let expected = 1.0 / (key_size as f64);
+                (key_size, ((observed - expected) / (1.0 - expected)).max(0.0))
+            })
+            .collect();
+        candidates.sort_by(|lhs, rhs| rhs.1.partial_cmp(&lhs.1).unwrap().then(lhs.0.cmp(&rhs.0)));
+        candidates
+    }
+
+    /// The default largest key size considered when the size of the key is unknown.
+    pub const MAX_KEY_SIZE: usize = 40;
+
+    /// The default number of key sizes tried when the size of the key is unknown.
+    pub const KEY_SIZE_CANDIDATES: usize = 3;
+
+    /// The result of a repeating key XOR attack.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Recovery {
+        pub key: Vec<u8>,
+        pub plaintext: String,
+    }
+
+    /// Parameters used to search for the size of an unknown key.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct KeySizeSearch {
+        max_key_size: usize,
+        candidates: usize,
+    }
+
+    impl KeySizeSearch {
+        /// Returns the default parameters, trying the `KEY_SIZE_CANDIDATES` most likely sizes up
+        /// to `MAX_KEY_SIZE`.
+        pub fn new() -> Self {
+            KeySizeSearch { max_key_size: MAX_KEY_SIZE, candidates: KEY_SIZE_CANDIDATES }
+        }
+
+        /// Sets the largest key size considered.
+        pub fn with_max_key_size(mut self, max_key_size: usize) -> Self {
+            self.max_key_size = max_key_size;
+            self
+        }
+
+        /// Sets the number of key sizes tried.
+        pub fn with_candidates(mut self, candidates: usize) -> Self {
+            self.candidates = candidates;
+            self
+        }
+    }
+
+    impl Default for KeySizeSearch {
+        fn default() -> Self {
+            KeySizeSearch::new()
+        }
+    }
+
+    fn recover_key_byte<S: PlaintextScorer>(ciphertext: &[u8], scorer: &S) -> u8 {
         (0..=255).minimize(|&key|
-            single_byte_xor::decrypt_ciphertext(key, ciphertext)
-                .iter()
-                .collect::<Distribution<u8>>()
-                .distance_from(distribution)
-            ).0
+            scorer.score(&single_byte_xor::decrypt_ciphertext(key, ciphertext))
+        ).0
     }
     
-    /// Recover the plaintext from a `ciphertext` encrypted using repeating key XOR. If the size of
-    /// the repeating key is known, it may be provided as an argument. If the size is not known, we
-    /// choose the size which minimizes the average Hamming distance per byte. (For details of how
-    /// this is done, see `score_key_size`.)
-    pub fn recover_plaintext(ciphertext: &[u8], key_size: Option<usize>) -> Result<String, Error> {
-        let key_size = key_size.unwrap_or(
-            (1..40).minimize(|&key_size| score_key_size(key_size, ciphertext)).0
-        );
+    /// Returns the `k` most likely key sizes up to `max_key_size`, together with the average
+    /// Hamming distance per byte, ranked from best to worst. (For details of how this is
+    /// computed, see `score_key_size`.)
+    pub fn rank_key_sizes(ciphertext: &[u8], max_key_size: usize, k: usize) -> Vec<(usize, f64)> {
+        (1..=max_key_size).k_smallest(k, |&key_size| score_key_size(key_size, ciphertext))
+    }
 
-        let mut key = Vec::new();
-        let distribution = single_byte_xor::get_monogram_statistics();
-        for offset in 0..key_size {
-            let bytes: Vec<u8> = ciphertext
-                .iter()
-                .skip(offset)
-                .step_by(key_size)
-                .cloned()
-                .collect();
-            key.push(recover_key_byte(&bytes, &distribution));
+    /// Recover the key and plaintext from a `ciphertext` encrypted using repeating key XOR. If the
+    /// size of the repeating key is known, it may be provided as an argument. If the size is not
+    /// known, we consider the `KEY_SIZE_CANDIDATES` sizes which minimize the average Hamming
+    /// distance per byte (see `rank_key_sizes`), and return the plaintext with the best score.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given key size is 0, or if no candidate decrypts to valid UTF-8.
+    pub fn recover_plaintext(ciphertext: &[u8], key_size: Option<usize>) -> Result<Recovery, Error> {
+        recover_plaintext_with(ciphertext, key_size, KeySizeSearch::new(), &MonogramScorer::default())
+    }
+
+    /// Recover the key and plaintext from a `ciphertext` encrypted using repeating key XOR, using
+    /// the given detector to determine the size of the key. Key sizes up to the maximum size of
+    /// `search` are considered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the maximum size of `search` is 0, or if the recovered plaintext is
+    /// not valid UTF-8.
+    pub fn recover_plaintext_using(
+        ciphertext: &[u8],
+        detector: KeySizeDetector,
+        search: KeySizeSearch
+    ) -> Result<Recovery, Error> {
+        if search.max_key_size == 0 {
+            return Err(Error::RecoveryError);
         }
-        let plaintext = RepeatingKeyXor::new(&key).decrypt_buffer(ciphertext)?;
-        Ok(String::from_utf8(plaintext)?)
+        let key_size = Some(detector.detect(ciphertext, search.max_key_size));
+        recover_plaintext_with(ciphertext, key_size, search, &MonogramScorer::default())
+    }
+
+    /// Recover the key and plaintext from a `ciphertext` encrypted using repeating key XOR, using
+    /// the given scorer to rank the candidates for each key byte. If the size of the key is not
+    /// known, candidate sizes are chosen according to `search`, and tried in order of the score
+    /// of the corresponding plaintext. Candidates which do not decrypt to valid UTF-8 are
+    /// skipped.
+    ///
+    /// # Note
+    ///
+    /// Each key byte is scored on every `key_size`-th byte of the plaintext, so scorers relying on
+    /// the order of the bytes (like n-gram scorers) are not useful here.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given key size is 0, or if no candidate decrypts to valid UTF-8.
+    pub fn recover_plaintext_with<S: PlaintextScorer>(
+        ciphertext: &[u8],
+        key_size: Option<usize>,
+        search: KeySizeSearch,
+        scorer: &S
+    ) -> Result<Recovery, Error> {
+        let key_sizes = match key_size {
+            Some(0) => return Err(Error::RecoveryError),
+            Some(key_size) => vec![key_size],
+            None => rank_key_sizes(ciphertext, search.max_key_size, search.candidates)
+                .into_iter()
+                .map(|(key_size, _)| key_size)
+                .collect()
+        };
+        // Candidates which do not decrypt to valid UTF-8 are given the worst possible score,
+        // so they are only returned (as an error) if there is no valid candidate.
+        let (_, (key, plaintext), _) = key_sizes
+            .into_iter()
+            .try_minimize_by_cached_key(
+                |&key_size| {
+                    let key = recover_key(ciphertext, key_size, scorer);
+                    let plaintext = RepeatingKeyXor::new(&key)
+                        .decrypt_buffer(ciphertext)
+                        .map_err(Error::from)
+                        .and_then(|plaintext| Ok(String::from_utf8(plaintext)?));
+                    (key, plaintext)
+                },
+                |(_, plaintext)| plaintext.as_ref().map_or(f64::INFINITY, |plaintext| scorer.score(plaintext.as_bytes()))
+            )
+            .ok_or(Error::RecoveryError)?;
+        Ok(Recovery { key, plaintext: plaintext? })
+    }
+
+    fn recover_key<S: PlaintextScorer>(ciphertext: &[u8], key_size: usize, scorer: &S) -> Vec<u8> {
+        bytes::transpose_chunks(ciphertext, key_size)
+            .iter()
+            .map(|bytes| recover_key_byte(bytes, scorer))
+            .collect()
     }
 }
 
 pub mod fixed_nonce_ctr {
 
+    /// Returns the bytes at the given position of every ciphertext long enough to have one.
+    pub fn column(ciphertexts: &[Vec<u8>], index: usize) -> Vec<u8> {
+        ciphertexts.iter().filter_map(|buffer| buffer.get(index).cloned()).collect()
+    }
+
+    fn max_length(ciphertexts: &[Vec<u8>]) -> usize {
+        ciphertexts.iter().map(|buffer| buffer.len()).max().unwrap_or(0)
+    }
+
     pub mod using_substitutions {
+        use rand::Rng;
+
+        use super::{column, max_length};
+        use super::super::Error;
+        use super::super::scoring::{MonogramScorer, PlaintextScorer};
+        use crate::math::optimization::{HillClimbing, Minimize};
+        use crate::math::statistics::{english, Frequencies};
+
+        /// Plaintext bytes tried by the annealing pass, roughly in order of frequency.
+        const COMMON_BYTES: &[u8] = b" etaoinshrdlcumwfgypbvkjxqzETAOINSHRDLCUMWFGYPBVKJXQZ,.'-;:!?";
+
+        /// Guesses the keystream by assuming that the most frequent plaintext byte in each column
+        /// is a space.
+        pub fn most_frequent_byte_keystream(ciphertexts: &[Vec<u8>]) -> Vec<u8> {
+            (0..max_length(ciphertexts))
+                .map(|index| {
+                    let frequencies: Frequencies<u8> = column(ciphertexts, index).into_iter().collect();
+                    // Each column is non-empty, since the index is less than the maximum length.
+                    frequencies.most_common(1)[0].0 ^ b' '
+                })
+                .collect()
+        }
+
+        /// Guesses the keystream one column at a time, choosing the key byte which minimizes the
+        /// score of the column. Keys which decrypt the column to printable ASCII are preferred.
+        pub fn guess_keystream<S: PlaintextScorer>(ciphertexts: &[Vec<u8>], scorer: &S) -> Vec<u8> {
+            (0..max_length(ciphertexts))
+                .map(|index| {
+                    let column = column(ciphertexts, index);
+                    let decrypt = |key: u8| column.iter().map(|byte| byte ^ key).collect::<Vec<u8>>();
+                    let printable: Vec<u8> = (0..=255)
+                        .filter(|&key| decrypt(key).iter().all(|&byte| is_printable(byte)))
+                        .collect();
+                    let candidates = if printable.is_empty() { (0..=255).collect() } else { printable };
+                    candidates.into_iter().minimize(|&key| scorer.score(&decrypt(key))).0
+                })
+                .collect()
+        }
+
+        /// Updates the keystream under the assumption that the ciphertext decrypts to `crib` at
+        /// the given offset. Positions beyond the end of the keystream or ciphertext are ignored.
+        pub fn apply_crib(keystream: &mut [u8], ciphertext: &[u8], offset: usize, crib: &[u8]) {
+            let positions = keystream.iter_mut().zip(ciphertext).skip(offset);
+            for ((key, byte), value) in positions.zip(crib) {
+                *key = byte ^ value;
+            }
+        }
+
+        /// Drags the crib across every position of every ciphertext. For each placement, the
+        /// implied keystream is used to decrypt the corresponding fragments of the remaining
+        /// ciphertexts, and the placement is scored by the average score of these fragments.
+        /// Returns the placements as `(index, offset, score)`, ranked from best to worst.
+        pub fn drag_crib<S: PlaintextScorer>(
+            ciphertexts: &[Vec<u8>],
+            crib: &[u8],
+            scorer: &S
+        ) -> Vec<(usize, usize, f64)> {
+            let mut result = Vec::new();
+            for (index, ciphertext) in ciphertexts.iter().enumerate() {
+                for offset in 0..(ciphertext.len() + 1).saturating_sub(crib.len()) {
+                    let keystream: Vec<u8> = ciphertext[offset..offset + crib.len()]
+                        .iter()
+                        .zip(crib)
+                        .map(|(byte, value)| byte ^ value)
+                        .collect();
+                    let scores: Vec<f64> = ciphertexts
+                        .iter()
+                        .enumerate()
+                        .filter(|&(other, buffer)| other != index && buffer.len() > offset)
+                        .map(|(_, buffer)| {
+                            let fragment: Vec<u8> = buffer[offset..]
+                                .iter()
+                                .zip(&keystream)
+                                .map(|(byte, key)| byte ^ key)
+                                .collect();
+                            scorer.score(&fragment)
+                        })
+                        .collect();
+                    if !scores.is_empty() {
+                        let score = scores.iter().sum::<f64>() / (scores.len() as f64);
+                        result.push((index, offset, score));
+                    }
+                }
+            }
+            result.sort_by(|lhs, rhs| lhs.2.partial_cmp(&rhs.2).unwrap_or(std::cmp::Ordering::Equal));
+            result
+        }
+
+        /// Decrypts the ciphertexts using the given keystream. Bytes beyond the end of the
+        /// keystream are left unchanged.
+        pub fn decrypt(ciphertexts: &[Vec<u8>], keystream: &[u8]) -> Vec<Vec<u8>> {
+            ciphertexts
+                .iter()
+                .map(|buffer| {
+                    buffer
+                        .iter()
+                        .enumerate()
+                        .map(|(index, byte)| byte ^ keystream.get(index).unwrap_or(&0))
+                        .collect()
+                })
+                .collect()
+        }
+
+        /// Refines the keystream using hill climbing. Each step replaces a random keystream byte
+        /// by the byte which decrypts a random ciphertext to a common plaintext byte at that
+        /// position. A keystream is scored by the length-weighted average score of all
+        /// plaintexts. Random choices are drawn from `random`, so the result is reproducible
+        /// given a seeded generator.
+        pub fn anneal<S: PlaintextScorer, R: Rng>(
+            ciphertexts: &[Vec<u8>],
+            keystream: &[u8],
+            scorer: &S,
+            steps: usize,
+            random: &mut R
+        ) -> Vec<u8> {
+            if keystream.is_empty() {
+                return Vec::new();
+            }
+            let score = |keystream: &Vec<u8>| {
+                let plaintexts = decrypt(ciphertexts, keystream);
+                let total: usize = plaintexts.iter().map(|buffer| buffer.len()).sum();
+                plaintexts
+                    .iter()
+                    .map(|buffer| (buffer.len() as f64) * scorer.score(buffer))
+                    .sum::<f64>() / (total.max(1) as f64)
+            };
+            let neighbour = |keystream: &Vec<u8>, random: &mut R| {
+                let mut result = keystream.clone();
+                let index = random.gen_range(0, keystream.len());
+                let candidates: Vec<&Vec<u8>> = ciphertexts.iter().filter(|buffer| buffer.len() > index).collect();
+                if !candidates.is_empty() {
+                    let buffer = candidates[random.gen_range(0, candidates.len())];
+                    result[index] = buffer[index] ^ COMMON_BYTES[random.gen_range(0, COMMON_BYTES.len())];
+                }
+                result
+            };
+            HillClimbing::new()
+                .with_max_steps(steps)
+                .with_plateau_steps(steps)
+                .minimize(random, |_| keystream.to_vec(), neighbour, score)
+                .0
+        }
+
+        /// Recovers the plaintexts by guessing the keystream column by column. Unlike
+        /// `using_statistics::recover_plaintexts`, the plaintexts are not truncated to the length
+        /// of the shortest ciphertext.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if a recovered plaintext is not valid UTF-8.
+        pub fn recover_plaintexts(ciphertexts: &[Vec<u8>]) -> Result<Vec<String>, Error> {
+            let keystream = guess_keystream(ciphertexts, &MonogramScorer::default());
+            into_strings(decrypt(ciphertexts, &keystream))
+        }
+
+        /// Recovers the plaintexts as `recover_plaintexts`, but refines the keystream guess
+        /// using English n-gram statistics for the given number of annealing steps. This is
+        /// mainly useful for the tails of the longest plaintexts, which are covered by too few
+        /// ciphertexts to be recovered column by column.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if a recovered plaintext is not valid UTF-8.
+        pub fn recover_plaintexts_with_annealing<R: Rng>(
+            ciphertexts: &[Vec<u8>],
+            steps: usize,
+            random: &mut R
+        ) -> Result<Vec<String>, Error> {
+            let keystream = guess_keystream(ciphertexts, &MonogramScorer::default());
+            let keystream = anneal(ciphertexts, &keystream, &english::Scorer::new(), steps, random);
+            into_strings(decrypt(ciphertexts, &keystream))
+        }
+
+        fn into_strings(plaintexts: Vec<Vec<u8>>) -> Result<Vec<String>, Error> {
+            plaintexts
+                .into_iter()
+                .map(|buffer| String::from_utf8(buffer).map_err(Error::from))
+                .collect()
+        }
+
+        fn is_printable(byte: u8) -> bool {
+            byte.is_ascii_graphic() || byte == b' '
+        }
     }
 
     pub mod using_statistics {
-        use super::super::{repeating_key_xor, Error};
+        use super::{column, max_length};
+        use super::super::Error;
+        use super::super::scoring::{MonogramScorer, PlaintextScorer};
         use crate::math::optimization::Minimize;
+        use crate::utils::bytes;
 
         pub fn recover_plaintexts(ciphertexts: &[Vec<u8>]) -> Result<Vec<String>, Error> {
-            // Compute the minimum length M and concatenate the corresponding prefixes.
-            let prefix_length = ciphertexts.iter().minimize(|buffer|
-                buffer.len() 
-            ).1;
-            let ciphertext = ciphertexts
-                .iter()
-                .map(|buffer| &buffer[..prefix_length])
-                .collect::<Vec<&[u8]>>()
-                .concat();
+            recover_plaintexts_with(ciphertexts, &MonogramScorer::default())
+        }
 
-            // Recover the plaintext which is encrypted using a repeationg key of length M.
-            let plaintext = repeating_key_xor::recover_plaintext(
-                &ciphertext, 
-                Some(prefix_length)
-            )?;
+        /// Recover the plaintexts using the given scorer to rank the candidates for each byte
+        /// of the keystream. The plaintexts are recovered in full, but bytes towards the end of
+        /// the longest plaintexts are covered by few ciphertexts and are less reliable. (See
+        /// `recover_keystream_with` for a measure of confidence in each keystream byte.) Since
+        /// incorrectly recovered bytes may not be valid UTF-8, invalid sequences are replaced
+        /// with `U+FFFD`.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if all ciphertexts are empty.
+        pub fn recover_plaintexts_with<S: PlaintextScorer>(
+            ciphertexts: &[Vec<u8>],
+            scorer: &S
+        ) -> Result<Vec<String>, Error> {
+            if max_length(ciphertexts) == 0 {
+                return Err(Error::RecoveryError);
+            }
+            let keystream: Vec<u8> = recover_keystream_with(ciphertexts, scorer)
+                .into_iter()
+                .map(|(key, _)| key)
+                .collect();
+            Ok(ciphertexts
+                .iter()
+                .map(|buffer| {
+                    let plaintext = bytes::xor_slices(buffer, &keystream);
+                    String::from_utf8_lossy(&plaintext).into_owned()
+                })
+                .collect())
+        }
 
-            // Split the resulting plaintext into chunks of length M and return the result.
-            plaintext
-                .as_bytes()
-                .chunks(prefix_length)
-                .map(|buffer| 
-                     String::from_utf8(buffer.to_owned()).map_err(Error::from)
-                )
+        /// Recover the keystream one byte at a time, choosing the byte which minimizes the score
+        /// of the corresponding column of plaintext bytes. Each byte is returned together with
+        /// a confidence in [0, 1], computed as the relative gap between the scores of the best
+        /// and second best candidate. (A confidence of 0 means that the two were tied.)
+        pub fn recover_keystream_with<S: PlaintextScorer>(ciphertexts: &[Vec<u8>], scorer: &S) -> Vec<(u8, f64)> {
+            (0..max_length(ciphertexts))
+                .map(|index| {
+                    let column = column(ciphertexts, index);
+                    let candidates = (0..=255u8).k_smallest(2, |&key|
+                        scorer.score(&column.iter().map(|byte| byte ^ key).collect::<Vec<u8>>())
+                    );
+                    let (best, second) = (candidates[0].1, candidates[1].1);
+                    let confidence = if second > 0.0 { (second - best) / second } else { 0.0 };
+                    (candidates[0].0, confidence.clamp(0.0, 1.0))
+                })
                 .collect()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::scoring::*;
+
+    #[test]
+    fn normalization_pipeline() {
+        let plaintext = b"Hello,  World!\n\tBye.";
+        assert_eq!(Normalizer::new().normalize(plaintext), plaintext.to_vec());
+        assert_eq!(Normalizer::new().lowercase().normalize(plaintext), b"hello,  world!\n\tbye.".to_vec());
+        assert_eq!(
+            Normalizer::new().lowercase().fold_whitespace().strip_punctuation().normalize(plaintext),
+            b"hello world bye".to_vec()
+        );
+        // Punctuation is stripped before whitespace is folded.
+        assert_eq!(Normalizer::new().fold_whitespace().strip_punctuation().normalize(b"a - b"), b"a b".to_vec());
+    }
+
+    #[test]
+    fn chunked_scoring() {
+        let scorer = PrintableScorer;
+        let plaintext = b"printable\x00\x01\x02\x03\x04\x05\x06";
+        let score = scorer.score_reader(&plaintext[..], plaintext.len()).unwrap();
+        assert_eq!(score, scorer.score(plaintext));
+
+        // The fraction of non-printable bytes is additive over chunks.
+        let score = scorer.score_reader(&plaintext[..], 4).unwrap();
+        assert!((score - scorer.score(plaintext)).abs() < 1e-12);
+        assert_eq!(scorer.score_reader(&b""[..], 4).unwrap(), 0.0);
+    }
+}
@@ -1,25 +1,58 @@
 //! This module contains attacks against symmetric primitives.
 
 pub mod ecb_detection {
-    use std::convert::TryInto;
-    use std::collections::HashSet;
+    use std::collections::HashMap;
 
     use crate::crypto::symmetric::{Aes128, Cipher};
+    use crate::utils::bytes;
+
+    /// Returns all pairs of positions `(i, j)` with `i < j` such that the blocks at index `i`
+    /// and `j` are identical. A trailing partial block is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block size is zero.
+    pub fn find_repeated_blocks(buffer: &[u8], block_size: usize) -> Vec<(usize, usize)> {
+        let mut positions: HashMap<&[u8], Vec<usize>> = HashMap::new();
+        let mut result = Vec::new();
+        for (j, block) in buffer.chunks_exact(block_size).enumerate() {
+            let previous = positions.entry(block).or_default();
+            result.extend(previous.iter().map(|&i| (i, j)));
+            previous.push(j);
+        }
+        result
+    }
+
+    /// Returns the number of blocks which are identical to some earlier block in the buffer.
+    /// Ciphertexts encrypted in ECB-mode typically have a non-zero score, while ciphertexts
+    /// encrypted using other modes should have score zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block size is zero.
+    pub fn get_repeated_block_score(buffer: &[u8], block_size: usize) -> usize {
+        bytes::count_repeated_blocks(buffer, block_size)
+    }
 
     /// We attempt to detect ECB-mode by searching for repeating cipher blocks.
     /// 
     /// # Note
     ///
-    /// We assume a 16 byte block size.
+    /// We assume a 16 byte block size. Use `find_repeated_blocks` for other block sizes.
     pub fn detect_ecb_mode(encrypted_buffer: &[u8]) -> bool {
-        let mut block_hashes = HashSet::new();
-        for block in encrypted_buffer.chunks(Aes128::BLOCK_SIZE) {
-            let block_hash = u64::from_le_bytes(block[..8].try_into().unwrap());
-            if !block_hashes.insert(block_hash) {
-                return true;
-            }
-        }
-        false
+        get_repeated_block_score(encrypted_buffer, Aes128::BLOCK_SIZE) > 0
+    }
+
+    /// Returns the index of the ciphertext with the largest number of repeated 16 byte blocks,
+    /// or `None` if no ciphertext contains a repeated block.
+    pub fn detect_ecb_in_set(ciphertexts: &[Vec<u8>]) -> Option<usize> {
+        ciphertexts
+            .iter()
+            .map(|ciphertext| get_repeated_block_score(ciphertext, Aes128::BLOCK_SIZE))
+            .enumerate()
+            .filter(|&(_, score)| score > 0)
+            .max_by_key(|&(_, score)| score)
+            .map(|(index, _)| index)
     }
 }
 
@@ -31,7 +64,7 @@ pub mod ecb_cbc_detection {
     use oracles::symmetric::ecb_cbc_detection::Mode;
    
     /// By encrypting mutiple identical blocks, we can detect ECB-mode since the corresponding
-    /// ciphertext blocks will also be identical.
+    /// ciphertext blocks will also be identical. Only `Mode::Ecb` and `Mode::Cbc` are returned.
     pub fn get_cipher_mode<Oracle>(mut encrypt_buffer: Oracle) -> Result<Mode, Error>
         where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error>
     {
@@ -50,7 +83,74 @@ pub mod ecb_cbc_detection {
 }
 
 
+pub mod mode_detection {
+    use crate::{crypto, oracles};
+
+    use crypto::symmetric::Error;
+    use crypto::symmetric::ciphers::{Cipher, Aes128};
+    use oracles::symmetric::mode_detection::Mode;
+
+    use super::ecb_detection::get_repeated_block_score;
+
+    /// The confidence in each cipher mode, given a single ciphertext. The confidences sum to 1.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Confidence {
+        pub ecb: f64,
+        pub cbc: f64,
+        pub ctr: f64,
+    }
+
+    impl Confidence {
+        /// Returns the confidence in the given mode.
+        pub fn of(&self, mode: Mode) -> f64 {
+            match mode {
+                Mode::Ecb => self.ecb,
+                Mode::Cbc => self.cbc,
+                Mode::Ctr => self.ctr,
+            }
+        }
+
+        /// Returns the most likely cipher mode.
+        pub fn mode(&self) -> Mode {
+            [Mode::Ecb, Mode::Cbc, Mode::Ctr]
+                .iter()
+                .copied()
+                .fold(Mode::Ecb, |best, mode| if self.of(mode) > self.of(best) { mode } else { best })
+        }
+    }
+
+    /// Encrypts multiple identical blocks and analyses the resulting ciphertext.
+    ///
+    /// *   If the ciphertext contains repeated blocks, the mode is ECB.
+    /// *   If the ciphertext length is not a multiple of the block size, the mode is CTR.
+    /// *   Otherwise, the mode is either CBC, or CTR with a plaintext length which happens to be
+    ///     a multiple of the block size. Assuming that the length of the data added by the oracle
+    ///     is uniformly distributed modulo the block size, the latter happens with probability
+    ///     `1 / BLOCK_SIZE`.
+    pub fn get_cipher_mode<Oracle>(mut encrypt_buffer: Oracle) -> Result<Confidence, Error>
+        where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error>
+    {
+        let known_data = [0; 3 * Aes128::BLOCK_SIZE];
+        let result = encrypt_buffer(&known_data)?;
+
+        if get_repeated_block_score(&result, Aes128::BLOCK_SIZE) > 0 {
+            return Ok(Confidence { ecb: 1.0, cbc: 0.0, ctr: 0.0 });
+        }
+        if result.len() % Aes128::BLOCK_SIZE != 0 {
+            return Ok(Confidence { ecb: 0.0, cbc: 0.0, ctr: 1.0 });
+        }
+        // Compute the posterior given a uniform prior on the CBC and CTR-modes.
+        let ctr_likelihood = 1.0 / Aes128::BLOCK_SIZE as f64;
+        Ok(Confidence {
+            ecb: 0.0,
+            cbc: 1.0 / (1.0 + ctr_likelihood),
+            ctr: ctr_likelihood / (1.0 + ctr_likelihood),
+        })
+    }
+}
+
 pub mod simple_ecb_decryption {
+    use crate::trace::{self, Event};
     use crate::crypto::symmetric::Error;
 
     fn get_known_data(suffix_size: usize, block_size: usize) -> Vec<u8> {
@@ -79,93 +179,557 @@ pub mod simple_ecb_decryption {
         Err(Error::CipherError)
     }
 
+    /// Returns the last `block_size - 1` bytes of the known data followed by the recovered
+    /// data. Appending a candidate byte to this gives a full dictionary block.
+    fn get_dictionary_prefix(unknown_data: &[u8], block_size: usize) -> Vec<u8> {
+        let known_data = get_known_data_with_suffix(unknown_data, block_size);
+        known_data[known_data.len() + 1 - block_size..].to_vec()
+    }
+
+    /// Returns a query consisting of one dictionary block for each candidate, followed by the
+    /// known data used to align the next unknown byte with the end of a block.
+    pub fn get_query(unknown_data: &[u8], candidates: &[u8], block_size: usize) -> Vec<u8> {
+        let prefix = get_dictionary_prefix(unknown_data, block_size);
+        let mut result = Vec::with_capacity((candidates.len() + 1) * block_size);
+        for &candidate in candidates {
+            result.extend(&prefix);
+            result.push(candidate);
+        }
+        result.extend(get_known_data(unknown_data.len(), block_size));
+        result
+    }
+
+    /// Returns the candidate whose dictionary block matches the block containing the next
+    /// unknown byte, given the result of the query returned by `get_query`.
+    pub fn find_candidate(
+        result: &[u8],
+        unknown_data: &[u8],
+        candidates: &[u8],
+        block_size: usize
+    ) -> Option<u8> {
+        let begin = block_size * (candidates.len() + unknown_data.len() / block_size);
+        let target = result.get(begin..begin + block_size)?;
+        candidates
+            .iter()
+            .zip(result.chunks(block_size))
+            .find(|(_, block)| *block == target)
+            .map(|(&candidate, _)| candidate)
+    }
+
+    /// Recovers the unknown data appended by the oracle, one byte at a time. Each byte is
+    /// recovered using a single query containing a dictionary block for every candidate byte,
+    /// followed by the known data aligning the unknown byte with the end of a block.
+    ///
+    /// # Note
+    ///
+    /// The block size is detected using `get_block_size`, so this works for any block cipher
+    /// in ECB mode.
     pub fn get_unknown_data<Oracle>(mut encrypt_buffer: Oracle) -> Result<Vec<u8>, Error> 
         where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error> 
     {
         let block_size = get_block_size(|buffer| encrypt_buffer(buffer))?;
+        let candidates: Vec<u8> = (0..=255).collect();
         
         let mut unknown_data = Vec::new();
         loop {
-            let mut known_data = get_known_data(unknown_data.len(), block_size);
-            let target_data = encrypt_buffer(&known_data)?;
-            
-            known_data = get_known_data_with_suffix(&unknown_data, block_size);
-            let mut last_byte = 0;
-            known_data.push(last_byte);
-            let mut test_data = encrypt_buffer(&known_data)?;
-            
-            let begin = block_size * (unknown_data.len() / block_size);
-            let end = begin + block_size;
-            while test_data[begin..end] != target_data[begin..end] {           
-                if last_byte == 255 {
-                    // Note that this is not an error state. This will in fact
-                    // happen when we are trying to recover the padding bytes
-                    // since these change depending on the size of the message.
+            let result = encrypt_buffer(&get_query(&unknown_data, &candidates, block_size))?;
+            match find_candidate(&result, &unknown_data, &candidates, block_size) {
+                Some(byte) => {
+                    trace::emit(|| Event::Candidate {
+                        attack: "simple_ecb_decryption",
+                        position: unknown_data.len(),
+                        value: byte
+                    });
+                    unknown_data.push(byte)
+                },
+                None => {
+                    // Note that this is not an error state. This will in fact happen when we are
+                    // trying to recover the padding bytes since these change depending on the
+                    // size of the message.
+                    unknown_data.pop();
+                    return Ok(unknown_data);
+                }
+            }
+        }
+    }
+
+    /// Recovers the unknown data as `get_unknown_data`, but splits the candidate bytes between
+    /// the given number of threads, each issuing its own dictionary queries. This requires the
+    /// oracle to be thread-safe.
+    pub fn get_unknown_data_parallel<Oracle>(encrypt_buffer: Oracle, threads: usize) -> Result<Vec<u8>, Error>
+        where Oracle: Fn(&[u8]) -> Result<Vec<u8>, Error> + Sync
+    {
+        let block_size = get_block_size(&encrypt_buffer)?;
+        let candidates: Vec<u8> = (0..=255).collect();
+        let chunk_size = candidates.len().div_ceil(threads.max(1));
+
+        let mut unknown_data = Vec::new();
+        loop {
+            let results: Vec<Result<Option<u8>, Error>> = std::thread::scope(|scope| {
+                let handles: Vec<_> = candidates
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        let (encrypt_buffer, unknown_data) = (&encrypt_buffer, &unknown_data);
+                        scope.spawn(move || {
+                            let result = encrypt_buffer(&get_query(unknown_data, chunk, block_size))?;
+                            Ok(find_candidate(&result, unknown_data, chunk, block_size))
+                        })
+                    })
+                    .collect();
+                handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+            });
+            let mut byte = None;
+            for result in results {
+                byte = byte.or(result?);
+            }
+            match byte {
+                Some(byte) => unknown_data.push(byte),
+                None => {
                     unknown_data.pop();
                     return Ok(unknown_data);
                 }
-                last_byte += 1;
-                *known_data.last_mut().unwrap() = last_byte;
-                test_data = encrypt_buffer(&known_data)?;
             }
-            unknown_data.push(last_byte);
         }
     }
 }
 
 
 pub mod ecb_cut_and_paste {
-        use std::iter::repeat;
-
-        use crate::oracles;
-        use oracles::symmetric::ecb_cut_and_paste::Error;
-
-        use crate::crypto;
-        use crypto::symmetric::ciphers::{Cipher, Aes128};
-        use crypto::symmetric::padding_modes::{PaddingMode, Pkcs7};
-
-        /// Encrypting the profile corresponding to the first email address yields `email=...`
-        /// `admin\x11 ... \x11` `...` where the second contains the string `admin` followed by a
-        /// valid PKCS7 padding.
-        fn get_admin_string() -> String {
-            // The length of the first block ("email=" + padding) must be 16.
-            let padding_size = Pkcs7::min_padding_size(Aes128::BLOCK_SIZE, "email=".len());
-            let padding_string = repeat(" ").take(padding_size).collect::<String>();
-            
-            // The length of the second block ("admin" + padding) must be 16.
-            let padding_size = Pkcs7::min_padding_size(Aes128::BLOCK_SIZE, "admin".len());
-            let padding_bytes = repeat(padding_size as u8).take(padding_size).collect::<Vec<u8>>();
-            format!("{}admin{}@bar.com", padding_string, std::str::from_utf8(&padding_bytes).unwrap())
-        }
-
-        /// Encrypting the profile corresponding to the second email address yields `email=...`
-        /// `...role=` `...`. Thus if we replace the third with the second block from above, we get
-        /// a valid parameter string corresponding to a profile with admin privileges.
-        fn get_email_string() -> String {
-            // The length of the email plus '&uid=10&role=' must be and even multiple of 16.
-            "admin@cryp.to".to_string()
+    use crate::oracles;
+    use oracles::symmetric::ecb_cut_and_paste::Error;
+
+    use crate::crypto;
+    use crypto::symmetric::padding_modes::{PaddingMode, Pkcs7};
+
+    /// The layout of the encoded profile, as discovered by probing the oracle.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Layout {
+        /// The block size of the cipher.
+        pub block_size: usize,
+        /// The number of bytes preceding the email address.
+        pub prefix_size: usize,
+        /// The number of bytes following the email address.
+        pub suffix_size: usize,
+        /// The size of the value of the last field.
+        pub value_size: usize,
+    }
+
+    fn get_blocks(buffer: &[u8], block_size: usize) -> Vec<&[u8]> {
+        buffer.chunks(block_size).collect()
+    }
+
+    /// Returns a string of `A`s of the given size.
+    fn get_filler(size: usize) -> String {
+        "A".repeat(size)
+    }
+
+    /// Returns the given bytes followed by PKCS7 padding as a string.
+    fn get_padded_string(bytes: &[u8], block_size: usize) -> Result<String, Error> {
+        let mut buffer = bytes.to_vec();
+        Pkcs7::new(block_size).pad_buffer(&mut buffer)?;
+        String::from_utf8(buffer).map_err(|_| Error::EncodingError)
+    }
+
+    /// Returns the block size and the combined size of the data surrounding the email address.
+    /// The ciphertext grows by one block when the size of the encoded profile reaches a multiple
+    /// of the block size.
+    fn get_block_size<Oracle>(get_profile_for: &mut Oracle) -> Result<(usize, usize), Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let initial_size = get_profile_for("")?.len();
+        for email_size in 1..=256 {
+            let size = get_profile_for(&get_filler(email_size))?.len();
+            if size > initial_size {
+                return Ok((size - initial_size, initial_size - email_size));
+            }
         }
-    
-        pub fn get_admin_profile<Oracle>(mut get_profile_for: Oracle) -> Result<Vec<u8>, Error>
-            where Oracle : FnMut(&str) -> Result<Vec<u8>, Error> {
-            let admin_bytes = get_profile_for(&get_admin_string())?
-                .chunks(Aes128::BLOCK_SIZE)
-                .nth(1)
-                .ok_or(Error::CipherError)?
-                .to_owned();
-            let mut profile_bytes: Vec<u8> = get_profile_for(&get_email_string())?;
-            profile_bytes.splice(2 * Aes128::BLOCK_SIZE.., admin_bytes.iter().cloned());
-            Ok(profile_bytes)
+        Err(Error::CipherError)
+    }
+
+    /// Changing the first byte of the email address changes the block B containing it. By
+    /// prepending an increasing number of `A`s to the changed byte, we find the smallest number
+    /// of bytes required to fill B, at which point B no longer changes.
+    fn get_prefix_size<Oracle>(get_profile_for: &mut Oracle, block_size: usize) -> Result<usize, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let first_differing_block = |lhs: &[u8], rhs: &[u8]| {
+            get_blocks(lhs, block_size)
+                .into_iter()
+                .zip(get_blocks(rhs, block_size))
+                .position(|(lhs, rhs)| lhs != rhs)
+        };
+        let block_index = first_differing_block(&get_profile_for("A")?, &get_profile_for("B")?)
+            .ok_or(Error::CipherError)?;
+        for size in 0..=block_size {
+            let lhs = get_profile_for(&format!("{}A", get_filler(size)))?;
+            let rhs = get_profile_for(&format!("{}B", get_filler(size)))?;
+            if first_differing_block(&lhs, &rhs) != Some(block_index) {
+                return Ok((block_index + 1) * block_size - size);
+            }
+        }
+        Err(Error::CipherError)
+    }
+
+    /// Recovers the value of the last field one byte at a time, starting from the end. By
+    /// choosing the size of the email address, we can ensure that the last block consists of
+    /// the last `k` bytes of the profile followed by padding. We then forge the same block for
+    /// each candidate byte as part of the email address and compare. Since `=` is escaped or
+    /// rejected by the oracle, it can never be matched, which tells us where the value starts.
+    fn get_value_size<Oracle>(
+        get_profile_for: &mut Oracle,
+        block_size: usize,
+        prefix_size: usize,
+        suffix_size: usize
+    ) -> Result<usize, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let alignment = (block_size - prefix_size % block_size) % block_size;
+        let block_index = (prefix_size + alignment) / block_size;
+
+        let mut value = Vec::new();
+        for size in 1..block_size {
+            let filler_size = (block_size + size - suffix_size % block_size) % block_size;
+            let mut recovered = false;
+            for candidate in (0..0x80).filter(|&byte| byte != b'=' && byte != b'&') {
+                let bytes = [&[candidate][..], &value].concat();
+                let email = format!(
+                    "{}{}{}",
+                    get_filler(alignment),
+                    get_padded_string(&bytes, block_size)?,
+                    get_filler(filler_size)
+                );
+                // Candidates rejected by the oracle cannot be forged and are skipped.
+                let profile = match get_profile_for(&email) {
+                    Ok(profile) => profile,
+                    Err(Error::EncodingError) => continue,
+                    Err(error) => return Err(error),
+                };
+                let blocks = get_blocks(&profile, block_size);
+                if blocks.get(block_index) == blocks.last() {
+                    value.insert(0, candidate);
+                    recovered = true;
+                    break;
+                }
+            }
+            if !recovered {
+                return Ok(value.len());
+            }
+        }
+        Err(Error::CipherError)
+    }
+
+    /// Discovers the layout of the encoded profile by probing the oracle with email addresses
+    /// of varying sizes.
+    pub fn get_layout<Oracle>(get_profile_for: &mut Oracle) -> Result<Layout, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let (block_size, surrounding_size) = get_block_size(get_profile_for)?;
+        let prefix_size = get_prefix_size(get_profile_for, block_size)?;
+        let suffix_size = surrounding_size - prefix_size;
+        let value_size = get_value_size(get_profile_for, block_size, prefix_size, suffix_size)?;
+        Ok(Layout { block_size, prefix_size, suffix_size, value_size })
+    }
+
+    /// Returns a ciphertext where the value of the last field is replaced by the given value.
+    /// We choose an email address which consists of the blocks containing the target value
+    /// followed by valid padding, followed by enough bytes to ensure that the value of the last
+    /// field starts at a block boundary. We then replace the blocks containing the original
+    /// value with the forged blocks. All offsets are computed from the discovered layout, so
+    /// this works for any prefix and suffix (like a uid of unknown size).
+    ///
+    /// # Note
+    ///
+    /// Only the value of the last field can be replaced. The forged blocks end with padding, so
+    /// they must be the last blocks of the ciphertext, and everything following the value is
+    /// discarded. The target value cannot contain characters which are escaped or rejected by
+    /// the oracle.
+    pub fn inject_value<Oracle>(mut get_profile_for: Oracle, value: &str) -> Result<Vec<u8>, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let layout = get_layout(&mut get_profile_for)?;
+        let block_size = layout.block_size;
+        let alignment = (block_size - layout.prefix_size % block_size) % block_size;
+        let block_index = (layout.prefix_size + alignment) / block_size;
+        let padded_value = get_padded_string(value.as_bytes(), block_size)?;
+        let nof_blocks = padded_value.len() / block_size;
+        let filler_size = 
+            (block_size + layout.value_size % block_size - layout.suffix_size % block_size) % block_size;
+        let email = format!(
+            "{}{}{}",
+            get_filler(alignment),
+            padded_value,
+            get_filler(filler_size)
+        );
+        let profile = get_profile_for(&email)?;
+        
+        let value_offset = 
+            layout.prefix_size + email.len() + layout.suffix_size - layout.value_size;
+        let forged_blocks = &profile[block_index * block_size..(block_index + nof_blocks) * block_size];
+        let mut result = profile[..value_offset].to_vec();
+        result.extend_from_slice(forged_blocks);
+        Ok(result)
+    }
+
+    /// Forges a profile with admin privileges by replacing the role with `admin`.
+    pub fn get_admin_profile<Oracle>(get_profile_for: Oracle) -> Result<Vec<u8>, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        inject_value(get_profile_for, "admin")
+    }
+}
+
+/// Analyses which mitigations implemented by the hardened profile oracle prevent the
+/// cut-and-paste attack from `ecb_cut_and_paste`. Since the attack discovers the layout of the
+/// profile, it is unaffected by the size of the uid and by quoting of the email address. It
+/// does require the role to be the last field, and the profile to be encrypted in ECB-mode.
+pub mod hardened_cut_and_paste {
+    use crate::oracles;
+    use oracles::symmetric::hardened_cut_and_paste::{Oracle, Role, Error};
+
+    use super::ecb_cut_and_paste;
+
+    /// The effect of a set of mitigations on the cut-and-paste attack.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Outcome {
+        /// The original attack succeeds.
+        Ineffective,
+        /// The original attack fails, but an adapted version of the attack succeeds.
+        Complicates,
+        /// Both the original and the adapted attack fail.
+        Blocks,
+    }
+
+    const LOCAL_PART: &str = "a";
+    const DOMAIN: &str = "@a.co";
+
+    /// Forges a profile with admin privileges against an oracle which validates the format of
+    /// the email address. Since the local part and domain are constant, they simply become part
+    /// of the data surrounding the email address, and the layout of the profile can be
+    /// discovered as before.
+    ///
+    /// # Note
+    ///
+    /// The forged block contains PKCS7 padding, so the attack fails if the oracle only accepts
+    /// printable characters. Forging the last block without padding would require a value
+    /// containing `&` or `=`, which are always escaped or rejected.
+    pub fn get_admin_profile<Oracle>(mut get_profile_for: Oracle) -> Result<Vec<u8>, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        ecb_cut_and_paste::get_admin_profile(|email| {
+            get_profile_for(&format!("{}{}{}", LOCAL_PART, email, DOMAIN))
+        })
+    }
+
+    fn is_admin_profile(oracle: &mut Oracle, profile: Result<Vec<u8>, Error>) -> bool {
+        profile
+            .and_then(|profile| oracle.get_role_from(&profile))
+            .is_ok_and(|role| role == Role::Admin)
+    }
+
+    /// Runs the original and the adapted attack against the given oracle.
+    pub fn analyze(oracle: &mut Oracle) -> Outcome {
+        let profile = ecb_cut_and_paste::get_admin_profile(|email| oracle.get_profile_for(email));
+        if is_admin_profile(oracle, profile) {
+            return Outcome::Ineffective;
+        }
+        let profile = get_admin_profile(|email| oracle.get_profile_for(email));
+        if is_admin_profile(oracle, profile) {
+            return Outcome::Complicates;
+        }
+        Outcome::Blocks
     }
 }
 
+/// Cut-and-paste and bitflipping attacks against profiles encoded as JSON. Both attacks rely
+/// on the encoding being known: The profile is encoded as `{"email":"<email>","uid":10,
+/// "role":"user"}`, where quotes and backslashes in the email address are escaped.
+#[cfg(feature = "serde")]
+pub mod json_cut_and_paste {
+    use crate::oracles;
+    use oracles::symmetric::json_cut_and_paste::Error;
+
+    use crate::crypto::symmetric::{Cipher, Aes128};
+
+    const BLOCK_SIZE: usize = Aes128::BLOCK_SIZE;
+
+    const PREFIX: &str = "{\"email\":\"";
+    const SUFFIX: &str = "\",\"uid\":10,\"role\":\"user\"}";
+    const ROLE_PREFIX: &str = "\",\"uid\":10,\"role\":\"";
+
+    fn get_blocks(buffer: &[u8]) -> Vec<&[u8]> {
+        buffer.chunks(BLOCK_SIZE).collect()
+    }
+
+    /// Returns a string of `A`s of the given size.
+    fn get_filler(size: usize) -> String {
+        "A".repeat(size)
+    }
+
+    /// Returns the size of the filler needed to pad the given size to a multiple of the block
+    /// size.
+    fn get_alignment(size: usize) -> usize {
+        (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE
+    }
+
+    /// Returns a block consisting of a full block of PKCS7 padding. This is the last block of
+    /// any profile whose size is a multiple of the block size.
+    fn get_padding_block<Oracle>(get_profile_for: &mut Oracle) -> Result<Vec<u8>, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let email = get_filler(get_alignment(PREFIX.len() + SUFFIX.len()));
+        let profile = get_profile_for(&email)?;
+        get_blocks(&profile).last().map(|block| block.to_vec()).ok_or(Error::CipherError)
+    }
+
+    /// Forges a profile with admin privileges using cut-and-paste under ECB-mode.
+    ///
+    /// Since quotes in the email address are escaped, we cannot forge blocks containing quotes
+    /// directly. However, if the email address contains a quote and the escaping backslash
+    /// ends up as the last byte of a block, the next block starts with an unescaped quote. We
+    /// use this to forge the key and value of an additional field, which allows us to
+    /// terminate the profile after the block `admin","uid":10,`. The resulting profile is
+    ///
+    /// ```text
+    /// {"email":"AAA","uid":10,"role":"admin","uid":10,"AAAAAAAAAAAAAAA":0}
+    /// ```
+    ///
+    /// followed by whitespace.
+    pub fn get_admin_profile_ecb<Oracle>(mut get_profile_for: Oracle) -> Result<Vec<u8>, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let value = "admin";
+        let field_end = ":0}";
+
+        // The blocks up to and including `"role":"`.
+        let email = get_filler(get_alignment(PREFIX.len() + ROLE_PREFIX.len()));
+        let head_size = PREFIX.len() + email.len() + ROLE_PREFIX.len();
+        let head = get_profile_for(&email)?[..head_size].to_vec();
+
+        // Align the backslash escaping the first quote with the end of the first block. The
+        // email address then produces the blocks
+        //
+        //   1: "AAAAAAAAAAAAAAA
+        //   2: AAAAAAAAAAAAAAA\
+        //   3: ":0} followed by whitespace
+        //   4: admin","uid":10,
+        let email = format!(
+            "{}\"{}\"{}{}{}",
+            get_filler(get_alignment(PREFIX.len() + 1)),
+            get_filler(2 * BLOCK_SIZE - 2),
+            field_end,
+            " ".repeat(BLOCK_SIZE - 1 - field_end.len()),
+            value
+        );
+        let profile = get_profile_for(&email)?;
+        let blocks = get_blocks(&profile);
+        let first_block = (PREFIX.len() + 1) / BLOCK_SIZE + 1;
+
+        let mut result = head;
+        result.extend(blocks[first_block + 3]);
+        result.extend(blocks[first_block]);
+        result.extend(blocks[first_block + 2]);
+        result.extend(get_padding_block(&mut get_profile_for)?);
+        Ok(result)
+    }
+
+    /// Forges a profile with admin privileges by flipping bits in the IV under CBC-mode.
+    ///
+    /// Flipping bits in a ciphertext block garbles the corresponding plaintext block, which
+    /// is almost never valid JSON. Flipping bits in the IV only affects the first block, but
+    /// this is not enough to change the role since the original role always comes last.
+    /// Instead, we drop everything except the last two blocks, and use the block preceding them
+    /// as the IV. Since we know the plaintext, we can then flip bits in the new IV to obtain
+    /// the profile `{"role":"admin"}`, followed by a full block of padding.
+    pub fn get_admin_profile_cbc<Oracle>(mut get_profile_for: Oracle) -> Result<Vec<u8>, Error>
+        where Oracle: FnMut(&str) -> Result<Vec<u8>, Error>
+    {
+        let target_block = b"{\"role\":\"admin\"}";
+        let email = get_filler(get_alignment(PREFIX.len() + SUFFIX.len()));
+        let plaintext = format!("{}{}{}", PREFIX, email, SUFFIX);
+        let profile = get_profile_for(&email)?;
+
+        // The profile consists of the IV, the encrypted plaintext and a full padding block.
+        let blocks = get_blocks(&profile);
+        if blocks.len() < 3 || plaintext.len() != (blocks.len() - 2) * BLOCK_SIZE {
+            return Err(Error::CipherError);
+        }
+        let last_block = &plaintext.as_bytes()[plaintext.len() - BLOCK_SIZE..];
+        let iv: Vec<u8> = blocks[blocks.len() - 3]
+            .iter()
+            .zip(last_block)
+            .zip(target_block)
+            .map(|((x, y), z)| x ^ y ^ z)
+            .collect();
+
+        let mut result = iv;
+        result.extend(blocks[blocks.len() - 2]);
+        result.extend(blocks[blocks.len() - 1]);
+        Ok(result)
+    }
+}
 
 pub mod harder_ecb_decryption {
     use crate::crypto::symmetric::Error;
     use crate::crypto::symmetric::padding_modes::{PaddingMode, Pkcs7};
+    use crate::utils::bytes;
 
     use super::simple_ecb_decryption;
 
+    // Since the output size is always k * (block size) for some k, we can
+    // compute the block size as (k + 1) * (block size) - k * (block size).
+    fn get_block_size<Oracle>(mut encrypt_buffer: Oracle) -> Result<usize, Error> 
+        where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error> 
+    {
+        let output_size = encrypt_buffer(&[])?.len();
+        for input_size in 8..=256 {
+            let block_size = encrypt_buffer(&vec![0; 2 * input_size])?.len() - output_size;
+            if block_size > 0 { return Ok(block_size) }
+        }
+        Err(Error::CipherError)
+    }
+
+    /// Estimates the size of the random prefix using known data consisting of the given byte.
+    /// If the known data consists of two blocks and `k` additional bytes, two consecutive
+    /// encrypted blocks at index `i` are equal when `k` aligns the known data with a block
+    /// boundary, in which case the prefix size is `i * (block size) - k`.
+    fn estimate_prefix_size<Oracle>(
+        encrypt_buffer: &mut Oracle, 
+        block_size: usize, 
+        byte: u8
+    ) -> Result<usize, Error>
+        where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error> 
+    {
+        for known_size in 0..block_size {
+            let result = encrypt_buffer(&vec![byte; 2 * block_size + known_size])?;
+            if let Some(index) = bytes::find_identical_consecutive_blocks(&result, block_size) {
+                return (index * block_size)
+                    .checked_sub(known_size)
+                    .ok_or(Error::CipherError);
+            }
+        }
+        Err(Error::CipherError)
+    }
+
+    /// Determines the size of the random prefix.
+    ///
+    /// # Note
+    ///
+    /// The estimate is too small if the prefix ends with the byte used for the known data, and
+    /// too large if the unknown data starts with it. Since the prefix and the unknown data can
+    /// only affect a single byte each, the median of the estimates for three different bytes
+    /// is always correct.
+    pub fn get_prefix_size<Oracle>(mut encrypt_buffer: Oracle) -> Result<usize, Error>
+        where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error> 
+    {
+        let block_size = get_block_size(|buffer| encrypt_buffer(buffer))?;
+        let mut estimates = [0; 3];
+        for (byte, estimate) in estimates.iter_mut().enumerate() {
+            *estimate = estimate_prefix_size(&mut encrypt_buffer, block_size, byte as u8)?;
+        }
+        estimates.sort_unstable();
+        Ok(estimates[1])
+    }
+
     // A proxy object wrapping the encrypt_buffer oracle.
     struct Proxy<Oracle> where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error> {
         prefix_size: usize,
@@ -175,8 +739,8 @@ pub mod harder_ecb_decryption {
 
     impl<Oracle> Proxy<Oracle> where Oracle: FnMut(&[u8]) -> Result<Vec<u8>, Error> {
         fn new(mut encrypt_buffer: Oracle) -> Result<Self, Error> {
-            let block_size = Proxy::get_block_size(|buffer| encrypt_buffer(buffer))?;
-            let prefix_size = Proxy::get_prefix_size(|buffer| encrypt_buffer(buffer))?;
+            let block_size = get_block_size(|buffer| encrypt_buffer(buffer))?;
+            let prefix_size = get_prefix_size(|buffer| encrypt_buffer(buffer))?;
             let padding_size = Pkcs7::min_padding_size(block_size, prefix_size);
             Ok(Proxy { 
                 prefix_size, 
@@ -185,35 +749,6 @@ pub mod harder_ecb_decryption {
             })
         }
 
-        // Since the output size is always k * (block size) for some k, we can
-        // compute the block size as (k + 1) * (block size) - k * (block size).
-        fn get_block_size(mut encrypt_buffer: Oracle) -> Result<usize, Error> {
-            let output_size = encrypt_buffer(&[])?.len();
-            for input_size in 8..=256 {
-                let block_size = encrypt_buffer(&vec![0; 2 * input_size])?.len() - output_size;
-                if block_size > 0 { return Ok(block_size) }
-            }
-            Err(Error::CipherError)
-        }
-
-        // If two consecutive encrypted blocks are equal, the size of the known
-        // data must be (prefix size) % (block size) + k * (block size) for k > 1.
-        fn get_prefix_size(mut encrypt_buffer: Oracle) -> Result<usize, Error> {
-            let block_size = Proxy::get_block_size(|buffer| encrypt_buffer(buffer))?;
-            for known_size in 1..=256 {
-                let result = encrypt_buffer(&vec![0; known_size])?;
-                let blocks: Vec<&[u8]> = result.chunks(block_size).collect();
-                for i in 0 .. blocks.len() - 1 {
-                    if blocks[i] == blocks[i + 1] {
-                        let padding_size = known_size % block_size;
-                        // TODO: This can panic if padding_size > i * block_size.
-                        return Ok(i * block_size - padding_size);
-                    }
-                }
-            }
-            Err(Error::CipherError)
-        }
-
         pub fn encrypt_buffer(&mut self, buffer: &[u8]) -> Result<Vec<u8>, Error> {
             let padded_size = self.padding_size +  buffer.len();
             let mut padded_buffer = Vec::with_capacity(padded_size);
@@ -233,7 +768,7 @@ pub mod harder_ecb_decryption {
     }
 }
 
-pub mod cbc_bitflipping_attacks {
+pub mod bitflipping {
     use crate::crypto::symmetric;
     use symmetric::ciphers::{Cipher, Aes128};
 
@@ -249,23 +784,95 @@ pub mod cbc_bitflipping_attacks {
         }
     }
 
-    /// By encrypting a long sequence of `A`s we know that (at least) one ciphertext block C will
-    /// decrypt to a plaintext block P with prefix `AA...A` of the same size as the target
-    /// plaintext. Now, if we XOR the ciphertext block immediately before C with the difference
-    /// between our target plaintext and `AA...A`, the resulting ciphertext will decrypt to a
-    /// random block, followed by the target plaintext.
-    pub fn get_admin_profile<Oracle>(
-        prefix_size: usize,
-        encrypt_buffer: &mut Oracle
+    /// Describes how flipping a bit in the ciphertext affects the decrypted plaintext.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Injection {
+        /// Flipping a bit in a ciphertext block flips the corresponding bit in the next plaintext
+        /// block (and scrambles the current block). This is the case for CBC-mode.
+        PreviousBlock,
+        /// Flipping a bit in the ciphertext flips the bit at the same position in the plaintext.
+        /// This is the case for stream ciphers like CTR-mode.
+        SamePosition,
+    }
+
+    /// Returns the index of the first block which differs between the two buffers.
+    fn first_differing_block(lhs: &[u8], rhs: &[u8]) -> Option<usize> {
+        lhs.chunks(Aes128::BLOCK_SIZE)
+            .zip(rhs.chunks(Aes128::BLOCK_SIZE))
+            .position(|(lhs, rhs)| lhs != rhs)
+    }
+
+    /// Determines the size of the data preceding the user data.
+    /// 
+    /// *   For stream ciphers, the first byte which changes when we change the first byte of
+    ///     the user data is the first byte of the user data.
+    /// *   For CBC-mode, changing the first byte of the user data changes the block B
+    ///     containing the start of the user data. By prepending an increasing number of `A`s to
+    ///     the changed byte, we find the smallest number of bytes required to fill B, at which
+    ///     point B no longer changes.
+    pub fn get_prefix_size<Oracle>(
+        encrypt_buffer: &mut Oracle, 
+        injection: Injection
+    ) -> Result<usize, Error> where
+        Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
+    {
+        let lhs = encrypt_buffer("A")?;
+        let rhs = encrypt_buffer("B")?;
+        if injection == Injection::SamePosition {
+            return lhs.iter().zip(&rhs).position(|(x, y)| x != y).ok_or(Error::RecoveryError);
+        }
+        let block_index = first_differing_block(&lhs, &rhs).ok_or(Error::RecoveryError)?;
+        for size in 0..=Aes128::BLOCK_SIZE {
+            let known_str = "A".repeat(size);
+            let lhs = encrypt_buffer(&format!("{}A", known_str))?;
+            let rhs = encrypt_buffer(&format!("{}B", known_str))?;
+            if first_differing_block(&lhs, &rhs) != Some(block_index) {
+                return Ok((block_index + 1) * Aes128::BLOCK_SIZE - size);
+            }
+        }
+        Err(Error::RecoveryError)
+    }
+
+    /// Returns a ciphertext which decrypts to a plaintext containing the target string, even
+    /// though the oracle would escape it if passed as user data. We encrypt a sequence of `A`s
+    /// and XOR the ciphertext bytes which affect the plaintext `A`s with the difference between
+    /// `A` and the target string.
+    ///
+    /// For CBC-mode, the user data is first aligned with a block boundary and we prepend a
+    /// block of `A`s which is scrambled by the modification.
+    pub fn inject<Oracle>(
+        encrypt_buffer: &mut Oracle,
+        injection: Injection,
+        target_str: &str
     ) -> Result<Vec<u8>, Error> where
         Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
     {
-        let target_str = ";admin=true;";
-        let user_str = std::iter::repeat("A")
-            .take(Aes128::BLOCK_SIZE + target_str.len())
-            .collect::<String>();
+        let prefix_size = get_prefix_size(encrypt_buffer, injection)?;
+        inject_with_prefix_size(encrypt_buffer, injection, target_str, prefix_size)
+    }
+
+    /// Like `inject`, but uses the given prefix size instead of determining it using the
+    /// oracle.
+    pub fn inject_with_prefix_size<Oracle>(
+        encrypt_buffer: &mut Oracle,
+        injection: Injection,
+        target_str: &str,
+        prefix_size: usize
+    ) -> Result<Vec<u8>, Error> where
+        Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
+    {
+        let padding_size = match injection {
+            Injection::PreviousBlock => 
+                (Aes128::BLOCK_SIZE - prefix_size % Aes128::BLOCK_SIZE) % Aes128::BLOCK_SIZE + 
+                Aes128::BLOCK_SIZE,
+            Injection::SamePosition => 0
+        };
+        let user_str = "A".repeat(padding_size + target_str.len());
         let mut result = encrypt_buffer(&user_str)?;
-        let offset = prefix_size - (prefix_size % Aes128::BLOCK_SIZE);
+        let offset = match injection {
+            Injection::PreviousBlock => prefix_size + padding_size - Aes128::BLOCK_SIZE,
+            Injection::SamePosition => prefix_size
+        };
         for (index, byte) in target_str.as_bytes().iter().enumerate() {
             result[offset + index] ^= b'A' ^ byte;
         }
@@ -273,8 +880,49 @@ pub mod cbc_bitflipping_attacks {
     }
 }
 
+pub mod cbc_bitflipping_attacks {
+    use crate::crypto::symmetric;
+    use super::bitflipping::{self, Injection};
+
+    pub use bitflipping::Error;
+
+    /// Determines the size of the data preceding the user data.
+    pub fn get_prefix_size<Oracle>(encrypt_buffer: &mut Oracle) -> Result<usize, Error> where
+        Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
+    {
+        bitflipping::get_prefix_size(encrypt_buffer, Injection::PreviousBlock)
+    }
+
+    /// We first align the user data with a block boundary, using the size of the prefix (which
+    /// is determined using `get_prefix_size`). By encrypting two blocks of `A`s we know that
+    /// the second ciphertext block C will decrypt to `AA...A`. Now, if we XOR the ciphertext
+    /// block immediately before C with the difference between our target plaintext and
+    /// `AA...A`, the resulting ciphertext will decrypt to a random block, followed by the target
+    /// plaintext.
+    pub fn get_admin_profile<Oracle>(encrypt_buffer: &mut Oracle) -> Result<Vec<u8>, Error> where
+        Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
+    {
+        bitflipping::inject(encrypt_buffer, Injection::PreviousBlock, ";admin=true;")
+    }
+
+    /// Like `get_admin_profile`, but uses the given prefix size instead of determining it
+    /// using the oracle. This saves the queries used to determine the prefix size.
+    pub fn get_admin_profile_with_prefix_size<Oracle>(
+        encrypt_buffer: &mut Oracle,
+        prefix_size: usize
+    ) -> Result<Vec<u8>, Error> where
+        Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
+    {
+        bitflipping::inject_with_prefix_size(encrypt_buffer, Injection::PreviousBlock, ";admin=true;", prefix_size)
+    }
+}
+
 pub mod cbc_padding_oracle {
-    use std::collections::VecDeque;
+    use std::cell::Cell;
+
+    use crate::progress::{self, CancellationToken, Reporter};
+    use crate::trace::{self, Event};
+    use crate::utils::bytes;
     use crate::crypto::symmetric;
     use symmetric::{
         PaddingMode,
@@ -287,6 +935,7 @@ pub mod cbc_padding_oracle {
     pub enum Error {
         CipherError,
         RecoveryError,
+        Cancelled,
     }
 
     impl From<symmetric::Error> for Error {
@@ -294,119 +943,305 @@ pub mod cbc_padding_oracle {
             Error::CipherError
         }
     }
-    
-    fn edit_encrypted_buffer(
-        encrypted_buffer: &[u8],
-        plaintext_buffer: &VecDeque<u8>,
-    ) -> Vec<u8> {
-        assert!(Aes128::BLOCK_SIZE + plaintext_buffer.len() <= encrypted_buffer.len());
-        let first_index = 
-            encrypted_buffer.len() - plaintext_buffer.len() - Aes128::BLOCK_SIZE;
-        let last_index =
-            first_index + Aes128::BLOCK_SIZE - (first_index % Aes128::BLOCK_SIZE);
-       
-        let padding_length = last_index - first_index;
-        let mut edited_buffer = encrypted_buffer.to_owned();
-        edited_buffer.truncate(last_index + Aes128::BLOCK_SIZE);
-        for index in 0..padding_length {
-            edited_buffer[first_index + index] ^= 
-                plaintext_buffer[index] ^ (padding_length as u8);
-        }
-        edited_buffer
+
+    /// Returns the padding used by `P` when `size` bytes of padding are required.
+    pub fn get_padding<P: PaddingMode>(padding_mode: &P, size: usize) -> Result<Vec<u8>, Error> {
+        let mut padding = vec![0; size];
+        padding_mode.pad_mut(&mut padding, 0)?;
+        Ok(padding)
+    }
+
+    /// Recovers the intermediate state D(C) of the given ciphertext block C, one byte at a time,
+    /// starting with the last. To recover the byte at index `i`, we choose the previous block
+    /// such that the bytes following `i` decrypt to valid padding of size `BLOCK_SIZE - i`, and
+    /// test all possible values of the byte at index `i`.
+    ///
+    /// # Note
+    ///
+    /// A valid response may be caused by a longer padding than intended (e.g. if the plaintext
+    /// ends with `02 02` rather than `01` for PKCS7). We rule this out by modifying the byte
+    /// preceding index `i` and verifying that the padding remains valid.
+    pub fn get_intermediate_block<C, P, Oracle>(
+        block: &[u8],
+        padding_mode: &P,
+        verify_padding: &mut Oracle
+    ) -> Result<Vec<u8>, Error> where
+        C: Cipher,
+        P: PaddingMode,
+        Oracle: FnMut(&[u8]) -> bool
+    {
+        let mut intermediate_block = vec![0; C::BLOCK_SIZE];
+        let mut edited_buffer = [vec![0; C::BLOCK_SIZE], block.to_vec()].concat();
+        for index in (0..C::BLOCK_SIZE).rev() {
+            let padding = get_padding(padding_mode, C::BLOCK_SIZE - index)?;
+            for (offset, byte) in padding.iter().enumerate().skip(1) {
+                edited_buffer[index + offset] = intermediate_block[index + offset] ^ byte;
+            }
+            let mut recovered = false;
+            for guess in 0..=255 {
+                edited_buffer[index] = guess;
+                if !verify_padding(&edited_buffer) {
+                    continue;
+                }
+                if index > 0 {
+                    edited_buffer[index - 1] ^= 0xff;
+                    let verified = verify_padding(&edited_buffer);
+                    edited_buffer[index - 1] ^= 0xff;
+                    if !verified {
+                        continue;
+                    }
+                }
+                intermediate_block[index] = guess ^ padding[0];
+                trace::emit(|| Event::Candidate {
+                    attack: "cbc_padding_oracle",
+                    position: index,
+                    value: intermediate_block[index]
+                });
+                recovered = true;
+                break;
+            }
+            if !recovered {
+                return Err(Error::RecoveryError);
+            }
+        }
+        Ok(intermediate_block)
     }
 
     /// This function implements a classic CBC padding oracle attack. It takes an `encrypted_buffer`
     /// on the form IV || ciphertext (an IV concatenated with the corresponding ciphertext), 
     /// together with a padding oracle `verify_padding` of type `FnMut(&[u8]) -> bool`. 
+    ///
+    /// # Note
+    ///
+    /// We assume that the oracle uses `Aes128` and `Pkcs7`. Use `get_plaintext_buffer_with`
+    /// for other ciphers and padding modes.
     pub fn get_plaintext_buffer<Oracle>(
         encrypted_buffer: &[u8],
         verify_padding: &mut Oracle
     ) -> Result<Vec<u8>, Error> where
         Oracle: FnMut(&[u8]) -> bool
     {
-        let mut partial_solutions = VecDeque::new();
-        partial_solutions.push_back(VecDeque::<u8>::new());
+        get_plaintext_buffer_with::<Aes128, Pkcs7, _>(encrypted_buffer, verify_padding)
+    }
+
+    /// Generic version of `get_plaintext_buffer` for an oracle using the block cipher `C` and
+    /// the padding mode `P`. Each block is recovered by querying the oracle with the block,
+    /// prefixed by a chosen block.
+    pub fn get_plaintext_buffer_with<C, P, Oracle>(
+        encrypted_buffer: &[u8],
+        verify_padding: &mut Oracle
+    ) -> Result<Vec<u8>, Error> where
+        C: Cipher,
+        P: PaddingMode,
+        Oracle: FnMut(&[u8]) -> bool
+    {
+        let token = CancellationToken::new();
+        get_plaintext_buffer_with_progress::<C, P, _, _>(encrypted_buffer, verify_padding, &progress::Silent, &token)
+    }
+
+    /// Version of `get_plaintext_buffer_with` which reports the number of recovered bytes and
+    /// oracle calls after each block, and returns `Error::Cancelled` once the token is
+    /// cancelled. The token is checked before each block is recovered.
+    pub fn get_plaintext_buffer_with_progress<C, P, Oracle, R>(
+        encrypted_buffer: &[u8],
+        verify_padding: &mut Oracle,
+        reporter: &R,
+        token: &CancellationToken
+    ) -> Result<Vec<u8>, Error> where
+        C: Cipher,
+        P: PaddingMode,
+        Oracle: FnMut(&[u8]) -> bool,
+        R: Reporter
+    {
+        let size = encrypted_buffer.len();
+        if size < 2 * C::BLOCK_SIZE || !size.is_multiple_of(C::BLOCK_SIZE) {
+            return Err(Error::RecoveryError);
+        }
+        let padding_mode = P::new(C::BLOCK_SIZE);
+        let blocks: Vec<&[u8]> = encrypted_buffer.chunks(C::BLOCK_SIZE).collect();
         
-        while let Some(mut partial_solution) = partial_solutions.pop_front() {
-            if Aes128::BLOCK_SIZE + partial_solution.len() == encrypted_buffer.len() {
-                // The entire plaintext has been recovered.
-                partial_solutions.push_back(partial_solution);
-                break;
+        let oracle_calls = Cell::new(0);
+        let mut counting_oracle = |buffer: &[u8]| {
+            oracle_calls.set(oracle_calls.get() + 1);
+            verify_padding(buffer)
+        };
+        let mut solution = Vec::with_capacity(encrypted_buffer.len() - C::BLOCK_SIZE);
+        for (index, blocks) in blocks.windows(2).enumerate() {
+            if token.is_cancelled() {
+                return Err(Error::Cancelled);
             }
-            // Attempt to extend the partial solution.
-            partial_solution.push_front(0x00);
-            loop {
-                let edited_buffer = edit_encrypted_buffer(
-                    &encrypted_buffer,
-                    &partial_solution,
-                );
-                if verify_padding(&edited_buffer) {
-                    partial_solutions.push_back(partial_solution.clone());
-                }
+            let intermediate_block = get_intermediate_block::<C, _, _>(
+                blocks[1], 
+                &padding_mode, 
+                &mut counting_oracle
+            )?;
+            solution.extend(bytes::xor_slices(&intermediate_block, blocks[0]));
+            trace::emit(|| Event::Progress {
+                attack: "cbc_padding_oracle",
+                completed: index + 1,
+                total: size / C::BLOCK_SIZE - 1
+            });
+            reporter.report(progress::Event::OracleCalls { count: oracle_calls.get() });
+            reporter.report(progress::Event::BytesRecovered {
+                completed: solution.len(),
+                total: size - C::BLOCK_SIZE
+            });
+        }
+        let length = padding_mode.unpad_mut(&solution)?;
+        solution.truncate(length);
+        Ok(solution)
+    }
+}
 
-                if partial_solution[0] == 0xff { 
-                    break;
-                } else {
-                    partial_solution[0] += 1;
-                }
-            }
+/// Padding oracle attack against an oracle which reports invalid UTF-8 separately from
+/// invalid padding. The content is only validated once the padding has been verified, so a
+/// content error reveals that the padding is valid. This remains true if padding errors are
+/// masked, which means that uniform padding errors alone do not prevent the attack.
+pub mod cbc_error_oracle {
+    use crate::oracles::symmetric::cbc_error_oracle as oracle;
+    use crate::crypto::symmetric::{PaddingMode, Cipher, Aes128, Pkcs7};
+    use crate::random_vec;
+    use crate::utils::bytes;
+
+    use super::cbc_padding_oracle::{get_intermediate_block, Error};
+
+    /// The number of times we attempt to recover each block before giving up.
+    const MAX_ATTEMPTS: usize = 4;
+
+    /// Recovers the plaintext of the given buffer (on the form IV || ciphertext) using the
+    /// content errors returned by `submit_buffer`.
+    ///
+    /// # Note
+    ///
+    /// To ensure that a valid padding results in a content error, each query is prefixed by a
+    /// random block. This block is used as the IV, and the chosen block decrypts to random
+    /// bytes which are very unlikely to be valid UTF-8. If a block cannot be recovered, the
+    /// block is retried with fresh random blocks.
+    pub fn get_plaintext_buffer<Oracle>(
+        encrypted_buffer: &[u8],
+        submit_buffer: &mut Oracle
+    ) -> Result<Vec<u8>, Error> where
+        Oracle: FnMut(&[u8]) -> Result<(), oracle::Error>
+    {
+        let size = encrypted_buffer.len();
+        if size < 2 * Aes128::BLOCK_SIZE || !size.is_multiple_of(Aes128::BLOCK_SIZE) {
+            return Err(Error::RecoveryError);
         }
-        assert!(partial_solutions.len() == 1);
-        let mut solution: Vec<u8> = partial_solutions
-            .pop_front()
-            .unwrap()
-            .into();
+        let padding_mode = Pkcs7::new(Aes128::BLOCK_SIZE);
+        let mut verify_padding = |buffer: &[u8]| {
+            let buffer = [random_vec!(Aes128::BLOCK_SIZE), buffer.to_vec()].concat();
+            submit_buffer(&buffer) == Err(oracle::Error::ContentError)
+        };
 
-        let pkcs7 = Pkcs7::new(Aes128::BLOCK_SIZE);
-        let length = pkcs7.unpad_mut(&solution)?;
-        
+        let blocks: Vec<&[u8]> = encrypted_buffer.chunks(Aes128::BLOCK_SIZE).collect();
+        let mut solution = Vec::with_capacity(size - Aes128::BLOCK_SIZE);
+        for blocks in blocks.windows(2) {
+            let intermediate_block = (0..MAX_ATTEMPTS)
+                .find_map(|_| {
+                    get_intermediate_block::<Aes128, _, _>(
+                        blocks[1],
+                        &padding_mode,
+                        &mut verify_padding
+                    ).ok()
+                })
+                .ok_or(Error::RecoveryError)?;
+            solution.extend(bytes::xor_slices(&intermediate_block, blocks[0]));
+        }
+        let length = padding_mode.unpad_mut(&solution)?;
         solution.truncate(length);
         Ok(solution)
     }
 }
 
-pub mod ctr_bitflipping_attacks {
-    use crate::crypto::symmetric;
-    use symmetric::ciphers::{Cipher, Aes128};
+pub mod random_access_read_write {
+    use crate::crypto::symmetric::Error;
+    use crate::utils::bytes;
 
-    #[derive(Debug)]
-    pub enum Error {
-        CipherError,
-        RecoveryError
+    /// Recovers the plaintext given an edit function which takes a ciphertext, an offset, and
+    /// a new plaintext, and replaces the ciphertext at the given offset with the encrypted
+    /// plaintext. Replacing the entire ciphertext with zeroes will return the keystream, which
+    /// we XOR with the original ciphertext.
+    pub fn recover_plaintext<Edit>(ciphertext: &[u8], edit_buffer: &mut Edit) -> Result<Vec<u8>, Error>
+        where Edit: FnMut(&mut [u8], usize, &[u8]) -> Result<(), Error>
+    {
+        let mut keystream = ciphertext.to_vec();
+        edit_buffer(&mut keystream, 0, &vec![0; ciphertext.len()])?;
+        Ok(bytes::xor_slices(ciphertext, &keystream))
     }
 
-    impl From<symmetric::Error> for Error {
-        fn from(_: symmetric::Error) -> Error {
-            Error::CipherError
+    /// Recovers the plaintext as `recover_plaintext`, but only overwrites `block_size` bytes
+    /// of the ciphertext at a time. This works against edit functions which limit the size
+    /// of each edit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::CipherError` if `block_size` is 0.
+    pub fn recover_plaintext_by_block<Edit>(
+        ciphertext: &[u8], 
+        block_size: usize,
+        edit_buffer: &mut Edit
+    ) -> Result<Vec<u8>, Error> where 
+        Edit: FnMut(&mut [u8], usize, &[u8]) -> Result<(), Error>
+    {
+        if block_size == 0 {
+            return Err(Error::CipherError);
+        }
+        let zeroes = vec![0; block_size];
+        let mut keystream = ciphertext.to_vec();
+        for offset in (0..ciphertext.len()).step_by(block_size) {
+            let size = block_size.min(ciphertext.len() - offset);
+            edit_buffer(&mut keystream, offset, &zeroes[..size])?;
         }
+        Ok(bytes::xor_slices(ciphertext, &keystream))
+    }
+
+    /// Recovers the plaintext using only truncate and append operations. Truncating the
+    /// ciphertext to zero bytes and appending zeroes encrypts the zeroes using the keystream
+    /// at the same offsets as the original plaintext, which again returns the keystream.
+    pub fn recover_plaintext_by_append<Truncate, Append>(
+        ciphertext: &[u8],
+        truncate_buffer: &mut Truncate,
+        append_buffer: &mut Append
+    ) -> Result<Vec<u8>, Error> where
+        Truncate: FnMut(&mut Vec<u8>, usize) -> Result<(), Error>,
+        Append: FnMut(&mut Vec<u8>, &[u8]) -> Result<(), Error>
+    {
+        let mut keystream = ciphertext.to_vec();
+        truncate_buffer(&mut keystream, 0)?;
+        append_buffer(&mut keystream, &vec![0; ciphertext.len()])?;
+        Ok(bytes::xor_slices(ciphertext, &keystream))
+    }
+}
+
+pub mod ctr_bitflipping_attacks {
+    use crate::crypto::symmetric;
+    use super::bitflipping::{self, Injection};
+
+    pub use bitflipping::Error;
+
+    /// Determines the size of the data preceding the user data.
+    pub fn get_prefix_size<Oracle>(encrypt_buffer: &mut Oracle) -> Result<usize, Error> where
+        Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
+    {
+        bitflipping::get_prefix_size(encrypt_buffer, Injection::SamePosition)
     }
 
     /// We encrypt a sequence of `A`s of the same size as the target string. We can then simply XOR
     /// each byte of the resulting ciphertext with the difference between the current plaintext
     /// byte (i.e. `A`), and the required target plaintext byte to obtain a ciphertext which
     /// decrypts to the target string.
-    pub fn get_admin_profile<Oracle>(
-        prefix_size: usize,
-        encrypt_buffer: &mut Oracle
-    ) -> Result<Vec<u8>, Error> where
+    pub fn get_admin_profile<Oracle>(encrypt_buffer: &mut Oracle) -> Result<Vec<u8>, Error> where
         Oracle: FnMut(&str) -> Result<Vec<u8>, symmetric::Error>
     {
-        let target_str = ";admin=true;";
-        let user_str = std::iter::repeat("A")
-            .take(target_str.len())
-            .collect::<String>();
-        let mut result = encrypt_buffer(&user_str)?;
-        let offset = prefix_size - (prefix_size % Aes128::BLOCK_SIZE);
-        for (index, byte) in target_str.as_bytes().iter().enumerate() {
-            result[offset + 16 + index] ^= b'A' ^ byte;
-        }
-        Ok(result)
+        bitflipping::inject(encrypt_buffer, Injection::SamePosition, ";admin=true;")
     }
 }
 
 pub mod cbc_with_key_as_iv {
-    use crate::crypto::symmetric::{Aes128, Cipher};
+    use crate::crypto::symmetric::{Aes128, Cipher, PaddingMode, Pkcs7};
+    use crate::utils::bytes;
     use crate::oracles::symmetric::cbc_with_key_as_iv as oracle;
+    use super::cbc_padding_oracle::get_intermediate_block;
 
     #[derive(Debug)]
     pub enum Error {
@@ -428,11 +1263,14 @@ pub mod cbc_with_key_as_iv {
     {
         // Encrypt a plaintext which encrypts to 3 blocks C_0, C_1, C_2.
         let plaintext = "00000000000000001111111111111111";
-        let mut blocks: Vec<Vec<u8>> = encrypt(&plaintext)
+        let mut blocks: Vec<Vec<u8>> = encrypt(plaintext)
             .map_err(Error::from)?
             .chunks(Aes128::BLOCK_SIZE)
             .map(|block| block.to_vec())
             .collect();
+        if blocks.is_empty() {
+            return Err(Error::RecoveryError);
+        }
         
         // Decrypt C_0, 0, C_0 to get P_0, P_1, P_2. 
         let mut ciphertext = blocks[0].clone();
@@ -446,16 +1284,112 @@ pub mod cbc_with_key_as_iv {
             Err(oracle::Error::DecodingError(result)) => Ok(result),
             Err(_) => Err(Error::RecoveryError),
         }?;
+        if plaintext.len() < 3 * Aes128::BLOCK_SIZE {
+            return Err(Error::RecoveryError);
+        }
         let blocks: Vec<Vec<u8>> = plaintext
             .chunks(Aes128::BLOCK_SIZE)
             .map(|block| block.to_vec())
             .collect();
         
         // Now, from the definition of CBC, the key is given by P_0 ^ P_2.
-        let mut key = Vec::new();
-        for i in 0..Aes128::BLOCK_SIZE {
-            key.push(blocks[0][i] ^ blocks[2][i]);
+        Ok(bytes::xor_slices(&blocks[0], &blocks[2]))
+    }
+
+    /// Recovers the key when the oracle only discloses the type of error on decryption. Since
+    /// padding errors are distinguishable from decoding errors, the oracle is a padding oracle.
+    /// We encrypt a single block P_0, which gives C_0 = E(P_0 ^ K), and use the padding oracle
+    /// to recover D(C_0) = P_0 ^ K.
+    pub fn get_key_from_errors<Encrypt, Decrypt>(encrypt: &mut Encrypt, decrypt: &mut Decrypt) -> Result<Vec<u8>, Error>
+        where
+            Encrypt: FnMut(&str) -> Result<Vec<u8>, oracle::Error>,
+            Decrypt: FnMut(&[u8]) -> Result<Vec<u8>, oracle::Error>,
+    {
+        let plaintext = "000000000000000";
+        let ciphertext = encrypt(plaintext).map_err(Error::from)?;
+        if ciphertext.len() < Aes128::BLOCK_SIZE {
+            return Err(Error::RecoveryError);
         }
+        
+        let intermediate_block = get_intermediate_block::<Aes128, _, _>(
+            &ciphertext[..Aes128::BLOCK_SIZE],
+            &Pkcs7::new(Aes128::BLOCK_SIZE),
+            &mut |buffer| !matches!(
+                decrypt(buffer), 
+                Err(oracle::Error::OpaquePaddingError) | Err(oracle::Error::PaddingError(_))
+            )
+        ).map_err(|_| Error::RecoveryError)?;
+
+        // The first plaintext block is the plaintext followed by a single padding byte.
+        let key = plaintext
+            .bytes()
+            .chain(Some(1))
+            .zip(intermediate_block)
+            .map(|(x, y)| x ^ y)
+            .collect();
         Ok(key)
     }
 }
+
+pub mod rc4_single_byte_biases {
+    use crate::crypto::random::Random;
+    use crate::crypto::symmetric::Rc4;
+
+    /// A table of observed keystream byte counts. `table[i][b]` is the number of sampled
+    /// keystreams where the byte at offset `i` is equal to `b`.
+    pub type BiasTable = Vec<[usize; 256]>;
+
+    /// Samples the first `size` bytes of the keystream under `samples` random 128-bit keys,
+    /// and returns the resulting counts for each offset.
+    pub fn get_bias_table(size: usize, samples: usize) -> BiasTable {
+        let mut table = vec![[0; 256]; size];
+        for _ in 0..samples {
+            for (counts, byte) in table.iter_mut().zip(Rc4::random()) {
+                counts[byte as usize] += 1;
+            }
+        }
+        table
+    }
+
+    /// Returns the most likely keystream byte at each offset of the table.
+    pub fn get_most_likely_bytes(table: &[[usize; 256]]) -> Vec<u8> {
+        table.iter()
+            .map(|counts| (0..=255).max_by_key(|&byte| counts[byte as usize]).unwrap_or(0))
+            .collect()
+    }
+}
+
+pub mod mac_then_encrypt {
+    use crate::oracles::symmetric::mac_then_encrypt as oracle;
+    use crate::crypto::symmetric::{Aes128, Pkcs7};
+
+    use super::cbc_padding_oracle::{get_plaintext_buffer_with, Error};
+
+    /// Returns true if the response reveals that the padding of the submitted buffer was
+    /// valid. Since the tag is only checked after the padding, any other response means that
+    /// the padding was accepted.
+    pub fn is_valid_padding<T>(response: &Result<T, oracle::Error>) -> bool {
+        !matches!(response, Err(oracle::Error::PaddingError) | Err(oracle::Error::LengthError))
+    }
+
+    /// Recovers the plaintext of the given buffer (on the form IV || ciphertext) from an
+    /// oracle which distinguishes padding errors from MAC errors. The MAC does not help since
+    /// it is checked after the padding, so this is a regular padding oracle attack. The tag is
+    /// stripped from the recovered plaintext.
+    pub fn get_plaintext_buffer<Oracle, T>(
+        encrypted_buffer: &[u8],
+        decrypt_and_verify: &mut Oracle
+    ) -> Result<Vec<u8>, Error> where
+        Oracle: FnMut(&[u8]) -> Result<T, oracle::Error>
+    {
+        let mut plaintext = get_plaintext_buffer_with::<Aes128, Pkcs7, _>(
+            encrypted_buffer,
+            &mut |buffer| is_valid_padding(&decrypt_and_verify(buffer))
+        )?;
+        if plaintext.len() < oracle::Oracle::TAG_SIZE {
+            return Err(Error::RecoveryError);
+        }
+        plaintext.truncate(plaintext.len() - oracle::Oracle::TAG_SIZE);
+        Ok(plaintext)
+    }
+}
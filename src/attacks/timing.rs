@@ -0,0 +1,110 @@
+/// Set 4, problems 31 and 32. Since the comparison returns early on the first differing byte,
+/// the time taken to verify a signature grows with the length of the correct prefix. We
+/// recover the signature one byte at a time by selecting the candidate byte which takes the
+/// longest to verify.
+pub mod hmac_timing_leak {
+    use std::time::Duration;
+
+    use crate::crypto::hash::{Mac, Sha1Hmac};
+    use crate::oracles::timing::Clock;
+    use crate::progress::{self, CancellationToken, Reporter};
+    use crate::trace::{self, Event};
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Error {
+        RecoveryError,
+        Cancelled,
+    }
+
+    /// Returns the total time taken to verify the signature the given number of times.
+    fn get_timing<C, Oracle>(
+        verify_signature: &mut Oracle,
+        clock: &C,
+        file: &[u8],
+        signature: &[u8],
+        samples: usize
+    ) -> Duration
+        where
+            C: Clock,
+            Oracle: FnMut(&[u8], &[u8]) -> bool
+    {
+        let start = clock.now();
+        for _ in 0..samples {
+            verify_signature(file, signature);
+        }
+        clock.now() - start
+    }
+
+    /// Recovers the HMAC-SHA1 signature of the given file. The clock should be shared with the
+    /// oracle. Each candidate is timed using the given number of samples, which needs to be
+    /// increased when the delay is small compared to the noise.
+    pub fn get_signature<C, Oracle>(
+        verify_signature: Oracle,
+        clock: &C,
+        file: &[u8],
+        samples: usize
+    ) -> Option<Vec<u8>>
+        where
+            C: Clock,
+            Oracle: FnMut(&[u8], &[u8]) -> bool
+    {
+        let token = CancellationToken::new();
+        get_signature_with_progress(verify_signature, clock, file, samples, &progress::Silent, &token).ok()
+    }
+
+    /// Version of `get_signature` which reports the number of recovered bytes and oracle calls
+    /// after each byte, and returns `Error::Cancelled` once the token is cancelled. The token is
+    /// checked before each candidate byte is timed.
+    pub fn get_signature_with_progress<C, Oracle, R>(
+        mut verify_signature: Oracle,
+        clock: &C,
+        file: &[u8],
+        samples: usize,
+        reporter: &R,
+        token: &CancellationToken
+    ) -> Result<Vec<u8>, Error>
+        where
+            C: Clock,
+            Oracle: FnMut(&[u8], &[u8]) -> bool,
+            R: Reporter
+    {
+        let mut signature = vec![0; Sha1Hmac::TAG_SIZE];
+        let mut oracle_calls = 0;
+        for index in 0..signature.len() {
+            let mut best_timing = Duration::from_secs(0);
+            let mut best_byte = 0;
+            for byte in 0..=255 {
+                if token.is_cancelled() {
+                    return Err(Error::Cancelled);
+                }
+                signature[index] = byte;
+                oracle_calls += samples + 1;
+                if verify_signature(file, &signature) {
+                    reporter.report(progress::Event::OracleCalls { count: oracle_calls });
+                    reporter.report(progress::Event::BytesRecovered {
+                        completed: signature.len(),
+                        total: signature.len()
+                    });
+                    return Ok(signature);
+                }
+                let timing = get_timing(&mut verify_signature, clock, file, &signature, samples);
+                if timing > best_timing {
+                    best_timing = timing;
+                    best_byte = byte;
+                }
+            }
+            signature[index] = best_byte;
+            trace::emit(|| Event::Candidate {
+                attack: "hmac_timing_leak",
+                position: index,
+                value: best_byte
+            });
+            reporter.report(progress::Event::OracleCalls { count: oracle_calls });
+            reporter.report(progress::Event::BytesRecovered {
+                completed: index + 1,
+                total: signature.len()
+            });
+        }
+        Err(Error::RecoveryError)
+    }
+}
@@ -0,0 +1,384 @@
+//! Authenticated encryption with associated data. Only full-length tags are accepted. (Oracles
+//! which truncate tags compare prefixes of `compute_tag` themselves.)
+
+use crate::prelude::*;
+#[cfg(feature = "std")]
+use crate::random_vec;
+#[cfg(feature = "std")]
+use crate::crypto::random::Random;
+use crate::crypto::symmetric::Error;
+use crate::utils::bytes;
+
+pub trait Aead: Sized {
+    const KEY_SIZE: usize;
+    const NONCE_SIZE: usize;
+    const TAG_SIZE: usize;
+
+    fn new(key: &[u8]) -> Result<Self, Error>;
+
+    /// XORs the keystream for the given nonce into the buffer.
+    fn apply_keystream(&self, nonce: &[u8], buffer: &mut [u8]) -> Result<(), Error>;
+
+    /// Returns the full-length tag of the given ciphertext and associated data.
+    fn compute_tag(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error>;
+
+    /// Returns the ciphertext together with the full-length tag.
+    fn seal(&self, nonce: &[u8], aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let mut ciphertext = plaintext.to_vec();
+        self.apply_keystream(nonce, &mut ciphertext)?;
+        let tag = self.compute_tag(nonce, aad, &ciphertext)?;
+        Ok((ciphertext, tag))
+    }
+
+    /// Returns true if `tag` is a valid full-length tag for the given ciphertext and
+    /// associated data. Truncated tags are never valid.
+    fn verify(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> bool {
+        if tag.len() != Self::TAG_SIZE {
+            return false;
+        }
+        match self.compute_tag(nonce, aad, ciphertext) {
+            Ok(expected_tag) => bytes::constant_time_eq(&expected_tag, tag),
+            Err(_) => false,
+        }
+    }
+
+    /// Verifies the tag and returns the decrypted ciphertext.
+    fn open(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>, Error> {
+        if !self.verify(nonce, aad, ciphertext, tag) {
+            return Err(Error::AuthenticationError);
+        }
+        let mut plaintext = ciphertext.to_vec();
+        self.apply_keystream(nonce, &mut plaintext)?;
+        Ok(plaintext)
+    }
+}
+
+/// AES-128 in Galois/counter mode (NIST SP 800-38D), restricted to 96-bit nonces. This wraps
+/// `symmetric::Gcm`, which takes the nonce when it is created.
+pub mod gcm {
+    use super::*;
+    use crate::crypto::symmetric::{self, Cipher, Aes128};
+    use crate::math::gf128::Gf128;
+
+    #[derive(Clone, Debug)]
+    pub struct Gcm {
+        cipher: Aes128,
+        hash_key: Gf128,
+    }
+
+    impl Gcm {
+        /// Returns the authentication key H = E(K, 0^128).
+        pub fn hash_key(&self) -> Gf128 { self.hash_key }
+
+        /// Returns GHASH of the associated data and ciphertext, padded and followed by a
+        /// block containing their lengths in bits.
+        pub fn ghash(&self, aad: &[u8], ciphertext: &[u8]) -> Gf128 {
+            symmetric::cipher_modes::ghash(self.hash_key, aad, ciphertext)
+        }
+
+        fn with_nonce(&self, nonce: &[u8]) -> Result<symmetric::Gcm<Aes128>, Error> {
+            if nonce.len() != Self::NONCE_SIZE {
+                return Err(Error::CipherError);
+            }
+            symmetric::Gcm::from_cipher(self.cipher.clone(), nonce)
+        }
+    }
+
+    impl Aead for Gcm {
+        const KEY_SIZE: usize = Aes128::KEY_SIZE;
+        const NONCE_SIZE: usize = 12;
+        const TAG_SIZE: usize = 16;
+
+        fn new(key: &[u8]) -> Result<Self, Error> {
+            let cipher = Aes128::new(key)?;
+            let hash_key = Gf128::from_block(&cipher.encrypt_block(&[0; 16]));
+            Ok(Gcm { cipher, hash_key })
+        }
+
+        fn apply_keystream(&self, nonce: &[u8], buffer: &mut [u8]) -> Result<(), Error> {
+            self.with_nonce(nonce)?.apply_keystream(buffer);
+            Ok(())
+        }
+
+        fn compute_tag(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+            Ok(self.with_nonce(nonce)?.compute_tag(aad, ciphertext))
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Random for Gcm {
+        fn random() -> Self {
+            // It is okay to unwrap here since the key size is known.
+            Gcm::new(&random_vec!(Self::KEY_SIZE)).unwrap()
+        }
+    }
+}
+
+/// ChaCha20-Poly1305 as specified in RFC 8439.
+pub mod chacha20_poly1305 {
+    use core::convert::TryInto;
+
+    use super::*;
+
+    fn read_u32(buffer: &[u8]) -> u32 {
+        // It is okay to unwrap here since the slice has length 4.
+        u32::from_le_bytes(buffer[..4].try_into().unwrap())
+    }
+
+    fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        state[a] = state[a].wrapping_add(state[b]); state[d] = (state[d] ^ state[a]).rotate_left(16);
+        state[c] = state[c].wrapping_add(state[d]); state[b] = (state[b] ^ state[c]).rotate_left(12);
+        state[a] = state[a].wrapping_add(state[b]); state[d] = (state[d] ^ state[a]).rotate_left(8);
+        state[c] = state[c].wrapping_add(state[d]); state[b] = (state[b] ^ state[c]).rotate_left(7);
+    }
+
+    /// Returns the ChaCha20 block for the given key, counter and nonce.
+    pub fn chacha20_block(key: &[u8], counter: u32, nonce: &[u8]) -> [u8; 64] {
+        let mut input = [0; 16];
+        input[..4].copy_from_slice(&[0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574]);
+        for (word, chunk) in input[4..12].iter_mut().zip(key.chunks(4)) {
+            *word = read_u32(chunk);
+        }
+        input[12] = counter;
+        for (word, chunk) in input[13..].iter_mut().zip(nonce.chunks(4)) {
+            *word = read_u32(chunk);
+        }
+        let mut state = input;
+        for _ in 0..10 {
+            quarter_round(&mut state, 0, 4, 8, 12);
+            quarter_round(&mut state, 1, 5, 9, 13);
+            quarter_round(&mut state, 2, 6, 10, 14);
+            quarter_round(&mut state, 3, 7, 11, 15);
+            quarter_round(&mut state, 0, 5, 10, 15);
+            quarter_round(&mut state, 1, 6, 11, 12);
+            quarter_round(&mut state, 2, 7, 8, 13);
+            quarter_round(&mut state, 3, 4, 9, 14);
+        }
+        let mut block = [0; 64];
+        for (index, chunk) in block.chunks_mut(4).enumerate() {
+            chunk.copy_from_slice(&state[index].wrapping_add(input[index]).to_le_bytes());
+        }
+        block
+    }
+
+    /// Returns the Poly1305 tag of the message under the given 32-byte one-time key. The
+    /// accumulator is represented using five 26-bit limbs.
+    pub fn poly1305(key: &[u8], message: &[u8]) -> [u8; 16] {
+        const MASK: u64 = 0x3ff_ffff;
+        let r = [
+            (read_u32(&key[0..]) & 0x3ff_ffff) as u64,
+            ((read_u32(&key[3..]) >> 2) & 0x3ff_ff03) as u64,
+            ((read_u32(&key[6..]) >> 4) & 0x3ff_c0ff) as u64,
+            ((read_u32(&key[9..]) >> 6) & 0x3f0_3fff) as u64,
+            ((read_u32(&key[12..]) >> 8) & 0x00f_ffff) as u64,
+        ];
+        let s = [r[1] * 5, r[2] * 5, r[3] * 5, r[4] * 5];
+        let mut h = [0u64; 5];
+
+        for chunk in message.chunks(16) {
+            // Partial blocks are padded with a one byte followed by zeroes, while full blocks
+            // have the one bit set at 2^128.
+            let mut block = [0; 17];
+            block[..chunk.len()].copy_from_slice(chunk);
+            block[chunk.len()] = 1;
+            let high_bit = if chunk.len() == 16 { 1 << 24 } else { 0 };
+
+            h[0] += (read_u32(&block[0..]) & 0x3ff_ffff) as u64;
+            h[1] += ((read_u32(&block[3..]) >> 2) & 0x3ff_ffff) as u64;
+            h[2] += ((read_u32(&block[6..]) >> 4) & 0x3ff_ffff) as u64;
+            h[3] += ((read_u32(&block[9..]) >> 6) & 0x3ff_ffff) as u64;
+            h[4] += ((read_u32(&block[12..]) >> 8) as u64) | high_bit;
+
+            let d = [
+                h[0] * r[0] + h[1] * s[3] + h[2] * s[2] + h[3] * s[1] + h[4] * s[0],
+                h[0] * r[1] + h[1] * r[0] + h[2] * s[3] + h[3] * s[2] + h[4] * s[1],
+                h[0] * r[2] + h[1] * r[1] + h[2] * r[0] + h[3] * s[3] + h[4] * s[2],
+                h[0] * r[3] + h[1] * r[2] + h[2] * r[1] + h[3] * r[0] + h[4] * s[3],
+                h[0] * r[4] + h[1] * r[3] + h[2] * r[2] + h[3] * r[1] + h[4] * r[0],
+            ];
+            let mut carry = 0;
+            for index in 0..5 {
+                let limb = d[index] + carry;
+                h[index] = limb & MASK;
+                carry = limb >> 26;
+            }
+            h[0] += carry * 5;
+            h[1] += h[0] >> 26;
+            h[0] &= MASK;
+        }
+
+        // Fully reduce the accumulator modulo 2^130 - 5.
+        let mut carry = 0;
+        for limb in h.iter_mut().skip(1) {
+            *limb += carry;
+            carry = *limb >> 26;
+            *limb &= MASK;
+        }
+        h[0] += carry * 5;
+        h[1] += h[0] >> 26;
+        h[0] &= MASK;
+
+        // Compute h + 5 - 2^130, and select it if it is non-negative.
+        let mut g = [0u64; 5];
+        let mut carry = 5;
+        for index in 0..5 {
+            let limb = h[index] + carry;
+            g[index] = limb & MASK;
+            carry = limb >> 26;
+        }
+        if carry > 0 {
+            h = g;
+        }
+
+        let accumulator = h.iter()
+            .enumerate()
+            .fold(0u128, |accumulator, (index, limb)| accumulator | ((*limb as u128) << (26 * index)));
+        let s = u128::from_le_bytes(key[16..32].try_into().unwrap());
+        accumulator.wrapping_add(s).to_le_bytes()
+    }
+
+    fn pad16(buffer: &[u8]) -> Vec<u8> {
+        vec![0; (16 - buffer.len() % 16) % 16]
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct ChaCha20Poly1305 {
+        key: Vec<u8>,
+    }
+
+    impl ChaCha20Poly1305 {
+        /// Returns the one-time Poly1305 key used for the given nonce.
+        pub fn one_time_key(&self, nonce: &[u8]) -> Vec<u8> {
+            chacha20_block(&self.key, 0, nonce)[..32].to_vec()
+        }
+    }
+
+    impl Aead for ChaCha20Poly1305 {
+        const KEY_SIZE: usize = 32;
+        const NONCE_SIZE: usize = 12;
+        const TAG_SIZE: usize = 16;
+
+        fn new(key: &[u8]) -> Result<Self, Error> {
+            if key.len() != Self::KEY_SIZE {
+                return Err(Error::CipherError);
+            }
+            Ok(ChaCha20Poly1305 { key: key.to_vec() })
+        }
+
+        fn apply_keystream(&self, nonce: &[u8], buffer: &mut [u8]) -> Result<(), Error> {
+            if nonce.len() != Self::NONCE_SIZE {
+                return Err(Error::CipherError);
+            }
+            // The first block is reserved for the one-time Poly1305 key.
+            for (index, chunk) in buffer.chunks_mut(64).enumerate() {
+                let keystream = chacha20_block(&self.key, index as u32 + 1, nonce);
+                chunk.iter_mut().zip(keystream.iter()).for_each(|(x, y)| *x ^= y);
+            }
+            Ok(())
+        }
+
+        fn compute_tag(&self, nonce: &[u8], aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+            if nonce.len() != Self::NONCE_SIZE {
+                return Err(Error::CipherError);
+            }
+            let message = [
+                aad,
+                &pad16(aad),
+                ciphertext,
+                &pad16(ciphertext),
+                &(aad.len() as u64).to_le_bytes(),
+                &(ciphertext.len() as u64).to_le_bytes(),
+            ].concat();
+            Ok(poly1305(&self.one_time_key(nonce), &message).to_vec())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Random for ChaCha20Poly1305 {
+        fn random() -> Self {
+            ChaCha20Poly1305 { key: random_vec!(Self::KEY_SIZE) }
+        }
+    }
+}
+
+pub use gcm::Gcm;
+pub use chacha20_poly1305::ChaCha20Poly1305;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    fn decode(input: &str) -> Vec<u8> {
+        hex::decode(input).unwrap()
+    }
+
+    #[test]
+    fn gcm_test_vectors() {
+        let cipher = Gcm::new(&[0; 16]).unwrap();
+        let (ciphertext, tag) = cipher.seal(&[0; 12], &[], &[0; 16]).unwrap();
+        assert_eq!(ciphertext, decode("0388dace60b6a392f328c2b971b2fe78"));
+        assert_eq!(tag, decode("ab6e47d42cec13bdf53a67b21257bddf"));
+
+        let cipher = Gcm::new(&decode("feffe9928665731c6d6a8f9467308308")).unwrap();
+        let nonce = decode("cafebabefacedbaddecaf888");
+        let aad = decode("feedfacedeadbeeffeedfacedeadbeefabaddad2");
+        let plaintext = decode(
+            "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+             1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39"
+        );
+        let (ciphertext, tag) = cipher.seal(&nonce, &aad, &plaintext).unwrap();
+        assert_eq!(ciphertext, decode(
+            "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+             21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091"
+        ));
+        assert_eq!(tag, decode("5bc94fbc3221a5db94fae95ae7121a47"));
+        assert_eq!(cipher.open(&nonce, &aad, &ciphertext, &tag).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn poly1305_test_vector() {
+        let key = decode("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b");
+        let tag = chacha20_poly1305::poly1305(&key, b"Cryptographic Forum Research Group");
+        assert_eq!(tag.to_vec(), decode("a8061dc1305136c6c22b8baf0c0127a9"));
+    }
+
+    #[test]
+    fn chacha20_poly1305_test_vector() {
+        let key: Vec<u8> = (0x80..0xa0).collect();
+        let cipher = ChaCha20Poly1305::new(&key).unwrap();
+        let nonce = decode("070000004041424344454647");
+        let aad = decode("50515253c0c1c2c3c4c5c6c7");
+        let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only \
+            one tip for the future, sunscreen would be it.";
+        let (ciphertext, tag) = cipher.seal(&nonce, &aad, plaintext).unwrap();
+        assert_eq!(ciphertext, decode(
+            "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+             3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+             92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+             3ff4def08e4b7a9de576d26586cec64b6116"
+        ));
+        assert_eq!(tag, decode("1ae10b594f09e26a7e902ecbd0600691"));
+        assert_eq!(cipher.open(&nonce, &aad, &ciphertext, &tag).unwrap(), plaintext.to_vec());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn full_length_tags() {
+        let cipher = Gcm::random();
+        let nonce = [0; 12];
+        let (ciphertext, tag) = cipher.seal(&nonce, &[], b"YELLOW SUBMARINE").unwrap();
+        assert!(cipher.verify(&nonce, &[], &ciphertext, &tag));
+        assert!(!cipher.verify(&nonce, &[], &ciphertext, &tag[..4]));
+        assert!(!cipher.verify(&nonce, &[], &ciphertext, &[]));
+        assert!(!cipher.verify(&nonce, b"aad", &ciphertext, &tag));
+        assert_eq!(
+            cipher.open(&nonce, &[], &ciphertext[1..], &tag),
+            Err(Error::AuthenticationError)
+        );
+        assert_eq!(
+            cipher.open(&nonce, &[], &ciphertext, &tag[..15]),
+            Err(Error::AuthenticationError)
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! A portable, byte-oriented implementation of the AES block function (FIPS 197). This is used
+//! by `Aes128`, `Aes192` and `Aes256` when the `openssl` feature is disabled, e.g. on `no_std` and WASM
+//! targets. The interface mirrors the OpenSSL bindings.
+//!
+//! # Note
+//!
+//! The implementation uses table lookups indexed by secret data and is not constant-time.
+
+use super::symmetric::Error;
+
+pub const AES_BLOCK_SIZE: usize = 16;
+
+const MAX_ROUNDS: usize = 14;
+
+const ROUND_CONSTANTS: [u8; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+const INV_SBOX: [u8; 256] = [
+    0x52, 0x09, 0x6a, 0xd5, 0x30, 0x36, 0xa5, 0x38, 0xbf, 0x40, 0xa3, 0x9e, 0x81, 0xf3, 0xd7, 0xfb,
+    0x7c, 0xe3, 0x39, 0x82, 0x9b, 0x2f, 0xff, 0x87, 0x34, 0x8e, 0x43, 0x44, 0xc4, 0xde, 0xe9, 0xcb,
+    0x54, 0x7b, 0x94, 0x32, 0xa6, 0xc2, 0x23, 0x3d, 0xee, 0x4c, 0x95, 0x0b, 0x42, 0xfa, 0xc3, 0x4e,
+    0x08, 0x2e, 0xa1, 0x66, 0x28, 0xd9, 0x24, 0xb2, 0x76, 0x5b, 0xa2, 0x49, 0x6d, 0x8b, 0xd1, 0x25,
+    0x72, 0xf8, 0xf6, 0x64, 0x86, 0x68, 0x98, 0x16, 0xd4, 0xa4, 0x5c, 0xcc, 0x5d, 0x65, 0xb6, 0x92,
+    0x6c, 0x70, 0x48, 0x50, 0xfd, 0xed, 0xb9, 0xda, 0x5e, 0x15, 0x46, 0x57, 0xa7, 0x8d, 0x9d, 0x84,
+    0x90, 0xd8, 0xab, 0x00, 0x8c, 0xbc, 0xd3, 0x0a, 0xf7, 0xe4, 0x58, 0x05, 0xb8, 0xb3, 0x45, 0x06,
+    0xd0, 0x2c, 0x1e, 0x8f, 0xca, 0x3f, 0x0f, 0x02, 0xc1, 0xaf, 0xbd, 0x03, 0x01, 0x13, 0x8a, 0x6b,
+    0x3a, 0x91, 0x11, 0x41, 0x4f, 0x67, 0xdc, 0xea, 0x97, 0xf2, 0xcf, 0xce, 0xf0, 0xb4, 0xe6, 0x73,
+    0x96, 0xac, 0x74, 0x22, 0xe7, 0xad, 0x35, 0x85, 0xe2, 0xf9, 0x37, 0xe8, 0x1c, 0x75, 0xdf, 0x6e,
+    0x47, 0xf1, 0x1a, 0x71, 0x1d, 0x29, 0xc5, 0x89, 0x6f, 0xb7, 0x62, 0x0e, 0xaa, 0x18, 0xbe, 0x1b,
+    0xfc, 0x56, 0x3e, 0x4b, 0xc6, 0xd2, 0x79, 0x20, 0x9a, 0xdb, 0xc0, 0xfe, 0x78, 0xcd, 0x5a, 0xf4,
+    0x1f, 0xdd, 0xa8, 0x33, 0x88, 0x07, 0xc7, 0x31, 0xb1, 0x12, 0x10, 0x59, 0x27, 0x80, 0xec, 0x5f,
+    0x60, 0x51, 0x7f, 0xa9, 0x19, 0xb5, 0x4a, 0x0d, 0x2d, 0xe5, 0x7a, 0x9f, 0x93, 0xc9, 0x9c, 0xef,
+    0xa0, 0xe0, 0x3b, 0x4d, 0xae, 0x2a, 0xf5, 0xb0, 0xc8, 0xeb, 0xbb, 0x3c, 0x83, 0x53, 0x99, 0x61,
+    0x17, 0x2b, 0x04, 0x7e, 0xba, 0x77, 0xd6, 0x26, 0xe1, 0x69, 0x14, 0x63, 0x55, 0x21, 0x0c, 0x7d,
+];
+
+/// Expanded round keys for AES-128, AES-192 or AES-256.
+#[derive(Clone, Debug)]
+pub struct AesKey {
+    round_keys: [[u8; AES_BLOCK_SIZE]; MAX_ROUNDS + 1],
+    rounds: usize,
+}
+
+impl AesKey {
+    pub fn new_encrypt_key(raw_key: &[u8]) -> Result<Self, Error> {
+        Self::expand_key(raw_key)
+    }
+
+    /// The inverse cipher uses the same round keys (in reverse order) as the cipher.
+    pub fn new_decrypt_key(raw_key: &[u8]) -> Result<Self, Error> {
+        Self::expand_key(raw_key)
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    fn expand_key(raw_key: &[u8]) -> Result<Self, Error> {
+        if raw_key.len() != 16 && raw_key.len() != 24 && raw_key.len() != 32 {
+            return Err(Error::CipherError);
+        }
+        let key_words = raw_key.len() / 4;
+        let rounds = key_words + 6;
+
+        let mut words = [[0; 4]; 4 * (MAX_ROUNDS + 1)];
+        for (word, bytes) in words.iter_mut().zip(raw_key.chunks_exact(4)) {
+            word.copy_from_slice(bytes);
+        }
+        for i in key_words..4 * (rounds + 1) {
+            let mut temp = words[i - 1];
+            if i % key_words == 0 {
+                temp.rotate_left(1);
+                temp.iter_mut().for_each(|byte| *byte = SBOX[*byte as usize]);
+                temp[0] ^= ROUND_CONSTANTS[i / key_words - 1];
+            } else if key_words > 6 && i % key_words == 4 {
+                temp.iter_mut().for_each(|byte| *byte = SBOX[*byte as usize]);
+            }
+            for j in 0..4 {
+                words[i][j] = words[i - key_words][j] ^ temp[j];
+            }
+        }
+
+        let mut round_keys = [[0; AES_BLOCK_SIZE]; MAX_ROUNDS + 1];
+        for (round_key, words) in round_keys.iter_mut().zip(words.chunks_exact(4)) {
+            for (bytes, word) in round_key.chunks_exact_mut(4).zip(words) {
+                bytes.copy_from_slice(word);
+            }
+        }
+        Ok(AesKey { round_keys, rounds })
+    }
+}
+
+/// Multiplies the element by x in GF(2^8).
+#[inline(always)]
+fn xtime(x: u8) -> u8 {
+    (x << 1) ^ if x & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+#[inline(always)]
+fn multiply(mut x: u8, mut y: u8) -> u8 {
+    let mut result = 0;
+    while y != 0 {
+        if y & 1 != 0 {
+            result ^= x;
+        }
+        x = xtime(x);
+        y >>= 1;
+    }
+    result
+}
+
+fn add_round_key(state: &mut [u8], round_key: &[u8; AES_BLOCK_SIZE]) {
+    state.iter_mut().zip(round_key).for_each(|(x, y)| *x ^= y);
+}
+
+fn substitute_bytes(state: &mut [u8], sbox: &[u8; 256]) {
+    state.iter_mut().for_each(|byte| *byte = sbox[*byte as usize]);
+}
+
+// The state is stored in column-major order, so the byte at row `r` and column `c` is
+// stored at index `r + 4 * c`. Row `r` is rotated left by `r` positions.
+fn shift_rows(state: &mut [u8]) {
+    let original: [u8; AES_BLOCK_SIZE] = [
+        state[0], state[1], state[2], state[3], state[4], state[5], state[6], state[7],
+        state[8], state[9], state[10], state[11], state[12], state[13], state[14], state[15],
+    ];
+    for row in 1..4 {
+        for column in 0..4 {
+            state[row + 4 * column] = original[row + 4 * ((column + row) % 4)];
+        }
+    }
+}
+
+fn inverse_shift_rows(state: &mut [u8]) {
+    let original: [u8; AES_BLOCK_SIZE] = [
+        state[0], state[1], state[2], state[3], state[4], state[5], state[6], state[7],
+        state[8], state[9], state[10], state[11], state[12], state[13], state[14], state[15],
+    ];
+    for row in 1..4 {
+        for column in 0..4 {
+            state[row + 4 * ((column + row) % 4)] = original[row + 4 * column];
+        }
+    }
+}
+
+fn mix_columns(state: &mut [u8], coefficients: [u8; 4]) {
+    for column in state.chunks_exact_mut(4) {
+        let original = [column[0], column[1], column[2], column[3]];
+        for (row, byte) in column.iter_mut().enumerate() {
+            *byte = (0..4).fold(0, |sum, i| sum ^ multiply(coefficients[(4 + i - row) % 4], original[i]));
+        }
+    }
+}
+
+/// Encrypts the first `AES_BLOCK_SIZE` bytes of the block in place.
+///
+/// # Panics
+///
+/// Panics if the block is smaller than `AES_BLOCK_SIZE`.
+pub fn encrypt_mut<'a>(block: &'a mut [u8], key: &AesKey) -> &'a [u8] {
+    let state = &mut block[..AES_BLOCK_SIZE];
+    add_round_key(state, &key.round_keys[0]);
+    for round in 1..=key.rounds {
+        substitute_bytes(state, &SBOX);
+        shift_rows(state);
+        if round != key.rounds {
+            mix_columns(state, [0x02, 0x03, 0x01, 0x01]);
+        }
+        add_round_key(state, &key.round_keys[round]);
+    }
+    block
+}
+
+/// Decrypts the first `AES_BLOCK_SIZE` bytes of the block in place.
+///
+/// # Panics
+///
+/// Panics if the block is smaller than `AES_BLOCK_SIZE`.
+pub fn decrypt_mut<'a>(block: &'a mut [u8], key: &AesKey) -> &'a [u8] {
+    let state = &mut block[..AES_BLOCK_SIZE];
+    for round in (1..=key.rounds).rev() {
+        add_round_key(state, &key.round_keys[round]);
+        if round != key.rounds {
+            mix_columns(state, [0x0e, 0x0b, 0x0d, 0x09]);
+        }
+        inverse_shift_rows(state);
+        substitute_bytes(state, &INV_SBOX);
+    }
+    add_round_key(state, &key.round_keys[0]);
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encoding::hex;
+
+    // The example vectors from appendix C of FIPS 197.
+    const PLAINTEXT: &str = "00112233445566778899aabbccddeeff";
+    const TEST_VECTORS: [(&str, &str); 3] = [
+        ("000102030405060708090a0b0c0d0e0f", "69c4e0d86a7b0430d8cdb78070b4c55a"),
+        ("000102030405060708090a0b0c0d0e0f1011121314151617", "dda97ca4864cdfe06eaf70a0ec0d7191"),
+        ("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f", "8ea2b7ca516745bfeafc49904b496089"),
+    ];
+
+    #[test]
+    fn known_output() {
+        for (raw_key, ciphertext) in TEST_VECTORS.iter() {
+            let raw_key = hex::decode(raw_key).unwrap();
+            let mut block = hex::decode(PLAINTEXT).unwrap();
+            encrypt_mut(&mut block, &AesKey::new_encrypt_key(&raw_key).unwrap());
+            assert_eq!(hex::encode(&block), *ciphertext);
+            decrypt_mut(&mut block, &AesKey::new_decrypt_key(&raw_key).unwrap());
+            assert_eq!(hex::encode(&block), PLAINTEXT);
+        }
+        assert_eq!(AesKey::new_encrypt_key(&[0; 32]).unwrap().rounds(), 14);
+        assert!(AesKey::new_encrypt_key(&[0; 20]).is_err());
+    }
+
+    #[cfg(all(feature = "openssl", not(target_arch = "wasm32")))]
+    #[test]
+    fn identical_to_openssl() {
+        use crate::crypto::openssl::aes;
+        use crate::random_vec;
+
+        for &key_size in &[16, 24, 32] {
+            let raw_key = random_vec!(key_size);
+            let block = random_vec!(AES_BLOCK_SIZE);
+            let mut expected = block.clone();
+            aes::encrypt_mut(&mut expected, &aes::AES_KEY::new_encrypt_key(&raw_key).unwrap());
+            let mut actual = block.clone();
+            encrypt_mut(&mut actual, &AesKey::new_encrypt_key(&raw_key).unwrap());
+            assert_eq!(actual, expected);
+
+            aes::decrypt_mut(&mut expected, &aes::AES_KEY::new_decrypt_key(&raw_key).unwrap());
+            decrypt_mut(&mut actual, &AesKey::new_decrypt_key(&raw_key).unwrap());
+            assert_eq!(actual, expected);
+            assert_eq!(actual, block);
+        }
+    }
+}
@@ -1,7 +1,15 @@
-use std::fmt;
-use std::convert::AsRef;
-use std::num::Wrapping;
-use std::convert::TryInto;
+use core::fmt;
+use core::convert::AsRef;
+use core::num::Wrapping;
+use core::convert::TryInto;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
+use crate::prelude::*;
+use crate::encoding::hex;
+use crate::crypto::symmetric::Aes128;
+#[cfg(feature = "std")]
+use crate::crypto::io::HashingReader;
 
 
 type W32 = Wrapping<u32>;
@@ -43,6 +51,11 @@ impl MessageDigest {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
  
     pub fn to_str(&self) -> String {
         hex::encode(&self.0)
@@ -61,10 +74,38 @@ impl fmt::Display for MessageDigest {
     }
 }
 
+/// Digests are serialized as hex strings.
+#[cfg(feature = "serde")]
+mod serialization {
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::prelude::*;
+    use crate::encoding::hex;
+    use super::MessageDigest;
+
+    impl Serialize for MessageDigest {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.to_str())
+        }
+    }
+
+    impl<'de> Deserialize<'de> for MessageDigest {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let digest = String::deserialize(deserializer)?;
+            hex::decode(digest)
+                .map(MessageDigest)
+                .map_err(|_| de::Error::custom("invalid hex digest"))
+        }
+    }
+}
+
 pub trait HashFunction where Self: Sized {
     /// The output size.
     const DIGEST_SIZE: usize;
 
+    /// The size of the chunks processed by the compression function.
+    const BLOCK_SIZE: usize;
+
     fn new() -> Self;
 
     /// Hash the given buffer. Returns `self`.
@@ -73,12 +114,53 @@ pub trait HashFunction where Self: Sized {
     /// Should return a `MessageDigest` of length `Self::DIGEST_SIZE`.
     fn finalize(&mut self) -> MessageDigest;
 
+    /// Returns the padding appended by `finalize` to a message of the given size.
+    fn padding(message_size: usize) -> Vec<u8>;
+
+    /// Returns the current state of the compression function, encoded in the same way as the
+    /// digest. Any cached partial chunk is not included.
+    fn state(&self) -> Vec<u8>;
+
+    /// Returns the hash function in the state reached after processing a message of the given
+    /// size together with its padding, where `digest` is the digest of the message. Further
+    /// updates then hash the message, the padding and the new data. This is what makes hash
+    /// functions based on the Merkle-Damgard construction vulnerable to length extension.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the digest is not `Self::DIGEST_SIZE` bytes long.
+    fn from_digest(digest: &MessageDigest, message_size: usize) -> Self;
+
     /// Returns the digest of the given buffer.
     fn digest<B: AsRef<[u8]>>(buffer: B) -> MessageDigest {
         Self::new()
             .update(buffer.as_ref())
             .finalize()
     }
+
+    /// Returns the digest of the data read from the given reader. The input is hashed as it is
+    /// read, so arbitrarily large inputs may be hashed in constant memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the underlying reader fails.
+    #[cfg(feature = "std")]
+    fn digest_reader<R: Read>(reader: R) -> io::Result<MessageDigest> {
+        let mut reader = HashingReader::<R, Self>::new(reader);
+        io::copy(&mut reader, &mut io::sink())?;
+        Ok(reader.finalize())
+    }
+}
+
+/// Returns the Merkle-Damgard padding used by MD4, SHA-1 and SHA-256: a single 0x80 byte,
+/// followed by zeroes and the encoded message size (in bits), so that the total size is a
+/// multiple of the block size.
+fn merkle_damgard_padding(message_size: usize, block_size: usize, encoded_size: [u8; 8]) -> Vec<u8> {
+    let zero_size = (2 * block_size - (message_size + 9) % block_size) % block_size;
+    let mut padding = vec![0; 1 + zero_size];
+    padding[0] = 0x80;
+    padding.extend_from_slice(&encoded_size);
+    padding
 }
 
 pub trait Mac where Self: Sized {
@@ -101,11 +183,14 @@ pub trait Mac where Self: Sized {
 }
 
 pub mod sha {
-    use std::cmp;
-    use std::num::Wrapping;
-    use std::convert::TryInto;
+    use core::cmp;
+    use core::num::Wrapping;
+    use core::ops::Range;
+
+    use crate::prelude::*;
+    use core::convert::TryInto;
 
-    use super::{W32, WrappingExt, HashFunction, MessageDigest};
+    use super::{W32, WrappingExt, HashFunction, MessageDigest, merkle_damgard_padding};
 
     /// A byte oriented implementation of the SHA-1 hash function.
     pub struct Sha1 {
@@ -150,65 +235,75 @@ pub mod sha {
             (x & y) | (x & z) | (y & z) 
         }
         
+        /// Returns the message schedule word for the given round. Only the previous 16 words
+        /// of the schedule are needed, so the schedule is kept in a rolling window where word
+        /// `i` is stored at index `i % 16`.
+        #[inline(always)]
+        fn schedule(words: &mut [W32; 16], round: usize) -> W32 {
+            if round >= 16 {
+                words[round % 16] = (
+                    words[(round - 3) % 16] ^
+                    words[(round - 8) % 16] ^
+                    words[(round - 14) % 16] ^
+                    words[round % 16]
+                ).left_rotate(1);
+            }
+            words[round % 16]
+        }
+
         #[inline(always)]
-        fn process_state(
-            mut a: W32,
-            mut b: W32,
-            mut c: W32,
-            mut d: W32,
-            mut e: W32,
+        fn process_rounds(
+            state: &mut [W32; 5],
+            words: &mut [W32; 16],
+            rounds: Range<usize>,
             k: W32,
-            words: &[W32; 20],
             f: impl Fn(W32, W32, W32) -> W32
-            ) -> (W32, W32, W32, W32, W32) {
-            for word in words {
-                let temp = a.left_rotate(5) + f(b, c, d) + e + k + word;
+        ) {
+            let [mut a, mut b, mut c, mut d, mut e] = *state;
+            for round in rounds {
+                let temp = a.left_rotate(5) + f(b, c, d) + e + k + Sha1::schedule(words, round);
                 e = d;
                 d = c;
                 c = b.left_rotate(30);
                 b = a;
-                a = temp; 
+                a = temp;
             }
-            (a, b, c, d, e)
+            *state = [a, b, c, d, e];
         }
-    
+
         fn process_chunk(state: &mut [W32; 5], chunk: &[u8]) {
-            let mut words: [W32; Sha1::NOF_ROUNDS] = [Wrapping(0); Sha1::NOF_ROUNDS];
-            for i in 0..16 {
-                words[i] = W32::from_be_bytes(&chunk[4 * i .. 4 * i + 4]);
+            let mut words = [Wrapping(0); 16];
+            for (word, bytes) in words.iter_mut().zip(chunk.chunks_exact(4)) {
+                *word = W32::from_be_bytes(bytes);
             }
-            for i in 16..Sha1::NOF_ROUNDS {
-                words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).left_rotate(1);
-            }
-            let a = state[0];
-            let b = state[1];
-            let c = state[2];
-            let d = state[3];
-            let e = state[4];
+            let mut working_state = *state;
+            Sha1::process_rounds(&mut working_state, &mut words, 0..20, Wrapping(0x5a82_7999), Sha1::choose);
+            Sha1::process_rounds(&mut working_state, &mut words, 20..40, Wrapping(0x6ed9_eba1), Sha1::parity);
+            Sha1::process_rounds(&mut working_state, &mut words, 40..60, Wrapping(0x8f1b_bcdc), Sha1::majority);
+            Sha1::process_rounds(&mut working_state, &mut words, 60..Sha1::NOF_ROUNDS, Wrapping(0xca62_c1d6), Sha1::parity);
 
-            let (a, b, c, d, e) = Sha1::process_state(
-                a, b, c, d, e, Wrapping(0x5a82_7999), words[0 ..20].try_into().unwrap(), Sha1::choose
-            );
-            let (a, b, c, d, e) = Sha1::process_state(
-                a, b, c, d, e, Wrapping(0x6ed9_eba1), words[20..40].try_into().unwrap(), Sha1::parity
-            );
-            let (a, b, c, d, e) = Sha1::process_state(
-                a, b, c, d, e, Wrapping(0x8f1b_bcdc), words[40..60].try_into().unwrap(), Sha1::majority
-            );
-            let (a, b, c, d, e) = Sha1::process_state(
-                a, b, c, d, e, Wrapping(0xca62_c1d6), words[60..80].try_into().unwrap(), Sha1::parity
-            );
+            for (word, value) in state.iter_mut().zip(&working_state) {
+                *word += value;
+            }
+        }
 
-            state[0] += a;
-            state[1] += b;
-            state[2] += c;
-            state[3] += d;
-            state[4] += e;
+        /// Processes each complete chunk of the buffer and returns the number of bytes
+        /// processed. The state is kept in a local copy across chunks.
+        fn process_chunks(state: &mut [W32; 5], buffer: &[u8]) -> usize {
+            let mut local_state = *state;
+            let chunks = buffer.chunks_exact(Sha1::CHUNK_SIZE);
+            let size = buffer.len() - chunks.remainder().len();
+            for chunk in chunks {
+                Sha1::process_chunk(&mut local_state, chunk);
+            }
+            *state = local_state;
+            size
         }
     }
 
     impl HashFunction for Sha1 {
         const DIGEST_SIZE: usize = 20;
+        const BLOCK_SIZE: usize = Sha1::CHUNK_SIZE;
 
         fn new() -> Self {
             Self::from_state(&[
@@ -231,15 +326,13 @@ pub mod sha {
                 buffer_offset = copy_size;
             }
             if self.chunk_size == Sha1::CHUNK_SIZE {
-                Sha1::process_chunk(&mut self.state, &self.chunk.clone());
+                let chunk = self.chunk;
+                Sha1::process_chunk(&mut self.state, &chunk);
                 self.chunk_size = 0;
             }
             
-            // Process input buffer, one chunk at a time.
-            for chunk in buffer[buffer_offset..].chunks_exact(Sha1::CHUNK_SIZE) {
-                Sha1::process_chunk(&mut self.state, &chunk);
-                buffer_offset += Sha1::CHUNK_SIZE;
-            }
+            // Process all complete chunks in the input buffer at once.
+            buffer_offset += Sha1::process_chunks(&mut self.state, &buffer[buffer_offset..]);
             
             // Cache remaining partial chunk.
             if buffer_offset < buffer.len() {
@@ -253,29 +346,36 @@ pub mod sha {
         }
 
         fn finalize(&mut self) -> MessageDigest {
-            // Append padding and total message size (int bits) to the end of the input, ensuring
+            // Append padding and total message size (in bits) to the end of the input, ensuring
             // that the total input size is 0 modulo 64.
-            let reduced_size = self.message_size % Sha1::CHUNK_SIZE;
-            
-            // Ensure that we have enough space for the first 0x80 byte and the message size.
-            let padding_size = if (reduced_size + 9) < Sha1::CHUNK_SIZE { 
-                Sha1::CHUNK_SIZE - reduced_size
-            } else { 
-                2 * Sha1::CHUNK_SIZE - reduced_size
-            };
-            let mut padding = vec![0; padding_size];
-            padding[0] = 0x80;
-            padding[padding_size - 8 ..].copy_from_slice(&(8 * self.message_size as u64).to_be_bytes());
-            
+            let padding = Self::padding(self.message_size);
             self.update(&padding);
             assert!(self.chunk_size == 0);
-        
-            // Produce the final hash value by concatenating the state (as big endian integers).
-            let mut digest = vec![0; Self::DIGEST_SIZE];
-            for (i, word) in self.state.iter().enumerate() {
-                digest[4 * i .. 4 * i + 4].copy_from_slice(&word.to_be_bytes());
+            MessageDigest(self.state())
+        }
+
+        fn padding(message_size: usize) -> Vec<u8> {
+            merkle_damgard_padding(message_size, Sha1::CHUNK_SIZE, (8 * message_size as u64).to_be_bytes())
+        }
+
+        fn state(&self) -> Vec<u8> {
+            // The state is encoded as big endian integers.
+            let mut state = vec![0; Self::DIGEST_SIZE];
+            for (bytes, word) in state.chunks_exact_mut(4).zip(self.state.iter()) {
+                bytes.copy_from_slice(&word.to_be_bytes());
             }
-            MessageDigest(digest)
+            state
+        }
+
+        fn from_digest(digest: &MessageDigest, message_size: usize) -> Self {
+            assert_eq!(digest.len(), Self::DIGEST_SIZE);
+            let mut state = [0; 5];
+            for (word, bytes) in state.iter_mut().zip(digest.as_ref().chunks_exact(4)) {
+                *word = u32::from_be_bytes(bytes.try_into().unwrap());
+            }
+            let mut hash = Self::from_state(&state);
+            hash.message_size = message_size + Self::padding(message_size).len();
+            hash
         }
     }
 
@@ -285,10 +385,172 @@ pub mod sha {
         }
     }
 
+    /// A byte oriented implementation of the SHA-256 hash function.
+    pub struct Sha256 {
+        state: [W32; 8],
+        chunk: [u8; 64],
+        chunk_size: usize,
+        message_size: usize,
+    }
+
+    impl Sha256 {
+        const CHUNK_SIZE: usize = 64;
+        const NOF_ROUNDS: usize = 64;
+
+        const ROUND_CONSTANTS: [u32; 64] = [
+            0x428a_2f98, 0x7137_4491, 0xb5c0_fbcf, 0xe9b5_dba5, 0x3956_c25b, 0x59f1_11f1, 0x923f_82a4, 0xab1c_5ed5,
+            0xd807_aa98, 0x1283_5b01, 0x2431_85be, 0x550c_7dc3, 0x72be_5d74, 0x80de_b1fe, 0x9bdc_06a7, 0xc19b_f174,
+            0xe49b_69c1, 0xefbe_4786, 0x0fc1_9dc6, 0x240c_a1cc, 0x2de9_2c6f, 0x4a74_84aa, 0x5cb0_a9dc, 0x76f9_88da,
+            0x983e_5152, 0xa831_c66d, 0xb003_27c8, 0xbf59_7fc7, 0xc6e0_0bf3, 0xd5a7_9147, 0x06ca_6351, 0x1429_2967,
+            0x27b7_0a85, 0x2e1b_2138, 0x4d2c_6dfc, 0x5338_0d13, 0x650a_7354, 0x766a_0abb, 0x81c2_c92e, 0x9272_2c85,
+            0xa2bf_e8a1, 0xa81a_664b, 0xc24b_8b70, 0xc76c_51a3, 0xd192_e819, 0xd699_0624, 0xf40e_3585, 0x106a_a070,
+            0x19a4_c116, 0x1e37_6c08, 0x2748_774c, 0x34b0_bcb5, 0x391c_0cb3, 0x4ed8_aa4a, 0x5b9c_ca4f, 0x682e_6ff3,
+            0x748f_82ee, 0x78a5_636f, 0x84c8_7814, 0x8cc7_0208, 0x90be_fffa, 0xa450_6ceb, 0xbef9_a3f7, 0xc671_78f2,
+        ];
+
+        pub fn from_state(state: &[u32; 8]) -> Self {
+            let mut wrapped_state = [Wrapping(0); 8];
+            for (wrapped_word, word) in wrapped_state.iter_mut().zip(state) {
+                *wrapped_word = Wrapping(*word);
+            }
+            Self {
+                state: wrapped_state,
+                chunk: [0; Sha256::CHUNK_SIZE],
+                chunk_size: 0,
+                message_size: 0
+            }
+        }
+
+        #[inline(always)]
+        fn rotate(x: W32, n: u32) -> W32 {
+            Wrapping(x.0.rotate_right(n))
+        }
+
+        fn process_chunk(state: &mut [W32; 8], chunk: &[u8]) {
+            let mut words: [W32; Sha256::NOF_ROUNDS] = [Wrapping(0); Sha256::NOF_ROUNDS];
+            for i in 0..16 {
+                words[i] = W32::from_be_bytes(&chunk[4 * i .. 4 * i + 4]);
+            }
+            for i in 16..Sha256::NOF_ROUNDS {
+                let s0 = Self::rotate(words[i - 15], 7) ^ Self::rotate(words[i - 15], 18) ^ (words[i - 15] >> 3);
+                let s1 = Self::rotate(words[i - 2], 17) ^ Self::rotate(words[i - 2], 19) ^ (words[i - 2] >> 10);
+                words[i] = words[i - 16] + s0 + words[i - 7] + s1;
+            }
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+            for (word, k) in words.iter().zip(Sha256::ROUND_CONSTANTS.iter()) {
+                let s1 = Self::rotate(e, 6) ^ Self::rotate(e, 11) ^ Self::rotate(e, 25);
+                let choose = (e & f) ^ (!e & g);
+                let temp1 = h + s1 + choose + Wrapping(*k) + word;
+                let s0 = Self::rotate(a, 2) ^ Self::rotate(a, 13) ^ Self::rotate(a, 22);
+                let majority = (a & b) ^ (a & c) ^ (b & c);
+                let temp2 = s0 + majority;
+                h = g;
+                g = f;
+                f = e;
+                e = d + temp1;
+                d = c;
+                c = b;
+                b = a;
+                a = temp1 + temp2;
+            }
+            for (word, value) in state.iter_mut().zip(&[a, b, c, d, e, f, g, h]) {
+                *word += value;
+            }
+        }
+    }
+
+    impl HashFunction for Sha256 {
+        const DIGEST_SIZE: usize = 32;
+        const BLOCK_SIZE: usize = Sha256::CHUNK_SIZE;
+
+        fn new() -> Self {
+            Self::from_state(&[
+                0x6a09_e667,
+                0xbb67_ae85,
+                0x3c6e_f372,
+                0xa54f_f53a,
+                0x510e_527f,
+                0x9b05_688c,
+                0x1f83_d9ab,
+                0x5be0_cd19,
+            ])
+        }
+
+        fn update(&mut self, buffer: &[u8]) -> &mut Self {
+            let mut buffer_offset = 0;
+
+            // Handle cached partial chunk.
+            if self.chunk_size > 0 {
+                let copy_size = cmp::min(Sha256::CHUNK_SIZE - self.chunk_size, buffer.len());
+                self.chunk[self.chunk_size .. self.chunk_size + copy_size].copy_from_slice(&buffer[..copy_size]);
+                self.chunk_size += copy_size;
+                buffer_offset = copy_size;
+            }
+            if self.chunk_size == Sha256::CHUNK_SIZE {
+                Sha256::process_chunk(&mut self.state, &self.chunk.clone());
+                self.chunk_size = 0;
+            }
+
+            // Process input buffer, one chunk at a time.
+            for chunk in buffer[buffer_offset..].chunks_exact(Sha256::CHUNK_SIZE) {
+                Sha256::process_chunk(&mut self.state, chunk);
+                buffer_offset += Sha256::CHUNK_SIZE;
+            }
+
+            // Cache remaining partial chunk.
+            if buffer_offset < buffer.len() {
+                let copy_size = buffer.len() - buffer_offset;
+                self.chunk[..copy_size].copy_from_slice(&buffer[buffer_offset..]);
+                self.chunk_size = copy_size;
+            }
+
+            self.message_size += buffer.len();
+            self
+        }
+
+        fn finalize(&mut self) -> MessageDigest {
+            // The padding is identical to the padding used by SHA-1.
+            let padding = Self::padding(self.message_size);
+            self.update(&padding);
+            assert!(self.chunk_size == 0);
+            MessageDigest(self.state())
+        }
+
+        fn padding(message_size: usize) -> Vec<u8> {
+            merkle_damgard_padding(message_size, Sha256::CHUNK_SIZE, (8 * message_size as u64).to_be_bytes())
+        }
+
+        fn state(&self) -> Vec<u8> {
+            // The state is encoded as big endian integers.
+            let mut state = vec![0; Self::DIGEST_SIZE];
+            for (bytes, word) in state.chunks_exact_mut(4).zip(self.state.iter()) {
+                bytes.copy_from_slice(&word.to_be_bytes());
+            }
+            state
+        }
+
+        fn from_digest(digest: &MessageDigest, message_size: usize) -> Self {
+            assert_eq!(digest.len(), Self::DIGEST_SIZE);
+            let mut state = [0; 8];
+            for (word, bytes) in state.iter_mut().zip(digest.as_ref().chunks_exact(4)) {
+                *word = u32::from_be_bytes(bytes.try_into().unwrap());
+            }
+            let mut hash = Self::from_state(&state);
+            hash.message_size = message_size + Self::padding(message_size).len();
+            hash
+        }
+    }
+
+    impl Default for Sha256 {
+        fn default() -> Sha256 {
+            Sha256::new()
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use super::super::HashFunction;
-        use super::Sha1;
+        use super::{Sha1, Sha256};
         
         #[test]
         fn known_output() {
@@ -305,11 +567,328 @@ pub mod sha {
             let digest = hash.finalize();
             assert_eq!(digest.to_str(), "87f34c2186611148979f61f0b340360f815a27a2");
         }
+
+        #[test]
+        fn padding_boundaries() {
+            // 55 bytes leave exactly enough room for the padding in the final chunk, while 56
+            // bytes require an additional chunk.
+            let digest = Sha1::digest([b'a'; 55]);
+            assert_eq!(digest.to_str(), "c1c8bbdc22796e28c0e15163d20899b65621d65a");
+            let digest = Sha1::digest([b'a'; 56]);
+            assert_eq!(digest.to_str(), "c2db330f6083854c99d4b5bfb6e8f29f201be699");
+            let digest = Sha1::digest([b'a'; 63]);
+            assert_eq!(digest.to_str(), "03f09f5b158a7a8cdad920bddc29b81c18a551f5");
+            let digest = Sha1::digest([b'a'; 64]);
+            assert_eq!(digest.to_str(), "0098ba824b5c16427bd7a1122a5a442a25ec644d");
+        }
+
+        #[test]
+        #[cfg(feature = "std")]
+        fn digest_reader() {
+            let buffer = (0..200_000).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+            let digest = Sha1::digest_reader(&buffer[..]).unwrap();
+            assert_eq!(digest, Sha1::digest(&buffer));
+            let digest = Sha256::digest_reader(&buffer[..]).unwrap();
+            assert_eq!(digest, Sha256::digest(&buffer));
+        }
+
+        #[test]
+        fn sha256_known_output() {
+            let digest = Sha256::digest("");
+            assert_eq!(digest.to_str(), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+            let digest = Sha256::digest("The quick brown fox jumps over the lazy dog");
+            assert_eq!(digest.to_str(), "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592");
+            // 55 bytes leave exactly enough room for the padding in the final chunk.
+            let digest = Sha256::digest([b'a'; 55]);
+            assert_eq!(digest.to_str(), "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318");
+            let digest = Sha256::digest([b'a'; 56]);
+            assert_eq!(digest.to_str(), "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a");
+        }
+
+        #[test]
+        fn sha256_fips_vectors() {
+            // The one-block and two-block message examples from FIPS 180-2.
+            let digest = Sha256::digest("abc");
+            assert_eq!(digest.to_str(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+            let digest = Sha256::digest("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq");
+            assert_eq!(digest.to_str(), "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1");
+        }
+
+        #[test]
+        fn sha256_from_state() {
+            let state = [
+                0x6a09_e667, 0xbb67_ae85, 0x3c6e_f372, 0xa54f_f53a,
+                0x510e_527f, 0x9b05_688c, 0x1f83_d9ab, 0x5be0_cd19,
+            ];
+            let digest = Sha256::from_state(&state).update(b"abc").finalize();
+            assert_eq!(digest, Sha256::digest("abc"));
+        }
+
+        #[test]
+        fn resume_from_digest() {
+            let message = b"The quick brown fox jumps over the lazy dog";
+            let padding = Sha1::padding(message.len());
+            let digest = Sha1::digest(message);
+            assert_eq!(Sha1::from_digest(&digest, message.len()).state(), digest.as_ref());
+            assert_eq!(
+                Sha1::from_digest(&digest, message.len()).update(b"foo").finalize(),
+                Sha1::digest([&message[..], &padding, b"foo"].concat())
+            );
+            let padding = Sha256::padding(message.len());
+            let digest = Sha256::digest(message);
+            assert_eq!(
+                Sha256::from_digest(&digest, message.len()).update(b"foo").finalize(),
+                Sha256::digest([&message[..], &padding, b"foo"].concat())
+            );
+        }
+
+        #[test]
+        #[cfg(feature = "serde")]
+        fn serde_encoding() {
+            use super::super::MessageDigest;
+
+            let digest = Sha256::digest("abc");
+            let json = serde_json::to_string(&digest).unwrap();
+            assert_eq!(json, format!("\"{}\"", digest));
+            assert_eq!(serde_json::from_str::<MessageDigest>(&json).unwrap(), digest);
+            assert!(serde_json::from_str::<MessageDigest>("\"abc\"").is_err());
+        }
+
+        #[test]
+        fn sha256_chunked_update() {
+            let mut hash = Sha256::new();
+            for _ in 0..256 {
+                hash.update(b"abc");
+            }
+            let digest = hash.finalize();
+            assert_eq!(digest.to_str(), "0eb2d624ad16b7641c1902b91fc9ea61fcb5a04adb5aa1f74c6b16cf731bfa78");
+        }
+    }
+}
+
+pub mod md4 {
+    use core::cmp;
+    use core::num::Wrapping;
+    use core::convert::TryInto;
+
+    use crate::prelude::*;
+    use super::{W32, HashFunction, MessageDigest, merkle_damgard_padding};
+
+    /// A byte oriented implementation of the MD4 hash function from RFC 1320.
+    pub struct Md4 {
+        state: [W32; 4],
+        chunk: [u8; 64],
+        chunk_size: usize,
+        message_size: usize,
+    }
+
+    impl Md4 {
+        const CHUNK_SIZE: usize = 64;
+
+        pub fn from_state(state: &[u32; 4]) -> Self {
+            let mut wrapped_state = [Wrapping(0); 4];
+            for (wrapped_word, word) in wrapped_state.iter_mut().zip(state) {
+                *wrapped_word = Wrapping(*word);
+            }
+            Self {
+                state: wrapped_state,
+                chunk: [0; Md4::CHUNK_SIZE],
+                chunk_size: 0,
+                message_size: 0
+            }
+        }
+
+        /// Applies one of the three rounds. Each step updates a single word of the state,
+        /// after which the words are rotated so that the next word to update comes first.
+        #[inline(always)]
+        fn process_round(
+            state: &mut [W32; 4],
+            words: &[W32; 16],
+            order: &[usize; 16],
+            shifts: &[u32; 4],
+            k: W32,
+            f: impl Fn(W32, W32, W32) -> W32
+        ) {
+            let [mut a, mut b, mut c, mut d] = *state;
+            for (step, index) in order.iter().enumerate() {
+                let temp = Wrapping((a + f(b, c, d) + words[*index] + k).0.rotate_left(shifts[step % 4]));
+                a = d;
+                d = c;
+                c = b;
+                b = temp;
+            }
+            *state = [a, b, c, d];
+        }
+
+        fn process_chunk(state: &mut [W32; 4], chunk: &[u8]) {
+            let mut words = [Wrapping(0); 16];
+            for (word, bytes) in words.iter_mut().zip(chunk.chunks_exact(4)) {
+                *word = Wrapping(u32::from_le_bytes(bytes.try_into().unwrap()));
+            }
+            let mut working_state = *state;
+            Md4::process_round(
+                &mut working_state,
+                &words,
+                &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+                &[3, 7, 11, 19],
+                Wrapping(0),
+                |x, y, z| (x & y) | (!x & z)
+            );
+            Md4::process_round(
+                &mut working_state,
+                &words,
+                &[0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+                &[3, 5, 9, 13],
+                Wrapping(0x5a82_7999),
+                |x, y, z| (x & y) | (x & z) | (y & z)
+            );
+            Md4::process_round(
+                &mut working_state,
+                &words,
+                &[0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+                &[3, 9, 11, 15],
+                Wrapping(0x6ed9_eba1),
+                |x, y, z| x ^ y ^ z
+            );
+            for (word, value) in state.iter_mut().zip(&working_state) {
+                *word += value;
+            }
+        }
+    }
+
+    impl HashFunction for Md4 {
+        const DIGEST_SIZE: usize = 16;
+        const BLOCK_SIZE: usize = Md4::CHUNK_SIZE;
+
+        fn new() -> Self {
+            Self::from_state(&[
+                0x6745_2301,
+                0xefcd_ab89,
+                0x98ba_dcfe,
+                0x1032_5476,
+            ])
+        }
+
+        fn update(&mut self, buffer: &[u8]) -> &mut Self {
+            let mut buffer_offset = 0;
+
+            // Handle cached partial chunk.
+            if self.chunk_size > 0 {
+                let copy_size = cmp::min(Md4::CHUNK_SIZE - self.chunk_size, buffer.len());
+                self.chunk[self.chunk_size .. self.chunk_size + copy_size].copy_from_slice(&buffer[..copy_size]);
+                self.chunk_size += copy_size;
+                buffer_offset = copy_size;
+            }
+            if self.chunk_size == Md4::CHUNK_SIZE {
+                let chunk = self.chunk;
+                Md4::process_chunk(&mut self.state, &chunk);
+                self.chunk_size = 0;
+            }
+
+            // Process input buffer, one chunk at a time.
+            for chunk in buffer[buffer_offset..].chunks_exact(Md4::CHUNK_SIZE) {
+                Md4::process_chunk(&mut self.state, chunk);
+                buffer_offset += Md4::CHUNK_SIZE;
+            }
+
+            // Cache remaining partial chunk.
+            if buffer_offset < buffer.len() {
+                let copy_size = buffer.len() - buffer_offset;
+                self.chunk[..copy_size].copy_from_slice(&buffer[buffer_offset..]);
+                self.chunk_size = copy_size;
+            }
+
+            self.message_size += buffer.len();
+            self
+        }
+
+        fn finalize(&mut self) -> MessageDigest {
+            // The padding is identical to the padding used by SHA-1, except that the message
+            // size is encoded as a little endian integer.
+            let padding = Self::padding(self.message_size);
+            self.update(&padding);
+            assert!(self.chunk_size == 0);
+            MessageDigest(self.state())
+        }
+
+        fn padding(message_size: usize) -> Vec<u8> {
+            merkle_damgard_padding(message_size, Md4::CHUNK_SIZE, (8 * message_size as u64).to_le_bytes())
+        }
+
+        fn state(&self) -> Vec<u8> {
+            // The state is encoded as little endian integers.
+            let mut state = vec![0; Self::DIGEST_SIZE];
+            for (bytes, word) in state.chunks_exact_mut(4).zip(self.state.iter()) {
+                bytes.copy_from_slice(&word.0.to_le_bytes());
+            }
+            state
+        }
+
+        fn from_digest(digest: &MessageDigest, message_size: usize) -> Self {
+            assert_eq!(digest.len(), Self::DIGEST_SIZE);
+            let mut state = [0; 4];
+            for (word, bytes) in state.iter_mut().zip(digest.as_ref().chunks_exact(4)) {
+                *word = u32::from_le_bytes(bytes.try_into().unwrap());
+            }
+            let mut hash = Self::from_state(&state);
+            hash.message_size = message_size + Self::padding(message_size).len();
+            hash
+        }
+    }
+
+    impl Default for Md4 {
+        fn default() -> Md4 {
+            Md4::new()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::HashFunction;
+        use super::Md4;
+
+        #[test]
+        fn known_output() {
+            // Test vectors from RFC 1320.
+            assert_eq!(Md4::digest("").to_str(), "31d6cfe0d16ae931b73c59d7e0c089c0");
+            assert_eq!(Md4::digest("a").to_str(), "bde52cb31de33e46245e05fbdbd6fb24");
+            assert_eq!(Md4::digest("abc").to_str(), "a448017aaf21d8525fc10ae87aa6729d");
+            assert_eq!(Md4::digest("message digest").to_str(), "d9130a8164549fe818874806e1c7014b");
+            assert_eq!(
+                Md4::digest("12345678901234567890123456789012345678901234567890123456789012345678901234567890").to_str(),
+                "e33b4ddc9c38f2199c3e7b164fcc0536"
+            );
+        }
+
+        #[test]
+        fn chunked_update() {
+            let buffer = (0..1000).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+            let mut hash = Md4::new();
+            for chunk in buffer.chunks(7) {
+                hash.update(chunk);
+            }
+            assert_eq!(hash.finalize(), Md4::digest(&buffer));
+        }
+
+        #[test]
+        fn resume_from_digest() {
+            let message = b"The quick brown fox jumps over the lazy dog";
+            let padding = Md4::padding(message.len());
+            let digest = Md4::digest(message);
+            assert_eq!(
+                Md4::from_digest(&digest, message.len()).update(b"foo").finalize(),
+                Md4::digest([&message[..], &padding, b"foo"].concat())
+            );
+        }
     }
 }
 
 pub mod mac {
+    use crate::prelude::*;
     use super::{HashFunction, Mac, MessageDigest};
+    use crate::crypto::symmetric::{self, Cipher};
+    use crate::crypto::symmetric::padding_modes::{PaddingMode, Pkcs7};
+    use crate::utils::bytes;
 
     pub struct NaiveMac<H: HashFunction> {
         hash: H
@@ -322,7 +901,7 @@ pub mod mac {
 
         fn new(key: &[u8]) -> Self {
             let mut hash: H = H::new();
-            hash.update(&key);
+            hash.update(key);
             Self { hash }
         }
 
@@ -337,10 +916,167 @@ pub mod mac {
             self.hash.finalize()
         }
     }
+
+    /// The HMAC construction from RFC 2104.
+    pub struct Hmac<H: HashFunction> {
+        inner_hash: H,
+        outer_key: Vec<u8>,
+    }
+
+    impl<H: HashFunction> Mac for Hmac<H> {
+        const TAG_SIZE: usize = H::DIGEST_SIZE;
+
+        fn new(key: &[u8]) -> Self {
+            // Keys longer than the block size are hashed first.
+            let mut key = if key.len() > H::BLOCK_SIZE {
+                H::digest(key).as_ref().to_vec()
+            } else {
+                key.to_vec()
+            };
+            key.resize(H::BLOCK_SIZE, 0);
+
+            let inner_key: Vec<u8> = key.iter().map(|byte| byte ^ 0x36).collect();
+            let outer_key: Vec<u8> = key.iter().map(|byte| byte ^ 0x5c).collect();
+            let mut inner_hash = H::new();
+            inner_hash.update(&inner_key);
+            Self { inner_hash, outer_key }
+        }
+
+        fn update(&mut self, buffer: &[u8]) -> &mut Self {
+            self.inner_hash.update(buffer);
+            self
+        }
+
+        fn finalize(&mut self) -> MessageDigest {
+            let inner_digest = self.inner_hash.finalize();
+            H::new()
+                .update(&self.outer_key)
+                .update(inner_digest.as_ref())
+                .finalize()
+        }
+    }
+
+    /// CBC-MAC with PKCS#7 padding. The tag is the last block of the CBC encryption of the
+    /// message. `Mac::new` uses a zero IV, while `with_iv` allows the IV to be chosen by the
+    /// caller (as in challenge 49, where the IV is sent along with the message).
+    ///
+    /// # Note
+    ///
+    /// CBC-MAC is only secure for messages of a fixed length.
+    #[derive(Clone, Debug)]
+    pub struct CbcMac<C: Cipher> {
+        cipher: C,
+        state: Vec<u8>,
+        buffer: Vec<u8>,
+    }
+
+    impl<C: Cipher> CbcMac<C> {
+        /// Returns a CBC-MAC instance using the given IV.
+        pub fn with_iv(key: &[u8], iv: &[u8]) -> Result<Self, symmetric::Error> {
+            if iv.len() != C::BLOCK_SIZE {
+                return Err(symmetric::Error::CipherError);
+            }
+            Ok(Self {
+                cipher: C::new(key)?,
+                state: iv.to_vec(),
+                buffer: Vec::with_capacity(C::BLOCK_SIZE),
+            })
+        }
+
+        fn process_block(&mut self, block: &[u8]) {
+            bytes::xor_in_place(&mut self.state, block);
+            self.cipher.encrypt_mut(&mut self.state);
+        }
+    }
+
+    impl<C: Cipher> Mac for CbcMac<C> {
+        const TAG_SIZE: usize = C::BLOCK_SIZE;
+
+        /// Returns a CBC-MAC instance using a zero IV.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the key size is invalid for the cipher.
+        fn new(key: &[u8]) -> Self {
+            Self::with_iv(key, &vec![0; C::BLOCK_SIZE]).expect("invalid key size")
+        }
+
+        fn update(&mut self, buffer: &[u8]) -> &mut Self {
+            // Full blocks can be processed immediately since PKCS#7 always adds padding.
+            let mut buffer = buffer;
+            if !self.buffer.is_empty() {
+                let size = buffer.len().min(C::BLOCK_SIZE - self.buffer.len());
+                self.buffer.extend_from_slice(&buffer[..size]);
+                buffer = &buffer[size..];
+                if self.buffer.len() < C::BLOCK_SIZE {
+                    return self;
+                }
+                let block = core::mem::take(&mut self.buffer);
+                self.process_block(&block);
+            }
+            let mut blocks = buffer.chunks_exact(C::BLOCK_SIZE);
+            for block in blocks.by_ref() {
+                self.process_block(block);
+            }
+            self.buffer.extend_from_slice(blocks.remainder());
+            self
+        }
+
+        fn finalize(&mut self) -> MessageDigest {
+            let mut block = core::mem::take(&mut self.buffer);
+            let size = block.len();
+            block.resize(C::BLOCK_SIZE, 0);
+            // It is safe to unwrap here since the buffer is always shorter than a block.
+            Pkcs7::new(C::BLOCK_SIZE).pad_mut(&mut block, size).unwrap();
+            self.process_block(&block);
+            MessageDigest(self.state.clone())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use super::super::Sha1;
+        use crate::crypto::symmetric::{Aes128, Aes128Cbc, BlockCipherMode};
+
+        #[test]
+        fn known_output() {
+            // Test cases 1 and 6 from RFC 2202.
+            let tag = Hmac::<Sha1>::digest([0x0b; 20], "Hi There");
+            assert_eq!(tag.to_str(), "b617318655057264e28bc0b6fb378c8ef146be00");
+
+            let tag = Hmac::<Sha1>::digest(
+                [0xaa; 80], 
+                "Test Using Larger Than Block-Size Key - Hash Key First"
+            );
+            assert_eq!(tag.to_str(), "aa4ae5e15272d00e95705637ce8a3b55ed402112");
+        }
+
+        #[test]
+        fn cbc_mac() {
+            // The example from challenge 50.
+            let tag = CbcMac::<Aes128>::digest("YELLOW SUBMARINE", "alert('MZA who was that?');\n");
+            assert_eq!(tag.to_str(), "296b8d7cb78a243dda4d0a61d33bbdd1");
+
+            // The tag is the last ciphertext block, and does not depend on how the input is split.
+            let key = b"YELLOW SUBMARINE";
+            let iv = [0x42; 16];
+            let message = [0x17; 45];
+            let mut mac = CbcMac::<Aes128>::with_iv(key, &iv).unwrap();
+            let tag = mac.update(&message[..3]).update(&message[3..40]).update(&message[40..]).finalize();
+            let ciphertext = Aes128Cbc::new(key, &iv).unwrap().encrypt_buffer(&message).unwrap();
+            assert_eq!(tag.as_ref(), &ciphertext[ciphertext.len() - 16..]);
+            assert!(CbcMac::<Aes128>::with_iv(key, &iv[1..]).is_err());
+        }
+    }
 }
 
-// Re-export `Sha1` and `NaiveMac`.
-pub use sha::Sha1;
-pub use mac::NaiveMac;
+// Re-export `Sha1`, `Sha256`, `Md4`, `NaiveMac`, `Hmac` and `CbcMac`.
+pub use sha::{Sha1, Sha256};
+pub use md4::Md4;
+pub use mac::{NaiveMac, Hmac, CbcMac};
 
 pub type Sha1NaiveMac = NaiveMac<Sha1>;
+pub type Sha1Hmac = Hmac<Sha1>;
+pub type Md4NaiveMac = NaiveMac<Md4>;
+pub type Aes128CbcMac = CbcMac<Aes128>;
@@ -0,0 +1,198 @@
+//! Adapters which apply stream ciphers and hash functions to data as it is read or written. This
+//! allows large inputs to be encrypted or hashed incrementally, without first loading them into
+//! memory.
+
+use std::io::{self, Read, Write};
+
+use crate::crypto::symmetric::cipher_modes::StreamCipherMode;
+use crate::crypto::hash::{HashFunction, MessageDigest};
+
+/// The direction in which a cipher adapter applies the cipher mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+fn apply<M: StreamCipherMode>(mode: &mut M, direction: Direction, buffer: &mut [u8]) -> io::Result<()> {
+    let result = match direction {
+        Direction::Encrypt => mode.encrypt_mut(buffer),
+        Direction::Decrypt => mode.decrypt_mut(buffer),
+    };
+    result.map(|_| ()).map_err(io::Error::other)
+}
+
+/// The size of the buffer used by `CipherWriter`.
+const WRITE_CHUNK_SIZE: usize = 1 << 12;
+
+/// Encrypts (or decrypts) all data written to it, and writes the result to the underlying
+/// writer.
+pub struct CipherWriter<W: Write, M: StreamCipherMode> {
+    writer: W,
+    mode: M,
+    direction: Direction,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write, M: StreamCipherMode> CipherWriter<W, M> {
+    /// Returns a writer which encrypts the data written to it using the given mode.
+    pub fn encrypt(writer: W, mode: M) -> Self {
+        CipherWriter { writer, mode, direction: Direction::Encrypt, buffer: Vec::new() }
+    }
+
+    /// Returns a writer which decrypts the data written to it using the given mode.
+    pub fn decrypt(writer: W, mode: M) -> Self {
+        CipherWriter { writer, mode, direction: Direction::Decrypt, buffer: Vec::new() }
+    }
+
+    pub fn direction(&self) -> Direction { self.direction }
+
+    pub fn get_ref(&self) -> &W { &self.writer }
+
+    pub fn get_mut(&mut self) -> &mut W { &mut self.writer }
+
+    pub fn into_inner(self) -> W { self.writer }
+}
+
+impl<W: Write, M: StreamCipherMode> Write for CipherWriter<W, M> {
+    /// Since the cipher mode advances as data is processed, the processed data is always
+    /// written in full before returning.
+    fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+        let size = buffer.len().min(WRITE_CHUNK_SIZE);
+        self.buffer.clear();
+        self.buffer.extend_from_slice(&buffer[..size]);
+        apply(&mut self.mode, self.direction, &mut self.buffer)?;
+        self.writer.write_all(&self.buffer)?;
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Encrypts (or decrypts) the data read from the underlying reader.
+pub struct CipherReader<R: Read, M: StreamCipherMode> {
+    reader: R,
+    mode: M,
+    direction: Direction,
+}
+
+impl<R: Read, M: StreamCipherMode> CipherReader<R, M> {
+    /// Returns a reader which encrypts the data read from `reader` using the given mode.
+    pub fn encrypt(reader: R, mode: M) -> Self {
+        CipherReader { reader, mode, direction: Direction::Encrypt }
+    }
+
+    /// Returns a reader which decrypts the data read from `reader` using the given mode.
+    pub fn decrypt(reader: R, mode: M) -> Self {
+        CipherReader { reader, mode, direction: Direction::Decrypt }
+    }
+
+    pub fn direction(&self) -> Direction { self.direction }
+
+    pub fn get_ref(&self) -> &R { &self.reader }
+
+    pub fn get_mut(&mut self) -> &mut R { &mut self.reader }
+
+    pub fn into_inner(self) -> R { self.reader }
+}
+
+impl<R: Read, M: StreamCipherMode> Read for CipherReader<R, M> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let size = self.reader.read(buffer)?;
+        apply(&mut self.mode, self.direction, &mut buffer[..size])?;
+        Ok(size)
+    }
+}
+
+/// Hashes the data read from the underlying reader as it passes through.
+pub struct HashingReader<R: Read, H: HashFunction> {
+    reader: R,
+    hash: H,
+}
+
+impl<R: Read, H: HashFunction> HashingReader<R, H> {
+    pub fn new(reader: R) -> Self {
+        HashingReader { reader, hash: H::new() }
+    }
+
+    /// Returns the digest of the data read so far.
+    pub fn finalize(self) -> MessageDigest {
+        let mut hash = self.hash;
+        hash.finalize()
+    }
+
+    pub fn get_ref(&self) -> &R { &self.reader }
+
+    /// Returns a mutable reference to the underlying reader. Data read through this reference
+    /// is not hashed.
+    pub fn get_mut(&mut self) -> &mut R { &mut self.reader }
+
+    pub fn into_inner(self) -> R { self.reader }
+}
+
+impl<R: Read, H: HashFunction> Read for HashingReader<R, H> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let size = self.reader.read(buffer)?;
+        self.hash.update(&buffer[..size]);
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::symmetric::{Aes128Ctr, Aes128Cfb};
+    use crate::crypto::hash::Sha256;
+
+    const KEY: [u8; 16] = [0x2b; 16];
+    const IV: [u8; 16] = [0x7e; 16];
+
+    fn get_plaintext() -> Vec<u8> {
+        (0..100_000).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn cipher_writer() {
+        let plaintext = get_plaintext();
+        let mut writer = CipherWriter::encrypt(Vec::new(), Aes128Ctr::new(&KEY, &IV[..8]).unwrap());
+        for chunk in plaintext.chunks(999) {
+            writer.write_all(chunk).unwrap();
+        }
+        let ciphertext = writer.into_inner();
+        let expected = Aes128Ctr::new(&KEY, &IV[..8]).unwrap().encrypt_buffer(&plaintext).unwrap();
+        assert_eq!(ciphertext, expected);
+
+        // CFB-mode is not symmetric, so decryption has to be handled separately.
+        let ciphertext = Aes128Cfb::cfb8(&KEY, &IV).unwrap().encrypt_buffer(&plaintext).unwrap();
+        let mut writer = CipherWriter::decrypt(Vec::new(), Aes128Cfb::cfb8(&KEY, &IV).unwrap());
+        io::copy(&mut &ciphertext[..], &mut writer).unwrap();
+        assert_eq!(writer.into_inner(), plaintext);
+    }
+
+    #[test]
+    fn cipher_reader() {
+        let plaintext = get_plaintext();
+        let mut reader = CipherReader::encrypt(&plaintext[..], Aes128Cfb::cfb8(&KEY, &IV).unwrap());
+        let mut ciphertext = Vec::new();
+        reader.read_to_end(&mut ciphertext).unwrap();
+        let expected = Aes128Cfb::cfb8(&KEY, &IV).unwrap().encrypt_buffer(&plaintext).unwrap();
+        assert_eq!(ciphertext, expected);
+
+        let mut reader = CipherReader::decrypt(&ciphertext[..], Aes128Cfb::cfb8(&KEY, &IV).unwrap());
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+    }
+
+    #[test]
+    fn hashing_reader() {
+        let plaintext = get_plaintext();
+        let mut reader = HashingReader::<_, Sha256>::new(&plaintext[..]);
+        let mut buffer = Vec::new();
+        reader.read_to_end(&mut buffer).unwrap();
+        assert_eq!(buffer, plaintext);
+        assert_eq!(reader.finalize(), Sha256::digest(&plaintext));
+    }
+}
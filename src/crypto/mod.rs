@@ -1,4 +1,9 @@
+#[cfg(all(feature = "openssl", not(target_arch = "wasm32")))]
 mod openssl;
+pub mod aes;
 pub mod symmetric;
 pub mod random;
 pub mod hash;
+pub mod aead;
+#[cfg(feature = "std")]
+pub mod io;
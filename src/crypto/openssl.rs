@@ -154,7 +154,7 @@ pub mod aes {
         #[test]
         fn encrypt_aes_256() {
             let key = AES_KEY::new_encrypt_key(&RAW_KEY).unwrap();
-            let mut block = PLAINTEXT.clone();
+            let mut block = PLAINTEXT;
             encrypt_mut(&mut block, &key);
             assert_eq!(block, CIPHERTEXT);
         }
@@ -162,7 +162,7 @@ pub mod aes {
         #[test]
         fn decrypt_aes_256() {
             let key = AES_KEY::new_decrypt_key(&RAW_KEY).unwrap();
-            let mut block = CIPHERTEXT.clone();
+            let mut block = CIPHERTEXT;
             decrypt_mut(&mut block, &key);
             assert_eq!(block, PLAINTEXT);
         }
@@ -1,3 +1,6 @@
+#[cfg(feature = "std")]
+use rand::Rng;
+
 /// A random number generator trait.
 pub trait RandomGenerator {
     fn next_u8(&mut self) -> u8;
@@ -23,6 +26,7 @@ pub trait Random {
     fn random() -> Self;
 }
 
+#[cfg(feature = "std")]
 #[macro_export]
 macro_rules! random_vec {
     ($size:expr) => {
@@ -31,14 +35,17 @@ macro_rules! random_vec {
 }
 
 pub mod mersenne_twister {
-    use rand;
+    #[cfg(feature = "std")]
     use rand::Rng;
 
-    use std::fmt;
-    use std::cmp::PartialEq;
-    use std::num::Wrapping;
+    use core::fmt;
+    use core::cmp::PartialEq;
+    use core::num::Wrapping;
 
-    use super::{Random, RandomGenerator, SeedableGenerator};
+    use crate::prelude::*;
+    #[cfg(feature = "std")]
+    use super::Random;
+    use super::{RandomGenerator, SeedableGenerator};
    
     type W32 = Wrapping<u32>;
 
@@ -69,6 +76,34 @@ pub mod mersenne_twister {
             }
         }
         
+        /// Applies the tempering transform used to produce each output from a state word.
+        pub fn temper(word: u32) -> u32 {
+            let mut x = Wrapping(word);
+            x ^=  x >> 11;
+            x ^= (x <<  7) & Mt19337::FIRST_MASK;
+            x ^= (x << 15) & Mt19337::SECOND_MASK;
+            x ^=  x >> 18;
+            x.0
+        }
+
+        /// Inverts `temper`, returning the state word which produced the given output. Each
+        /// step `x ^= (x << shift) & mask` (or `x >> shift`) is inverted by repeatedly applying
+        /// it to the output, since every iteration fixes another `shift` bits of the input.
+        pub fn untemper(output: u32) -> u32 {
+            fn invert_right(output: u32, shift: u32) -> u32 {
+                (0..32 / shift).fold(output, |x, _| output ^ (x >> shift))
+            }
+
+            fn invert_left(output: u32, shift: u32, mask: u32) -> u32 {
+                (0..32 / shift).fold(output, |x, _| output ^ ((x << shift) & mask))
+            }
+
+            let x = invert_right(output, 18);
+            let x = invert_left(x, 15, Mt19337::SECOND_MASK.0);
+            let x = invert_left(x, 7, Mt19337::FIRST_MASK.0);
+            invert_right(x, 11)
+        }
+
         fn twist(&mut self) {
             let k = Mt19337::SIZE - 1;
             let m = 227;
@@ -87,6 +122,7 @@ pub mod mersenne_twister {
         }
     }
 
+    #[cfg(feature = "std")]
     impl Random for Mt19337 {
         fn random() -> Self {
             Self::new(rand::thread_rng().gen())
@@ -106,15 +142,9 @@ pub mod mersenne_twister {
             if self.index >= Mt19337::SIZE {
                 self.twist();
             }
-            let mut x = self.state[self.index];
-
-            x ^=  x >> 11;
-            x ^= (x <<  7) & Mt19337::FIRST_MASK;
-            x ^= (x << 15) & Mt19337::SECOND_MASK;
-            x ^=  x >> 18;
-
+            let x = Mt19337::temper(self.state[self.index].0);
             self.index += 1;
-            x.0
+            x
         }
 
         fn next_u64(&mut self) -> u64 {
@@ -160,6 +190,46 @@ pub mod mersenne_twister {
         }
     }
 
+    /// The generator state is serialized as the state array together with the current index, so
+    /// a cloned generator can be saved and resumed later.
+    #[cfg(feature = "serde")]
+    mod serialization {
+        use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+        use serde::ser::SerializeStruct;
+
+        use crate::prelude::*;
+        use super::Mt19337;
+
+        /// Unvalidated state used to check invariants on deserialization.
+        #[derive(Deserialize)]
+        struct RawState {
+            state: Vec<u32>,
+            index: usize,
+        }
+
+        impl Serialize for Mt19337 {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let state: Vec<u32> = self.state.iter().map(|x| x.0).collect();
+                let mut result = serializer.serialize_struct("Mt19337", 2)?;
+                result.serialize_field("state", &state)?;
+                result.serialize_field("index", &self.index)?;
+                result.end()
+            }
+        }
+
+        impl<'de> Deserialize<'de> for Mt19337 {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let raw = RawState::deserialize(deserializer)?;
+                if raw.state.len() != Mt19337::SIZE || raw.index > Mt19337::SIZE {
+                    return Err(de::Error::custom("invalid generator state"));
+                }
+                let mut state = [0; Mt19337::SIZE];
+                state.copy_from_slice(&raw.state);
+                Ok(Mt19337::from_state(state, raw.index))
+            }
+        }
+    }
+
     impl Iterator for Mt19337 {
         type Item = u8;
 
@@ -183,13 +253,34 @@ pub mod mersenne_twister {
             let mut random = Mt19337::new(1);
             let output = [
                 0x6ac1f425, 0xff4780eb, 0xb8672f8c, 0xeebc1448, 
-                0x00077EFF, 0x20CCC389, 0x4D65aacb, 0xffc11E85
+                0x00077eff, 0x20ccc389, 0x4d65aacb, 0xffc11e85
             ];
-            for i in 0..output.len() {
-                assert_eq!(random.next_u32(), output[i]);
+            for &value in output.iter() {
+                assert_eq!(random.next_u32(), value);
+            }
+        }
+
+        #[test]
+        fn untemper() {
+            let mut random = Mt19337::new(1);
+            let words = [0, 1, 0x8000_0000, 0xffff_ffff];
+            for word in words.iter().copied().chain((0..1000).map(|_| random.next_u32())) {
+                assert_eq!(Mt19337::untemper(Mt19337::temper(word)), word);
             }
         }
 
+        #[test]
+        #[cfg(feature = "serde")]
+        fn serde_encoding() {
+            let mut random = Mt19337::new(1);
+            random.next_u32();
+            let json = serde_json::to_string(&random).unwrap();
+            let mut result: Mt19337 = serde_json::from_str(&json).unwrap();
+            assert_eq!(result, random);
+            assert_eq!(result.next_u32(), random.next_u32());
+            assert!(serde_json::from_str::<Mt19337>("{\"state\": [1, 2, 3], \"index\": 0}").is_err());
+        }
+
         #[test]
         fn encrypt_buffer() {
             let mut random = Mt19337::new(1);
@@ -204,4 +295,37 @@ pub mod mersenne_twister {
     }
 }
 
+/// A cryptographically secure random number generator backed by the operating system.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SecureRandom;
+
+#[cfg(feature = "std")]
+impl RandomGenerator for SecureRandom {
+    fn next_u8(&mut self) -> u8 {
+        rand::rngs::OsRng.gen()
+    }
+
+    fn next_u16(&mut self) -> u16 {
+        rand::rngs::OsRng.gen()
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        rand::rngs::OsRng.gen()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand::rngs::OsRng.gen()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Iterator for SecureRandom {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        Some(self.next_u8())
+    }
+}
+
 pub use mersenne_twister::Mt19337;
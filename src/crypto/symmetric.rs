@@ -1,12 +1,15 @@
-use std::fmt;
-use std::error;
-use std::string::FromUtf8Error;
+use core::fmt;
+use core::error;
+use alloc::string::FromUtf8Error;
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Error {
     DecodingError,
     PaddingError,
     CipherError,
+    AuthenticationError,
+    /// The query budget of an oracle wrapped in `oracles::Counted` is exhausted.
+    BudgetExceeded,
 }
 
 impl fmt::Display for Error {
@@ -27,12 +30,46 @@ impl From<FromUtf8Error> for Error {
     }
 }
 
+/// Describes the validity of a decrypted plaintext, as returned by `decrypt_with_report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecryptionReport {
+    /// True if the padding was valid. This is always true for stream cipher modes.
+    pub valid_padding: bool,
+    /// The offset of the first byte which is not part of a valid UTF-8 sequence, if any.
+    pub invalid_utf8_offset: Option<usize>,
+}
+
+impl DecryptionReport {
+    fn new(plaintext: &[u8], valid_padding: bool) -> Self {
+        let invalid_utf8_offset = core::str::from_utf8(plaintext)
+            .err()
+            .map(|error| error.valid_up_to());
+        DecryptionReport { valid_padding, invalid_utf8_offset }
+    }
+
+    /// Returns true if the plaintext is correctly padded and valid UTF-8. This is the case
+    /// exactly when `decrypt_str` succeeds.
+    pub fn is_valid(&self) -> bool {
+        self.valid_padding && self.invalid_utf8_offset.is_none()
+    }
+}
+
+/// The AES ciphers use the OpenSSL bindings when the `openssl` feature is enabled, and the
+/// portable implementation in `crypto::aes` otherwise. The OpenSSL bindings are never used on
+/// `wasm32`.
 pub mod ciphers {
+    use crate::prelude::*;
+    #[cfg(feature = "std")]
     use crate::random_vec;
 
     use super::Error;
+    #[cfg(all(feature = "openssl", not(target_arch = "wasm32")))]
     use crate::crypto::openssl;
-    use crate::crypto::openssl::aes;
+    #[cfg(all(feature = "openssl", not(target_arch = "wasm32")))]
+    use crate::crypto::openssl::aes::{self, AES_KEY as AesKey};
+    #[cfg(not(all(feature = "openssl", not(target_arch = "wasm32"))))]
+    use crate::crypto::aes::{self, AesKey};
+    #[cfg(feature = "std")]
     use crate::crypto::random::Random;
 
     pub type Key = [u8];
@@ -64,6 +101,7 @@ pub mod ciphers {
         }
     }
     
+    #[cfg(all(feature = "openssl", not(target_arch = "wasm32")))]
     impl From<openssl::Error> for Error {
         fn from(_: openssl::Error) -> Self {
             Error::CipherError
@@ -72,8 +110,8 @@ pub mod ciphers {
     
     #[derive(Clone, Debug)]
     pub struct Aes128 {
-        encrypt_key: aes::AES_KEY,
-        decrypt_key: aes::AES_KEY
+        encrypt_key: AesKey,
+        decrypt_key: AesKey
     }
     
     impl Cipher for Aes128 {
@@ -84,8 +122,8 @@ pub mod ciphers {
             if raw_key.len() != Self::KEY_SIZE {
                 return Err(Error::CipherError)
             }
-            let encrypt_key = aes::AES_KEY::new_encrypt_key(raw_key)?;
-            let decrypt_key = aes::AES_KEY::new_decrypt_key(raw_key)?;
+            let encrypt_key = AesKey::new_encrypt_key(raw_key)?;
+            let decrypt_key = AesKey::new_decrypt_key(raw_key)?;
             
             Ok(Aes128 {
                 encrypt_key,
@@ -104,6 +142,7 @@ pub mod ciphers {
         }
     }
 
+    #[cfg(feature = "std")]
     impl Random for Aes128 {
         fn random() -> Self {
             let key = random_vec!(Aes128::KEY_SIZE);
@@ -113,10 +152,54 @@ pub mod ciphers {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Aes192 {
+        encrypt_key: AesKey,
+        decrypt_key: AesKey
+    }
+    
+    impl Cipher for Aes192 {
+        const KEY_SIZE: usize = 24;
+        const BLOCK_SIZE: usize = aes::AES_BLOCK_SIZE;
+        
+        fn new(raw_key: &Key) -> Result<Self, Error> {
+            if raw_key.len() != Self::KEY_SIZE {
+                return Err(Error::CipherError)
+            }
+            let encrypt_key = AesKey::new_encrypt_key(raw_key)?;
+            let decrypt_key = AesKey::new_decrypt_key(raw_key)?;
+            
+            Ok(Aes192 {
+                encrypt_key,
+                decrypt_key
+            })
+        }
+
+        fn encrypt_mut<'a>(&self, block: &'a mut [u8]) -> &'a [u8] { 
+            aes::encrypt_mut(block, &self.encrypt_key);
+            block
+        }
+
+        fn decrypt_mut<'a>(&self, block: &'a mut [u8]) -> &'a [u8] {
+            aes::decrypt_mut(block, &self.decrypt_key);
+            block
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Random for Aes192 {
+        fn random() -> Self {
+            let key = random_vec!(Aes192::KEY_SIZE);
+            // It is safe to call unwrap here since `new` only returns an error if the 
+            // key is of the wrong size.
+            Aes192::new(&key).unwrap()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct Aes256 {
-        encrypt_key: aes::AES_KEY,
-        decrypt_key: aes::AES_KEY
+        encrypt_key: AesKey,
+        decrypt_key: AesKey
     }
     
     impl Cipher for Aes256 {
@@ -127,8 +210,8 @@ pub mod ciphers {
             if raw_key.len() != Self::KEY_SIZE {
                 return Err(Error::CipherError)
             }
-            let encrypt_key = aes::AES_KEY::new_encrypt_key(raw_key)?;
-            let decrypt_key = aes::AES_KEY::new_decrypt_key(raw_key)?;
+            let encrypt_key = AesKey::new_encrypt_key(raw_key)?;
+            let decrypt_key = AesKey::new_decrypt_key(raw_key)?;
             
             Ok(Aes256 {
                 encrypt_key,
@@ -149,6 +232,7 @@ pub mod ciphers {
         }
     }
 
+    #[cfg(feature = "std")]
     impl Random for Aes256 {
         fn random() -> Self {
             let key = random_vec!(Aes256::KEY_SIZE);
@@ -183,6 +267,30 @@ pub mod ciphers {
             0x5e, 0x56, 0xdc, 0xbd
         ];
 
+        // The AES-192 example vector from appendix C.2 of FIPS 197.
+        const RAW_KEY_192: [u8; Aes192::KEY_SIZE] = [
+            0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07,
+            0x08, 0x09, 0x0a, 0x0b,
+            0x0c, 0x0d, 0x0e, 0x0f,
+            0x10, 0x11, 0x12, 0x13,
+            0x14, 0x15, 0x16, 0x17,
+        ];
+
+        const PLAINTEXT_192: [u8; Aes192::BLOCK_SIZE] = [
+            0x00, 0x11, 0x22, 0x33,
+            0x44, 0x55, 0x66, 0x77,
+            0x88, 0x99, 0xaa, 0xbb,
+            0xcc, 0xdd, 0xee, 0xff,
+        ];
+
+        const CIPHERTEXT_192: [u8; Aes192::BLOCK_SIZE] = [
+            0xdd, 0xa9, 0x7c, 0xa4,
+            0x86, 0x4c, 0xdf, 0xe0,
+            0x6e, 0xaf, 0x70, 0xa0,
+            0xec, 0x0d, 0x71, 0x91
+        ];
+
         const RAW_KEY_256: [u8; Aes256::KEY_SIZE] = [
             0xc0, 0xfe, 0xfe, 0x00,
             0xc0, 0xfe, 0xfe, 0x01,
@@ -217,7 +325,7 @@ pub mod ciphers {
         #[test]
         fn encrypt_aes_128() {
             let aes = Aes128::new(&RAW_KEY_128).unwrap();
-            let mut block = PLAINTEXT_128.clone();
+            let mut block = PLAINTEXT_128;
 
             aes.encrypt_mut(&mut block);
             assert_eq!(block, CIPHERTEXT_128);
@@ -227,13 +335,40 @@ pub mod ciphers {
         #[test]
         fn decrypt_aes_128() {
             let aes = Aes128::new(&RAW_KEY_128).unwrap();
-            let mut block = CIPHERTEXT_128.clone();
+            let mut block = CIPHERTEXT_128;
 
             aes.decrypt_mut(&mut block);
             assert_eq!(block, PLAINTEXT_128);
             assert_eq!(aes.decrypt_block(&CIPHERTEXT_128), PLAINTEXT_128);
         }
         
+        #[test]
+        fn key_aes_192() {
+            assert!(Aes192::new(&[0; Aes192::KEY_SIZE]).is_ok());
+            assert!(Aes192::new(&[0; Aes192::KEY_SIZE + 1]).is_err());
+            assert!(Aes192::new(&[0; Aes128::KEY_SIZE]).is_err());
+        }
+
+        #[test]
+        fn encrypt_aes_192() {
+            let aes = Aes192::new(&RAW_KEY_192).unwrap();
+            let mut block = PLAINTEXT_192;
+
+            aes.encrypt_mut(&mut block);
+            assert_eq!(block, CIPHERTEXT_192);
+            assert_eq!(aes.encrypt_block(&PLAINTEXT_192), CIPHERTEXT_192);
+        }
+    
+        #[test]
+        fn decrypt_aes_192() {
+            let aes = Aes192::new(&RAW_KEY_192).unwrap();
+            let mut block = CIPHERTEXT_192;
+
+            aes.decrypt_mut(&mut block);
+            assert_eq!(block, PLAINTEXT_192);
+            assert_eq!(aes.decrypt_block(&CIPHERTEXT_192), PLAINTEXT_192);
+        }
+        
         #[test]
         fn key_aes_256() {
             assert!(Aes256::new(&[0; Aes256::KEY_SIZE]).is_ok());
@@ -243,7 +378,7 @@ pub mod ciphers {
         #[test]
         fn encrypt_aes_256() {
             let aes = Aes256::new(&RAW_KEY_256).unwrap();
-            let mut block = PLAINTEXT_256.clone();
+            let mut block = PLAINTEXT_256;
             
             aes.encrypt_mut(&mut block);
             assert_eq!(block, CIPHERTEXT_256);
@@ -253,7 +388,7 @@ pub mod ciphers {
         #[test]
         fn decrypt_aes_256() {
             let aes = Aes256::new(&RAW_KEY_256).unwrap();
-            let mut block = CIPHERTEXT_256.clone();
+            let mut block = CIPHERTEXT_256;
             
             aes.decrypt_mut(&mut block);
             assert_eq!(block, PLAINTEXT_256);
@@ -265,10 +400,12 @@ pub mod ciphers {
 pub use ciphers::{
     Cipher, 
     Aes128, 
+    Aes192, 
     Aes256
 };
 
 pub mod padding_modes {
+    use crate::prelude::*;
     use super::Error;
 
     pub trait PaddingMode {
@@ -345,9 +482,84 @@ pub mod padding_modes {
         }
     }
 
+    /// ANSI X9.23 padding: zeros followed by a byte giving the padding size.
+    #[derive(Clone, Debug)]
+    pub struct AnsiX923 {
+        block_size: usize
+    }
+
+    impl PaddingMode for AnsiX923 {
+        fn new(block_size: usize) -> Self {
+            Self { block_size }
+        }
+
+        fn block_size(&self) -> usize { self.block_size }
+
+        fn pad_mut<'a>(&self, buffer: &'a mut [u8], size: usize) -> Result<&'a [u8], Error> {
+            if buffer.len() <= size || buffer.len() > size + 255 {
+                return Err(Error::PaddingError);
+            }
+            let padding_size = buffer.len() - size;
+            Pkcs7::set_bytes(&mut buffer[size..], 0);
+            buffer[size + padding_size - 1] = padding_size as u8;
+            Ok(buffer)
+        }
+
+        fn unpad_mut(&self, buffer: &[u8]) -> Result<usize, Error> {
+            let padding_size = *buffer.last().ok_or(Error::PaddingError)? as usize;
+            let is_valid = 0 < padding_size && padding_size <= buffer.len() && buffer
+                .iter()
+                .rev()
+                .skip(1)
+                .take(padding_size - 1)
+                .all(|&byte| byte == 0);
+            if !is_valid {
+                return Err(Error::PaddingError);
+            }
+            Ok(buffer.len() - padding_size)
+        }
+    }
+
+    /// ISO/IEC 7816-4 padding: a single `0x80` byte followed by zeros.
+    #[derive(Clone, Debug)]
+    pub struct Iso7816 {
+        block_size: usize
+    }
+
+    impl PaddingMode for Iso7816 {
+        fn new(block_size: usize) -> Self {
+            Self { block_size }
+        }
+
+        fn block_size(&self) -> usize { self.block_size }
+
+        fn pad_mut<'a>(&self, buffer: &'a mut [u8], size: usize) -> Result<&'a [u8], Error> {
+            if buffer.len() <= size || buffer.len() > size + self.block_size {
+                return Err(Error::PaddingError);
+            }
+            Pkcs7::set_bytes(&mut buffer[size..], 0);
+            buffer[size] = 0x80;
+            Ok(buffer)
+        }
+
+        fn unpad_mut(&self, buffer: &[u8]) -> Result<usize, Error> {
+            // The padding cannot be longer than a block.
+            let padding_size = buffer
+                .iter()
+                .rev()
+                .take(self.block_size)
+                .position(|&byte| byte != 0)
+                .ok_or(Error::PaddingError)? + 1;
+            if buffer[buffer.len() - padding_size] != 0x80 {
+                return Err(Error::PaddingError);
+            }
+            Ok(buffer.len() - padding_size)
+        }
+    }
+
     #[cfg(test)]
     mod tests {
-        use super::{PaddingMode, Pkcs7};
+        use super::{PaddingMode, Pkcs7, AnsiX923, Iso7816};
        
         #[test]
         fn padding_size() {
@@ -392,36 +604,88 @@ pub mod padding_modes {
             let result = pkcs7.pad_mut(&mut buffer, 4);
             assert!(result.is_err());
 
-            let result = pkcs7.unpad_mut(&mut buffer);
+            let result = pkcs7.unpad_mut(&buffer);
             assert!(result.is_err());
 
-            let result = pkcs7.unpad_mut(&mut [3, 2, 1, 0]);
+            let result = pkcs7.unpad_mut(&[3, 2, 1, 0]);
             assert!(result.is_err());
         }
+
+        #[test]
+        fn other_padding_modes() {
+            let ansi_x923 = AnsiX923::new(8);
+            let mut buffer = vec![4, 5, 6, 7, 8];
+            ansi_x923.pad_buffer(&mut buffer).unwrap();
+            assert_eq!(buffer, [4, 5, 6, 7, 8, 0, 0, 3]);
+            assert_eq!(ansi_x923.unpad_mut(&buffer).unwrap(), 5);
+            assert!(ansi_x923.unpad_mut(&[4, 5, 6, 7, 8, 1, 0, 3]).is_err());
+            assert!(ansi_x923.unpad_mut(&[4, 5, 6, 7, 8, 0, 0, 0]).is_err());
+
+            let iso_7816 = Iso7816::new(8);
+            let mut buffer = vec![4, 5, 6, 7, 8];
+            iso_7816.pad_buffer(&mut buffer).unwrap();
+            assert_eq!(buffer, [4, 5, 6, 7, 8, 0x80, 0, 0]);
+            assert_eq!(iso_7816.unpad_mut(&buffer).unwrap(), 5);
+            assert_eq!(iso_7816.unpad_mut(&[0x80; 8]).unwrap(), 7);
+            assert!(iso_7816.unpad_mut(&[4, 5, 6, 7, 8, 0x81, 0, 0]).is_err());
+            assert!(iso_7816.unpad_mut(&[0; 8]).is_err());
+            assert!(iso_7816.unpad_mut(&[]).is_err());
+        }
+
+        #[test]
+        fn iso_7816_full_block() {
+            let iso_7816 = Iso7816::new(4);
+            let mut buffer = vec![1, 2, 3, 4];
+            iso_7816.pad_buffer(&mut buffer).unwrap();
+            assert_eq!(buffer, [1, 2, 3, 4, 0x80, 0, 0, 0]);
+            assert_eq!(iso_7816.unpad_buffer(&mut buffer).unwrap(), &[1, 2, 3, 4]);
+
+            // The padding cannot span more than a block.
+            assert!(iso_7816.unpad_mut(&[0x80, 0, 0, 0, 0, 0, 0, 0]).is_err());
+            assert_eq!(iso_7816.unpad_mut(&[0, 0, 0, 0, 0x80, 0, 0, 0]).unwrap(), 4);
+            assert!(iso_7816.pad_mut(&mut [1, 2, 3, 4], 4).is_err());
+        }
     }
 }
 
 pub use padding_modes::{
     PaddingMode,
-    Pkcs7
+    Pkcs7,
+    AnsiX923,
+    Iso7816
 };
 
 pub mod cipher_modes {
-    use std::mem;
+    use core::mem;
+    #[cfg(feature = "std")]
+    use std::io::{self, Read, Write};
 
-    use rand;
+    #[cfg(feature = "std")]
     use rand::Rng;
 
-    use super::Error;
+    use super::{Error, DecryptionReport};
     use super::ciphers::{Cipher, Key};
     use super::padding_modes::PaddingMode;
 
+    use crate::prelude::*;
+    #[cfg(feature = "std")]
     use crate::random_vec;
+    #[cfg(feature = "std")]
     use crate::crypto::random::Random;
+    use crate::math::gf128::Gf128;
+    use crate::utils::bytes;
 
     pub type Iv = [u8];
     pub type Nonce = [u8];
 
+    /// Modes which take an IV (or nonce) when they are created.
+    pub trait IvMode: Sized {
+        const IV_SIZE: usize;
+
+        /// Returns a copy of the mode, using the same key but the given IV.
+        fn with_iv(&self, iv: &Iv) -> Result<Self, Error>;
+    }
+
     /// Block cipher mode trait.
     pub trait BlockCipherMode<C: Cipher, P: PaddingMode>: Sized {
 
@@ -429,7 +693,7 @@ pub mod cipher_modes {
         fn encrypt_mut<'a>(&mut self, buffer: &'a mut [u8], end: usize) -> Result<&'a [u8], Error>;
 
         /// Decrypt a mutable buffer in-place. Returns the buffer size after unpadding.
-        fn decrypt_mut<'a>(&mut self, buffer: &'a mut [u8]) -> Result<usize, Error>;
+        fn decrypt_mut(&mut self, buffer: &mut [u8]) -> Result<usize, Error>;
         
         fn encrypt_buffer(&mut self, input_buffer: &[u8]) -> Result<Vec<u8>, Error> {
             let padding_size = P::min_padding_size(C::BLOCK_SIZE, input_buffer.len());
@@ -455,6 +719,30 @@ pub mod cipher_modes {
             let output_buffer = self.decrypt_buffer(input_buffer)?;
             String::from_utf8(output_buffer).map_err(Error::from)
         }
+
+        /// Decrypt input and interpret the result as an UTF-8 string, replacing invalid
+        /// sequences with `U+FFFD`. Fails if the padding is invalid.
+        fn decrypt_str_lossy(&mut self, input_buffer: &[u8]) -> Result<String, Error> {
+            let output_buffer = self.decrypt_buffer(input_buffer)?;
+            Ok(String::from_utf8_lossy(&output_buffer).into_owned())
+        }
+
+        /// Decrypt input without failing on invalid padding or UTF-8. Returns the plaintext
+        /// together with a report describing its validity. If the padding is invalid, the
+        /// entire decrypted buffer is returned.
+        fn decrypt_with_report(&mut self, input_buffer: &[u8]) -> Result<(Vec<u8>, DecryptionReport), Error> {
+            let mut output_buffer = input_buffer.to_vec();
+            let valid_padding = match self.decrypt_mut(&mut output_buffer) {
+                Ok(output_size) => {
+                    output_buffer.truncate(output_size);
+                    true
+                },
+                Err(Error::PaddingError) => false,
+                Err(error) => return Err(error),
+            };
+            let report = DecryptionReport::new(&output_buffer, valid_padding);
+            Ok((output_buffer, report))
+        }
     }
     
     /// Generic ECB-mode type.
@@ -467,12 +755,13 @@ pub mod cipher_modes {
     impl<C: Cipher, P: PaddingMode> Ecb<C, P> {
         pub fn new(key: &Key) -> Result<Self, Error> {
             Ok(Self { 
-                cipher: C::new(&key)?, 
+                cipher: C::new(key)?, 
                 padding: P::new(C::BLOCK_SIZE)
             })
         }
     }
 
+    #[cfg(feature = "std")]
     impl<C: Cipher + Random, P: PaddingMode> Random for Ecb<C, P> {
         fn random() -> Self {
             Self { 
@@ -486,16 +775,16 @@ pub mod cipher_modes {
         fn encrypt_mut<'a>(&mut self, buffer: &'a mut [u8], size: usize) -> Result<&'a [u8], Error> {
             assert_eq!(buffer.len() % C::BLOCK_SIZE, 0);
             self.padding.pad_mut(buffer, size)?;
-            for mut block in buffer.chunks_mut(C::BLOCK_SIZE) {
-                self.cipher.encrypt_mut(&mut block);
+            for block in buffer.chunks_mut(C::BLOCK_SIZE) {
+                self.cipher.encrypt_mut(block);
             }
             Ok(buffer)
         }
 
-        fn decrypt_mut<'a>(&mut self, buffer: &'a mut [u8]) -> Result<usize, Error> {
+        fn decrypt_mut(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
             assert_eq!(buffer.len() % C::BLOCK_SIZE, 0);
-            for mut block in buffer.chunks_mut(C::BLOCK_SIZE) {
-                self.cipher.decrypt_mut(&mut block);
+            for block in buffer.chunks_mut(C::BLOCK_SIZE) {
+                self.cipher.decrypt_mut(block);
             }
             self.padding.unpad_mut(buffer)
         }
@@ -515,18 +804,25 @@ pub mod cipher_modes {
                 return Err(Error::CipherError)
             }
             Ok(Self { 
-                cipher: C::new(&key)?, 
+                cipher: C::new(key)?, 
                 padding: P::new(C::BLOCK_SIZE),
                 iv: iv.to_owned(),
             })
         }
-        
-        fn xor_mut<'a>(lhs: &'a mut [u8], rhs: &[u8]) -> &'a [u8] {
-            lhs.iter_mut().zip(rhs).for_each(|(x, y)| *x ^= y);
-            lhs
+    }
+
+    impl<C: Cipher + Clone, P: PaddingMode + Clone> IvMode for Cbc<C, P> {
+        const IV_SIZE: usize = C::BLOCK_SIZE;
+
+        fn with_iv(&self, iv: &Iv) -> Result<Self, Error> {
+            if iv.len() != Self::IV_SIZE {
+                return Err(Error::CipherError)
+            }
+            Ok(Self { iv: iv.to_owned(), ..self.clone() })
         }
     }
 
+    #[cfg(feature = "std")]
     impl<C: Cipher + Random, P: PaddingMode> Random for Cbc<C, P> {
         fn random() -> Self {
             Self {
@@ -541,28 +837,30 @@ pub mod cipher_modes {
         fn encrypt_mut<'a>(&mut self, buffer: &'a mut [u8], size: usize) -> Result<&'a [u8], Error> {
             assert_eq!(buffer.len() % C::BLOCK_SIZE, 0);
             self.padding.pad_mut(buffer, size)?;
-            for mut block in buffer.chunks_mut(C::BLOCK_SIZE) {
-                Self::xor_mut(&mut block, &self.iv);
-                self.cipher.encrypt_mut(&mut block);
+            for block in buffer.chunks_mut(C::BLOCK_SIZE) {
+                bytes::xor_in_place(block, &self.iv);
+                self.cipher.encrypt_mut(block);
                 self.iv = block.to_owned();
             }
             Ok(buffer)
         }
 
-        fn decrypt_mut<'a>(&mut self, buffer: &'a mut [u8]) -> Result<usize, Error> {
+        fn decrypt_mut(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
             assert_eq!(buffer.len() % C::BLOCK_SIZE, 0);
-            for mut block in buffer.chunks_mut(C::BLOCK_SIZE) {
+            for block in buffer.chunks_mut(C::BLOCK_SIZE) {
                 let next_iv = block.to_owned();
-                self.cipher.decrypt_mut(&mut block);
-                Self::xor_mut(&mut block, &self.iv); 
+                self.cipher.decrypt_mut(block);
+                bytes::xor_in_place(block, &self.iv);
                 self.iv = next_iv;
             }
             self.padding.unpad_mut(buffer)
         }
     }
 
-    /// Stream cipher mode trait.
-    pub trait StreamCipherMode: Sized + Iterator<Item=u8> {
+    /// Stream cipher mode trait. Modes with an independent keystream get an implementation for
+    /// free by implementing `Iterator<Item=u8>`, while modes where the keystream depends on the
+    /// ciphertext (like CFB) implement the trait directly.
+    pub trait StreamCipherMode: Sized {
         /// Encrypt a mutable buffer in-place.
         fn encrypt_mut<'a>(&mut self, buffer: &'a mut [u8]) -> Result<&'a [u8], Error>;
 
@@ -593,14 +891,84 @@ pub mod cipher_modes {
             let output_buffer = self.decrypt_buffer(input_buffer)?;
             String::from_utf8(output_buffer).map_err(Error::from)
         }
+
+        /// Decrypt input and interpret the result as an UTF-8 string, replacing invalid
+        /// sequences with `U+FFFD`.
+        fn decrypt_str_lossy(&mut self, input_buffer: &[u8]) -> Result<String, Error> {
+            let output_buffer = self.decrypt_buffer(input_buffer)?;
+            Ok(String::from_utf8_lossy(&output_buffer).into_owned())
+        }
+
+        /// Decrypt input without failing on invalid UTF-8. Returns the plaintext together with
+        /// a report describing its validity.
+        fn decrypt_with_report(&mut self, input_buffer: &[u8]) -> Result<(Vec<u8>, DecryptionReport), Error> {
+            let output_buffer = self.decrypt_buffer(input_buffer)?;
+            let report = DecryptionReport::new(&output_buffer, true);
+            Ok((output_buffer, report))
+        }
+
+        /// Encrypt the data read from `reader` and write the result to `writer`. The input is
+        /// processed in fixed-size chunks, so arbitrarily large inputs may be encrypted in
+        /// constant memory. Returns the number of bytes processed. (See `crypto::io` for
+        /// `Read` and `Write` adapters which apply the mode as data passes through.)
+        #[cfg(feature = "std")]
+        fn encrypt_stream<R: Read, W: Write>(&mut self, reader: R, writer: W) -> io::Result<u64> {
+            process_stream(reader, writer, |buffer| self.encrypt_mut(buffer).map(|_| ()))
+        }
+
+        /// Decrypt the data read from `reader` and write the result to `writer`. Returns the
+        /// number of bytes processed.
+        #[cfg(feature = "std")]
+        fn decrypt_stream<R: Read, W: Write>(&mut self, reader: R, writer: W) -> io::Result<u64> {
+            process_stream(reader, writer, |buffer| self.decrypt_mut(buffer).map(|_| ()))
+        }
+    }
+
+    /// The size of the chunks read by `StreamCipherMode::encrypt_stream` and `decrypt_stream`.
+    #[cfg(feature = "std")]
+    const STREAM_CHUNK_SIZE: usize = 1 << 16;
+
+    #[cfg(feature = "std")]
+    fn process_stream<R, W, F>(mut reader: R, mut writer: W, mut process: F) -> io::Result<u64>
+    where
+        R: Read,
+        W: Write,
+        F: FnMut(&mut [u8]) -> Result<(), Error>
+    {
+        let mut buffer = vec![0; STREAM_CHUNK_SIZE];
+        let mut total_size = 0;
+        loop {
+            let size = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(size) => size,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            process(&mut buffer[..size]).map_err(io::Error::other)?;
+            writer.write_all(&buffer[..size])?;
+            total_size += size as u64;
+        }
+        writer.flush()?;
+        Ok(total_size)
     }
     
     /// Generic implementation of `StreamCipherMode` for implementaions of `Iterator<Item=u8>`.
     /// This allows us to implement `Iterator<Item=u8>` and get `StreamCipherMode` for free.
     impl<I: Iterator<Item=u8>> StreamCipherMode for I {
         fn encrypt_mut<'a>(&mut self, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
-            for (x, y) in buffer.iter_mut().zip(self) {
-                *x ^= y;
+            // The keystream is buffered so that it can be applied using `xor_in_place`. We never
+            // consume more of the keystream than the size of the buffer.
+            let mut keystream = [0; 64];
+            for chunk in buffer.chunks_mut(keystream.len()) {
+                let mut size = 0;
+                for (key, byte) in keystream.iter_mut().zip(self.by_ref().take(chunk.len())) {
+                    *key = byte;
+                    size += 1;
+                }
+                bytes::xor_in_place(chunk, &keystream[..size]);
+                if size < chunk.len() {
+                    break;
+                }
             }
             Ok(buffer)
         }
@@ -633,7 +1001,7 @@ pub mod cipher_modes {
                 return Err(Error::CipherError)
             }
             Ok(Self { 
-                cipher: C::new(&key)?,
+                cipher: C::new(key)?,
                 nonce: nonce.to_owned(),
                 counter: vec![0; C::BLOCK_SIZE / 2],
                 key: Vec::new(),
@@ -656,6 +1024,7 @@ pub mod cipher_modes {
         }
     }
 
+    #[cfg(feature = "std")]
     impl<C: Cipher + Random> Random for Ctr<C> {
         fn random() -> Self {
             Self {
@@ -718,6 +1087,7 @@ pub mod cipher_modes {
         }
     }
 
+    #[cfg(feature = "std")]
     impl Random for RepeatingKeyXor {
         fn random() -> Self {
             let key_size = rand::thread_rng().gen_range(2, 32);
@@ -739,13 +1109,405 @@ pub mod cipher_modes {
         }
     }
 
+    /// The RC4 stream cipher.
+    #[derive(Clone, Debug)]
+    pub struct Rc4 {
+        state: [u8; 256],
+        i: u8,
+        j: u8
+    }
+
+    impl Rc4 {
+        /// The key size used by `Random`.
+        pub const KEY_SIZE: usize = 16;
+
+        /// Returns an RC4 instance using the given key, which must be between 1 and 256 bytes.
+        pub fn new(key: &Key) -> Result<Self, Error> {
+            if key.is_empty() || key.len() > 256 {
+                return Err(Error::CipherError)
+            }
+            let mut state = [0; 256];
+            state.iter_mut().enumerate().for_each(|(i, x)| *x = i as u8);
+            // The key-scheduling algorithm.
+            let mut j: u8 = 0;
+            for i in 0..256 {
+                j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+                state.swap(i, j as usize);
+            }
+            Ok(Self { state, i: 0, j: 0 })
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Random for Rc4 {
+        fn random() -> Self {
+            // It is safe to unwrap here since the key size is valid.
+            Self::new(&random_vec!(Self::KEY_SIZE)).unwrap()
+        }
+    }
+
+    impl Iterator for Rc4 {
+        type Item = u8;
+
+        fn next(&mut self) -> Option<u8> {
+            self.i = self.i.wrapping_add(1);
+            self.j = self.j.wrapping_add(self.state[self.i as usize]);
+            self.state.swap(self.i as usize, self.j as usize);
+            let index = self.state[self.i as usize].wrapping_add(self.state[self.j as usize]);
+            Some(self.state[index as usize])
+        }
+    }
+
+    /// Generic OFB-mode type.
+    #[derive(Clone, Debug)]
+    pub struct Ofb<C: Cipher> {
+        cipher: C,
+        key: Vec<u8>,
+        offset: usize
+    }
+
+    impl<C: Cipher> Ofb<C> {
+        pub fn new(key: &Key, iv: &Iv) -> Result<Self, Error> {
+            if iv.len() != C::BLOCK_SIZE {
+                return Err(Error::CipherError)
+            }
+            Ok(Self {
+                cipher: C::new(key)?,
+                key: iv.to_owned(),
+                offset: C::BLOCK_SIZE
+            })
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<C: Cipher + Random> Random for Ofb<C> {
+        fn random() -> Self {
+            Self {
+                cipher: C::random(),
+                key: random_vec!(C::BLOCK_SIZE),
+                offset: C::BLOCK_SIZE
+            }
+        }
+    }
+
+    impl<C: Cipher> Iterator for Ofb<C> {
+        type Item = u8;
+
+        fn next(&mut self) -> Option<u8> {
+            if self.offset >= C::BLOCK_SIZE {
+                self.offset = 0;
+                self.cipher.encrypt_mut(&mut self.key);
+            }
+            let offset = self.offset;
+            self.offset += 1;
+            Some(self.key[offset])
+        }
+    }
+
+    /// Generic GCM-mode type (NIST SP 800-38D) for ciphers with a 128-bit block size. The nonce
+    /// is fixed when the instance is created, so encrypting several messages with the same
+    /// instance reuses the nonce.
+    #[derive(Clone, Debug)]
+    pub struct Gcm<C: Cipher> {
+        cipher: C,
+        hash_key: Gf128,
+        initial_counter: Vec<u8>
+    }
+
+    impl<C: Cipher> Gcm<C> {
+        /// The size of the full-length authentication tag.
+        pub const TAG_SIZE: usize = 16;
+
+        pub fn new(key: &Key, nonce: &Nonce) -> Result<Self, Error> {
+            Self::from_cipher(C::new(key)?, nonce)
+        }
+
+        /// Returns a GCM instance using the given cipher. 96-bit nonces are used directly as
+        /// the initial counter block, while nonces of any other length are hashed.
+        pub fn from_cipher(cipher: C, nonce: &Nonce) -> Result<Self, Error> {
+            if C::BLOCK_SIZE != Gf128::BLOCK_SIZE || nonce.is_empty() {
+                return Err(Error::CipherError)
+            }
+            let hash_key = Gf128::from_block(&cipher.encrypt_block(&[0; 16]));
+            let initial_counter = if nonce.len() == 12 {
+                [nonce, &[0, 0, 0, 1]].concat()
+            } else {
+                ghash(hash_key, &[], nonce).to_block().to_vec()
+            };
+            Ok(Self { cipher, hash_key, initial_counter })
+        }
+
+        /// Returns the authentication key H = E(K, 0^128).
+        pub fn hash_key(&self) -> Gf128 {
+            self.hash_key
+        }
+
+        /// Returns GHASH of the associated data and ciphertext, padded and followed by a
+        /// block containing their lengths in bits.
+        pub fn ghash(&self, aad: &[u8], ciphertext: &[u8]) -> Gf128 {
+            ghash(self.hash_key, aad, ciphertext)
+        }
+
+        /// XORs the keystream into the buffer. The first counter block is reserved for the tag.
+        pub fn apply_keystream(&self, buffer: &mut [u8]) {
+            let mut counter = self.initial_counter.clone();
+            for chunk in buffer.chunks_mut(C::BLOCK_SIZE) {
+                Self::update_counter(&mut counter);
+                let keystream = self.cipher.encrypt_block(&counter);
+                bytes::xor_in_place(chunk, &keystream[..chunk.len()]);
+            }
+        }
+
+        /// Returns the full-length tag of the given associated data and ciphertext.
+        pub fn compute_tag(&self, aad: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+            let mask = Gf128::from_block(&self.cipher.encrypt_block(&self.initial_counter));
+            (self.ghash(aad, ciphertext) + mask).to_block().to_vec()
+        }
+
+        /// Returns the ciphertext together with the full-length tag.
+        pub fn encrypt(&self, aad: &[u8], plaintext: &[u8]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+            let mut ciphertext = plaintext.to_vec();
+            self.apply_keystream(&mut ciphertext);
+            let tag = self.compute_tag(aad, &ciphertext);
+            Ok((ciphertext, tag))
+        }
+
+        /// Verifies the full-length tag and returns the decrypted ciphertext. Truncated tags are
+        /// never valid.
+        pub fn decrypt(&self, aad: &[u8], ciphertext: &[u8], tag: &[u8]) -> Result<Vec<u8>, Error> {
+            if tag.len() != Self::TAG_SIZE {
+                return Err(Error::AuthenticationError)
+            }
+            if !bytes::constant_time_eq(&self.compute_tag(aad, ciphertext), tag) {
+                return Err(Error::AuthenticationError)
+            }
+            let mut plaintext = ciphertext.to_vec();
+            self.apply_keystream(&mut plaintext);
+            Ok(plaintext)
+        }
+
+        // The counter is the last 32 bits of the counter block, updated as a big-endian integer.
+        fn update_counter(counter: &mut [u8]) {
+            let offset = counter.len() - 4;
+            let value = u32::from_be_bytes([
+                counter[offset], counter[offset + 1], counter[offset + 2], counter[offset + 3]
+            ]);
+            counter[offset..].copy_from_slice(&value.wrapping_add(1).to_be_bytes());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<C: Cipher + Random> Random for Gcm<C> {
+        fn random() -> Self {
+            // It is okay to unwrap here since the nonce size is valid, and `from_cipher` only
+            // fails for ciphers with the wrong block size.
+            Self::from_cipher(C::random(), &random_vec!(12)).unwrap()
+        }
+    }
+
+    /// Returns GHASH of the associated data and ciphertext under the given hash key.
+    pub fn ghash(hash_key: Gf128, aad: &[u8], ciphertext: &[u8]) -> Gf128 {
+        let lengths = Gf128::new(((aad.len() as u128 * 8) << 64) | (ciphertext.len() as u128 * 8));
+        aad.chunks(Gf128::BLOCK_SIZE)
+            .chain(ciphertext.chunks(Gf128::BLOCK_SIZE))
+            .map(Gf128::from_block)
+            .chain(core::iter::once(lengths))
+            .fold(Gf128::zero(), |state, block| (state + block) * hash_key)
+    }
+
+    /// Generic XTS-mode type (IEEE 1619) for ciphers with a 128-bit block size. The key is the
+    /// concatenation of the data key and the tweak key. Each sector (data unit) is encrypted
+    /// independently under a tweak derived from the sector number, and sectors which are not a
+    /// multiple of the block size are handled using ciphertext stealing.
+    #[derive(Clone, Debug)]
+    pub struct Xts<C: Cipher> {
+        data_cipher: C,
+        tweak_cipher: C
+    }
+
+    impl<C: Cipher> Xts<C> {
+        pub fn new(key: &Key) -> Result<Self, Error> {
+            if C::BLOCK_SIZE != 16 || key.len() != 2 * C::KEY_SIZE {
+                return Err(Error::CipherError)
+            }
+            let (data_key, tweak_key) = key.split_at(C::KEY_SIZE);
+            Ok(Self { data_cipher: C::new(data_key)?, tweak_cipher: C::new(tweak_key)? })
+        }
+
+        /// Encrypts the given sector in-place. Sectors must be at least one block long.
+        pub fn encrypt_sector(&self, sector: u128, buffer: &mut [u8]) -> Result<(), Error> {
+            if buffer.len() < C::BLOCK_SIZE {
+                return Err(Error::CipherError)
+            }
+            let full_size = buffer.len() - buffer.len() % C::BLOCK_SIZE;
+            let mut tweak = self.initial_tweak(sector);
+            for block in buffer[..full_size].chunks_mut(C::BLOCK_SIZE) {
+                Self::process_block(|block| self.data_cipher.encrypt_mut(block), block, tweak);
+                tweak = multiply_by_alpha(tweak);
+            }
+            if full_size < buffer.len() {
+                // The tail steals the end of the last full ciphertext block, and the final
+                // (partial) ciphertext block is the beginning of that block.
+                let (head, tail) = buffer.split_at_mut(full_size);
+                let last_block = &mut head[full_size - C::BLOCK_SIZE..];
+                let mut block = [&tail[..], &last_block[tail.len()..]].concat();
+                tail.copy_from_slice(&last_block[..tail.len()]);
+                Self::process_block(|block| self.data_cipher.encrypt_mut(block), &mut block, tweak);
+                last_block.copy_from_slice(&block);
+            }
+            Ok(())
+        }
+
+        /// Decrypts the given sector in-place. Sectors must be at least one block long.
+        pub fn decrypt_sector(&self, sector: u128, buffer: &mut [u8]) -> Result<(), Error> {
+            if buffer.len() < C::BLOCK_SIZE {
+                return Err(Error::CipherError)
+            }
+            let tail_size = buffer.len() % C::BLOCK_SIZE;
+            // With ciphertext stealing, the last full block is decrypted using the final tweak.
+            let full_size = if tail_size > 0 {
+                buffer.len() - tail_size - C::BLOCK_SIZE
+            } else {
+                buffer.len()
+            };
+            let mut tweak = self.initial_tweak(sector);
+            for block in buffer[..full_size].chunks_mut(C::BLOCK_SIZE) {
+                Self::process_block(|block| self.data_cipher.decrypt_mut(block), block, tweak);
+                tweak = multiply_by_alpha(tweak);
+            }
+            if tail_size > 0 {
+                let (last_block, tail) = buffer[full_size..].split_at_mut(C::BLOCK_SIZE);
+                let final_tweak = multiply_by_alpha(tweak);
+                Self::process_block(|block| self.data_cipher.decrypt_mut(block), last_block, final_tweak);
+                let mut block = [&tail[..], &last_block[tail_size..]].concat();
+                tail.copy_from_slice(&last_block[..tail_size]);
+                Self::process_block(|block| self.data_cipher.decrypt_mut(block), &mut block, tweak);
+                last_block.copy_from_slice(&block);
+            }
+            Ok(())
+        }
+
+        /// Returns the encrypted sector number, interpreted as a little-endian integer.
+        fn initial_tweak(&self, sector: u128) -> u128 {
+            let mut block = sector.to_le_bytes();
+            self.tweak_cipher.encrypt_mut(&mut block);
+            u128::from_le_bytes(block)
+        }
+
+        fn process_block<F: FnOnce(&mut [u8]) -> &[u8]>(cipher: F, block: &mut [u8], tweak: u128) {
+            let tweak = tweak.to_le_bytes();
+            bytes::xor_in_place(block, &tweak);
+            cipher(block);
+            bytes::xor_in_place(block, &tweak);
+        }
+    }
+
+    /// Multiplies the tweak by the primitive element x of GF(2^128). XTS interprets tweaks as
+    /// little-endian integers where bit `i` is the coefficient of x^i. (This is the reverse of
+    /// the bit order used by `Gf128`.)
+    fn multiply_by_alpha(tweak: u128) -> u128 {
+        let carry = tweak >> 127;
+        (tweak << 1) ^ (carry * 0x87)
+    }
+
+    /// Generic CFB-mode type. The segment size is given in bytes, and ranges from 1 (CFB-8) to
+    /// the block size of the cipher (full-block CFB).
+    ///
+    /// Since the keystream depends on the ciphertext, `Cfb` implements `StreamCipherMode`
+    /// directly rather than `Iterator<Item=u8>`.
+    #[derive(Clone, Debug)]
+    pub struct Cfb<C: Cipher> {
+        cipher: C,
+        register: Vec<u8>,
+        key: Vec<u8>,
+        segment: Vec<u8>,
+        segment_size: usize
+    }
+
+    impl<C: Cipher> Cfb<C> {
+        pub fn new(key: &Key, iv: &Iv, segment_size: usize) -> Result<Self, Error> {
+            if iv.len() != C::BLOCK_SIZE || segment_size == 0 || segment_size > C::BLOCK_SIZE {
+                return Err(Error::CipherError)
+            }
+            Ok(Self {
+                cipher: C::new(key)?,
+                register: iv.to_owned(),
+                key: Vec::new(),
+                segment: Vec::with_capacity(segment_size),
+                segment_size
+            })
+        }
+
+        /// Returns a CFB-8 instance, which shifts a single byte into the register at a time.
+        pub fn cfb8(key: &Key, iv: &Iv) -> Result<Self, Error> {
+            Self::new(key, iv, 1)
+        }
+
+        /// Returns a full-block CFB instance.
+        pub fn full_block(key: &Key, iv: &Iv) -> Result<Self, Error> {
+            Self::new(key, iv, C::BLOCK_SIZE)
+        }
+
+        pub fn segment_size(&self) -> usize {
+            self.segment_size
+        }
+
+        /// Returns the next keystream byte. `feedback` is called with the keystream byte and
+        /// should return the corresponding ciphertext byte, which is shifted into the register
+        /// once the segment is complete.
+        fn process_byte<F: FnOnce(u8) -> u8>(&mut self, feedback: F) -> u8 {
+            if self.segment.is_empty() {
+                self.key = self.cipher.encrypt_block(&self.register);
+            }
+            let key = self.key[self.segment.len()];
+            self.segment.push(feedback(key));
+            if self.segment.len() == self.segment_size {
+                self.register.drain(..self.segment_size);
+                self.register.append(&mut self.segment);
+            }
+            key
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<C: Cipher + Random> Random for Cfb<C> {
+        fn random() -> Self {
+            Self {
+                cipher: C::random(),
+                register: random_vec!(C::BLOCK_SIZE),
+                key: Vec::new(),
+                segment: Vec::with_capacity(C::BLOCK_SIZE),
+                segment_size: C::BLOCK_SIZE
+            }
+        }
+    }
+
+    impl<C: Cipher> StreamCipherMode for Cfb<C> {
+        fn encrypt_mut<'a>(&mut self, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
+            for byte in buffer.iter_mut() {
+                let plaintext = *byte;
+                *byte = self.process_byte(|key| key ^ plaintext) ^ plaintext;
+            }
+            Ok(buffer)
+        }
+
+        fn decrypt_mut<'a>(&mut self, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
+            for byte in buffer.iter_mut() {
+                let ciphertext = *byte;
+                *byte = self.process_byte(|_| ciphertext) ^ ciphertext;
+            }
+            Ok(buffer)
+        }
+    }
+
     #[cfg(test)]
     mod tests {
         use std::convert::TryInto;
 
         use super::*;
         use crate::crypto::symmetric::padding_modes::Pkcs7;
-        use crate::crypto::symmetric::ciphers::{Cipher, Aes128};
+        use crate::crypto::symmetric::ciphers::{Cipher, Aes128, Aes256};
+        use crate::encoding::hex;
 
         type Aes128Ecb = Ecb<Aes128, Pkcs7>;
         type Aes128Cbc = Cbc<Aes128, Pkcs7>;
@@ -833,7 +1595,7 @@ pub mod cipher_modes {
         #[test]
         fn decrypt_ecb_mode() {
             let mut cipher = Aes128Ecb::new(&RAW_KEY).unwrap();
-            let mut buffer = ECB_CIPHERTEXT.clone();
+            let mut buffer = ECB_CIPHERTEXT;
             let result = cipher.decrypt_mut(&mut buffer);
             assert_eq!(buffer[..result.unwrap()], PLAINTEXT);
             
@@ -860,7 +1622,7 @@ pub mod cipher_modes {
         #[test]
         fn decrypt_cbc_mode() {
             let mut cipher = Aes128Cbc::new(&RAW_KEY, &RAW_IV).unwrap();
-            let mut buffer = CBC_CIPHERTEXT.clone();
+            let mut buffer = CBC_CIPHERTEXT;
             let result = cipher.decrypt_mut(&mut buffer);
             assert_eq!(buffer[..result.unwrap()], PLAINTEXT);
             
@@ -908,6 +1670,7 @@ pub mod cipher_modes {
         }
 
         #[test]
+        #[cfg(feature = "std")]
         fn seekable_ctr_mode() {
             let length = rand::thread_rng().gen_range(0, 1024);
 
@@ -931,6 +1694,216 @@ pub mod cipher_modes {
             }
         }
 
+        #[test]
+        #[cfg(feature = "std")]
+        fn ctr_mode_stream() {
+            let plaintext = (0..100_000).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+            let mut cipher = Aes128Ctr::new(&RAW_KEY, &RAW_NONCE).unwrap();
+            let mut ciphertext = Vec::new();
+            let size = cipher.encrypt_stream(&plaintext[..], &mut ciphertext).unwrap();
+            assert_eq!(size, plaintext.len() as u64);
+
+            let mut cipher = Aes128Ctr::new(&RAW_KEY, &RAW_NONCE).unwrap();
+            assert_eq!(ciphertext, cipher.encrypt_buffer(&plaintext).unwrap());
+
+            let mut cipher = Aes128Ctr::new(&RAW_KEY, &RAW_NONCE).unwrap();
+            let mut buffer = Vec::new();
+            cipher.decrypt_stream(&ciphertext[..], &mut buffer).unwrap();
+            assert_eq!(buffer, plaintext);
+        }
+
+        #[test]
+        fn decrypt_with_report() {
+            // The plaintext is not valid UTF-8, since 0xc0 is never part of a valid sequence.
+            let mut cipher = Aes128Cbc::new(&RAW_KEY, &RAW_IV).unwrap();
+            let (plaintext, report) = cipher.decrypt_with_report(&CBC_CIPHERTEXT).unwrap();
+            assert_eq!(plaintext, PLAINTEXT);
+            assert_eq!(report, DecryptionReport { valid_padding: true, invalid_utf8_offset: Some(0) });
+            assert!(!report.is_valid());
+            assert!(cipher.decrypt_str(&CBC_CIPHERTEXT).is_err());
+            assert_eq!(cipher.decrypt_str_lossy(&CBC_CIPHERTEXT).unwrap().chars().next(), Some('\u{fffd}'));
+
+            // Corrupting the last byte of the IV corrupts the padding of a single-block message.
+            let mut cipher = Aes128Cbc::new(&RAW_KEY, &RAW_IV).unwrap();
+            let ciphertext = cipher.encrypt_str("YELLOW SUBMARIN").unwrap();
+            let mut iv = RAW_IV;
+            iv[Aes128::BLOCK_SIZE - 1] ^= 1;
+            let mut cipher = Aes128Cbc::new(&RAW_KEY, &iv).unwrap();
+            let (plaintext, report) = cipher.decrypt_with_report(&ciphertext).unwrap();
+            assert_eq!(&plaintext, b"YELLOW SUBMARIN\x00");
+            assert_eq!(plaintext.len(), ciphertext.len());
+            assert!(!report.valid_padding);
+            assert!(cipher.decrypt_str_lossy(&ciphertext).is_err());
+
+            let mut cipher = Aes128Ctr::new(&RAW_KEY, &RAW_NONCE).unwrap();
+            let ciphertext = cipher.encrypt_str("caf\u{e9}").unwrap();
+            let mut cipher = Aes128Ctr::new(&RAW_KEY, &RAW_NONCE).unwrap();
+            let (plaintext, report) = cipher.decrypt_with_report(&ciphertext).unwrap();
+            assert_eq!(plaintext, "caf\u{e9}".as_bytes());
+            assert!(report.is_valid());
+        }
+
+        #[test]
+        fn rc4() {
+            let test_vectors = [
+                ("Key", "Plaintext", "bbf316e8d940af0ad3"),
+                ("Wiki", "pedia", "1021bf0420"),
+                ("Secret", "Attack at dawn", "45a01f645fc35b383552544b9bf5"),
+            ];
+            for &(key, plaintext, ciphertext) in test_vectors.iter() {
+                let mut cipher = Rc4::new(key.as_bytes()).unwrap();
+                assert_eq!(hex::encode(cipher.encrypt_str(plaintext).unwrap()), ciphertext);
+                let mut cipher = Rc4::new(key.as_bytes()).unwrap();
+                assert_eq!(cipher.decrypt_str(&hex::decode(ciphertext).unwrap()).unwrap(), plaintext);
+            }
+            // The first keystream block for the 40-bit key from RFC 6229.
+            let keystream = Rc4::new(&[1, 2, 3, 4, 5]).unwrap().take(16).collect::<Vec<u8>>();
+            assert_eq!(hex::encode(&keystream), "b2396305f03dc027ccc3524a0a1118a8");
+            assert!(Rc4::new(&[]).is_err());
+        }
+
+        #[test]
+        fn ofb_mode() {
+            // The OFB-AES128 vector from appendix F.4.1 of NIST SP 800-38A.
+            let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+            let iv = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+            let plaintext = hex::decode(
+                "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51"
+            ).unwrap();
+            let ciphertext = hex::decode(
+                "3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c52dac54ed825"
+            ).unwrap();
+
+            let mut cipher = Ofb::<Aes128>::new(&key, &iv).unwrap();
+            assert_eq!(cipher.encrypt_buffer(&plaintext).unwrap(), ciphertext);
+            let mut cipher = Ofb::<Aes128>::new(&key, &iv).unwrap();
+            assert_eq!(cipher.decrypt_buffer(&ciphertext).unwrap(), plaintext);
+
+            // The keystream does not depend on the plaintext.
+            let mut cipher = Ofb::<Aes128>::new(&key, &iv).unwrap();
+            let keystream = cipher.encrypt_buffer(&[0; 32]).unwrap();
+            let expected = plaintext.iter().zip(&ciphertext).map(|(x, y)| x ^ y).collect::<Vec<u8>>();
+            assert_eq!(keystream, expected);
+            assert!(Ofb::<Aes128>::new(&key, &iv[1..]).is_err());
+        }
+
+        #[test]
+        fn gcm_mode() {
+            // Test cases 1, 6 and 16 from the GCM specification.
+            let cipher = Gcm::<Aes128>::new(&[0; 16], &[0; 12]).unwrap();
+            let (ciphertext, tag) = cipher.encrypt(&[], &[]).unwrap();
+            assert!(ciphertext.is_empty());
+            assert_eq!(hex::encode(&tag), "58e2fccefa7e3061367f1d57a4e7455a");
+
+            let aad = hex::decode("feedfacedeadbeeffeedfacedeadbeefabaddad2").unwrap();
+            let plaintext = hex::decode(
+                "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+                 1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39"
+            ).unwrap();
+            let key = hex::decode("feffe9928665731c6d6a8f9467308308").unwrap();
+            let nonce = hex::decode(
+                "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728\
+                 c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b"
+            ).unwrap();
+            let cipher = Gcm::<Aes128>::new(&key, &nonce).unwrap();
+            let (ciphertext, tag) = cipher.encrypt(&aad, &plaintext).unwrap();
+            assert_eq!(hex::encode(&ciphertext),
+                "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca7\
+                 01e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5");
+            assert_eq!(hex::encode(&tag), "619cc5aefffe0bfa462af43c1699d050");
+            assert_eq!(cipher.decrypt(&aad, &ciphertext, &tag).unwrap(), plaintext);
+
+            let key = [&key[..], &key[..]].concat();
+            let nonce = hex::decode("cafebabefacedbaddecaf888").unwrap();
+            let cipher = Gcm::<Aes256>::new(&key, &nonce).unwrap();
+            let (ciphertext, tag) = cipher.encrypt(&aad, &plaintext).unwrap();
+            assert_eq!(hex::encode(&ciphertext),
+                "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+                 8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662");
+            assert_eq!(hex::encode(&tag), "76fc6ece0f4e1768cddf8853bb2d551b");
+
+            assert!(cipher.decrypt(&aad, &ciphertext, &tag).is_ok());
+            assert!(cipher.decrypt(&aad, &ciphertext, &tag[..8]).is_err());
+            assert!(cipher.decrypt(&aad, &ciphertext, &tag[..15]).is_err());
+            assert!(cipher.decrypt(&aad, &ciphertext, &[]).is_err());
+            assert!(cipher.decrypt(&aad[1..], &ciphertext, &tag).is_err());
+            assert!(Gcm::<Aes128>::new(&[0; 16], &[]).is_err());
+        }
+
+        #[test]
+        fn xts_mode() {
+            // Vectors 1 and 2 from IEEE 1619, and two ciphertext stealing vectors.
+            let test_vectors = [
+                (
+                    "0000000000000000000000000000000000000000000000000000000000000000",
+                    0,
+                    "0000000000000000000000000000000000000000000000000000000000000000",
+                    "917cf69ebd68b2ec9b9fe9a3eadda692cd43d2f59598ed858c02c2652fbf922e",
+                ),
+                (
+                    "1111111111111111111111111111111122222222222222222222222222222222",
+                    0x3333333333,
+                    "4444444444444444444444444444444444444444444444444444444444444444",
+                    "c454185e6a16936e39334038acef838bfb186fff7480adc4289382ecd6d394f0",
+                ),
+                (
+                    "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0",
+                    0x9a78563412,
+                    "000102030405060708090a0b0c0d0e0f10",
+                    "641610679dcbf92e505c41333fb06c2a95",
+                ),
+                (
+                    "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0",
+                    0x9a78563412,
+                    "000102030405060708090a0b0c0d0e0f10111213",
+                    "a8ba0048d75084603eb8423a09b7bf7595c871f6",
+                ),
+            ];
+            for &(key, sector, plaintext, ciphertext) in test_vectors.iter() {
+                let cipher = Xts::<Aes128>::new(&hex::decode(key).unwrap()).unwrap();
+                let mut buffer = hex::decode(plaintext).unwrap();
+                cipher.encrypt_sector(sector, &mut buffer).unwrap();
+                assert_eq!(hex::encode(&buffer), ciphertext);
+                cipher.decrypt_sector(sector, &mut buffer).unwrap();
+                assert_eq!(hex::encode(&buffer), plaintext);
+            }
+            let cipher = Xts::<Aes128>::new(&[0; 32]).unwrap();
+            assert!(cipher.encrypt_sector(0, &mut [0; 15]).is_err());
+            assert!(Xts::<Aes128>::new(&[0; 16]).is_err());
+        }
+
+        #[test]
+        fn cfb_mode() {
+            // The CFB8-AES128 and CFB128-AES128 vectors from appendix F.3 of NIST SP 800-38A.
+            let key = hex::decode("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+            let iv = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+            let test_vectors = [
+                (1, "6bc1bee22e409f96e93d7e117393172aae2d", "3b79424c9c0dd436bace9e0ed4586a4f32b9"),
+                (
+                    Aes128::BLOCK_SIZE,
+                    "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51",
+                    "3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b"
+                ),
+            ];
+            for &(segment_size, plaintext, ciphertext) in test_vectors.iter() {
+                let plaintext = hex::decode(plaintext).unwrap();
+                let ciphertext = hex::decode(ciphertext).unwrap();
+
+                let mut cipher = Cfb::<Aes128>::new(&key, &iv, segment_size).unwrap();
+                assert_eq!(cipher.encrypt_buffer(&plaintext).unwrap(), ciphertext);
+
+                // Splitting the input should not affect the output.
+                let mut cipher = Cfb::<Aes128>::new(&key, &iv, segment_size).unwrap();
+                let mut buffer = ciphertext.clone();
+                let (head, tail) = buffer.split_at_mut(7);
+                cipher.decrypt_mut(head).unwrap();
+                cipher.decrypt_mut(tail).unwrap();
+                assert_eq!(buffer, plaintext);
+            }
+            assert!(Cfb::<Aes128>::new(&key, &iv, 0).is_err());
+            assert!(Cfb::<Aes128>::new(&key, &iv, Aes128::BLOCK_SIZE + 1).is_err());
+        }
+
         #[test] 
         fn encrypt_repeating_key() {
             let mut cipher = RepeatingKeyXor::new(&RAW_KEY);
@@ -963,17 +1936,155 @@ pub use cipher_modes::{
     BlockCipherMode,
     StreamCipherMode,
     SeekableStreamCipherMode,
+    IvMode,
     RepeatingKeyXor,
+    Rc4,
     Ecb,
     Cbc,
-    Ctr
+    Ctr,
+    Ofb,
+    Cfb,
+    Gcm,
+    Xts
 };
 
+/// Composite modes which combine a block cipher mode with a MAC.
+pub mod authenticated_modes {
+    use core::marker::PhantomData;
+
+    use crate::prelude::*;
+    #[cfg(feature = "std")]
+    use crate::random_vec;
+    use crate::crypto::hash::Mac;
+    use crate::utils::bytes;
+    use super::Error;
+    use super::ciphers::Cipher;
+    use super::padding_modes::PaddingMode;
+    use super::cipher_modes::{BlockCipherMode, IvMode, Iv};
+
+    /// Encrypt-then-MAC: the ciphertext is authenticated using `T`, and messages are on the
+    /// form `iv || ciphertext || tag`, where the tag authenticates both the IV and the
+    /// ciphertext. Each message is encrypted using a fresh random IV. The tag is verified
+    /// (in constant time) before the ciphertext is decrypted.
+    #[derive(Clone, Debug)]
+    pub struct EncryptThenMac<M, T: Mac> {
+        mode: M,
+        mac_key: Vec<u8>,
+        mac: PhantomData<T>
+    }
+
+    impl<M: IvMode, T: Mac> EncryptThenMac<M, T> {
+        /// Returns an instance using the key of the given mode. The IV of the mode is not used.
+        pub fn new(mode: M, mac_key: &[u8]) -> Self {
+            Self { mode, mac_key: mac_key.to_vec(), mac: PhantomData }
+        }
+
+        /// Encrypts the plaintext using a random IV and returns `iv || ciphertext || tag`.
+        #[cfg(feature = "std")]
+        pub fn encrypt_buffer<C, P>(&self, plaintext: &[u8]) -> Result<Vec<u8>, Error>
+        where
+            C: Cipher,
+            P: PaddingMode,
+            M: BlockCipherMode<C, P>
+        {
+            self.encrypt_buffer_with_iv(&random_vec!(M::IV_SIZE), plaintext)
+        }
+
+        /// Encrypts the plaintext using the given IV and returns `iv || ciphertext || tag`. IVs
+        /// are not checked for reuse.
+        pub fn encrypt_buffer_with_iv<C, P>(&self, iv: &Iv, plaintext: &[u8]) -> Result<Vec<u8>, Error>
+        where
+            C: Cipher,
+            P: PaddingMode,
+            M: BlockCipherMode<C, P>
+        {
+            let mut buffer = iv.to_vec();
+            buffer.extend(self.mode.with_iv(iv)?.encrypt_buffer(plaintext)?);
+            let tag = T::digest(&self.mac_key, &buffer);
+            buffer.extend_from_slice(tag.as_ref());
+            Ok(buffer)
+        }
+
+        /// Verifies the tag of a buffer on the form `iv || ciphertext || tag` and returns the
+        /// decrypted ciphertext.
+        pub fn decrypt_buffer<C, P>(&self, buffer: &[u8]) -> Result<Vec<u8>, Error>
+        where
+            C: Cipher,
+            P: PaddingMode,
+            M: BlockCipherMode<C, P>
+        {
+            if buffer.len() < M::IV_SIZE + T::TAG_SIZE {
+                return Err(Error::AuthenticationError);
+            }
+            let (message, tag) = buffer.split_at(buffer.len() - T::TAG_SIZE);
+            if !bytes::constant_time_eq(T::digest(&self.mac_key, message).as_ref(), tag) {
+                return Err(Error::AuthenticationError);
+            }
+            let (iv, ciphertext) = message.split_at(M::IV_SIZE);
+            self.mode.with_iv(iv)?.decrypt_buffer(ciphertext)
+        }
+    }
+
+    #[cfg(all(test, feature = "std"))]
+    mod tests {
+        use super::*;
+        use crate::crypto::hash::Sha1Hmac;
+        use crate::crypto::symmetric::Aes128Cbc;
+
+        #[test]
+        fn encrypt_then_mac() {
+            let mode = Aes128Cbc::new(b"YELLOW SUBMARINE", &[0; 16]).unwrap();
+            let cipher = EncryptThenMac::<_, Sha1Hmac>::new(mode, b"MAC KEY");
+            let buffer = cipher.encrypt_buffer(b"attack at dawn").unwrap();
+            assert_eq!(buffer.len(), 16 + 16 + Sha1Hmac::TAG_SIZE);
+            assert_eq!(cipher.decrypt_buffer(&buffer).unwrap(), b"attack at dawn");
+
+            // Each message uses a fresh IV.
+            let other_buffer = cipher.encrypt_buffer(b"attack at dawn").unwrap();
+            assert_ne!(buffer[..16], other_buffer[..16]);
+            assert_ne!(buffer[16..32], other_buffer[16..32]);
+            assert_eq!(cipher.decrypt_buffer(&other_buffer).unwrap(), b"attack at dawn");
+
+            let buffer = cipher.encrypt_buffer_with_iv(&[1; 16], b"attack at dawn").unwrap();
+            assert_eq!(buffer[..16], [1; 16]);
+
+            // Tampering with the IV, the ciphertext or the tag is detected.
+            for index in [0, 15, 16, 31, 32, buffer.len() - 1].iter() {
+                let mut tampered = buffer.clone();
+                tampered[*index] ^= 1;
+                assert_eq!(cipher.decrypt_buffer(&tampered), Err(Error::AuthenticationError));
+            }
+            assert_eq!(cipher.decrypt_buffer(&buffer[1..]), Err(Error::AuthenticationError));
+            assert_eq!(cipher.decrypt_buffer(&[]), Err(Error::AuthenticationError));
+        }
+    }
+}
+
+pub use authenticated_modes::EncryptThenMac;
+
 pub type Aes128Ecb = Ecb<Aes128, Pkcs7>;
+pub type Aes192Ecb = Ecb<Aes192, Pkcs7>;
 pub type Aes256Ecb = Ecb<Aes256, Pkcs7>;
 
 pub type Aes128Cbc = Cbc<Aes128, Pkcs7>;
+pub type Aes192Cbc = Cbc<Aes192, Pkcs7>;
 pub type Aes256Cbc = Cbc<Aes256, Pkcs7>;
 
 pub type Aes128Ctr = Ctr<Aes128>;
+pub type Aes192Ctr = Ctr<Aes192>;
 pub type Aes256Ctr = Ctr<Aes256>;
+
+pub type Aes128Ofb = Ofb<Aes128>;
+pub type Aes192Ofb = Ofb<Aes192>;
+pub type Aes256Ofb = Ofb<Aes256>;
+
+pub type Aes128Gcm = Gcm<Aes128>;
+pub type Aes192Gcm = Gcm<Aes192>;
+pub type Aes256Gcm = Gcm<Aes256>;
+
+pub type Aes128Xts = Xts<Aes128>;
+pub type Aes256Xts = Xts<Aes256>;
+
+pub type Aes128Cfb = Cfb<Aes128>;
+pub type Aes192Cfb = Cfb<Aes192>;
+pub type Aes256Cfb = Cfb<Aes256>;
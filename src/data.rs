@@ -0,0 +1,442 @@
+//! Loads the challenge data in `data/set_*/problem_*.txt` at runtime.
+//!
+//! By default, files are loaded from the `data` directory relative to the current working
+//! directory (which is the crate root when running tests and examples). This may be overridden
+//! by setting the `CRYPTOPALS_DATA` environment variable, or by creating a `Loader` with a
+//! different root directory.
+//!
+//! The data files for the implemented problems are vendored in the repository. Data files which
+//! are missing from the data directory may instead be downloaded from a user-supplied mirror of
+//! the cryptopals challenge data, where the data for problem `n` is located at `{url}/{n}.txt`.
+//! The official cryptopals site cannot be used directly: it is only served over HTTPS, and
+//! `http::client` supports neither TLS nor redirects. Downloads are therefore opt-in, and are
+//! only enabled by setting the `CRYPTOPALS_DATA_URL` environment variable to the URL of a
+//! plain HTTP mirror, or by creating a `Loader` using `with_data_url`. Downloaded files are
+//! verified against the SHA-256 digests in `DIGESTS` before they are cached in the data
+//! directory, so new inputs only require a new entry in `DIGESTS` and a copy on the mirror.
+//!
+//! The files used by the oracles are also embedded in the crate, and may be read using
+//! `Loader::bundled`. This allows the oracles to be used on targets without a file system,
+//! like `wasm32-unknown-unknown`.
+
+use std::env;
+use std::fmt;
+use std::error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+
+use crate::crypto::hash::{HashFunction, Sha256};
+use crate::encoding::{base64, hex};
+use crate::http::client;
+
+/// The environment variable used to override the default data directory.
+pub const DATA_DIR_VAR: &str = "CRYPTOPALS_DATA";
+
+/// The environment variable used to enable downloads from the given URL.
+pub const DATA_URL_VAR: &str = "CRYPTOPALS_DATA_URL";
+
+/// The SHA-256 digests of the known data files, given as `(set, problem, digest)`.
+pub const DIGESTS: &[(usize, usize, &str)] = &[
+    (1, 4, "c87c921c561bf2a69cf4847dd6649f6d05430fcabae80fe5e78b56d78978a436"),
+    (1, 6, "9cce7ff2a0ade90b54c0e20ee8283c0cd8caa7663f995eff2d7b9ace3bd53d8d"),
+    (1, 7, "c50fd4291beb52b9fbac8c4bddc5454c7757d0988359e5625e430bc7cdd709c3"),
+    (1, 8, "d61d668f428e48b70c4148ba6a3201afb6d6bd8f630686f23162400683a066b7"),
+    (2, 10, "81fbeb6c3194bac8191cfcc8dbb63b705f1d499d3d43ab7795627501dbe34925"),
+    (2, 12, "c1e0035a10f9cf415a92f0434eaa16ed2df788d1f25581707d36e9d131846215"),
+    (3, 17, "efabd1b69becee79ea68375b6c4994cb82441e78227f2cb5b4c71a9805e8ba47"),
+    (3, 19, "1eacba97ab1f91c3f5be3cab097d44c786196c78e0a6dbbd4606c2b6ac46f5b0"),
+    (3, 20, "b742803c4fa1ee6b4e400e03a081ba46f559dad7b69012fd1582cbc5f1c8b2cd"),
+    (4, 25, "24df84533fc2778495577c844bcf3fe1d4d17c68d8c5cbc5a308286db58c69b6"),
+];
+
+/// The data files used by the oracles, given as `(set, problem, contents)`.
+const BUNDLED: &[(usize, usize, &str)] = &[
+    (1, 8, include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/data/set_1/problem_8.txt"))),
+    (2, 12, include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/data/set_2/problem_12.txt"))),
+    (3, 17, include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/data/set_3/problem_17.txt"))),
+];
+
+/// Returns the contents of the embedded data file for the given set and problem.
+pub fn bundled(set: usize, problem: usize) -> Option<&'static str> {
+    BUNDLED.iter()
+        .find(|&&(bundled_set, bundled_problem, _)| (bundled_set, bundled_problem) == (set, problem))
+        .map(|&(_, _, contents)| contents)
+}
+
+/// Returns the expected SHA-256 digest of the data file for the given set and problem.
+pub fn digest(set: usize, problem: usize) -> Option<&'static str> {
+    DIGESTS.iter()
+        .find(|&&(digest_set, digest_problem, _)| (digest_set, digest_problem) == (set, problem))
+        .map(|&(_, _, digest)| digest)
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(PathBuf, io::Error),
+    DecodingError(PathBuf),
+    /// There is no known digest for the given set and problem.
+    UnknownData(usize, usize),
+    DownloadError(String, client::Error),
+    /// The server returned a status code other than 2xx.
+    HttpError(String, u16),
+    /// The digest of the data does not match the expected digest.
+    DigestMismatch(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::IoError(path, error) => write!(formatter, "{}: {}", path.display(), error),
+            Error::DecodingError(path) => write!(formatter, "{}: invalid encoding", path.display()),
+            Error::UnknownData(set, problem) => write!(formatter, "no known data for set {} problem {}", set, problem),
+            Error::DownloadError(url, error) => write!(formatter, "{}: {}", url, error),
+            Error::HttpError(url, status) => write!(formatter, "{}: unexpected status {}", url, status),
+            Error::DigestMismatch(source) => write!(formatter, "{}: digest mismatch", source),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::IoError(_, error) => Some(error),
+            Error::DecodingError(_) => None,
+            Error::UnknownData(_, _) => None,
+            Error::DownloadError(_, error) => Some(error),
+            Error::HttpError(_, _) => None,
+            Error::DigestMismatch(_) => None,
+        }
+    }
+}
+
+fn verify_digest(source: &str, data: &[u8], digest: &str) -> Result<(), Error> {
+    if Sha256::digest(data).to_str() != digest {
+        return Err(Error::DigestMismatch(source.to_owned()));
+    }
+    Ok(())
+}
+
+/// Loads challenge data relative to a root directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Loader {
+    root: PathBuf,
+    data_url: Option<String>,
+    bundled: bool,
+}
+
+impl Loader {
+    /// Creates a loader which loads data relative to the given directory. Missing files are
+    /// not downloaded.
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Loader { root: root.as_ref().to_path_buf(), data_url: None, bundled: false }
+    }
+
+    /// Creates a loader which only reads the data files embedded in the crate, and never
+    /// accesses the file system. Reading any other file fails with `Error::UnknownData`.
+    pub fn bundled() -> Self {
+        Loader { root: PathBuf::from("data"), data_url: None, bundled: true }
+    }
+
+    /// Returns a loader which downloads missing files from the mirror at the given URL, and
+    /// caches them in the root directory. The URL must use plain HTTP and must not redirect, so
+    /// downloads from an `https://` URL fail with `Error::DownloadError`.
+    pub fn with_data_url(mut self, data_url: &str) -> Self {
+        self.data_url = Some(data_url.trim_end_matches('/').to_owned());
+        self
+    }
+
+    pub fn root(&self) -> &Path { &self.root }
+
+    pub fn data_url(&self) -> Option<&str> { self.data_url.as_deref() }
+
+    pub fn is_bundled(&self) -> bool { self.bundled }
+
+    /// Returns the URL of the data file for the given set and problem, if downloads are enabled.
+    pub fn url(&self, problem: usize) -> Option<String> {
+        self.data_url.as_ref().map(|data_url| format!("{}/{}.txt", data_url, problem))
+    }
+
+    /// Downloads the data file for the given set and problem and caches it in the root
+    /// directory, replacing any existing file. Fails if downloads are disabled, or if the
+    /// data does not match the known digest.
+    pub fn download(&self, set: usize, problem: usize) -> Result<Vec<u8>, Error> {
+        let digest = digest(set, problem).ok_or(Error::UnknownData(set, problem))?;
+        let url = self.url(problem).ok_or(Error::UnknownData(set, problem))?;
+        let response = client::get(&url, &[] as &[(&str, &str)])
+            .map_err(|error| Error::DownloadError(url.clone(), error))?;
+        if !response.is_success() {
+            return Err(Error::HttpError(url, response.status));
+        }
+        verify_digest(&url, &response.body, digest)?;
+
+        let path = self.path(set, problem);
+        let io_error = |error| Error::IoError(path.clone(), error);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(io_error)?;
+        }
+        // Write to a temporary file first so that interrupted downloads are never cached. The
+        // name is unique to avoid clashing with concurrent downloads of the same file.
+        static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+        let temporary_path = path.with_extension(format!(
+            "{}.{}.tmp",
+            process::id(),
+            DOWNLOADS.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = fs::write(&temporary_path, &response.body)
+            .and_then(|_| fs::rename(&temporary_path, &path));
+        if let Err(error) = result {
+            let _ = fs::remove_file(&temporary_path);
+            return Err(io_error(error));
+        }
+        Ok(response.body)
+    }
+
+    /// Checks the data file for the given set and problem against the known digest.
+    pub fn verify(&self, set: usize, problem: usize) -> Result<(), Error> {
+        let digest = digest(set, problem).ok_or(Error::UnknownData(set, problem))?;
+        let path = self.path(set, problem);
+        let data = if self.bundled {
+            self.read_string(set, problem)?.into_bytes()
+        } else {
+            fs::read(&path).map_err(|error| Error::IoError(path.clone(), error))?
+        };
+        verify_digest(&path.display().to_string(), &data, digest)
+    }
+
+    /// Returns the path to the data file for the given set and problem.
+    pub fn path(&self, set: usize, problem: usize) -> PathBuf {
+        self.root.join(format!("set_{}", set)).join(format!("problem_{}.txt", problem))
+    }
+
+    /// Returns the contents of the data file for the given set and problem. If the file is
+    /// missing, downloads are enabled, and the digest of the file is known, the file is
+    /// downloaded first. Otherwise, missing files fail with `io::ErrorKind::NotFound`.
+    pub fn read_string(&self, set: usize, problem: usize) -> Result<String, Error> {
+        if self.bundled {
+            return bundled(set, problem)
+                .map(String::from)
+                .ok_or(Error::UnknownData(set, problem));
+        }
+        let path = self.path(set, problem);
+        match fs::read_to_string(&path) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound
+                && self.data_url.is_some()
+                && digest(set, problem).is_some() =>
+            {
+                let data = self.download(set, problem)?;
+                String::from_utf8(data).map_err(|_| Error::DecodingError(path))
+            },
+            result => result.map_err(|error| Error::IoError(path, error)),
+        }
+    }
+
+    /// Memory-maps the data file for the given set and problem. Unlike `read_string`, missing
+    /// files are not downloaded.
+    #[cfg(feature = "mmap")]
+    pub fn map(&self, set: usize, problem: usize) -> Result<Mmap, Error> {
+        map_file(self.path(set, problem))
+    }
+
+    /// Returns the non-empty lines of the data file for the given set and problem.
+    pub fn read_lines(&self, set: usize, problem: usize) -> Result<Vec<String>, Error> {
+        Ok(self.read_string(set, problem)?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    /// Decodes the entire data file as base64, ignoring line breaks.
+    pub fn read_base64(&self, set: usize, problem: usize) -> Result<Vec<u8>, Error> {
+        base64::decode_lenient(self.read_string(set, problem)?).map_err(|_| Error::DecodingError(self.path(set, problem)))
+    }
+
+    /// Decodes each non-empty line of the data file as base64.
+    pub fn read_base64_lines(&self, set: usize, problem: usize) -> Result<Vec<Vec<u8>>, Error> {
+        self.read_lines(set, problem)?
+            .iter()
+            .map(|line| base64::decode(line).map_err(|_| Error::DecodingError(self.path(set, problem))))
+            .collect()
+    }
+
+    /// Decodes each non-empty line of the data file as hex.
+    pub fn read_hex_lines(&self, set: usize, problem: usize) -> Result<Vec<Vec<u8>>, Error> {
+        self.read_lines(set, problem)?
+            .iter()
+            .map(|line| hex::decode(line).map_err(|_| Error::DecodingError(self.path(set, problem))))
+            .collect()
+    }
+}
+
+impl Default for Loader {
+    /// Returns a loader using the directory given by `CRYPTOPALS_DATA` if set, and the `data`
+    /// directory relative to the current working directory otherwise. Missing files are
+    /// downloaded from the URL given by `CRYPTOPALS_DATA_URL` if set, and are not downloaded
+    /// otherwise.
+    fn default() -> Self {
+        let loader = match env::var_os(DATA_DIR_VAR) {
+            Some(root) => Loader::new(root),
+            None => Loader::new("data"),
+        };
+        match env::var(DATA_URL_VAR) {
+            Ok(data_url) => loader.with_data_url(&data_url),
+            Err(_) => loader,
+        }
+    }
+}
+
+/// Memory-maps the file at the given path. This allows large inputs (like sampled keystreams
+/// or training corpora) to be passed to APIs taking `&[u8]` without reading them into memory.
+///
+/// # Note
+///
+/// The contents of the map are undefined if the file is modified while it is mapped.
+#[cfg(feature = "mmap")]
+pub fn map_file<P: AsRef<Path>>(path: P) -> Result<Mmap, Error> {
+    let path = path.as_ref();
+    let io_error = |error| Error::IoError(path.to_path_buf(), error);
+    let file = fs::File::open(path).map_err(io_error)?;
+    // Safety: The map is read-only, and the data files are not expected to change while mapped.
+    unsafe { Mmap::map(&file) }.map_err(io_error)
+}
+
+/// Returns the contents of the data file for the given set and problem.
+pub fn read_string(set: usize, problem: usize) -> Result<String, Error> {
+    Loader::default().read_string(set, problem)
+}
+
+/// Returns the non-empty lines of the data file for the given set and problem.
+pub fn read_lines(set: usize, problem: usize) -> Result<Vec<String>, Error> {
+    Loader::default().read_lines(set, problem)
+}
+
+/// Decodes the entire data file as base64, ignoring line breaks.
+pub fn read_base64(set: usize, problem: usize) -> Result<Vec<u8>, Error> {
+    Loader::default().read_base64(set, problem)
+}
+
+/// Decodes each non-empty line of the data file as base64.
+pub fn read_base64_lines(set: usize, problem: usize) -> Result<Vec<Vec<u8>>, Error> {
+    Loader::default().read_base64_lines(set, problem)
+}
+
+/// Decodes each non-empty line of the data file as hex.
+pub fn read_hex_lines(set: usize, problem: usize) -> Result<Vec<Vec<u8>>, Error> {
+    Loader::default().read_hex_lines(set, problem)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn missing_data() {
+        let loader = Loader::new("/nonexistent");
+        assert_eq!(loader.path(1, 4), Path::new("/nonexistent/set_1/problem_4.txt"));
+        assert!(matches!(loader.read_lines(1, 4), Err(Error::IoError(_, _))));
+
+        // Downloads are disabled unless explicitly enabled.
+        assert_eq!(loader.data_url(), None);
+        if env::var_os(DATA_URL_VAR).is_none() {
+            assert_eq!(Loader::default().data_url(), None);
+        }
+    }
+
+    #[test]
+    fn decoding_errors() {
+        // The plaintext for problem 25 is not valid hex.
+        assert!(matches!(read_hex_lines(4, 25), Err(Error::DecodingError(_))));
+        assert_eq!(read_hex_lines(1, 4).unwrap().len(), 327);
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn mapped_data() {
+        let loader = Loader::default();
+        let data = loader.map(1, 4).unwrap();
+        assert_eq!(&data[..], loader.read_string(1, 4).unwrap().as_bytes());
+        assert!(matches!(Loader::new("/nonexistent").map(1, 4), Err(Error::IoError(_, _))));
+    }
+
+    #[test]
+    fn bundled_digests() {
+        let loader = Loader::default();
+        for &(set, problem, _) in DIGESTS {
+            loader.verify(set, problem).unwrap();
+        }
+        assert!(matches!(loader.verify(1, 5), Err(Error::UnknownData(1, 5))));
+    }
+
+    #[test]
+    fn embedded_data() {
+        let loader = Loader::bundled();
+        for &(set, problem, _) in BUNDLED {
+            loader.verify(set, problem).unwrap();
+            assert_eq!(loader.read_string(set, problem).unwrap(), read_string(set, problem).unwrap());
+        }
+        assert!(matches!(loader.read_string(1, 7), Err(Error::UnknownData(1, 7))));
+    }
+
+    /// Serves the given body for every request on a local port, and returns the data URL.
+    fn serve(body: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let data_url = format!("http://{}/data", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                while !request.ends_with(b"\r\n\r\n") {
+                    let mut byte = [0];
+                    stream.read_exact(&mut byte).unwrap();
+                    request.push(byte[0]);
+                }
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len()).unwrap();
+                stream.write_all(body).unwrap();
+            }
+        });
+        data_url
+    }
+
+    #[test]
+    fn download_and_cache() {
+        let root = env::temp_dir().join(format!("cryptopals-data-{}", std::process::id()));
+        let body = fs::read(Loader::default().path(1, 7)).unwrap();
+        let data_url = serve(Box::leak(body.into_boxed_slice()));
+
+        let loader = Loader::new(&root).with_data_url(&data_url);
+        assert_eq!(loader.url(7).unwrap(), format!("{}/7.txt", data_url));
+        // Files without a known digest are never downloaded.
+        assert!(matches!(
+            loader.read_string(1, 5),
+            Err(Error::IoError(_, error)) if error.kind() == io::ErrorKind::NotFound
+        ));
+        assert_eq!(loader.read_base64(1, 7).unwrap(), read_base64(1, 7).unwrap());
+        loader.verify(1, 7).unwrap();
+
+        // The cached file is used once downloaded, and bad downloads are rejected.
+        let loader = Loader::new(&root).with_data_url(&serve(b"not the data"));
+        assert_eq!(loader.read_base64(1, 7).unwrap(), read_base64(1, 7).unwrap());
+        assert!(matches!(loader.download(1, 7), Err(Error::DigestMismatch(_))));
+        loader.verify(1, 7).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn https_mirror() {
+        let loader = Loader::new("data").with_data_url("https://cryptopals.com/static/challenge-data");
+        assert!(matches!(
+            loader.download(1, 7),
+            Err(Error::DownloadError(_, client::Error::InvalidUrl))
+        ));
+    }
+}
@@ -0,0 +1,742 @@
+//! Hex, Base64, Base32 and Base58 codecs, together with minimal DER and PEM support.
+
+use core::fmt;
+use core::error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The input has an invalid length for the encoding.
+    InvalidLength,
+    /// The input contains an invalid character at the given index.
+    InvalidCharacter(usize),
+    /// The input is incorrectly padded.
+    InvalidPadding,
+    /// The checksum of the decoded data is invalid.
+    InvalidChecksum,
+    /// The input contains an unsupported DER tag.
+    UnsupportedTag(u8),
+    /// The input is not valid (canonical) DER, or is not correctly armored.
+    InvalidEncoding,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:?}", self)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+pub mod hex {
+    use crate::prelude::*;
+    use super::Error;
+
+    const ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+    /// Returns the lowercase hex encoding of the input.
+    pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+        let input = input.as_ref();
+        let mut result = String::with_capacity(2 * input.len());
+        for byte in input {
+            result.push(char::from(ALPHABET[(byte >> 4) as usize]));
+            result.push(char::from(ALPHABET[(byte & 0xf) as usize]));
+        }
+        result
+    }
+
+    fn decode_digit(digit: u8) -> Option<u8> {
+        match digit {
+            b'0'..=b'9' => Some(digit - b'0'),
+            b'a'..=b'f' => Some(digit - b'a' + 10),
+            b'A'..=b'F' => Some(digit - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    /// Decodes the input, which may contain both upper and lowercase digits.
+    pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        let input = input.as_ref();
+        if !input.len().is_multiple_of(2) {
+            return Err(Error::InvalidLength);
+        }
+        let digits = input.iter()
+            .enumerate()
+            .map(|(index, &digit)| decode_digit(digit).ok_or(Error::InvalidCharacter(index)))
+            .collect::<Result<Vec<u8>, Error>>()?;
+        Ok(digits.chunks(2).map(|pair| (pair[0] << 4) | pair[1]).collect())
+    }
+}
+
+/// Base64 as specified in RFC 4648, using either the standard or the URL-safe alphabet.
+pub mod base64 {
+    use crate::prelude::*;
+    use super::Error;
+
+    const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+    fn encode_with(input: &[u8], alphabet: &[u8; 64], padding: bool) -> String {
+        let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+        for chunk in input.chunks(3) {
+            let mut buffer = [0; 3];
+            buffer[..chunk.len()].copy_from_slice(chunk);
+            let value = u32::from_be_bytes([0, buffer[0], buffer[1], buffer[2]]);
+            // A chunk of n bytes is encoded using n + 1 characters.
+            for index in 0..=chunk.len() {
+                output.push(alphabet[((value >> (18 - 6 * index)) & 0x3f) as usize] as char);
+            }
+            if padding {
+                (chunk.len()..3).for_each(|_| output.push('='));
+            }
+        }
+        output
+    }
+
+    /// Decodes the input. If `padding` is true the input must be padded, otherwise padding is
+    /// optional.
+    fn decode_with(input: &[u8], alphabet: &[u8; 64], padding: bool) -> Result<Vec<u8>, Error> {
+        let data_size = input.iter().rposition(|&byte| byte != b'=').map_or(0, |index| index + 1);
+        let padding_size = input.len() - data_size;
+        if padding_size > 2 {
+            return Err(Error::InvalidPadding);
+        }
+        if (padding || padding_size > 0) && !input.len().is_multiple_of(4) {
+            return Err(Error::InvalidPadding);
+        }
+        if data_size % 4 == 1 {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut output = Vec::with_capacity(data_size * 3 / 4);
+        for (chunk_index, chunk) in input[..data_size].chunks(4).enumerate() {
+            let mut value = 0;
+            for (index, &byte) in chunk.iter().enumerate() {
+                let digit = alphabet.iter()
+                    .position(|&symbol| symbol == byte)
+                    .ok_or(Error::InvalidCharacter(4 * chunk_index + index))?;
+                value |= (digit as u32) << (18 - 6 * index);
+            }
+            // A chunk of n characters encodes n - 1 bytes.
+            output.extend_from_slice(&value.to_be_bytes()[1..chunk.len()]);
+        }
+        Ok(output)
+    }
+
+    /// Returns the padded Base64 encoding of the input, using the standard alphabet.
+    pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+        encode_with(input.as_ref(), STANDARD, true)
+    }
+
+    /// Decodes padded Base64 using the standard alphabet.
+    pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        decode_with(input.as_ref(), STANDARD, true)
+    }
+
+    /// Decodes padded Base64 using the standard alphabet, ignoring all whitespace. This is
+    /// useful for the data files, which are split into multiple lines.
+    pub fn decode_lenient<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        let input: Vec<u8> = input.as_ref()
+            .iter()
+            .filter(|byte| !byte.is_ascii_whitespace())
+            .cloned()
+            .collect();
+        decode(input)
+    }
+
+    /// Returns the unpadded Base64 encoding of the input, using the URL-safe alphabet.
+    pub fn encode_url_safe<T: AsRef<[u8]>>(input: T) -> String {
+        encode_with(input.as_ref(), URL_SAFE, false)
+    }
+
+    /// Decodes Base64 using the URL-safe alphabet. Padding is optional.
+    pub fn decode_url_safe<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        decode_with(input.as_ref(), URL_SAFE, false)
+    }
+}
+
+/// Base32 as specified in RFC 4648.
+pub mod base32 {
+    use crate::prelude::*;
+    use super::Error;
+
+    const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    /// Returns the number of characters used to encode a chunk of the given size.
+    fn encoded_size(chunk_size: usize) -> usize {
+        (8 * chunk_size).div_ceil(5)
+    }
+
+    /// Returns the padded Base32 encoding of the input.
+    pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+        let input = input.as_ref();
+        let mut output = String::with_capacity(input.len().div_ceil(5) * 8);
+        for chunk in input.chunks(5) {
+            let mut buffer = [0; 8];
+            buffer[3..3 + chunk.len()].copy_from_slice(chunk);
+            let value = u64::from_be_bytes(buffer);
+            for index in 0..encoded_size(chunk.len()) {
+                output.push(ALPHABET[((value >> (35 - 5 * index)) & 0x1f) as usize] as char);
+            }
+            (encoded_size(chunk.len())..8).for_each(|_| output.push('='));
+        }
+        output
+    }
+
+    /// Decodes padded Base32.
+    pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        let input = input.as_ref();
+        if !input.len().is_multiple_of(8) {
+            return Err(Error::InvalidPadding);
+        }
+        let data_size = input.iter().rposition(|&byte| byte != b'=').map_or(0, |index| index + 1);
+        // Only chunks of 0, 2, 4, 5 and 7 characters are valid.
+        let chunk_size = (0..5).find(|&size| encoded_size(size) == data_size % 8);
+        if input.len() - data_size >= 8 || chunk_size.is_none() {
+            return Err(Error::InvalidPadding);
+        }
+
+        let mut output = Vec::with_capacity(data_size * 5 / 8);
+        for (chunk_index, chunk) in input[..data_size].chunks(8).enumerate() {
+            let mut value = 0;
+            for (index, &byte) in chunk.iter().enumerate() {
+                let digit = ALPHABET.iter()
+                    .position(|&symbol| symbol == byte)
+                    .ok_or(Error::InvalidCharacter(8 * chunk_index + index))?;
+                value |= (digit as u64) << (35 - 5 * index);
+            }
+            let size = (0..=5).find(|&size| encoded_size(size) == chunk.len()).unwrap_or(5);
+            output.extend_from_slice(&value.to_be_bytes()[3..3 + size]);
+        }
+        Ok(output)
+    }
+}
+
+/// Base58 using the Bitcoin alphabet, together with the Base58Check variant.
+pub mod base58 {
+    use crate::prelude::*;
+    use super::Error;
+    use crate::crypto::hash::{HashFunction, Sha256};
+
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    const CHECKSUM_SIZE: usize = 4;
+
+    /// Converts the big-endian number given by `digits` from base `from` to base `to`.
+    fn convert(digits: &[u8], from: u32, to: u32) -> Vec<u8> {
+        let mut output: Vec<u8> = Vec::new();
+        for &digit in digits {
+            // Compute output = from * output + digit, using little-endian digits.
+            let mut carry = digit as u32;
+            for output_digit in output.iter_mut() {
+                carry += from * (*output_digit as u32);
+                *output_digit = (carry % to) as u8;
+                carry /= to;
+            }
+            while carry > 0 {
+                output.push((carry % to) as u8);
+                carry /= to;
+            }
+        }
+        output.reverse();
+        output
+    }
+
+    /// Returns the Base58 encoding of the input. Leading zeroes are encoded as '1'.
+    pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+        let input = input.as_ref();
+        let zeroes = input.iter().take_while(|&&byte| byte == 0).count();
+        let digits = convert(&input[zeroes..], 256, 58);
+        core::iter::repeat_n(b'1', zeroes)
+            .chain(digits.iter().map(|&digit| ALPHABET[digit as usize]))
+            .map(char::from)
+            .collect()
+    }
+
+    pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        let input = input.as_ref();
+        let digits = input.iter()
+            .enumerate()
+            .map(|(index, &byte)| ALPHABET.iter()
+                .position(|&symbol| symbol == byte)
+                .map(|digit| digit as u8)
+                .ok_or(Error::InvalidCharacter(index)))
+            .collect::<Result<Vec<u8>, Error>>()?;
+        let zeroes = digits.iter().take_while(|&&digit| digit == 0).count();
+        Ok([vec![0; zeroes], convert(&digits[zeroes..], 58, 256)].concat())
+    }
+
+    /// Returns the first four bytes of SHA-256(SHA-256(input)).
+    fn checksum(input: &[u8]) -> Vec<u8> {
+        Sha256::digest(Sha256::digest(input)).as_ref()[..CHECKSUM_SIZE].to_vec()
+    }
+
+    /// Returns the Base58 encoding of the input followed by its checksum.
+    pub fn encode_check<T: AsRef<[u8]>>(input: T) -> String {
+        let input = input.as_ref();
+        encode([input, &checksum(input)].concat())
+    }
+
+    /// Decodes Base58Check and verifies the checksum. The checksum is not returned.
+    pub fn decode_check<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        let mut output = decode(input)?;
+        if output.len() < CHECKSUM_SIZE {
+            return Err(Error::InvalidLength);
+        }
+        let expected_checksum = output.split_off(output.len() - CHECKSUM_SIZE);
+        if checksum(&output) != expected_checksum {
+            return Err(Error::InvalidChecksum);
+        }
+        Ok(output)
+    }
+}
+
+/// A subset of the ASN.1 distinguished encoding rules, sufficient for RSA and EC keys and
+/// PKCS #1 signature payloads.
+pub mod der {
+    use crate::prelude::*;
+    use super::Error;
+
+    const INTEGER: u8 = 0x02;
+    const BIT_STRING: u8 = 0x03;
+    const OCTET_STRING: u8 = 0x04;
+    const NULL: u8 = 0x05;
+    const OBJECT_IDENTIFIER: u8 = 0x06;
+    const SEQUENCE: u8 = 0x30;
+
+    /// The maximum nesting depth of sequences accepted by the decoder.
+    pub const MAX_DEPTH: usize = 32;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Value {
+        /// A big-endian two's complement integer, using the minimal number of bytes.
+        Integer(Vec<u8>),
+        BitString { unused_bits: u8, data: Vec<u8> },
+        OctetString(Vec<u8>),
+        Null,
+        ObjectIdentifier(Vec<u64>),
+        Sequence(Vec<Value>),
+    }
+
+    fn encode_length(output: &mut Vec<u8>, length: usize) {
+        if length < 0x80 {
+            output.push(length as u8);
+        } else {
+            let bytes = length.to_be_bytes();
+            let bytes = &bytes[bytes.iter().take_while(|&&byte| byte == 0).count()..];
+            output.push(0x80 | bytes.len() as u8);
+            output.extend_from_slice(bytes);
+        }
+    }
+
+    /// Encodes the value using base 128, with the high bit set on all bytes but the last.
+    fn encode_base_128(output: &mut Vec<u8>, mut value: u64) {
+        let mut bytes = vec![(value & 0x7f) as u8];
+        value >>= 7;
+        while value > 0 {
+            bytes.push(0x80 | (value & 0x7f) as u8);
+            value >>= 7;
+        }
+        output.extend(bytes.iter().rev());
+    }
+
+    fn is_minimal_integer(bytes: &[u8]) -> bool {
+        match bytes {
+            [] => false,
+            [0x00, next, ..] => next & 0x80 != 0,
+            [0xff, next, ..] => next & 0x80 == 0,
+            _ => true,
+        }
+    }
+
+    impl Value {
+        /// Returns the integer with the given big-endian unsigned magnitude.
+        pub fn unsigned_integer(magnitude: &[u8]) -> Self {
+            let magnitude = &magnitude[magnitude.iter().take_while(|&&byte| byte == 0).count()..];
+            match magnitude.first() {
+                Some(byte) if byte & 0x80 == 0 => Value::Integer(magnitude.to_vec()),
+                _ => Value::Integer([&[0], magnitude].concat()),
+            }
+        }
+
+        /// Returns the big-endian magnitude of a non-negative integer, without leading zeroes.
+        pub fn to_unsigned(&self) -> Option<Vec<u8>> {
+            match self {
+                Value::Integer(bytes) if bytes.first().is_some_and(|byte| byte & 0x80 == 0) => {
+                    Some(bytes.iter().skip_while(|&&byte| byte == 0).cloned().collect())
+                },
+                _ => None,
+            }
+        }
+
+        /// Encodes the value.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Error::InvalidEncoding` if the value contains an object identifier with
+        /// fewer than two arcs, or with a first arc larger than 2, or with a second arc of 40
+        /// or larger when the first arc is 0 or 1.
+        pub fn encode(&self) -> Result<Vec<u8>, Error> {
+            let (tag, content) = match self {
+                Value::Integer(bytes) => (INTEGER, bytes.clone()),
+                Value::BitString { unused_bits, data } => (BIT_STRING, [&[*unused_bits], &data[..]].concat()),
+                Value::OctetString(data) => (OCTET_STRING, data.clone()),
+                Value::Null => (NULL, Vec::new()),
+                Value::ObjectIdentifier(arcs) => {
+                    let (x, y) = match arcs[..] {
+                        [x, y, ..] if x < 2 && y < 40 || x == 2 => (x, y),
+                        _ => return Err(Error::InvalidEncoding),
+                    };
+                    // The first two arcs are combined into a single value.
+                    let first = y.checked_add(40 * x).ok_or(Error::InvalidEncoding)?;
+                    let mut content = Vec::new();
+                    encode_base_128(&mut content, first);
+                    arcs[2..].iter().for_each(|&arc| encode_base_128(&mut content, arc));
+                    (OBJECT_IDENTIFIER, content)
+                },
+                Value::Sequence(values) => {
+                    let content = values.iter()
+                        .map(Value::encode)
+                        .collect::<Result<Vec<_>, Error>>()?
+                        .concat();
+                    (SEQUENCE, content)
+                },
+            };
+            let mut output = vec![tag];
+            encode_length(&mut output, content.len());
+            output.extend(content);
+            Ok(output)
+        }
+
+        /// Decodes a single value, which must span the entire input.
+        pub fn decode(input: &[u8]) -> Result<Self, Error> {
+            let (value, size) = Self::decode_prefix(input)?;
+            if size != input.len() {
+                return Err(Error::InvalidLength);
+            }
+            Ok(value)
+        }
+
+        /// Decodes the value at the start of the input. Returns the value together with the
+        /// number of bytes consumed. Sequences nested deeper than `MAX_DEPTH` are rejected.
+        pub fn decode_prefix(input: &[u8]) -> Result<(Self, usize), Error> {
+            Self::decode_prefix_with_depth(input, 0)
+        }
+
+        fn decode_prefix_with_depth(input: &[u8], depth: usize) -> Result<(Self, usize), Error> {
+            let (&tag, input) = input.split_first().ok_or(Error::InvalidLength)?;
+            let (&first, input) = input.split_first().ok_or(Error::InvalidLength)?;
+            let (length, input, header_size) = if first < 0x80 {
+                (first as usize, input, 2)
+            } else {
+                let size = (first & 0x7f) as usize;
+                if size == 0 || size > core::mem::size_of::<usize>() || input.len() < size {
+                    return Err(Error::InvalidLength);
+                }
+                let length = input[..size].iter().fold(0, |length, &byte| (length << 8) | byte as usize);
+                // The long form must use the minimal number of bytes.
+                if input[0] == 0 || length < 0x80 {
+                    return Err(Error::InvalidEncoding);
+                }
+                (length, &input[size..], 2 + size)
+            };
+            let content = input.get(..length).ok_or(Error::InvalidLength)?;
+
+            let value = match tag {
+                INTEGER if is_minimal_integer(content) => Value::Integer(content.to_vec()),
+                BIT_STRING => match content.split_first() {
+                    Some((&unused_bits, data)) if unused_bits < 8 && (unused_bits == 0 || !data.is_empty()) => {
+                        Value::BitString { unused_bits, data: data.to_vec() }
+                    },
+                    _ => return Err(Error::InvalidEncoding),
+                },
+                OCTET_STRING => Value::OctetString(content.to_vec()),
+                NULL if content.is_empty() => Value::Null,
+                OBJECT_IDENTIFIER => {
+                    let mut values = Vec::new();
+                    let mut value: u64 = 0;
+                    for (index, &byte) in content.iter().enumerate() {
+                        // Leading 0x80 bytes are not minimal.
+                        if value == 0 && byte == 0x80 || value >> 57 != 0 {
+                            return Err(Error::InvalidEncoding);
+                        }
+                        value = (value << 7) | (byte & 0x7f) as u64;
+                        if byte & 0x80 == 0 {
+                            values.push(value);
+                            value = 0;
+                        } else if index == content.len() - 1 {
+                            return Err(Error::InvalidEncoding);
+                        }
+                    }
+                    let first = *values.first().ok_or(Error::InvalidEncoding)?;
+                    let (x, y) = if first < 80 { (first / 40, first % 40) } else { (2, first - 80) };
+                    Value::ObjectIdentifier([&[x, y], &values[1..]].concat())
+                },
+                SEQUENCE => {
+                    if depth >= MAX_DEPTH {
+                        return Err(Error::InvalidEncoding);
+                    }
+                    let mut values = Vec::new();
+                    let mut offset = 0;
+                    while offset < content.len() {
+                        let (value, size) = Self::decode_prefix_with_depth(&content[offset..], depth + 1)?;
+                        values.push(value);
+                        offset += size;
+                    }
+                    Value::Sequence(values)
+                },
+                INTEGER | NULL => return Err(Error::InvalidEncoding),
+                _ => return Err(Error::UnsupportedTag(tag)),
+            };
+            Ok((value, header_size + length))
+        }
+    }
+}
+
+/// PEM armor as specified in RFC 7468.
+pub mod pem {
+    use crate::prelude::*;
+    use super::{Error, base64};
+
+    const LINE_SIZE: usize = 64;
+
+    /// Returns the data armored using the given label (e.g. `RSA PUBLIC KEY`).
+    pub fn encode(label: &str, data: &[u8]) -> String {
+        let encoded_data = base64::encode(data);
+        let mut output = format!("-----BEGIN {}-----\n", label);
+        for line in encoded_data.as_bytes().chunks(LINE_SIZE) {
+            // It is okay to unwrap here since the data is Base64.
+            output.push_str(core::str::from_utf8(line).unwrap());
+            output.push('\n');
+        }
+        output.push_str(&format!("-----END {}-----\n", label));
+        output
+    }
+
+    /// Decodes the first armored block in the input. Returns the label together with the
+    /// decoded data. Text outside the block is ignored.
+    pub fn decode(input: &str) -> Result<(String, Vec<u8>), Error> {
+        let mut lines = input.lines().map(str::trim).skip_while(|line| !line.starts_with("-----BEGIN "));
+        let label = lines.next()
+            .and_then(|line| line.strip_prefix("-----BEGIN "))
+            .and_then(|line| line.strip_suffix("-----"))
+            .ok_or(Error::InvalidEncoding)?
+            .to_owned();
+        let end_line = format!("-----END {}-----", label);
+        let mut data = String::new();
+        for line in lines {
+            if line == end_line {
+                return Ok((label, base64::decode_lenient(data)?));
+            }
+            data.push_str(line);
+        }
+        Err(Error::InvalidEncoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        let buffer: Vec<u8> = (0..=255).collect();
+        assert_eq!(hex::decode(hex::encode(&buffer)).unwrap(), buffer);
+        assert_eq!(hex::encode(b"\x00\xc0\xfe"), "00c0fe");
+        assert_eq!(hex::decode("C0fE").unwrap(), b"\xc0\xfe");
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert_eq!(hex::decode("abc"), Err(Error::InvalidLength));
+        assert_eq!(hex::decode("abcg"), Err(Error::InvalidCharacter(3)));
+    }
+
+    #[test]
+    fn base64_test_vectors() {
+        // Test vectors from RFC 4648.
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+        for (input, output) in vectors.iter() {
+            assert_eq!(base64::encode(input), *output);
+            assert_eq!(base64::decode(output).unwrap(), input.as_bytes());
+            assert_eq!(base64::decode_url_safe(output).unwrap(), input.as_bytes());
+            assert_eq!(base64::decode_url_safe(output.trim_end_matches('=')).unwrap(), input.as_bytes());
+        }
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        for size in 0..64 {
+            let buffer: Vec<u8> = (0..size).map(|byte| (byte * 37) as u8 ^ 0xfb).collect();
+            assert_eq!(base64::decode(base64::encode(&buffer)).unwrap(), buffer);
+            assert_eq!(base64::decode_url_safe(base64::encode_url_safe(&buffer)).unwrap(), buffer);
+        }
+    }
+
+    #[test]
+    fn url_safe_alphabet() {
+        assert_eq!(base64::encode(b"\xfb\xff"), "+/8=");
+        assert_eq!(base64::encode_url_safe(b"\xfb\xff"), "-_8");
+        assert_eq!(base64::decode("-_8="), Err(Error::InvalidCharacter(0)));
+    }
+
+    #[test]
+    fn invalid_base64() {
+        assert_eq!(base64::decode("Zm9"), Err(Error::InvalidPadding));
+        assert_eq!(base64::decode("Z==="), Err(Error::InvalidPadding));
+        assert_eq!(base64::decode("Zm=v"), Err(Error::InvalidCharacter(2)));
+        assert_eq!(base64::decode_url_safe("Zm9vY"), Err(Error::InvalidLength));
+        assert_eq!(base64::decode("Zm9v\nYmF"), Err(Error::InvalidCharacter(4)));
+        assert_eq!(base64::decode_lenient("Zm9v\nYmFy\n").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn base32_test_vectors() {
+        // Test vectors from RFC 4648.
+        let vectors = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (input, output) in vectors.iter() {
+            assert_eq!(base32::encode(input), *output);
+            assert_eq!(base32::decode(output).unwrap(), input.as_bytes());
+        }
+    }
+
+    #[test]
+    fn invalid_base32() {
+        assert_eq!(base32::decode("MZXW6YQ"), Err(Error::InvalidPadding));
+        assert_eq!(base32::decode("MZX====="), Err(Error::InvalidPadding));
+        assert_eq!(base32::decode("MZXW6Y1="), Err(Error::InvalidCharacter(6)));
+    }
+
+    #[test]
+    fn base58_test_vectors() {
+        assert_eq!(base58::encode(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(base58::encode(b"\x00\x00\x28\x7f\xb4\xcd"), "11233QC4");
+        assert_eq!(base58::decode("11233QC4").unwrap(), b"\x00\x00\x28\x7f\xb4\xcd");
+        assert_eq!(base58::decode("0OIl"), Err(Error::InvalidCharacter(0)));
+
+        // The address corresponding to the zero hash, using version byte zero.
+        let address = base58::encode_check([0; 21]);
+        assert_eq!(address, "1111111111111111111114oLvT2");
+        assert_eq!(base58::decode_check(&address).unwrap(), [0; 21]);
+        assert_eq!(base58::decode_check("1111111111111111111114oLvT3"), Err(Error::InvalidChecksum));
+    }
+
+    /// Every codec should agree with the hex codec on random buffers.
+    #[test]
+    #[cfg(feature = "std")]
+    fn random_round_trips() {
+        use crate::random_vec;
+
+        for size in 0..128 {
+            let buffer = random_vec!(size);
+            let expected = hex::encode(&buffer);
+            let decoded = [
+                base64::decode(base64::encode(&buffer)).unwrap(),
+                base64::decode_url_safe(base64::encode_url_safe(&buffer)).unwrap(),
+                base32::decode(base32::encode(&buffer)).unwrap(),
+                base58::decode(base58::encode(&buffer)).unwrap(),
+                base58::decode_check(base58::encode_check(&buffer)).unwrap(),
+            ];
+            for output in decoded.iter() {
+                assert_eq!(hex::encode(output), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn der_digest_info() {
+        use der::Value;
+
+        // The DigestInfo prefix for SHA-1 from RFC 8017.
+        let digest_info = Value::Sequence(vec![
+            Value::Sequence(vec![Value::ObjectIdentifier(vec![1, 3, 14, 3, 2, 26]), Value::Null]),
+            Value::OctetString(vec![0; 20]),
+        ]);
+        let encoded = digest_info.encode().unwrap();
+        assert_eq!(hex::encode(&encoded[..15]), "3021300906052b0e03021a05000414");
+        assert_eq!(Value::decode(&encoded).unwrap(), digest_info);
+    }
+
+    #[test]
+    fn der_values() {
+        use der::Value;
+
+        let modulus = [0xff; 200];
+        let public_key = Value::Sequence(vec![
+            Value::unsigned_integer(&modulus),
+            Value::unsigned_integer(&[0x01, 0x00, 0x01]),
+            Value::BitString { unused_bits: 0, data: vec![1, 2, 3] },
+            Value::ObjectIdentifier(vec![1, 2, 840, 10045, 3, 1, 7]),
+            Value::Integer(vec![0x80]),
+        ]);
+        let encoded = public_key.encode().unwrap();
+        assert_eq!(hex::encode(&encoded[..8]), "3081e40281c900ff");
+        assert_eq!(Value::decode(&encoded).unwrap(), public_key);
+        assert_eq!(Value::unsigned_integer(&[0, 0, 0x7f]), Value::Integer(vec![0x7f]));
+        assert_eq!(Value::unsigned_integer(&modulus).to_unsigned().unwrap(), modulus.to_vec());
+        assert_eq!(Value::Integer(vec![0x80]).to_unsigned(), None);
+        assert_eq!(
+            hex::encode(Value::ObjectIdentifier(vec![1, 2, 840, 10045, 3, 1, 7]).encode().unwrap()),
+            "06082a8648ce3d030107"
+        );
+        assert_eq!(hex::encode(Value::ObjectIdentifier(vec![2, 999, 3]).encode().unwrap()), "0603883703");
+
+        // Invalid object identifiers cannot be encoded.
+        for arcs in [vec![1], vec![3, 1], vec![1, 40], vec![2, u64::MAX]].iter() {
+            let value = Value::Sequence(vec![Value::ObjectIdentifier(arcs.clone())]);
+            assert_eq!(value.encode(), Err(Error::InvalidEncoding));
+        }
+    }
+
+    #[test]
+    fn invalid_der() {
+        use der::Value;
+
+        assert_eq!(Value::decode(&hex::decode("020100").unwrap()), Ok(Value::Integer(vec![0])));
+        assert_eq!(Value::decode(&hex::decode("02020001").unwrap()), Err(Error::InvalidEncoding));
+        assert_eq!(Value::decode(&hex::decode("0281010a").unwrap()), Err(Error::InvalidEncoding));
+        assert_eq!(Value::decode(&hex::decode("3004020100").unwrap()), Err(Error::InvalidLength));
+        assert_eq!(Value::decode(&hex::decode("020100ff").unwrap()), Err(Error::InvalidLength));
+        assert_eq!(Value::decode(&hex::decode("0c00").unwrap()), Err(Error::UnsupportedTag(0x0c)));
+        assert_eq!(Value::decode(&hex::decode("06028001").unwrap()), Err(Error::InvalidEncoding));
+
+        // Sequences nested too deeply are rejected.
+        let mut value = Value::Null;
+        for _ in 0..der::MAX_DEPTH {
+            value = Value::Sequence(vec![value]);
+        }
+        let encoded = value.encode().unwrap();
+        assert_eq!(Value::decode(&encoded), Ok(value.clone()));
+        let encoded = Value::Sequence(vec![value]).encode().unwrap();
+        assert_eq!(Value::decode(&encoded), Err(Error::InvalidEncoding));
+    }
+
+    #[test]
+    fn pem_round_trip() {
+        let data: Vec<u8> = (0..=255).collect();
+        let armored = pem::encode("RSA PUBLIC KEY", &data);
+        assert!(armored.lines().all(|line| line.len() <= 64));
+        let input = format!("Some comment\n{}trailing text", armored);
+        assert_eq!(pem::decode(&input).unwrap(), ("RSA PUBLIC KEY".to_owned(), data));
+        assert_eq!(pem::decode("-----BEGIN A-----\nAAAA\n-----END B-----"), Err(Error::InvalidEncoding));
+    }
+}
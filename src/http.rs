@@ -0,0 +1,1326 @@
+//! Utilities for the HTTP-style parameter strings used by the oracles, together with a
+//! minimal HTTP client.
+//!
+//! Parameter strings have the form `key=value&key=value`. Keys and values are encoded by
+//! percent-encoding `%`, `&` and `=`, leaving all other characters unchanged, and every
+//! percent-encoded byte is decoded. Empty segments (as in `a=1&&b=2`) are ignored.
+
+use std::fmt;
+use std::error;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The segment starting at the given position does not contain `=`.
+    MissingSeparator(usize),
+    /// The input contains an invalid percent-encoding at the given position.
+    InvalidEscape(usize),
+    /// The segment starting at the given position does not decode to valid UTF-8.
+    DecodingError(usize),
+    /// The cookie name at the given position is not a valid token.
+    InvalidName(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:?}", self)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+pub trait ToParamStr {
+    fn to_param_str(&self) -> String;
+}
+
+pub trait FromParamStr: Sized {
+    fn from_param_str(param_str: &str) -> Result<Self, Error>;
+}
+
+/// Parameters are encoded in order.
+impl<K: AsRef<str>, V: AsRef<str>> ToParamStr for [(K, V)] {
+    fn to_param_str(&self) -> String {
+        self.iter()
+            .map(|(key, value)| format!(
+                "{}={}",
+                urlencoding::encode_delimiters(key.as_ref(), &['&', '=']),
+                urlencoding::encode_delimiters(value.as_ref(), &['&', '='])
+            ))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Decodes a key or value, where `offset` is the position of the token in the input.
+fn decode_token(token: &str, offset: usize, segment_offset: usize) -> Result<String, Error> {
+    urlencoding::decode_str(token).map_err(|error| match error {
+        urlencoding::Error::InvalidEscape(index) => Error::InvalidEscape(offset + index),
+        urlencoding::Error::DecodingError => Error::DecodingError(segment_offset),
+    })
+}
+
+/// Parameters are returned in order, including repeated keys.
+impl FromParamStr for Vec<(String, String)> {
+    fn from_param_str(param_str: &str) -> Result<Self, Error> {
+        let mut params = Vec::new();
+        let mut offset = 0;
+        for segment in param_str.split('&') {
+            if !segment.is_empty() {
+                // Only the first `=` separates the key from the value.
+                let separator = segment.find('=').ok_or(Error::MissingSeparator(offset))?;
+                let (key, value) = (&segment[..separator], &segment[separator + 1..]);
+                params.push((
+                    decode_token(key, offset, offset)?,
+                    decode_token(value, offset + separator + 1, offset)?
+                ));
+            }
+            offset += segment.len() + 1;
+        }
+        Ok(params)
+    }
+}
+
+/// If a key is repeated, the last value is used.
+impl FromParamStr for HashMap<String, String> {
+    fn from_param_str(param_str: &str) -> Result<Self, Error> {
+        Ok(Vec::<(String, String)>::from_param_str(param_str)?.into_iter().collect())
+    }
+}
+
+/// Percent-encoding as specified in RFC 3986.
+pub mod urlencoding {
+    use std::fmt;
+    use std::error;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Error {
+        /// The input contains a `%` which is not followed by two hex digits, at the given
+        /// index.
+        InvalidEscape(usize),
+        /// The decoded input is not valid UTF-8.
+        DecodingError,
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{:?}", self)
+        }
+    }
+
+    impl error::Error for Error {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            None
+        }
+    }
+
+    /// Returns true if the byte is an unreserved character, which never needs to be encoded.
+    pub fn is_unreserved(byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || b"-._~".contains(&byte)
+    }
+
+    fn encode_byte(output: &mut String, byte: u8) {
+        output.push_str(&format!("%{:02X}", byte));
+    }
+
+    /// Percent-encodes every byte which is not an unreserved character.
+    pub fn encode<T: AsRef<[u8]>>(input: T) -> String {
+        let mut output = String::with_capacity(input.as_ref().len());
+        for &byte in input.as_ref() {
+            if is_unreserved(byte) {
+                output.push(byte as char);
+            } else {
+                encode_byte(&mut output, byte);
+            }
+        }
+        output
+    }
+
+    /// Percent-encodes `%` and the given delimiters, leaving all other characters unchanged.
+    /// The result can always be decoded back to the input using `decode_str`.
+    pub fn encode_delimiters(input: &str, delimiters: &[char]) -> String {
+        let mut output = String::with_capacity(input.len());
+        for char in input.chars() {
+            if char == '%' || delimiters.contains(&char) {
+                let mut buffer = [0; 4];
+                char.encode_utf8(&mut buffer).bytes().for_each(|byte| encode_byte(&mut output, byte));
+            } else {
+                output.push(char);
+            }
+        }
+        output
+    }
+
+    fn decode_digit(digit: Option<&u8>) -> Option<u8> {
+        (*digit? as char).to_digit(16).map(|digit| digit as u8)
+    }
+
+    /// Decodes all percent-encoded bytes. Other bytes are left unchanged.
+    pub fn decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, Error> {
+        let input = input.as_ref();
+        let mut output = Vec::with_capacity(input.len());
+        let mut index = 0;
+        while index < input.len() {
+            if input[index] == b'%' {
+                let high = decode_digit(input.get(index + 1));
+                let low = decode_digit(input.get(index + 2));
+                match (high, low) {
+                    (Some(high), Some(low)) => output.push((high << 4) | low),
+                    _ => return Err(Error::InvalidEscape(index)),
+                }
+                index += 3;
+            } else {
+                output.push(input[index]);
+                index += 1;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Decodes all percent-encoded bytes, and decodes the result as UTF-8.
+    pub fn decode_str(input: &str) -> Result<String, Error> {
+        String::from_utf8(decode(input)?).map_err(|_| Error::DecodingError)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn round_trip() {
+            let buffer: Vec<u8> = (0..=255).collect();
+            assert_eq!(decode(encode(&buffer)).unwrap(), buffer);
+
+            let input = "email=foo%26bar&role=admin;ü";
+            assert_eq!(decode_str(&encode(input)).unwrap(), input);
+            assert_eq!(decode_str(&encode_delimiters(input, &['&', '='])).unwrap(), input);
+        }
+
+        #[test]
+        fn known_output() {
+            assert_eq!(encode("a b&c=d~"), "a%20b%26c%3Dd~");
+            assert_eq!(encode_delimiters("a b&c=%26", &['&', '=']), "a b%26c%3D%2526");
+            assert_eq!(decode_str("a%20b%26c%3dd").unwrap(), "a b&c=d");
+        }
+
+        #[test]
+        fn invalid_input() {
+            assert_eq!(decode("100%"), Err(Error::InvalidEscape(3)));
+            assert_eq!(decode("%2g"), Err(Error::InvalidEscape(0)));
+            assert_eq!(decode_str("%ff"), Err(Error::DecodingError));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn param_str_round_trip() {
+        let params = vec![
+            ("email".to_owned(), "foo%26bar&role=admin".to_owned()),
+            ("role".to_owned(), "user".to_owned()),
+            ("role".to_owned(), "".to_owned()),
+        ];
+        let param_str = params.to_param_str();
+        assert_eq!(param_str, "email=foo%2526bar%26role%3Dadmin&role=user&role=");
+        assert_eq!(Vec::<(String, String)>::from_param_str(&param_str).unwrap(), params);
+    }
+
+    #[test]
+    fn repeated_keys() {
+        let params = HashMap::<String, String>::from_param_str("a=1&&b=2&a=3&").unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(params["a"], "3");
+        assert_eq!(params["b"], "2");
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(Vec::<(String, String)>::from_param_str("a=1&b&c=3"), Err(Error::MissingSeparator(4)));
+        assert_eq!(Vec::<(String, String)>::from_param_str("a=1&b=%2"), Err(Error::InvalidEscape(6)));
+        assert_eq!(Vec::<(String, String)>::from_param_str("a=1&b%3d=%ff"), Err(Error::DecodingError(4)));
+    }
+}
+
+/// A minimal blocking HTTP/1.1 client. Each request uses a new connection, and only plain
+/// `http://` URLs are supported.
+pub mod client {
+    use std::fmt;
+    use std::error;
+    use std::io;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    use super::ToParamStr;
+
+    /// The maximum size of a response, including the headers. Larger responses are rejected.
+    pub const MAX_RESPONSE_SIZE: usize = 1 << 24;
+
+    #[derive(Debug)]
+    pub enum Error {
+        IoError(io::Error),
+        /// The URL is not on the form `http://host[:port][/path]`.
+        InvalidUrl,
+        /// The response could not be parsed.
+        InvalidResponse,
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{:?}", self)
+        }
+    }
+
+    impl error::Error for Error {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            match self {
+                Error::IoError(error) => Some(error),
+                Error::InvalidUrl => None,
+                Error::InvalidResponse => None,
+            }
+        }
+    }
+
+    impl From<io::Error> for Error {
+        fn from(error: io::Error) -> Self {
+            Error::IoError(error)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Response {
+        pub status: u16,
+        pub reason: String,
+        pub headers: Vec<(String, String)>,
+        pub body: Vec<u8>,
+    }
+
+    impl Response {
+        /// Returns the value of the first header with the given (case-insensitive) name.
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+
+        /// Returns true if the status is 2xx.
+        pub fn is_success(&self) -> bool {
+            (200..300).contains(&self.status)
+        }
+
+        fn parse(buffer: &[u8]) -> Result<Self, Error> {
+            let header_size = buffer.windows(4)
+                .position(|window| window == b"\r\n\r\n")
+                .ok_or(Error::InvalidResponse)?;
+            let head = std::str::from_utf8(&buffer[..header_size]).map_err(|_| Error::InvalidResponse)?;
+            let mut lines = head.split("\r\n");
+
+            // The status line has the form `HTTP/1.1 200 OK`.
+            let status_line = lines.next().ok_or(Error::InvalidResponse)?;
+            let mut tokens = status_line.splitn(3, ' ');
+            let (status, reason) = match (tokens.next(), tokens.next(), tokens.next()) {
+                (Some(version), Some(status), reason) if version.starts_with("HTTP/") => (
+                    status.parse().map_err(|_| Error::InvalidResponse)?,
+                    reason.unwrap_or("").to_owned()
+                ),
+                _ => return Err(Error::InvalidResponse)
+            };
+            let headers = lines
+                .map(|line| {
+                    let separator = line.find(':').ok_or(Error::InvalidResponse)?;
+                    Ok((line[..separator].trim().to_owned(), line[separator + 1..].trim().to_owned()))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            let mut response = Response { status, reason, headers, body: Vec::new() };
+            let body = &buffer[header_size + 4..];
+            response.body = if response.header("Transfer-Encoding").is_some_and(|value| value.eq_ignore_ascii_case("chunked")) {
+                Self::parse_chunked(body)?
+            } else if let Some(length) = response.header("Content-Length") {
+                let length: usize = length.parse().map_err(|_| Error::InvalidResponse)?;
+                body.get(..length).ok_or(Error::InvalidResponse)?.to_vec()
+            } else {
+                body.to_vec()
+            };
+            Ok(response)
+        }
+
+        fn parse_chunked(mut body: &[u8]) -> Result<Vec<u8>, Error> {
+            let mut output = Vec::new();
+            loop {
+                let line_size = body.windows(2)
+                    .position(|window| window == b"\r\n")
+                    .ok_or(Error::InvalidResponse)?;
+                // Chunk extensions following `;` are ignored.
+                let size_str = std::str::from_utf8(&body[..line_size]).map_err(|_| Error::InvalidResponse)?;
+                let size_str = size_str.split(';').next().unwrap_or("").trim();
+                let size = usize::from_str_radix(size_str, 16).map_err(|_| Error::InvalidResponse)?;
+                if size == 0 {
+                    return Ok(output);
+                }
+                // Each chunk is followed by `\r\n`. The size is untrusted, so the offsets may
+                // overflow.
+                let chunk_start = line_size + 2;
+                let chunk_end = chunk_start.checked_add(size).ok_or(Error::InvalidResponse)?;
+                let chunk = body.get(chunk_start..chunk_end).ok_or(Error::InvalidResponse)?;
+                if body.get(chunk_end..chunk_end + 2) != Some(b"\r\n") {
+                    return Err(Error::InvalidResponse);
+                }
+                output.extend_from_slice(chunk);
+                body = &body[chunk_end + 2..];
+            }
+        }
+    }
+
+    /// Percent-encodes characters which are not allowed in the request target. Parameter
+    /// strings only encode `%`, `&` and `=`.
+    fn escape_target(target: &str) -> String {
+        let mut output = String::with_capacity(target.len());
+        for &byte in target.as_bytes() {
+            if byte <= 0x20 || byte >= 0x7f || b"\"#<>\\^`{|}".contains(&byte) {
+                output.push_str(&format!("%{:02X}", byte));
+            } else {
+                output.push(byte as char);
+            }
+        }
+        output
+    }
+
+    /// Splits the URL into the host (including the port) and the path.
+    fn parse_url(url: &str) -> Result<(String, String), Error> {
+        let url = url.strip_prefix("http://").ok_or(Error::InvalidUrl)?;
+        let (host, path) = match url.find('/') {
+            Some(index) => (&url[..index], &url[index..]),
+            None => (url, "/"),
+        };
+        if host.is_empty() {
+            return Err(Error::InvalidUrl);
+        }
+        Ok((host.to_owned(), path.to_owned()))
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct Client {
+        timeout: Option<Duration>,
+    }
+
+    impl Client {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Returns a client which fails requests when reading from or writing to the
+        /// connection blocks for longer than the given duration.
+        pub fn with_timeout(mut self, timeout: Duration) -> Self {
+            self.timeout = Some(timeout);
+            self
+        }
+
+        /// Sends a GET request, with the given parameters appended to the URL as a query
+        /// string.
+        pub fn get<P: ToParamStr + ?Sized>(&self, url: &str, params: &P) -> Result<Response, Error> {
+            let query = params.to_param_str();
+            let url = match (query.is_empty(), url.contains('?')) {
+                (true, _) => url.to_owned(),
+                (false, true) => format!("{}&{}", url, query),
+                (false, false) => format!("{}?{}", url, query),
+            };
+            self.request("GET", &url, None)
+        }
+
+        /// Sends a POST request with the given parameters as a form-encoded body.
+        pub fn post<P: ToParamStr + ?Sized>(&self, url: &str, params: &P) -> Result<Response, Error> {
+            let body = params.to_param_str();
+            self.request("POST", url, Some(("application/x-www-form-urlencoded", body.as_bytes())))
+        }
+
+        /// Sends a request with an optional body, given together with its content type.
+        pub fn request(&self, method: &str, url: &str, body: Option<(&str, &[u8])>) -> Result<Response, Error> {
+            let (host, path) = parse_url(url)?;
+            let address = if host.contains(':') { host.clone() } else { format!("{}:80", host) };
+            let mut stream = TcpStream::connect(address)?;
+            stream.set_read_timeout(self.timeout)?;
+            stream.set_write_timeout(self.timeout)?;
+
+            let mut request = format!(
+                "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+                method,
+                escape_target(&path),
+                host
+            ).into_bytes();
+            if let Some((content_type, body)) = body {
+                request.extend(format!(
+                    "Content-Type: {}\r\nContent-Length: {}\r\n",
+                    content_type,
+                    body.len()
+                ).bytes());
+                request.extend_from_slice(b"\r\n");
+                request.extend_from_slice(body);
+            } else {
+                request.extend_from_slice(b"\r\n");
+            }
+            stream.write_all(&request)?;
+
+            // Since we send `Connection: close`, the response ends when the connection is closed.
+            // We read one byte more than allowed to detect responses which are too large.
+            let mut response = Vec::new();
+            stream.take(MAX_RESPONSE_SIZE as u64 + 1).read_to_end(&mut response)?;
+            if response.len() > MAX_RESPONSE_SIZE {
+                return Err(Error::InvalidResponse);
+            }
+            Response::parse(&response)
+        }
+    }
+
+    /// Sends a GET request using the default client.
+    pub fn get<P: ToParamStr + ?Sized>(url: &str, params: &P) -> Result<Response, Error> {
+        Client::new().get(url, params)
+    }
+
+    /// Sends a POST request using the default client.
+    pub fn post<P: ToParamStr + ?Sized>(url: &str, params: &P) -> Result<Response, Error> {
+        Client::new().post(url, params)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+        use std::thread;
+
+        use super::*;
+
+        /// Accepts a single connection and sends the given response. Returns the URL of the
+        /// server, and a handle which returns the received request line and body.
+        fn serve_once(response: &'static [u8]) -> (String, thread::JoinHandle<(String, Vec<u8>)>) {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let handle = thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                reader.get_mut().write_all(response).unwrap();
+                (request_line.trim_end().to_owned(), body)
+            });
+            (url, handle)
+        }
+
+        #[test]
+        fn get_request() {
+            let (url, handle) = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nX-Test: a b\r\n\r\nhello");
+            let params = [("file", "foo bar"), ("signature", "a&b")];
+            let response = get(&format!("{}/test", url), &params[..]).unwrap();
+            assert!(response.is_success());
+            assert_eq!(response.header("x-test"), Some("a b"));
+            assert_eq!(response.body, b"hello");
+
+            let (request_line, _) = handle.join().unwrap();
+            assert_eq!(request_line, "GET /test?file=foo%20bar&signature=a%26b HTTP/1.1");
+        }
+
+        #[test]
+        fn post_request() {
+            let (url, handle) = serve_once(
+                b"HTTP/1.1 500 Internal Server Error\r\nTransfer-Encoding: chunked\r\n\r\n\
+                  5\r\nhello\r\n7;ext\r\n, world\r\n0\r\n\r\n"
+            );
+            let params = [("email", "foo=bar")];
+            let response = post(&url, &params[..]).unwrap();
+            assert_eq!(response.status, 500);
+            assert_eq!(response.reason, "Internal Server Error");
+            assert_eq!(response.body, b"hello, world");
+
+            let (request_line, body) = handle.join().unwrap();
+            assert_eq!(request_line, "POST / HTTP/1.1");
+            assert_eq!(body, b"email=foo%3Dbar");
+        }
+
+        #[test]
+        fn invalid_input() {
+            assert!(matches!(get("https://example.com", &[("a", "b")][..]), Err(Error::InvalidUrl)));
+            assert!(matches!(Response::parse(b"HTTP/1.1 abc\r\n\r\n"), Err(Error::InvalidResponse)));
+            assert!(matches!(
+                Response::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort"),
+                Err(Error::InvalidResponse)
+            ));
+        }
+
+        #[test]
+        fn invalid_chunks() {
+            let head = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+            let bodies: [&[u8]; 3] = [
+                // The chunk is not followed by `\r\n`.
+                b"5\r\nhelloXX0\r\n\r\n",
+                // The chunk is longer than the given size.
+                b"3\r\nhello\r\n0\r\n\r\n",
+                // The chunk size overflows.
+                b"ffffffffffffffff\r\nhello\r\n0\r\n\r\n",
+            ];
+            for body in bodies.iter() {
+                let response = [&head[..], body].concat();
+                assert!(matches!(Response::parse(&response), Err(Error::InvalidResponse)));
+            }
+        }
+    }
+}
+
+/// Parsing and formatting of `Cookie` and `Set-Cookie` headers (RFC 6265). Cookie values are
+/// percent-encoded if they contain characters which are not allowed in a cookie value, and
+/// are always percent-decoded when parsed.
+pub mod cookie {
+    use std::fmt;
+
+    use super::{urlencoding, decode_token, Error};
+    use super::client::Response;
+
+    /// The characters which are percent-encoded in cookie values, in addition to `%`.
+    const DELIMITERS: [char; 6] = [';', ',', ' ', '"', '\\', '='];
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SameSite {
+        Strict,
+        Lax,
+        None,
+    }
+
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct Attributes {
+        pub path: Option<String>,
+        pub domain: Option<String>,
+        pub expires: Option<String>,
+        pub max_age: Option<i64>,
+        pub secure: bool,
+        pub http_only: bool,
+        pub same_site: Option<SameSite>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Cookie {
+        pub name: String,
+        pub value: String,
+        pub attributes: Attributes,
+    }
+
+    /// Strips the header name, if present.
+    fn strip_name<'a>(header: &'a str, name: &str) -> &'a str {
+        match header.split_once(':') {
+            Some((prefix, value)) if prefix.trim().eq_ignore_ascii_case(name) => value,
+            _ => header,
+        }
+    }
+
+    /// Splits the header into `;`-separated segments, returning each trimmed segment together
+    /// with its position in the header.
+    fn segments(header: &str) -> impl Iterator<Item = (usize, &str)> {
+        let base = header.as_ptr() as usize;
+        header.split(';')
+            .map(move |segment| {
+                let trimmed = segment.trim();
+                (trimmed.as_ptr() as usize - base, trimmed)
+            })
+            .filter(|(_, segment)| !segment.is_empty())
+    }
+
+    /// Returns true if the name is a token (RFC 7230), which excludes control characters,
+    /// whitespace and separators like `;` and `=`.
+    fn is_token(name: &str) -> bool {
+        !name.is_empty() && name.bytes().all(|byte| {
+            byte.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&byte)
+        })
+    }
+
+    fn parse_pair(segment: &str, offset: usize) -> Result<(String, String), Error> {
+        let separator = segment.find('=').ok_or(Error::MissingSeparator(offset))?;
+        let name = segment[..separator].trim();
+        if !is_token(name) {
+            return Err(Error::InvalidName(offset));
+        }
+        let value = segment[separator + 1..].trim();
+        // Cookie values may be quoted.
+        let value = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+        Ok((name.to_owned(), decode_token(value, offset + separator + 1, offset)?))
+    }
+
+    impl Cookie {
+        pub fn new(name: &str, value: &str) -> Self {
+            Cookie { name: name.to_owned(), value: value.to_owned(), attributes: Attributes::default() }
+        }
+
+        pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+            self.attributes = attributes;
+            self
+        }
+
+        /// Parses a `Set-Cookie` header, with or without the header name. Unknown attributes
+        /// are ignored.
+        pub fn parse_set_cookie(header: &str) -> Result<Self, Error> {
+            let value = strip_name(header, "Set-Cookie");
+            let offset = header.len() - value.len();
+            let mut segments = segments(value);
+            let (position, pair) = segments.next().ok_or(Error::MissingSeparator(offset))?;
+            let (name, value) = parse_pair(pair, offset + position)?;
+
+            let mut attributes = Attributes::default();
+            for (_, segment) in segments {
+                let (key, value) = match segment.split_once('=') {
+                    Some((key, value)) => (key.trim(), Some(value.trim())),
+                    None => (segment, None),
+                };
+                match (key.to_ascii_lowercase().as_str(), value) {
+                    ("path", Some(value)) => attributes.path = Some(value.to_owned()),
+                    ("domain", Some(value)) => attributes.domain = Some(value.trim_start_matches('.').to_owned()),
+                    ("expires", Some(value)) => attributes.expires = Some(value.to_owned()),
+                    ("max-age", Some(value)) => attributes.max_age = value.parse().ok(),
+                    ("secure", _) => attributes.secure = true,
+                    ("httponly", _) => attributes.http_only = true,
+                    ("samesite", Some(value)) => attributes.same_site = match value.to_ascii_lowercase().as_str() {
+                        "strict" => Some(SameSite::Strict),
+                        "lax" => Some(SameSite::Lax),
+                        "none" => Some(SameSite::None),
+                        _ => None,
+                    },
+                    _ => {}
+                }
+            }
+            Ok(Cookie { name, value, attributes })
+        }
+
+        /// Parses a `Cookie` header, with or without the header name.
+        pub fn parse_cookie(header: &str) -> Result<Vec<Self>, Error> {
+            let value = strip_name(header, "Cookie");
+            let offset = header.len() - value.len();
+            segments(value)
+                .map(|(position, segment)| {
+                    let (name, value) = parse_pair(segment, offset + position)?;
+                    Ok(Cookie::new(&name, &value))
+                })
+                .collect()
+        }
+
+        /// Returns the `name=value` pair used in `Cookie` headers.
+        ///
+        /// # Errors
+        ///
+        /// Returns `Error::InvalidName` if the name is not a valid token. (Names cannot be
+        /// escaped, so a name containing e.g. `;` or `=` would inject additional cookies.)
+        pub fn to_pair(&self) -> Result<String, Error> {
+            if !is_token(&self.name) {
+                return Err(Error::InvalidName(0));
+            }
+            Ok(format!("{}={}", self.name, urlencoding::encode_delimiters(&self.value, &DELIMITERS)))
+        }
+
+        /// Returns the value of the `Set-Cookie` header (without the header name).
+        ///
+        /// # Errors
+        ///
+        /// Returns `Error::InvalidName` if the name is not a valid token.
+        pub fn to_set_cookie(&self) -> Result<String, Error> {
+            let mut header = self.to_pair()?;
+            let attributes = &self.attributes;
+            if let Some(path) = &attributes.path {
+                header.push_str(&format!("; Path={}", path));
+            }
+            if let Some(domain) = &attributes.domain {
+                header.push_str(&format!("; Domain={}", domain));
+            }
+            if let Some(expires) = &attributes.expires {
+                header.push_str(&format!("; Expires={}", expires));
+            }
+            if let Some(max_age) = attributes.max_age {
+                header.push_str(&format!("; Max-Age={}", max_age));
+            }
+            if attributes.secure {
+                header.push_str("; Secure");
+            }
+            if attributes.http_only {
+                header.push_str("; HttpOnly");
+            }
+            if let Some(same_site) = attributes.same_site {
+                header.push_str(&format!("; SameSite={:?}", same_site));
+            }
+            Ok(header)
+        }
+    }
+
+    /// Formats the `name=value` pair. Formatting fails if the name is not a valid token.
+    impl fmt::Display for Cookie {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{}", self.to_pair().map_err(|_| fmt::Error)?)
+        }
+    }
+
+    /// A collection of cookies. Cookies are identified by their name, path and domain.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct CookieJar {
+        cookies: Vec<Cookie>,
+    }
+
+    impl CookieJar {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Adds the cookie, replacing any cookie with the same name, path and domain. Cookies
+        /// with a non-positive `Max-Age` remove the existing cookie instead.
+        pub fn insert(&mut self, cookie: Cookie) {
+            self.cookies.retain(|other| !(
+                other.name == cookie.name && 
+                other.attributes.path == cookie.attributes.path &&
+                other.attributes.domain == cookie.attributes.domain
+            ));
+            if cookie.attributes.max_age.is_none_or(|max_age| max_age > 0) {
+                self.cookies.push(cookie);
+            }
+        }
+
+        /// Returns the first cookie with the given name.
+        pub fn get(&self, name: &str) -> Option<&Cookie> {
+            self.cookies.iter().find(|cookie| cookie.name == name)
+        }
+
+        pub fn cookies(&self) -> &[Cookie] {
+            &self.cookies
+        }
+
+        /// Adds the cookies from all `Set-Cookie` headers in the response. Invalid headers
+        /// are ignored.
+        pub fn update_from(&mut self, response: &Response) {
+            response.headers
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case("Set-Cookie"))
+                .filter_map(|(_, value)| Cookie::parse_set_cookie(value).ok())
+                .for_each(|cookie| self.insert(cookie));
+        }
+
+        /// Returns the value of the `Cookie` header (without the header name).
+        ///
+        /// # Errors
+        ///
+        /// Returns `Error::InvalidName` if the name of a cookie is not a valid token.
+        pub fn to_cookie_header(&self) -> Result<String, Error> {
+            Ok(self.cookies.iter().map(Cookie::to_pair).collect::<Result<Vec<_>, Error>>()?.join("; "))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn set_cookie() {
+            let cookie = Cookie::parse_set_cookie(
+                "Set-Cookie: session=a%3Bb=c; Path=/; Domain=.example.com; Max-Age=3600; Secure; \
+                 HttpOnly; SameSite=Lax; Unknown=1"
+            ).unwrap();
+            assert_eq!(cookie.name, "session");
+            assert_eq!(cookie.value, "a;b=c");
+            assert_eq!(cookie.attributes, Attributes {
+                path: Some("/".to_owned()),
+                domain: Some("example.com".to_owned()),
+                expires: None,
+                max_age: Some(3600),
+                secure: true,
+                http_only: true,
+                same_site: Some(SameSite::Lax),
+            });
+            assert_eq!(
+                cookie.to_set_cookie().unwrap(),
+                "session=a%3Bb%3Dc; Path=/; Domain=example.com; Max-Age=3600; Secure; HttpOnly; SameSite=Lax"
+            );
+            assert_eq!(Cookie::parse_set_cookie(&cookie.to_set_cookie().unwrap()).unwrap(), cookie);
+        }
+
+        #[test]
+        fn cookie_header() {
+            let cookies = Cookie::parse_cookie("Cookie: a=1; b=\"x y\";c=%25").unwrap();
+            let values: Vec<_> = cookies.iter().map(|cookie| (&cookie.name[..], &cookie.value[..])).collect();
+            assert_eq!(values, [("a", "1"), ("b", "x y"), ("c", "%")]);
+            assert_eq!(Cookie::parse_cookie("Cookie: a=1; b"), Err(Error::MissingSeparator(13)));
+            assert_eq!(Cookie::parse_cookie("a=%zz"), Err(Error::InvalidEscape(2)));
+            assert_eq!(Cookie::parse_cookie("a=1; b c=2"), Err(Error::InvalidName(5)));
+        }
+
+        #[test]
+        fn invalid_names() {
+            for name in ["", "a;b", "a=b", "a b", "a\"b", "a\r\nb"].iter() {
+                let cookie = Cookie::new(name, "1");
+                assert_eq!(cookie.to_pair(), Err(Error::InvalidName(0)));
+                assert_eq!(cookie.to_set_cookie(), Err(Error::InvalidName(0)));
+            }
+            let mut jar = CookieJar::new();
+            jar.insert(Cookie::new("a;admin", "true"));
+            assert_eq!(jar.to_cookie_header(), Err(Error::InvalidName(0)));
+        }
+
+        #[test]
+        fn cookie_jar() {
+            let mut jar = CookieJar::new();
+            jar.insert(Cookie::new("a", "1"));
+            jar.insert(Cookie::new("b", "x;y"));
+            jar.insert(Cookie::new("a", "2"));
+            assert_eq!(jar.to_cookie_header().unwrap(), "b=x%3By; a=2");
+            assert_eq!(Cookie::parse_cookie(&jar.to_cookie_header().unwrap()).unwrap()[0].value, "x;y");
+
+            let response = Response {
+                status: 200,
+                reason: "OK".to_owned(),
+                headers: vec![
+                    ("Set-Cookie".to_owned(), "b=; Max-Age=0".to_owned()),
+                    ("set-cookie".to_owned(), "c=3".to_owned()),
+                ],
+                body: Vec::new(),
+            };
+            jar.update_from(&response);
+            assert_eq!(jar.to_cookie_header().unwrap(), "a=2; c=3");
+            assert_eq!(jar.get("c").map(|cookie| &cookie.value[..]), Some("3"));
+        }
+    }
+}
+
+/// A serde data format for parameter strings. Structs and maps with scalar values (strings,
+/// numbers, booleans, unit variants and options) are supported. Keys and values are encoded
+/// using `ToParamStr` and `FromParamStr`, so the escaping rules are shared with the rest of
+/// the module. `None` values are omitted when serializing.
+#[cfg(feature = "serde")]
+pub mod serde {
+    use std::fmt;
+    use std::error;
+
+    use ::serde::{ser, de, Serialize};
+    use ::serde::de::{DeserializeOwned, IntoDeserializer};
+    use ::serde::ser::Impossible;
+
+    use super::{ToParamStr, FromParamStr};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Error {
+        /// The input is not a valid parameter string.
+        Syntax(super::Error),
+        /// The type cannot be represented as a parameter string.
+        Unsupported(&'static str),
+        /// An error reported by the serialized or deserialized type.
+        Custom(String),
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{:?}", self)
+        }
+    }
+
+    impl error::Error for Error {}
+
+    impl ser::Error for Error {
+        fn custom<T: fmt::Display>(message: T) -> Self {
+            Error::Custom(message.to_string())
+        }
+    }
+
+    impl de::Error for Error {
+        fn custom<T: fmt::Display>(message: T) -> Self {
+            Error::Custom(message.to_string())
+        }
+    }
+
+    /// Serializes the value as a parameter string.
+    pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+        let mut serializer = Serializer { params: Vec::new(), key: None };
+        value.serialize(&mut serializer)?;
+        Ok(serializer.params.to_param_str())
+    }
+
+    /// Deserializes the value from a parameter string.
+    pub fn from_str<T: DeserializeOwned>(param_str: &str) -> Result<T, Error> {
+        let params = Vec::<(String, String)>::from_param_str(param_str).map_err(Error::Syntax)?;
+        T::deserialize(Deserializer { params })
+    }
+
+    /// Serializes the top-level struct or map.
+    struct Serializer {
+        params: Vec<(String, String)>,
+        key: Option<String>,
+    }
+
+    impl Serializer {
+        fn push<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), Error> {
+            if let Some(value) = value.serialize(ValueSerializer)? {
+                self.params.push((key, value));
+            }
+            Ok(())
+        }
+    }
+
+    macro_rules! unsupported {
+        ($($method:ident($($arg:ty),*) -> $output:ty;)*) => {
+            $(
+                fn $method(self, $(_: $arg),*) -> Result<$output, Error> {
+                    Err(Error::Unsupported(stringify!($method)))
+                }
+            )*
+        }
+    }
+
+    impl ser::Serializer for &mut Serializer {
+        type Ok = ();
+        type Error = Error;
+        type SerializeSeq = Impossible<(), Error>;
+        type SerializeTuple = Impossible<(), Error>;
+        type SerializeTupleStruct = Impossible<(), Error>;
+        type SerializeTupleVariant = Impossible<(), Error>;
+        type SerializeMap = Self;
+        type SerializeStruct = Self;
+        type SerializeStructVariant = Impossible<(), Error>;
+
+        unsupported! {
+            serialize_bool(bool) -> ();
+            serialize_i8(i8) -> ();
+            serialize_i16(i16) -> ();
+            serialize_i32(i32) -> ();
+            serialize_i64(i64) -> ();
+            serialize_u8(u8) -> ();
+            serialize_u16(u16) -> ();
+            serialize_u32(u32) -> ();
+            serialize_u64(u64) -> ();
+            serialize_f32(f32) -> ();
+            serialize_f64(f64) -> ();
+            serialize_char(char) -> ();
+            serialize_str(&str) -> ();
+            serialize_bytes(&[u8]) -> ();
+            serialize_none() -> ();
+            serialize_unit() -> ();
+            serialize_unit_struct(&'static str) -> ();
+            serialize_unit_variant(&'static str, u32, &'static str) -> ();
+            serialize_seq(Option<usize>) -> Self::SerializeSeq;
+            serialize_tuple(usize) -> Self::SerializeTuple;
+            serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+            serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+            serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+        }
+
+        fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &'static str, value: &T) -> Result<(), Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: &T
+        ) -> Result<(), Error> {
+            Err(Error::Unsupported("serialize_newtype_variant"))
+        }
+
+        fn serialize_map(self, _: Option<usize>) -> Result<Self, Error> {
+            Ok(self)
+        }
+
+        fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self, Error> {
+            Ok(self)
+        }
+    }
+
+    impl ser::SerializeStruct for &mut Serializer {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+            self.push(key.to_owned(), value)
+        }
+
+        fn end(self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    impl ser::SerializeMap for &mut Serializer {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+            self.key = Some(key.serialize(ValueSerializer)?.ok_or(Error::Unsupported("serialize_none"))?);
+            Ok(())
+        }
+
+        fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+            // Keys are always serialized before their values.
+            let key = self.key.take().unwrap();
+            self.push(key, value)
+        }
+
+        fn end(self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    /// Serializes a scalar value. Returns `None` for `None`.
+    struct ValueSerializer;
+
+    macro_rules! serialize_display {
+        ($($method:ident($type:ty);)*) => {
+            $(
+                fn $method(self, value: $type) -> Result<Option<String>, Error> {
+                    Ok(Some(value.to_string()))
+                }
+            )*
+        }
+    }
+
+    impl ser::Serializer for ValueSerializer {
+        type Ok = Option<String>;
+        type Error = Error;
+        type SerializeSeq = Impossible<Option<String>, Error>;
+        type SerializeTuple = Impossible<Option<String>, Error>;
+        type SerializeTupleStruct = Impossible<Option<String>, Error>;
+        type SerializeTupleVariant = Impossible<Option<String>, Error>;
+        type SerializeMap = Impossible<Option<String>, Error>;
+        type SerializeStruct = Impossible<Option<String>, Error>;
+        type SerializeStructVariant = Impossible<Option<String>, Error>;
+
+        serialize_display! {
+            serialize_bool(bool);
+            serialize_i8(i8);
+            serialize_i16(i16);
+            serialize_i32(i32);
+            serialize_i64(i64);
+            serialize_u8(u8);
+            serialize_u16(u16);
+            serialize_u32(u32);
+            serialize_u64(u64);
+            serialize_f32(f32);
+            serialize_f64(f64);
+            serialize_char(char);
+            serialize_str(&str);
+        }
+
+        unsupported! {
+            serialize_bytes(&[u8]) -> Option<String>;
+            serialize_unit() -> Option<String>;
+            serialize_unit_struct(&'static str) -> Option<String>;
+            serialize_seq(Option<usize>) -> Self::SerializeSeq;
+            serialize_tuple(usize) -> Self::SerializeTuple;
+            serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+            serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+            serialize_map(Option<usize>) -> Self::SerializeMap;
+            serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+            serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+        }
+
+        fn serialize_none(self) -> Result<Option<String>, Error> {
+            Ok(None)
+        }
+
+        fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Option<String>, Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<Option<String>, Error> {
+            Ok(Some(variant.to_owned()))
+        }
+
+        fn serialize_newtype_struct<T: Serialize + ?Sized>(
+            self,
+            _: &'static str,
+            value: &T
+        ) -> Result<Option<String>, Error> {
+            value.serialize(self)
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: &T
+        ) -> Result<Option<String>, Error> {
+            Err(Error::Unsupported("serialize_newtype_variant"))
+        }
+    }
+
+    /// Deserializes the top-level struct or map.
+    struct Deserializer {
+        params: Vec<(String, String)>,
+    }
+
+    impl<'de> de::Deserializer<'de> for Deserializer {
+        type Error = Error;
+
+        fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_map(MapAccess { params: self.params.into_iter(), value: None })
+        }
+
+        ::serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf option unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct enum identifier ignored_any
+        }
+    }
+
+    struct MapAccess {
+        params: std::vec::IntoIter<(String, String)>,
+        value: Option<String>,
+    }
+
+    impl<'de> de::MapAccess<'de> for MapAccess {
+        type Error = Error;
+
+        fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+            match self.params.next() {
+                Some((key, value)) => {
+                    self.value = Some(value);
+                    seed.deserialize(key.into_deserializer()).map(Some)
+                },
+                None => Ok(None)
+            }
+        }
+
+        fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+            // Values are always deserialized after their keys.
+            seed.deserialize(ValueDeserializer(self.value.take().unwrap()))
+        }
+    }
+
+    /// Deserializes a scalar value, parsing the string according to the requested type.
+    struct ValueDeserializer(String);
+
+    macro_rules! deserialize_parse {
+        ($($method:ident => $visit:ident;)*) => {
+            $(
+                fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                    match self.0.parse() {
+                        Ok(value) => visitor.$visit(value),
+                        Err(_) => Err(de::Error::invalid_value(de::Unexpected::Str(&self.0), &visitor)),
+                    }
+                }
+            )*
+        }
+    }
+
+    impl<'de> de::Deserializer<'de> for ValueDeserializer {
+        type Error = Error;
+
+        fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_string(self.0)
+        }
+
+        deserialize_parse! {
+            deserialize_bool => visit_bool;
+            deserialize_i8 => visit_i8;
+            deserialize_i16 => visit_i16;
+            deserialize_i32 => visit_i32;
+            deserialize_i64 => visit_i64;
+            deserialize_u8 => visit_u8;
+            deserialize_u16 => visit_u16;
+            deserialize_u32 => visit_u32;
+            deserialize_u64 => visit_u64;
+            deserialize_f32 => visit_f32;
+            deserialize_f64 => visit_f64;
+            deserialize_char => visit_char;
+        }
+
+        fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+            self,
+            _: &'static str,
+            visitor: V
+        ) -> Result<V::Value, Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_enum<V: de::Visitor<'de>>(
+            self,
+            _: &'static str,
+            _: &'static [&'static str],
+            visitor: V
+        ) -> Result<V::Value, Error> {
+            visitor.visit_enum(self.0.into_deserializer())
+        }
+
+        ::serde::forward_to_deserialize_any! {
+            i128 u128 str string bytes byte_buf unit unit_struct seq tuple
+            tuple_struct map struct identifier ignored_any
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::collections::BTreeMap;
+        use ::serde::{Serialize, Deserialize};
+
+        use super::*;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Kind {
+            Cookie,
+            Token,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Params {
+            name: String,
+            count: u32,
+            valid: bool,
+            kind: Kind,
+            comment: Option<String>,
+        }
+
+        #[test]
+        fn round_trip() {
+            let params = Params {
+                name: "a=1&b=%26".to_owned(),
+                count: 10,
+                valid: true,
+                kind: Kind::Cookie,
+                comment: None,
+            };
+            let param_str = to_string(&params).unwrap();
+            assert_eq!(param_str, "name=a%3D1%26b%3D%2526&count=10&valid=true&kind=cookie");
+            assert_eq!(from_str::<Params>(&param_str).unwrap(), params);
+        }
+
+        #[test]
+        fn maps() {
+            let params: BTreeMap<String, u8> = from_str("b=2&a=1&b=3").unwrap();
+            assert_eq!(to_string(&params).unwrap(), "a=1&b=3");
+        }
+
+        #[test]
+        fn invalid_input() {
+            assert_eq!(from_str::<Params>("name"), Err(Error::Syntax(super::super::Error::MissingSeparator(0))));
+            assert!(from_str::<Params>("name=x&count=-1&valid=true&kind=token").is_err());
+            assert!(from_str::<Params>("name=x&count=1&valid=true&kind=admin").is_err());
+            assert!(from_str::<Params>("name=x&count=1&valid=true").is_err());
+            assert_eq!(to_string(&[1, 2]), Err(Error::Unsupported("serialize_tuple")));
+        }
+    }
+}
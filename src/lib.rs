@@ -1,4 +1,38 @@
+//! Without the default `std` feature, the crate is `no_std` and only requires `alloc`. This
+//! leaves the hash functions, the encodings, the padding and cipher modes together with the
+//! ciphers, `math::linear_algebra` and `math::sparse`.
+//!
+//! AES is backed by OpenSSL when the `openssl` feature is enabled, and by the pure-Rust
+//! implementation in `crypto::aes` otherwise (and always on `wasm32`). APIs which read the
+//! system clock require the `system-time` feature. Both are enabled by the default `native`
+//! feature. To build for `wasm32-unknown-unknown`, disable the default features and enable
+//! `wasm`, which seeds `thread_rng` through `getrandom`. The oracles only use data files which
+//! are embedded in the crate, so they do not require a file system.
+//!
+//! The `serde` feature implements `Serialize` and `Deserialize` for message digests, the
+//! Mersenne twister state, frequency tables and distributions, and the vectors and matrices in
+//! `math::linear_algebra`. This allows long-running attacks to save intermediate state and resume
+//! later.
+
+#![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
+
+extern crate alloc;
+
+mod prelude;
+
 pub mod math;
 pub mod crypto;
+#[cfg(feature = "std")]
 pub mod oracles;
+#[cfg(feature = "std")]
 pub mod attacks;
+#[cfg(feature = "std")]
+pub mod data;
+pub mod encoding;
+#[cfg(feature = "std")]
+pub mod http;
+#[cfg(feature = "std")]
+pub mod trace;
+#[cfg(feature = "std")]
+pub mod progress;
+pub mod utils;
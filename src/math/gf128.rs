@@ -0,0 +1,157 @@
+//! This module implements the field GF(2^128) used by GHASH in GCM.
+//!
+//! Elements are represented using the bit order from the GCM specification (NIST SP 800-38D),
+//! where the most significant bit of the underlying `u128` is the constant term. The field is
+//! defined by the polynomial `x^128 + x^7 + x^2 + x + 1`.
+
+use core::{fmt, ops};
+
+/// An element of GF(2^128).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Gf128(u128);
+
+impl Gf128 {
+    /// The block size of the byte representation of an element.
+    pub const BLOCK_SIZE: usize = 16;
+
+    /// Returns the additive identity.
+    pub fn zero() -> Self {
+        Gf128(0)
+    }
+
+    /// Returns the multiplicative identity.
+    pub fn one() -> Self {
+        Gf128(1 << 127)
+    }
+
+    /// Returns the element with the given representation.
+    pub fn new(value: u128) -> Self {
+        Gf128(value)
+    }
+
+    /// Returns the underlying representation of the element.
+    pub fn value(&self) -> u128 {
+        self.0
+    }
+
+    /// Returns the element represented by the given block. Short blocks are padded with zeroes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block is longer than `Self::BLOCK_SIZE` bytes.
+    pub fn from_block(block: &[u8]) -> Self {
+        let mut buffer = [0; Self::BLOCK_SIZE];
+        buffer[..block.len()].copy_from_slice(block);
+        Gf128(u128::from_be_bytes(buffer))
+    }
+
+    /// Returns the byte representation of the element.
+    pub fn to_block(&self) -> [u8; Self::BLOCK_SIZE] {
+        self.0.to_be_bytes()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `self^exponent`.
+    pub fn pow(&self, mut exponent: u128) -> Self {
+        let mut base = *self;
+        let mut result = Self::one();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// Returns the multiplicative inverse of the element, or `None` for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        // The multiplicative group has order 2^128 - 1.
+        Some(self.pow(u128::MAX - 1))
+    }
+}
+
+impl From<u128> for Gf128 {
+    fn from(value: u128) -> Self {
+        Gf128(value)
+    }
+}
+
+impl fmt::Debug for Gf128 {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Gf128({:032x})", self.0)
+    }
+}
+
+/// Implements `x + y` for field elements `x` and `y`.
+impl ops::Add for Gf128 {
+    type Output = Gf128;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, other: Gf128) -> Gf128 {
+        Gf128(self.0 ^ other.0)
+    }
+}
+
+/// Implements `x += y` for field elements `x` and `y`.
+impl ops::AddAssign for Gf128 {
+    fn add_assign(&mut self, other: Gf128) {
+        *self = *self + other;
+    }
+}
+
+/// Implements `x * y` for field elements `x` and `y`.
+impl ops::Mul for Gf128 {
+    type Output = Gf128;
+
+    fn mul(self, other: Gf128) -> Gf128 {
+        // Multiplying by x shifts the representation right, and reduces by x^128 = x^7 + x^2 + x + 1.
+        const R: u128 = 0xe1 << 120;
+        let mut result = 0;
+        let mut y = other.0;
+        for index in (0..128).rev() {
+            if (self.0 >> index) & 1 == 1 {
+                result ^= y;
+            }
+            y = if y & 1 == 1 { (y >> 1) ^ R } else { y >> 1 };
+        }
+        Gf128(result)
+    }
+}
+
+/// Implements `x *= y` for field elements `x` and `y`.
+impl ops::MulAssign for Gf128 {
+    fn mul_assign(&mut self, other: Gf128) {
+        *self = *self * other;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_arithmetic() {
+        let x = Gf128::new(0x66e94bd4ef8a2c3b884cfa59ca342b2e);
+        let y = Gf128::new(0x0388dace60b6a392f328c2b971b2fe78);
+        assert_eq!(x * Gf128::one(), x);
+        assert_eq!(x * Gf128::zero(), Gf128::zero());
+        assert_eq!(x + x, Gf128::zero());
+        assert_eq!(x * y, y * x);
+        assert_eq!(x * (x + y), x * x + x * y);
+        assert_eq!(x.pow(3), x * x * x);
+        assert_eq!(x * x.inverse().unwrap(), Gf128::one());
+        assert_eq!(Gf128::zero().inverse(), None);
+        // x * x^127 = x^128 = x^7 + x^2 + x + 1.
+        let monomial = |degree: u32| Gf128::new(1 << (127 - degree));
+        assert_eq!(monomial(1) * monomial(127), Gf128::new(0xe1 << 120));
+        assert_eq!(Gf128::from_block(&x.to_block()), x);
+    }
+}
@@ -1,9 +1,19 @@
 //! This module implements vectors, matrices, and Gauss elimination over
 //! the two element field {0, 1}.
 
-use rand;
+#[cfg(feature = "std")]
 use rand::Rng;
-use std::{fmt, ops, convert};
+#[cfg(feature = "std")]
+use rand::seq::SliceRandom;
+use core::{fmt, ops, convert, error};
+use core::convert::TryInto;
+
+use crate::prelude::*;
+use crate::crypto::random::RandomGenerator;
+use super::polynomial::Polynomial;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 #[derive(Debug)]
 pub enum Error {
@@ -12,8 +22,43 @@ pub enum Error {
     UnderDeterminedSystemError,
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:?}", self)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
+
+/// Reads a little-endian `u64` from the start of `buffer` and advances the slice.
+fn read_u64(buffer: &mut &[u8]) -> Result<u64, Error> {
+    if buffer.len() < 8 {
+        return Err(Error::ConversionError);
+    }
+    let (head, tail) = buffer.split_at(8);
+    *buffer = tail;
+    Ok(u64::from_le_bytes(head.try_into().unwrap()))
+}
+
+/// Reads a dimension, which must fit in a `usize`.
+fn read_dimension(buffer: &mut &[u8]) -> Result<usize, Error> {
+    read_u64(buffer)?.try_into().map_err(|_| Error::ConversionError)
+}
+
+/// Returns the number of limbs needed for the given dimension. The dimension is untrusted
+/// when decoding, so this may overflow.
+fn limb_count(dimension: usize) -> Result<usize, Error> {
+    dimension.checked_add(63).map(|size| size >> 6).ok_or(Error::ConversionError)
+}
+
 /// A custom bit vector type.
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serialization::RawVector"))]
 pub struct Vector {
     pub dimension: usize,
     limbs: Vec<u64>,
@@ -39,17 +84,12 @@ impl Vector {
             dimension,
             limbs: vec![0xffffffff_ffffffff; (dimension + 63) >> 6]
         };
-        // Ensure that unused bits are always zero. Note: This is
-        // required to ensure that the derived implementation of 
-        // the PartialEq trait does what it should.
-        let mask = (1 << (dimension & 63)) - 1;
-        if let Some(x) = result.limbs.last_mut() {
-            *x &= mask;
-        }
+        result.normalize();
         result
     }
 
     /// Returns a random vector of the given dimension.
+    #[cfg(feature = "std")]
     pub fn random(dimension: usize) -> Self {
         let mut result = Vector::zeroes(dimension);
         (0..dimension)
@@ -57,6 +97,55 @@ impl Vector {
             .for_each(|i| result.set_element(i, 1));
         result
     }
+
+    /// Returns a random vector of the given dimension, using the given generator.
+    fn random_using<R: RandomGenerator>(dimension: usize, random: &mut R) -> Self {
+        let mut result = Vector::zeroes(dimension);
+        result.limbs.iter_mut().for_each(|limb| *limb = random.next_u64());
+        result.normalize();
+        result
+    }
+
+    /// Returns the vector of dimension `8 * buffer.len()` given by the bits of the buffer.
+    /// Element `8 * i + j` is bit `j` of byte `i`, which means that the buffer is read as a
+    /// little-endian integer (e.g. `Vector::from_buffer(&x.to_le_bytes()) == Vector::from(x)`).
+    ///
+    /// # Note
+    ///
+    /// This is not the same as `from_bytes`, which decodes the binary encoding produced by
+    /// `to_bytes`.
+    pub fn from_buffer(buffer: &[u8]) -> Self {
+        let limbs = buffer
+            .chunks(8)
+            .map(|chunk| {
+                let mut limb = [0; 8];
+                limb[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(limb)
+            })
+            .collect();
+        Self { dimension: 8 * buffer.len(), limbs }
+    }
+
+    /// Returns the bits of the vector as a buffer, using the bit order from `from_buffer`. If
+    /// the dimension is not a multiple of 8, the last byte is padded with zeroes.
+    pub fn to_buffer(&self) -> Vec<u8> {
+        let mut result: Vec<u8> = self.limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+        result.truncate(self.dimension.div_ceil(8));
+        result
+    }
+
+    /// Returns the vector with the given elements, where `true` corresponds to 1.
+    pub fn from_bits<I: IntoIterator<Item = bool>>(bits: I) -> Self {
+        let mut result = Vector::zeroes(0);
+        for (index, bit) in bits.into_iter().enumerate() {
+            if index & 63 == 0 {
+                result.limbs.push(0);
+            }
+            result.limbs[index >> 6] |= (bit as u64) << (index & 63);
+            result.dimension += 1;
+        }
+        result
+    }
     
     /// Gets the element at the given index.
     ///
@@ -103,6 +192,119 @@ impl Vector {
     pub fn add_to_element(&mut self, index: usize, value: u8) {
         self.set_element(index, self.get_element(index) ^ value);
     }
+
+    /// Returns the inner product of the two vectors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.dimension != other.dimension`.
+    pub fn dot(&self, other: &Vector) -> u8 {
+        assert_eq!(self.dimension, other.dimension);
+        let ones: u32 = self.limbs.iter().zip(other.limbs.iter()).map(|(x, y)| (x & y).count_ones()).sum();
+        (ones & 1) as u8
+    }
+
+    /// Ensure that unused bits are always zero. Note: This is required to
+    /// ensure that the derived implementation of the PartialEq trait does
+    /// what it should.
+    fn normalize(&mut self) {
+        let unused = self.dimension & 63;
+        if unused > 0 {
+            if let Some(x) = self.limbs.last_mut() {
+                *x &= (1 << unused) - 1;
+            }
+        }
+    }
+
+    /// Returns the vector with element `i` given by element `i + n` of `self`.
+    /// (This corresponds to `x >> n` if the vector represents an integer `x`.)
+    fn shift_down(&self, n: usize) -> Vector {
+        let (offset, shift) = (n >> 6, n & 63);
+        let limbs = (0..self.limbs.len()).map(|i| {
+            let low = self.limbs.get(i + offset).map_or(0, |x| x >> shift);
+            let high = match shift {
+                0 => 0,
+                _ => self.limbs.get(i + offset + 1).map_or(0, |x| x << (64 - shift))
+            };
+            low | high
+        }).collect();
+        Vector { dimension: self.dimension, limbs }
+    }
+
+    /// Returns the vector with element `i` given by element `i - n` of `self`.
+    /// (This corresponds to `x << n` if the vector represents an integer `x`.)
+    fn shift_up(&self, n: usize) -> Vector {
+        let (offset, shift) = (n >> 6, n & 63);
+        let limb = |i: usize| {
+            i.checked_sub(offset).map_or(0, |j| self.limbs[j])
+        };
+        let limbs = (0..self.limbs.len()).map(|i| {
+            let low = match (shift, i.checked_sub(1)) {
+                (0, _) | (_, None) => 0,
+                (_, Some(j)) => limb(j) >> (64 - shift)
+            };
+            (limb(i) << shift) | low
+        }).collect();
+        let mut result = Vector { dimension: self.dimension, limbs };
+        result.normalize();
+        result
+    }
+
+    /// Returns true if the limbs match the dimension and all unused bits are zero.
+    fn is_normalized(&self) -> bool {
+        if limb_count(self.dimension).ok() != Some(self.limbs.len()) {
+            return false;
+        }
+        match (self.limbs.last(), self.dimension & 63) {
+            (Some(x), unused) if unused > 0 => x >> unused == 0,
+            _ => true
+        }
+    }
+
+    /// Returns a compact binary encoding of the vector. The encoding is given
+    /// by the dimension followed by the limbs, all as little-endian `u64`s.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = Vec::with_capacity(8 * (self.limbs.len() + 1));
+        result.extend_from_slice(&(self.dimension as u64).to_le_bytes());
+        self.write_limbs(&mut result);
+        result
+    }
+
+    /// Decodes a vector from the binary encoding produced by `to_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer is not a valid encoding of a vector.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, Error> {
+        let mut buffer = buffer;
+        let dimension = read_dimension(&mut buffer)?;
+        let result = Self::read_limbs(dimension, &mut buffer)?;
+        if !buffer.is_empty() {
+            return Err(Error::ConversionError);
+        }
+        Ok(result)
+    }
+
+    fn write_limbs(&self, buffer: &mut Vec<u8>) {
+        for limb in &self.limbs {
+            buffer.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+
+    fn read_limbs(dimension: usize, buffer: &mut &[u8]) -> Result<Self, Error> {
+        let size = limb_count(dimension)?;
+        if size.checked_mul(8).ok_or(Error::ConversionError)? > buffer.len() {
+            return Err(Error::ConversionError);
+        }
+        let limbs = (0..size)
+            .map(|_| read_u64(buffer))
+            .collect::<Result<Vec<u64>, Error>>()?;
+        let result = Self { dimension, limbs };
+        if !result.is_normalized() {
+            return Err(Error::ConversionError);
+        }
+        Ok(result)
+    }
 }
 
 impl fmt::Debug for Vector {
@@ -312,8 +514,178 @@ impl ops::AddAssign<&Vector> for Vector {
     }
 }
 
+/// Implements `v & w` (element-wise multiplication) for vectors `v` and `w`.
+///
+/// # Panics
+///
+/// The function will panic if `self.dimension != other.dimension`.
+impl ops::BitAnd<Vector> for Vector {
+    type Output = Vector;
+
+    fn bitand(self, other: Vector) -> Vector {
+        &self & &other
+    }
+}
+
+/// Implements `v & w` (element-wise multiplication) for vector references `v` and `w`.
+///
+/// # Panics
+///
+/// The function will panic if `self.dimension != other.dimension`.
+impl ops::BitAnd<&Vector> for &Vector {
+    type Output = Vector;
+
+    fn bitand(self, other: &Vector) -> Vector {
+        assert_eq!(self.dimension, other.dimension);
+        Vector {
+            dimension: self.dimension, 
+            limbs: self.limbs.iter().zip(other.limbs.iter()).map( |(x, y)| *x & *y).collect()
+        }
+    }
+}
+
+/// Implements `v | w` (element-wise or) for vectors `v` and `w`.
+///
+/// # Panics
+///
+/// The function will panic if `self.dimension != other.dimension`.
+impl ops::BitOr<Vector> for Vector {
+    type Output = Vector;
+
+    fn bitor(self, other: Vector) -> Vector {
+        &self | &other
+    }
+}
+
+/// Implements `v | w` (element-wise or) for vector references `v` and `w`.
+///
+/// # Panics
+///
+/// The function will panic if `self.dimension != other.dimension`.
+impl ops::BitOr<&Vector> for &Vector {
+    type Output = Vector;
+
+    fn bitor(self, other: &Vector) -> Vector {
+        assert_eq!(self.dimension, other.dimension);
+        Vector {
+            dimension: self.dimension, 
+            limbs: self.limbs.iter().zip(other.limbs.iter()).map( |(x, y)| *x | *y).collect()
+        }
+    }
+}
+
+/// Shifts each element up by `rhs` indices (corresponding to `x << rhs` for integers).
+impl ops::Shl<usize> for Vector {
+    type Output = Vector;
+
+    fn shl(self, rhs: usize) -> Vector {
+        self.shift_up(rhs)
+    }
+}
+
+/// Shifts each element up by `rhs` indices (corresponding to `x << rhs` for integers).
+impl ops::Shl<usize> for &Vector {
+    type Output = Vector;
+
+    fn shl(self, rhs: usize) -> Vector {
+        self.shift_up(rhs)
+    }
+}
+
+/// Shifts each element up by `rhs` indices (corresponding to `x << rhs` for integers).
+impl ops::Shl<i32> for Vector {
+    type Output = Vector;
+
+    fn shl(self, rhs: i32) -> Vector {
+        self.shift_up(rhs as usize)
+    }
+}
+
+/// Shifts each element up by `rhs` indices (corresponding to `x << rhs` for integers).
+impl ops::Shl<i32> for &Vector {
+    type Output = Vector;
+
+    fn shl(self, rhs: i32) -> Vector {
+        self.shift_up(rhs as usize)
+    }
+}
+
+/// Shifts each element up by `rhs` indices (corresponding to `x << rhs` for integers).
+impl ops::Shl<u32> for Vector {
+    type Output = Vector;
+
+    fn shl(self, rhs: u32) -> Vector {
+        self.shift_up(rhs as usize)
+    }
+}
+
+/// Shifts each element up by `rhs` indices (corresponding to `x << rhs` for integers).
+impl ops::Shl<u32> for &Vector {
+    type Output = Vector;
+
+    fn shl(self, rhs: u32) -> Vector {
+        self.shift_up(rhs as usize)
+    }
+}
+
+/// Shifts each element down by `rhs` indices (corresponding to `x >> rhs` for integers).
+impl ops::Shr<usize> for Vector {
+    type Output = Vector;
+
+    fn shr(self, rhs: usize) -> Vector {
+        self.shift_down(rhs)
+    }
+}
+
+/// Shifts each element down by `rhs` indices (corresponding to `x >> rhs` for integers).
+impl ops::Shr<usize> for &Vector {
+    type Output = Vector;
+
+    fn shr(self, rhs: usize) -> Vector {
+        self.shift_down(rhs)
+    }
+}
+
+/// Shifts each element down by `rhs` indices (corresponding to `x >> rhs` for integers).
+impl ops::Shr<i32> for Vector {
+    type Output = Vector;
+
+    fn shr(self, rhs: i32) -> Vector {
+        self.shift_down(rhs as usize)
+    }
+}
+
+/// Shifts each element down by `rhs` indices (corresponding to `x >> rhs` for integers).
+impl ops::Shr<i32> for &Vector {
+    type Output = Vector;
+
+    fn shr(self, rhs: i32) -> Vector {
+        self.shift_down(rhs as usize)
+    }
+}
+
+/// Shifts each element down by `rhs` indices (corresponding to `x >> rhs` for integers).
+impl ops::Shr<u32> for Vector {
+    type Output = Vector;
+
+    fn shr(self, rhs: u32) -> Vector {
+        self.shift_down(rhs as usize)
+    }
+}
+
+/// Shifts each element down by `rhs` indices (corresponding to `x >> rhs` for integers).
+impl ops::Shr<u32> for &Vector {
+    type Output = Vector;
+
+    fn shr(self, rhs: u32) -> Vector {
+        self.shift_down(rhs as usize)
+    }
+}
+
 /// A custom binary matrix type.
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "serialization::RawMatrix"))]
 pub struct Matrix {
     pub dimensions: (usize, usize),
     rows: Vec<Vector>
@@ -354,11 +726,87 @@ impl Matrix {
     }
     
     /// Returns a new random matrix with the given dimensions.
+    #[cfg(feature = "std")]
     pub fn random(rows: usize, columns: usize) -> Matrix {
         Matrix { 
             dimensions: (rows, columns),
-            rows: vec![Vector::random(columns); rows]
+            rows: (0..rows).map(|_| Vector::random(columns)).collect()
+        }
+    }
+
+    /// Returns a random invertible matrix of the given dimension, sampled uniformly from all
+    /// invertible matrices. Random matrices are invertible with probability roughly 0.29, so
+    /// this takes around 3.5 attempts on average.
+    pub fn random_invertible<R: RandomGenerator>(dimension: usize, random: &mut R) -> Matrix {
+        loop {
+            let result = Matrix {
+                dimensions: (dimension, dimension),
+                rows: (0..dimension).map(|_| Vector::random_using(dimension, random)).collect()
+            };
+            if result.determinant() == 1 {
+                return result;
+            }
+        }
+    }
+
+    /// Returns a random matrix with the given dimensions and rank, sampled uniformly from all
+    /// such matrices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rank` is larger than either dimension.
+    pub fn random_of_rank<R: RandomGenerator>(
+        rows: usize,
+        columns: usize,
+        rank: usize,
+        random: &mut R
+    ) -> Matrix {
+        assert!(rank <= rows && rank <= columns);
+        // Every matrix of rank `r` factors as `C * B`, where `C` has `r` linearly independent
+        // columns and `B` has `r` linearly independent rows. We take these from random
+        // invertible matrices.
+        let left = Matrix::random_invertible(rows, random);
+        let right = Matrix::random_invertible(columns, random);
+        let rows = left.rows
+            .iter()
+            .map(|coefficients| {
+                let mut row = Vector::zeroes(columns);
+                for (index, basis_row) in right.rows.iter().take(rank).enumerate() {
+                    if coefficients.get_element(index) == 1 {
+                        row += basis_row;
+                    }
+                }
+                row
+            })
+            .collect::<Vec<Vector>>();
+        Matrix { dimensions: (rows.len(), columns), rows }
+    }
+
+    /// Returns the matrix with the given rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rows do not all have the same dimension. An empty list of rows
+    /// gives a matrix with dimensions `(0, 0)`.
+    pub fn from_rows(rows: Vec<Vector>) -> Result<Matrix, Error> {
+        let columns = rows.first().map_or(0, |row| row.dimension);
+        if rows.iter().any(|row| row.dimension != columns) {
+            return Err(Error::ConversionError);
+        }
+        Ok(Matrix { dimensions: (rows.len(), columns), rows })
+    }
+
+    /// Returns the matrix with the element at `(row, column)` given by `f(row, column)`.
+    pub fn from_fn<F>(rows: usize, columns: usize, mut f: F) -> Matrix 
+        where F: FnMut(usize, usize) -> u8
+    {
+        let mut result = Matrix::zeroes(rows, columns);
+        for row in 0..rows {
+            for column in 0..columns {
+                result.set_element(row, column, f(row, column));
+            }
         }
+        result
     }
     
     /// Gets the element at `(row, column)`.
@@ -406,6 +854,17 @@ impl Matrix {
         self.rows.swap(first, second);
     }
 
+    /// Swaps the two columns of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is too large.
+    pub fn swap_columns(&mut self, first: usize, second: usize) {
+        for row in self.rows.iter_mut() {
+            row.swap_elements(first, second);
+        }
+    }
+
     /// Adds the vector to the given row.
     ///
     /// # Panics
@@ -414,53 +873,229 @@ impl Matrix {
     pub fn add_to_row(&mut self, row: usize, value: &Vector) {
         self.rows[row] += value;
     }
-    
-    fn get_left_delim(&self, row: usize) -> String {
-        if row == 0 {
-            String::from("/ ")
-        } else if row == self.dimensions.0 - 1 {
-            String::from("\\ ")
-        } else {
-            String::from("| ")
+
+    /// Returns the matrix where row `i` is given by row `i` of `self` if element `i` of
+    /// `mask` is 1, and `(0, 0, ..., 0)` otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.dimensions.0 != mask.dimension`.
+    pub fn mask_rows(&self, mask: &Vector) -> Matrix {
+        assert_eq!(self.dimensions.0, mask.dimension);
+        let rows = self.rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| if mask.get_element(i) == 1 { 
+                row.clone() 
+            } else { 
+                Vector::zeroes(self.dimensions.1) 
+            })
+            .collect();
+        Matrix { dimensions: self.dimensions, rows }
+    }
+
+    /// Returns the matrix where column `j` is given by column `j` of `self` if element `j`
+    /// of `mask` is 1, and `(0, 0, ..., 0)` otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.dimensions.1 != mask.dimension`.
+    pub fn mask_columns(&self, mask: &Vector) -> Matrix {
+        assert_eq!(self.dimensions.1, mask.dimension);
+        Matrix {
+            dimensions: self.dimensions,
+            rows: self.rows.iter().map(|row| row & mask).collect()
         }
     }
 
-    fn get_right_delim(&self, row: usize) -> String {
-        if row == 0 {
-            String::from(" \\")
-        } else if row == self.dimensions.0 - 1 {
-            String::from(" /")
-        } else {
-            String::from(" |")
+    // Adds the column `source` to the column `target`.
+    fn add_column_to_column(&mut self, source: usize, target: usize) {
+        for row in self.rows.iter_mut() {
+            row.add_to_element(target, row.get_element(source));
         }
     }
-}
 
-impl fmt::Debug for Matrix {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        for row in &self.rows {
-            writeln!(formatter, "| {:?} |", row)?;
+    /// Returns the determinant of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn determinant(&self) -> u8 {
+        assert_eq!(self.dimensions.0, self.dimensions.1);
+        let size = self.dimensions.0;
+        let mut rows = self.rows.clone();
+        for column in 0..size {
+            match (column..size).find(|&row| rows[row].get_element(column) == 1) {
+                Some(row) => rows.swap(column, row),
+                None => return 0,
+            }
+            let (upper, lower) = rows.split_at_mut(column + 1);
+            for row in lower.iter_mut().filter(|row| row.get_element(column) == 1) {
+                *row += &upper[column];
+            }
         }
-        Ok(())
+        1
     }
-}
 
-impl fmt::Display for Matrix {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        for i in 0..self.dimensions.0 {
-            write!(formatter, "{}", self.get_left_delim(i))?;
-            for j in 0..self.dimensions.1 {
-                if j == 0 {
-                } else {
-                    write!(formatter, ", ")?;
-                }
-                write!(formatter, "{}", self.get_element(i, j))?
+    /// Returns the rank of the matrix.
+    pub fn rank(&self) -> usize {
+        let mut rows = self.rows.clone();
+        let mut rank = 0;
+        for column in 0..self.dimensions.1 {
+            if rank == rows.len() {
+                break;
             }
-            writeln!(formatter, "{}", self.get_right_delim(i))?;
+            match (rank..rows.len()).find(|&row| rows[row].get_element(column) == 1) {
+                Some(row) => rows.swap(rank, row),
+                None => continue,
+            }
+            let (upper, lower) = rows.split_at_mut(rank + 1);
+            for row in lower.iter_mut().filter(|row| row.get_element(column) == 1) {
+                *row += &upper[rank];
+            }
+            rank += 1;
         }
-        Ok(())
+        rank
     }
-}
+
+    /// Returns the characteristic polynomial `det(xI + A)` of the matrix `A`.
+    ///
+    /// The matrix is first reduced to upper Hessenberg form using similarity
+    /// transformations, after which the characteristic polynomial is computed
+    /// using the standard recurrence over the leading principal minors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the matrix is not square.
+    pub fn char_poly(&self) -> Polynomial {
+        assert_eq!(self.dimensions.0, self.dimensions.1);
+        let size = self.dimensions.0;
+        let mut hessenberg = self.clone();
+        for column in 0..size.saturating_sub(2) {
+            let pivot = match (column + 1..size).find(|&row| hessenberg.get_element(row, column) == 1) {
+                Some(pivot) => pivot,
+                None => continue,
+            };
+            hessenberg.swap_rows(pivot, column + 1);
+            hessenberg.swap_columns(pivot, column + 1);
+            for row in column + 2..size {
+                if hessenberg.get_element(row, column) == 1 {
+                    // Each row operation is followed by the inverse column operation.
+                    let pivot_row = hessenberg.get_row(column + 1);
+                    hessenberg.add_to_row(row, &pivot_row);
+                    hessenberg.add_column_to_column(row, column + 1);
+                }
+            }
+        }
+        
+        // The characteristic polynomials of the leading principal minors.
+        let mut minors = vec![Polynomial::one()];
+        for k in 1..=size {
+            let diagonal = Polynomial::from_coefficients(&[hessenberg.get_element(k - 1, k - 1), 1]);
+            let mut result = &diagonal * &minors[k - 1];
+            let mut product = 1;
+            for i in (1..k).rev() {
+                product &= hessenberg.get_element(i, i - 1);
+                if product == 0 {
+                    break;
+                }
+                if hessenberg.get_element(i - 1, k - 1) == 1 {
+                    result += &minors[i - 1];
+                }
+            }
+            minors.push(result);
+        }
+        minors.pop().unwrap()
+    }
+
+    /// Returns a compact binary encoding of the matrix. The encoding is given
+    /// by the number of rows and columns, followed by the limbs of each row,
+    /// all as little-endian `u64`s.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let limbs = (self.dimensions.1 + 63) >> 6;
+        let mut result = Vec::with_capacity(8 * (self.dimensions.0 * limbs + 2));
+        result.extend_from_slice(&(self.dimensions.0 as u64).to_le_bytes());
+        result.extend_from_slice(&(self.dimensions.1 as u64).to_le_bytes());
+        for row in &self.rows {
+            row.write_limbs(&mut result);
+        }
+        result
+    }
+
+    /// Decodes a matrix from the binary encoding produced by `to_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer is not a valid encoding of a matrix.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, Error> {
+        let mut buffer = buffer;
+        let dimensions = (
+            read_dimension(&mut buffer)?,
+            read_dimension(&mut buffer)?
+        );
+        // Check the size given by the header before allocating the rows.
+        let size = dimensions.0
+            .checked_mul(limb_count(dimensions.1)?)
+            .and_then(|limbs| limbs.checked_mul(8))
+            .ok_or(Error::ConversionError)?;
+        if size > buffer.len() {
+            return Err(Error::ConversionError);
+        }
+        let rows = (0..dimensions.0)
+            .map(|_| Vector::read_limbs(dimensions.1, &mut buffer))
+            .collect::<Result<Vec<Vector>, Error>>()?;
+        if !buffer.is_empty() {
+            return Err(Error::ConversionError);
+        }
+        Ok(Matrix { dimensions, rows })
+    }
+    
+    fn get_left_delim(&self, row: usize) -> String {
+        if row == 0 {
+            String::from("/ ")
+        } else if row == self.dimensions.0 - 1 {
+            String::from("\\ ")
+        } else {
+            String::from("| ")
+        }
+    }
+
+    fn get_right_delim(&self, row: usize) -> String {
+        if row == 0 {
+            String::from(" \\")
+        } else if row == self.dimensions.0 - 1 {
+            String::from(" /")
+        } else {
+            String::from(" |")
+        }
+    }
+}
+
+impl fmt::Debug for Matrix {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.rows {
+            writeln!(formatter, "| {:?} |", row)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.dimensions.0 {
+            write!(formatter, "{}", self.get_left_delim(i))?;
+            for j in 0..self.dimensions.1 {
+                if j == 0 {
+                } else {
+                    write!(formatter, ", ")?;
+                }
+                write!(formatter, "{}", self.get_element(i, j))?
+            }
+            writeln!(formatter, "{}", self.get_right_delim(i))?;
+        }
+        Ok(())
+    }
+}
 
 /// Implements `A + B` for matrices `A` and `B`.
 ///
@@ -672,23 +1307,389 @@ impl ops::Shr<u32> for &Matrix {
 ///
 ///   - row `i` of self if element `i` of `rhs` is 1,
 ///   - `(0, 0, ..., 0)` otherwise.
+///
+/// (See `Matrix::mask_rows`.)
 impl ops::BitAnd<Vector> for Matrix {
     type Output = Matrix;
 
     fn bitand(self, rhs: Vector) -> Matrix {
-        assert_eq!(self.dimensions.0, rhs.dimension);
-        let rows = (0..self.dimensions.0)
-            .map(|i| { if rhs.get_element(i) == 1 { 
-                self.rows[i].clone() 
-            } else { 
-                Vector::zeroes(self.dimensions.0) 
-            }})
-            .collect();
-        
+        self.mask_rows(&rhs)
+    }
+}
+
+/// Implements `A & B` (element-wise and) for matrices `A` and `B`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions != other.dimensions`.
+impl ops::BitAnd<Matrix> for Matrix {
+    type Output = Matrix;
+
+    fn bitand(self, other: Matrix) -> Matrix {
+        &self & &other
+    }
+}
+
+/// Implements `A & B` (element-wise and) for matrix references `A` and `B`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions != other.dimensions`.
+impl ops::BitAnd<&Matrix> for &Matrix {
+    type Output = Matrix;
+
+    fn bitand(self, other: &Matrix) -> Matrix {
+        assert_eq!(self.dimensions, other.dimensions);
         Matrix {
             dimensions: self.dimensions,
-            rows
+            rows: self.rows.iter().zip(other.rows.iter()).map(|(v, w)| v & w).collect()
+        }
+    }
+}
+
+/// Implements `A ^ B` (element-wise xor) for matrices `A` and `B`. This is the same as `A + B`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions != other.dimensions`.
+impl ops::BitXor<Matrix> for Matrix {
+    type Output = Matrix;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn bitxor(self, other: Matrix) -> Matrix {
+        self + other
+    }
+}
+
+/// Implements `A ^ B` (element-wise xor) for matrix references `A` and `B`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions != other.dimensions`.
+impl ops::BitXor<&Matrix> for &Matrix {
+    type Output = Matrix;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn bitxor(self, other: &Matrix) -> Matrix {
+        self + other
+    }
+}
+
+/// Implements `A ^= B` for matrices `A` and `B`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions != other.dimensions`.
+impl ops::BitXorAssign<Matrix> for Matrix {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn bitxor_assign(&mut self, other: Matrix) {
+        *self += other;
+    }
+}
+
+/// Implements `A ^= B` for matrix `A` and matrix reference `B`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions != other.dimensions`.
+impl ops::BitXorAssign<&Matrix> for Matrix {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn bitxor_assign(&mut self, other: &Matrix) {
+        *self += other;
+    }
+}
+
+/// Implements `A * c` for matrix `A` and field element `c` (where only the least significant
+/// bit of `c` is used).
+impl ops::Mul<u8> for Matrix {
+    type Output = Matrix;
+
+    fn mul(self, rhs: u8) -> Matrix {
+        &self * rhs
+    }
+}
+
+/// Implements `A * c` for matrix reference `A` and field element `c` (where only the least
+/// significant bit of `c` is used).
+impl ops::Mul<u8> for &Matrix {
+    type Output = Matrix;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: u8) -> Matrix {
+        match rhs & 1 {
+            1 => self.clone(),
+            _ => Matrix::zeroes(self.dimensions.0, self.dimensions.1)
+        }
+    }
+}
+
+/// Implements `A * v` for matrix reference `A` and vector reference `v`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions.1 != rhs.dimension`.
+impl ops::Mul<&Vector> for &Matrix {
+    type Output = Vector;
+
+    fn mul(self, rhs: &Vector) -> Vector {
+        assert_eq!(self.dimensions.1, rhs.dimension);
+        let mut result = Vector::zeroes(self.dimensions.0);
+        for (i, row) in self.rows.iter().enumerate() {
+            result.set_element(i, row.dot(rhs));
+        }
+        result
+    }
+}
+
+/// A permutation of the indices `0, 1, ..., n - 1`. Applying the permutation
+/// to a vector moves element `i` to index `permutation.get(i)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Permutation {
+    indices: Vec<usize>
+}
+
+impl Permutation {
+    /// Returns the permutation given by `i -> indices[i]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `indices` is not a permutation of `0, 1, ..., n - 1`.
+    pub fn new(indices: Vec<usize>) -> Result<Self, Error> {
+        let mut seen = vec![false; indices.len()];
+        for &index in &indices {
+            if index >= seen.len() || seen[index] {
+                return Err(Error::ConversionError);
+            }
+            seen[index] = true;
+        }
+        Ok(Self { indices })
+    }
+
+    /// Returns the identity permutation of the given size.
+    pub fn identity(size: usize) -> Self {
+        Self { indices: (0..size).collect() }
+    }
+
+    /// Returns a random permutation of the given size.
+    #[cfg(feature = "std")]
+    pub fn random(size: usize) -> Self {
+        let mut indices: Vec<usize> = (0..size).collect();
+        indices.shuffle(&mut rand::thread_rng());
+        Self { indices }
+    }
+
+    /// Returns the permutation of the given size which swaps `first` and `second`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is too large.
+    pub fn transposition(size: usize, first: usize, second: usize) -> Self {
+        let mut result = Self::identity(size);
+        result.indices.swap(first, second);
+        result
+    }
+
+    /// Returns the number of indices permuted.
+    pub fn size(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns the image of the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is too large.
+    pub fn get(&self, index: usize) -> usize {
+        self.indices[index]
+    }
+
+    /// Returns the inverse permutation.
+    pub fn inverse(&self) -> Self {
+        let mut indices = vec![0; self.size()];
+        for (i, &j) in self.indices.iter().enumerate() {
+            indices[j] = i;
+        }
+        Self { indices }
+    }
+
+    /// Returns the composition `self ∘ other` (i.e. `other` is applied first).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.size() != other.size()`.
+    pub fn compose(&self, other: &Permutation) -> Self {
+        assert_eq!(self.size(), other.size());
+        Self { indices: other.indices.iter().map(|&i| self.indices[i]).collect() }
+    }
+
+    /// Swaps the images of `first` and `second`. (This is the same as composing
+    /// with a transposition from the left.)
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is too large.
+    pub fn swap(&mut self, first: usize, second: usize) {
+        for index in self.indices.iter_mut() {
+            if *index == first {
+                *index = second;
+            } else if *index == second {
+                *index = first;
+            }
+        }
+    }
+
+    /// Returns the vector obtained by moving element `i` to index `self.get(i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.size() != vector.dimension`.
+    pub fn apply_to_vector(&self, vector: &Vector) -> Vector {
+        assert_eq!(self.size(), vector.dimension);
+        let mut result = Vector::zeroes(vector.dimension);
+        for (i, &j) in self.indices.iter().enumerate() {
+            result.set_element(j, vector.get_element(i));
+        }
+        result
+    }
+
+    /// Returns the matrix obtained by moving row `i` to row `self.get(i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.size() != matrix.dimensions.0`.
+    pub fn permute_rows(&self, matrix: &Matrix) -> Matrix {
+        assert_eq!(self.size(), matrix.dimensions.0);
+        let mut result = matrix.clone();
+        for (i, &j) in self.indices.iter().enumerate() {
+            result.rows[j] = matrix.rows[i].clone();
+        }
+        result
+    }
+
+    /// Returns the matrix obtained by moving column `i` to column `self.get(i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.size() != matrix.dimensions.1`.
+    pub fn permute_columns(&self, matrix: &Matrix) -> Matrix {
+        assert_eq!(self.size(), matrix.dimensions.1);
+        Matrix {
+            dimensions: matrix.dimensions,
+            rows: matrix.rows.iter().map(|row| self.apply_to_vector(row)).collect()
+        }
+    }
+
+    /// Returns the permutation matrix `P` such that `P * v` is equal to
+    /// `self.apply_to_vector(v)`.
+    pub fn to_matrix(&self) -> Matrix {
+        let mut result = Matrix::zeroes(self.size(), self.size());
+        for (i, &j) in self.indices.iter().enumerate() {
+            result.set_element(j, i, 1);
         }
+        result
+    }
+}
+
+/// A builder for linear maps on `n`-bit words. This allows us to model
+/// transformations like the Mersenne twister tempering function using the
+/// same expressions as the original implementation. E.g.
+///
+/// ```
+/// use cryptopals::math::linear_algebra::LinearMap;
+///
+/// let mut x = LinearMap::identity(32);
+/// x ^= &x >> 11;
+/// x ^= (&x << 7) & 0x9d2c_5680_u32;
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearMap {
+    matrix: Matrix
+}
+
+impl LinearMap {
+    /// Returns the identity map on `dimension`-bit words.
+    pub fn identity(dimension: usize) -> Self {
+        Self { matrix: Matrix::identity(dimension) }
+    }
+
+    /// Returns the dimension of the words the map operates on.
+    pub fn dimension(&self) -> usize {
+        self.matrix.dimensions.0
+    }
+
+    /// Returns the matrix representing the map.
+    pub fn matrix(&self) -> &Matrix {
+        &self.matrix
+    }
+
+    /// Applies the map to the given word.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.dimension() != input.dimension`.
+    pub fn apply(&self, input: &Vector) -> Vector {
+        &self.matrix * input
+    }
+
+    /// Returns the unique preimage of the given output, if it exists.
+    pub fn solve(&self, output: &Vector) -> Result<Vector, Error> {
+        GaussElimination::new(self.matrix.clone(), output.clone()).solve()
+    }
+}
+
+impl From<LinearMap> for Matrix {
+    fn from(map: LinearMap) -> Matrix {
+        map.matrix
+    }
+}
+
+/// Implements `x << n` for linear map references `x`.
+impl ops::Shl<usize> for &LinearMap {
+    type Output = LinearMap;
+
+    fn shl(self, rhs: usize) -> LinearMap {
+        LinearMap { matrix: &self.matrix << rhs }
+    }
+}
+
+/// Implements `x >> n` for linear map references `x`.
+impl ops::Shr<usize> for &LinearMap {
+    type Output = LinearMap;
+
+    fn shr(self, rhs: usize) -> LinearMap {
+        LinearMap { matrix: &self.matrix >> rhs }
+    }
+}
+
+/// Implements `x & mask` for linear maps `x`, where the mask is given by any
+/// type which can be converted into a vector (e.g. `u32` or `u64`).
+///
+/// # Panics
+///
+/// Panics if the dimension of the mask is different from the dimension of the map.
+impl<T: Into<Vector>> ops::BitAnd<T> for LinearMap {
+    type Output = LinearMap;
+
+    fn bitand(self, rhs: T) -> LinearMap {
+        LinearMap { matrix: self.matrix & rhs.into() }
+    }
+}
+
+/// Implements `x ^ y` for linear maps `x` and `y`.
+impl ops::BitXor<LinearMap> for LinearMap {
+    type Output = LinearMap;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn bitxor(self, rhs: LinearMap) -> LinearMap {
+        LinearMap { matrix: self.matrix + rhs.matrix }
+    }
+}
+
+/// Implements `x ^= y` for linear maps `x` and `y`.
+impl ops::BitXorAssign<LinearMap> for LinearMap {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn bitxor_assign(&mut self, rhs: LinearMap) {
+        self.matrix += rhs.matrix;
     }
 }
 
@@ -743,8 +1744,53 @@ impl GaussElimination {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serialization {
+    use core::convert::TryFrom;
+    use serde::Deserialize;
+
+    use super::{Error, Matrix, Vector};
+
+    /// Unvalidated vector used to check invariants on deserialization.
+    #[derive(Deserialize)]
+    pub struct RawVector {
+        dimension: usize,
+        limbs: Vec<u64>,
+    }
+
+    impl TryFrom<RawVector> for Vector {
+        type Error = Error;
+
+        fn try_from(raw: RawVector) -> Result<Self, Error> {
+            let result = Vector { dimension: raw.dimension, limbs: raw.limbs };
+            if !result.is_normalized() {
+                return Err(Error::ConversionError);
+            }
+            Ok(result)
+        }
+    }
+    
+    /// Unvalidated matrix used to check invariants on deserialization.
+    #[derive(Deserialize)]
+    pub struct RawMatrix {
+        dimensions: (usize, usize),
+        rows: Vec<Vector>,
+    }
+
+    impl TryFrom<RawMatrix> for Matrix {
+        type Error = Error;
+
+        fn try_from(raw: RawMatrix) -> Result<Self, Error> {
+            if raw.rows.len() != raw.dimensions.0 || 
+                raw.rows.iter().any(|row| row.dimension != raw.dimensions.1) {
+                return Err(Error::ConversionError);
+            }
+            Ok(Matrix { dimensions: raw.dimensions, rows: raw.rows })
+        }
+    }
+}
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
     use std::convert::{From, TryInto};
@@ -823,6 +1869,27 @@ mod tests {
         assert_eq!(result, Vector::ones(17));
     }
 
+    #[test]
+    fn vector_bit_operations() {
+        for _ in 0..100 {
+            let x = rand::thread_rng().gen::<u128>();
+            let y = rand::thread_rng().gen::<u128>();
+            let n = rand::thread_rng().gen_range(0, 130);
+            let (v, w) = (Vector::from(x), Vector::from(y));
+
+            assert_eq!(&v & &w, Vector::from(x & y));
+            assert_eq!(&v | &w, Vector::from(x | y));
+            assert_eq!(&v << n, Vector::from(x.checked_shl(n as u32).unwrap_or(0)));
+            assert_eq!(&v >> n, Vector::from(x.checked_shr(n as u32).unwrap_or(0)));
+        }
+        // Shifting must preserve the invariant that unused bits are zero.
+        let result: Vector = (Vector::ones(67) << 3) >> 3;
+        for i in 0..result.dimension {
+            assert_eq!(result.get_element(i), (i < 64) as u8);
+        }
+        assert_eq!(Vector::ones(64), Vector::from(u64::MAX));
+    }
+
     #[test]
     fn matrix_creation() {
         let mut matrix = Matrix::new(25, 43);
@@ -885,6 +1952,257 @@ mod tests {
         assert_eq!(result, Matrix::ones(17, 17));
     }
 
+    #[test]
+    fn matrix_bit_operations() {
+        let (lhs, rhs) = (Matrix::random(17, 65), Matrix::random(17, 65));
+        let (row_mask, column_mask) = (Vector::random(17), Vector::random(65));
+
+        let result = lhs.clone() & row_mask.clone();
+        assert_eq!(result, lhs.mask_rows(&row_mask));
+        let result = lhs.mask_columns(&column_mask);
+        let conjunction = &lhs & &rhs;
+        let sum = &lhs ^ &rhs;
+        for i in 0..17 {
+            assert_eq!(lhs.mask_rows(&row_mask).get_row(i).dimension, 65);
+            for j in 0..65 {
+                let (x, y) = (lhs.get_element(i, j), rhs.get_element(i, j));
+                assert_eq!(lhs.mask_rows(&row_mask).get_element(i, j), x & row_mask.get_element(i));
+                assert_eq!(result.get_element(i, j), x & column_mask.get_element(j));
+                assert_eq!(conjunction.get_element(i, j), x & y);
+                assert_eq!(sum.get_element(i, j), x ^ y);
+            }
+        }
+        assert_eq!(lhs.clone() & rhs.clone(), conjunction);
+        assert_eq!(lhs.clone() ^ rhs.clone(), &lhs + &rhs);
+        let mut result = lhs.clone();
+        result ^= &rhs;
+        result ^= rhs;
+        assert_eq!(result, lhs);
+
+        for scalar in 0..4 {
+            let expected = if scalar % 2 == 1 { lhs.clone() } else { Matrix::zeroes(17, 65) };
+            assert_eq!(&lhs * scalar, expected);
+            assert_eq!(lhs.clone() * scalar, expected);
+        }
+    }
+
+    #[test]
+    fn binary_encoding() {
+        let vector = Vector::random(131);
+        assert_eq!(Vector::from_bytes(&vector.to_bytes()).unwrap(), vector);
+
+        let matrix = Matrix::random(17, 65);
+        assert_eq!(Matrix::from_bytes(&matrix.to_bytes()).unwrap(), matrix);
+        
+        // Truncated input and non-zero unused bits should be rejected.
+        let buffer = matrix.to_bytes();
+        assert!(Matrix::from_bytes(&buffer[..buffer.len() - 1]).is_err());
+        let mut buffer = Vector::zeroes(3).to_bytes();
+        buffer[8] = 0xff;
+        assert!(Vector::from_bytes(&buffer).is_err());
+
+        // Headers with dimensions which overflow or exceed the buffer should be rejected.
+        let buffer = u64::MAX.to_le_bytes();
+        assert!(Vector::from_bytes(&buffer).is_err());
+        let buffer = [(1u64 << 58).to_le_bytes(), 64u64.to_le_bytes()].concat();
+        assert!(Matrix::from_bytes(&buffer).is_err());
+        let buffer = [u64::MAX.to_le_bytes(), u64::MAX.to_le_bytes()].concat();
+        assert!(Matrix::from_bytes(&buffer).is_err());
+    }
+
+    #[test]
+    fn buffer_conversion() {
+        let value = rand::thread_rng().gen::<u128>();
+        let vector = Vector::from_buffer(&value.to_le_bytes());
+        assert_eq!(vector, Vector::from(value));
+        assert_eq!(vector.to_buffer(), value.to_le_bytes());
+
+        let buffer: Vec<u8> = (0..21).map(|_| rand::thread_rng().gen()).collect();
+        let vector = Vector::from_buffer(&buffer);
+        assert_eq!(vector.dimension, 168);
+        assert_eq!(vector.to_buffer(), buffer);
+        assert_eq!(Vector::from_buffer(&[]), Vector::zeroes(0));
+
+        // The last byte is padded if the dimension is not a multiple of 8.
+        assert_eq!(Vector::ones(67).to_buffer(), [&[0xff; 8][..], &[0x07]].concat());
+    }
+
+    #[test]
+    fn bit_conversion() {
+        let bits: Vec<bool> = (0..131).map(|_| rand::thread_rng().gen()).collect();
+        let vector = Vector::from_bits(bits.iter().copied());
+        assert_eq!(vector.dimension, bits.len());
+        for (index, &bit) in bits.iter().enumerate() {
+            assert_eq!(vector.get_element(index), bit as u8);
+        }
+        assert_eq!(Vector::from_bits((0..8).map(|i| i % 3 == 0)), Vector::from(0b0100_1001u8));
+        assert_eq!(Vector::from_bits(Vec::new()), Vector::zeroes(0));
+    }
+
+    #[test]
+    fn matrix_construction() {
+        let rows: Vec<Vector> = (0..17).map(|_| Vector::random(65)).collect();
+        let matrix = Matrix::from_rows(rows.clone()).unwrap();
+        assert_eq!(matrix.dimensions, (17, 65));
+        for (index, row) in rows.iter().enumerate() {
+            assert_eq!(&matrix.get_row(index), row);
+        }
+        assert!(Matrix::from_rows(vec![Vector::zeroes(3), Vector::zeroes(4)]).is_err());
+        assert_eq!(Matrix::from_rows(Vec::new()).unwrap().dimensions, (0, 0));
+
+        let matrix = Matrix::from_fn(32, 32, |row, column| (row == column) as u8);
+        assert_eq!(matrix, Matrix::identity(32));
+        let matrix = Matrix::from_fn(17, 65, |row, column| rows[row].get_element(column));
+        assert_eq!(matrix, Matrix::from_rows(rows).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_encoding() {
+        let matrix = Matrix::random(17, 65);
+        let json = serde_json::to_string(&matrix).unwrap();
+        assert_eq!(serde_json::from_str::<Matrix>(&json).unwrap(), matrix);
+
+        let json = r#"{"dimension":3,"limbs":[255]}"#;
+        assert!(serde_json::from_str::<Vector>(json).is_err());
+
+        // The number of limbs overflows for this dimension.
+        let json = r#"{"dimension":18446744073709551615,"limbs":[]}"#;
+        assert!(serde_json::from_str::<Vector>(json).is_err());
+    }
+
+    #[test]
+    fn matrix_determinant() {
+        assert_eq!(Matrix::identity(37).determinant(), 1);
+        assert_eq!(Matrix::ones(37, 37).determinant(), 0);
+        
+        let mut matrix = Matrix::identity(37);
+        for i in 1..37 {
+            matrix.add_to_row(i, &matrix.get_row(i - 1));
+        }
+        matrix.swap_rows(3, 17);
+        assert_eq!(matrix.determinant(), 1);
+    }
+
+    #[test]
+    fn random_matrices() {
+        use crate::crypto::random::{Mt19337, SeedableGenerator};
+
+        // The rows of a random matrix should be sampled independently.
+        let matrix = Matrix::random(64, 64);
+        assert!((1..64).any(|i| matrix.get_row(i) != matrix.get_row(0)));
+
+        let mut random = Mt19337::new(5489);
+        for size in [0, 1, 17, 64, 65].iter().copied() {
+            let matrix = Matrix::random_invertible(size, &mut random);
+            assert_eq!(matrix.dimensions, (size, size));
+            assert_eq!(matrix.determinant(), 1);
+            assert_eq!(matrix.rank(), size);
+        }
+        for &(rows, columns, rank) in [(17, 65, 0), (17, 65, 9), (65, 17, 17), (40, 40, 39)].iter() {
+            let matrix = Matrix::random_of_rank(rows, columns, rank, &mut random);
+            assert_eq!(matrix.dimensions, (rows, columns));
+            assert_eq!(matrix.rank(), rank);
+        }
+        assert_eq!(Matrix::identity(37).rank(), 37);
+        assert_eq!(Matrix::ones(17, 65).rank(), 1);
+        assert_eq!(Matrix::zeroes(17, 65).rank(), 0);
+    }
+
+    #[test]
+    fn characteristic_polynomial() {
+        // The characteristic polynomial of the identity is (x + 1)^n.
+        let mut expected = Polynomial::one();
+        for _ in 0..13 {
+            expected = expected * Polynomial::from_coefficients(&[1, 1]);
+        }
+        assert_eq!(Matrix::identity(13).char_poly(), expected);
+
+        // The characteristic polynomial of a companion matrix is the polynomial itself.
+        let size = 71;
+        let coefficients = Vector::random(size);
+        let mut companion = Matrix::zeroes(size, size);
+        for i in 0..size {
+            if i > 0 { companion.set_element(i, i - 1, 1); }
+            companion.set_element(i, size - 1, coefficients.get_element(i));
+        }
+        let mut expected = Polynomial::monomial(size);
+        (0..size).for_each(|i| expected.set_coefficient(i, coefficients.get_element(i)));
+        assert_eq!(companion.char_poly(), expected);
+
+        // Check that p(0) = det(A) and p(1) = det(A + I).
+        for _ in 0..10 {
+            let matrix = Matrix::random(40, 40);
+            let result = matrix.char_poly();
+            assert_eq!(result.degree(), Some(40));
+            assert_eq!(result.evaluate(0), matrix.determinant());
+            assert_eq!(result.evaluate(1), (&matrix + &Matrix::identity(40)).determinant());
+        }
+    }
+
+    #[test]
+    fn permutation() {
+        let permutation = Permutation::random(67);
+        let inverse = permutation.inverse();
+        assert_eq!(permutation.compose(&inverse), Permutation::identity(67));
+        assert_eq!(inverse.compose(&permutation), Permutation::identity(67));
+        assert!(Permutation::new(vec![0, 2, 2]).is_err());
+        assert!(Permutation::new(vec![0, 3, 1]).is_err());
+
+        let vector = Vector::random(67);
+        let result = permutation.apply_to_vector(&vector);
+        assert_eq!(&permutation.to_matrix() * &vector, result);
+        assert_eq!(inverse.apply_to_vector(&result), vector);
+        
+        let mut other = permutation.clone();
+        other.swap(3, 5);
+        assert_eq!(other, Permutation::transposition(67, 3, 5).compose(&permutation));
+
+        let matrix = Matrix::random(67, 67);
+        let result = permutation.permute_rows(&matrix);
+        for i in 0..67 {
+            assert_eq!(result.get_row(permutation.get(i)), matrix.get_row(i));
+        }
+        assert_eq!(inverse.permute_rows(&result), matrix);
+        let result = permutation.permute_columns(&matrix);
+        assert_eq!(inverse.permute_columns(&result), matrix);
+    }
+
+    #[test]
+    fn linear_map() {
+        // Mersenne twister tempering.
+        let mut x = LinearMap::identity(32);
+        x ^= &x >> 11;
+        x ^= (&x << 7) & 0x9d2c_5680_u32;
+        x ^= (&x << 15) & 0xefc6_0000_u32;
+        x ^= &x >> 18;
+        
+        // Marsaglia's 64-bit xorshift.
+        let mut y = LinearMap::identity(64);
+        y ^= &y << 13;
+        y ^= &y >> 7;
+        y ^= &y << 17;
+
+        for _ in 0..100 {
+            let input = rand::thread_rng().gen::<u32>();
+            let mut output = input;
+            output ^= output >> 11;
+            output ^= (output << 7) & 0x9d2c_5680;
+            output ^= (output << 15) & 0xefc6_0000;
+            output ^= output >> 18;
+            assert_eq!(x.apply(&Vector::from(input)), Vector::from(output));
+            assert_eq!(x.solve(&Vector::from(output)).unwrap(), Vector::from(input));
+
+            let input = rand::thread_rng().gen::<u64>();
+            let mut output = input;
+            output ^= output << 13;
+            output ^= output >> 7;
+            output ^= output << 17;
+            assert_eq!(y.apply(&Vector::from(input)), Vector::from(output));
+            assert_eq!(y.solve(&Vector::from(output)).unwrap(), Vector::from(input));
+        }
+    }
+
     #[test]
     fn gauss_elimination() {
         for _ in 0..10 {
@@ -1,3 +1,8 @@
+#[cfg(feature = "std")]
 pub mod statistics;
+#[cfg(feature = "std")]
 pub mod optimization;
 pub mod linear_algebra;
+pub mod sparse;
+pub mod polynomial;
+pub mod gf128;
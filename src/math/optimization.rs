@@ -1,13 +1,31 @@
 use std::iter::Iterator;
-use std::cmp::PartialOrd;
+use std::cmp::{Ordering, PartialOrd};
+
+use rand::Rng;
 
 /// `Minimize` trait which computes a local minimum for the given function.
 pub trait Minimize<'a, F> {
     type Input;
     type Output;
 
-    fn minimize(&'a mut self, function: F) -> (Self::Input, Self::Output)
-        where F: Fn(&Self::Input) -> Self::Output;
+    /// Returns the input minimizing the function together with the corresponding output.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there are no inputs. (See `try_minimize` for a
+    /// non-panicking alternative.)
+    fn minimize(&'a mut self, function: F) -> (Self::Input, Self::Output) {
+        self.try_minimize(function).expect("cannot minimize over an empty set of inputs")
+    }
+
+    /// Returns the input minimizing the function together with the corresponding
+    /// output, or `None` if there are no inputs.
+    fn try_minimize(&'a mut self, function: F) -> Option<(Self::Input, Self::Output)>;
+
+    /// Returns (at most) `k` inputs together with the corresponding outputs,
+    /// ranked in increasing order of the output. Inputs with equal outputs
+    /// are returned in the order they were given.
+    fn k_smallest(&'a mut self, k: usize, function: F) -> Vec<(Self::Input, Self::Output)>;
 }
 
 /// Generic implementation of `Minimize` for implementations of `Iterator`.
@@ -19,7 +37,7 @@ pub trait Minimize<'a, F> {
 ///
 /// # Panics:
 ///
-/// This method panics if the iterator is empty.
+/// `minimize` panics if the iterator is empty.
 impl<'a, In, Out, F, It> Minimize<'a, F> for It where It: Iterator<Item=In>,
     F: Fn(&In) -> Out,
     In: Clone,
@@ -28,7 +46,7 @@ impl<'a, In, Out, F, It> Minimize<'a, F> for It where It: Iterator<Item=In>,
     type Input = In;
     type Output = Out;
 
-    fn minimize(&'a mut self, function: F) -> (In, Out) {
+    fn try_minimize(&'a mut self, function: F) -> Option<(In, Out)> {
         let mut result: (Option<In>, Option<Out>) = (None, None);
         for input in self {
             let output = function(&input);
@@ -43,7 +61,19 @@ impl<'a, In, Out, F, It> Minimize<'a, F> for It where It: Iterator<Item=In>,
                 }
             }
         }
-        (result.0.unwrap(), result.1.unwrap())
+        result.0.zip(result.1)
+    }
+
+    fn k_smallest(&'a mut self, k: usize, function: F) -> Vec<(In, Out)> {
+        let mut result: Vec<(In, Out)> = self
+            .map(|input| {
+                let output = function(&input);
+                (input, output)
+            })
+            .collect();
+        result.sort_by(|lhs, rhs| lhs.1.partial_cmp(&rhs.1).unwrap_or(Ordering::Equal));
+        result.truncate(k);
+        result
     }
 }
 
@@ -52,8 +82,24 @@ pub trait Maximize<'a, F> {
     type Input;
     type Output;
 
-    fn maximize(&'a mut self, function: F) -> (Self::Input, Self::Output)
-        where F: Fn(&Self::Input) -> Self::Output;
+    /// Returns the input maximizing the function together with the corresponding output.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if there are no inputs. (See `try_maximize` for a
+    /// non-panicking alternative.)
+    fn maximize(&'a mut self, function: F) -> (Self::Input, Self::Output) {
+        self.try_maximize(function).expect("cannot maximize over an empty set of inputs")
+    }
+
+    /// Returns the input maximizing the function together with the corresponding
+    /// output, or `None` if there are no inputs.
+    fn try_maximize(&'a mut self, function: F) -> Option<(Self::Input, Self::Output)>;
+
+    /// Returns (at most) `k` inputs together with the corresponding outputs,
+    /// ranked in decreasing order of the output. Inputs with equal outputs
+    /// are returned in the order they were given.
+    fn k_largest(&'a mut self, k: usize, function: F) -> Vec<(Self::Input, Self::Output)>;
 }
 
 /// Generic implementation of `Maximize` for implementations of `Iterator`.
@@ -65,7 +111,7 @@ pub trait Maximize<'a, F> {
 ///
 /// # Panics:
 ///
-/// This method panics if the iterator is empty.
+/// `maximize` panics if the iterator is empty.
 impl<'a, In, Out, F, It> Maximize<'a, F> for It where It: Iterator<Item=In>,
     F: Fn(&In) -> Out,
     In: Clone,
@@ -74,7 +120,7 @@ impl<'a, In, Out, F, It> Maximize<'a, F> for It where It: Iterator<Item=In>,
     type Input = In;
     type Output = Out;
 
-    fn maximize(&'a mut self, function: F) -> (In, Out) {
+    fn try_maximize(&'a mut self, function: F) -> Option<(In, Out)> {
         let mut result: (Option<In>, Option<Out>) = (None, None);
         for input in self {
             let output = function(&input);
@@ -89,9 +135,283 @@ impl<'a, In, Out, F, It> Maximize<'a, F> for It where It: Iterator<Item=In>,
                 }
             }
         }
-        (result.0.unwrap(), result.1.unwrap())
+        result.0.zip(result.1)
+    }
+
+    fn k_largest(&'a mut self, k: usize, function: F) -> Vec<(In, Out)> {
+        let mut result: Vec<(In, Out)> = self
+            .map(|input| {
+                let output = function(&input);
+                (input, output)
+            })
+            .collect();
+        result.sort_by(|lhs, rhs| rhs.1.partial_cmp(&lhs.1).unwrap_or(Ordering::Equal));
+        result.truncate(k);
+        result
+    }
+}
+/// Extension trait for optimizing over iterators where the objective is
+/// computed from a derived key (like a decrypted plaintext), which is
+/// computed once per input and returned together with the optimum.
+pub trait OptimizeByCachedKey: Iterator + Sized {
+    /// Returns the input minimizing `objective(key(input))`, together with
+    /// the key and the objective value, or `None` if there are no inputs.
+    fn try_minimize_by_cached_key<K, Out, G, H>(self, key: G, objective: H) -> Option<(Self::Item, K, Out)>
+    where
+        G: Fn(&Self::Item) -> K,
+        H: Fn(&K) -> Out,
+        Out: PartialOrd
+    {
+        let mut result: Option<(Self::Item, K, Out)> = None;
+        for input in self {
+            let key = key(&input);
+            let output = objective(&key);
+            if result.as_ref().is_none_or(|(_, _, minimum)| output < *minimum) {
+                result = Some((input, key, output));
+            }
+        }
+        result
+    }
+
+    /// Returns the input maximizing `objective(key(input))`, together with
+    /// the key and the objective value, or `None` if there are no inputs.
+    fn try_maximize_by_cached_key<K, Out, G, H>(self, key: G, objective: H) -> Option<(Self::Item, K, Out)>
+    where
+        G: Fn(&Self::Item) -> K,
+        H: Fn(&K) -> Out,
+        Out: PartialOrd
+    {
+        let mut result: Option<(Self::Item, K, Out)> = None;
+        for input in self {
+            let key = key(&input);
+            let output = objective(&key);
+            if result.as_ref().is_none_or(|(_, _, maximum)| output > *maximum) {
+                result = Some((input, key, output));
+            }
+        }
+        result
+    }
+
+    /// Returns the input minimizing `objective(key(input))`, together with
+    /// the key and the objective value.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the iterator is empty.
+    fn minimize_by_cached_key<K, Out, G, H>(self, key: G, objective: H) -> (Self::Item, K, Out)
+    where
+        G: Fn(&Self::Item) -> K,
+        H: Fn(&K) -> Out,
+        Out: PartialOrd
+    {
+        self.try_minimize_by_cached_key(key, objective)
+            .expect("cannot minimize over an empty set of inputs")
+    }
+
+    /// Returns the input maximizing `objective(key(input))`, together with
+    /// the key and the objective value.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the iterator is empty.
+    fn maximize_by_cached_key<K, Out, G, H>(self, key: G, objective: H) -> (Self::Item, K, Out)
+    where
+        G: Fn(&Self::Item) -> K,
+        H: Fn(&K) -> Out,
+        Out: PartialOrd
+    {
+        self.try_maximize_by_cached_key(key, objective)
+            .expect("cannot maximize over an empty set of inputs")
     }
 }
+
+impl<It: Iterator> OptimizeByCachedKey for It {}
+
+/// A hill-climbing optimizer with random restarts.
+///
+/// Each climb starts from a random state and repeatedly moves to a random
+/// neighbour if this does not decrease the score. Moves to neighbours with
+/// equal score allow the search to cross plateaus. A climb ends when the
+/// score has not improved for `plateau_steps` consecutive steps, or after
+/// `max_steps` steps in total. The best state over all climbs is returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HillClimbing {
+    restarts: usize,
+    max_steps: usize,
+    plateau_steps: usize,
+    accept_equal: bool,
+}
+
+impl HillClimbing {
+    /// Returns an optimizer performing a single climb of at most 10000 steps,
+    /// ending after 1000 steps without improvement.
+    pub fn new() -> Self {
+        HillClimbing { restarts: 0, max_steps: 10_000, plateau_steps: 1_000, accept_equal: true }
+    }
+
+    /// Sets the number of additional climbs started from new random states.
+    pub fn with_restarts(mut self, restarts: usize) -> Self {
+        self.restarts = restarts;
+        self
+    }
+
+    /// Sets the maximum number of steps per climb.
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Sets the number of steps without improvement after which a climb ends.
+    pub fn with_plateau_steps(mut self, plateau_steps: usize) -> Self {
+        self.plateau_steps = plateau_steps;
+        self
+    }
+
+    /// Sets whether moves to neighbours with equal score are accepted. This
+    /// is what allows the optimizer to escape plateaus.
+    pub fn with_accept_equal(mut self, accept_equal: bool) -> Self {
+        self.accept_equal = accept_equal;
+        self
+    }
+
+    /// Returns the best state found together with its score. Here,
+    /// `initial` returns a random initial state, `neighbour` returns a random
+    /// neighbour of the given state, and `score` is the function to maximize.
+    pub fn maximize<S, R, I, N, F>(&self, random: &mut R, initial: I, neighbour: N, score: F) -> (S, f64)
+    where
+        R: Rng,
+        I: Fn(&mut R) -> S,
+        N: Fn(&S, &mut R) -> S,
+        F: Fn(&S) -> f64
+    {
+        let mut best: Option<(S, f64)> = None;
+        for _ in 0..=self.restarts {
+            let state = initial(random);
+            let result = self.climb(random, state, &neighbour, &score);
+            if best.as_ref().is_none_or(|(_, maximum)| result.1 > *maximum) {
+                best = Some(result);
+            }
+        }
+        // There is always at least one climb.
+        best.unwrap()
+    }
+
+    /// Returns the best state found together with its score, where `score`
+    /// is the function to minimize. (See `maximize` for details.)
+    pub fn minimize<S, R, I, N, F>(&self, random: &mut R, initial: I, neighbour: N, score: F) -> (S, f64)
+    where
+        R: Rng,
+        I: Fn(&mut R) -> S,
+        N: Fn(&S, &mut R) -> S,
+        F: Fn(&S) -> f64
+    {
+        let (state, score) = self.maximize(random, initial, neighbour, |state| -score(state));
+        (state, -score)
+    }
+
+    fn climb<S, R, N, F>(&self, random: &mut R, state: S, neighbour: &N, score: &F) -> (S, f64)
+    where
+        R: Rng,
+        N: Fn(&S, &mut R) -> S,
+        F: Fn(&S) -> f64
+    {
+        let mut current_score = score(&state);
+        let mut current = state;
+        let mut steps_without_improvement = 0;
+        for _ in 0..self.max_steps {
+            if steps_without_improvement >= self.plateau_steps {
+                break;
+            }
+            let candidate = neighbour(&current, random);
+            let candidate_score = score(&candidate);
+            if candidate_score > current_score {
+                steps_without_improvement = 0;
+            } else {
+                steps_without_improvement += 1;
+            }
+            if candidate_score > current_score || (self.accept_equal && candidate_score == current_score) {
+                current = candidate;
+                current_score = candidate_score;
+            }
+        }
+        (current, current_score)
+    }
+}
+
+impl Default for HillClimbing {
+    fn default() -> Self {
+        HillClimbing::new()
+    }
+}
+
+/// A beam search over incrementally extended candidates.
+///
+/// Starting from an initial state, each round replaces every state in the
+/// beam by its extensions, and keeps the `width` best states. States without
+/// extensions are complete and are kept as they are. The search ends after
+/// the given number of rounds, or when every state in the beam is complete.
+/// Compared to a greedy search (a beam of width 1), a wider beam tolerates
+/// ambiguous choices which are only resolved by later extensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BeamSearch {
+    width: usize,
+}
+
+impl BeamSearch {
+    /// Returns a beam search keeping (at most) `width` states in each round.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width is zero.
+    pub fn new(width: usize) -> Self {
+        assert!(width > 0, "beam width must be non-zero");
+        BeamSearch { width }
+    }
+
+    /// Returns the width of the beam.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Runs the search for at most `rounds` rounds, and returns the final beam
+    /// together with the scores, ranked from best to worst. Here, `extend`
+    /// returns the extensions of the given state, and `score` is the function
+    /// to maximize.
+    pub fn search<S, I, E, F>(&self, initial: S, rounds: usize, extend: E, score: F) -> Vec<(S, f64)>
+    where
+        I: IntoIterator<Item=S>,
+        E: Fn(&S) -> I,
+        F: Fn(&S) -> f64
+    {
+        let initial_score = score(&initial);
+        let mut beam = vec![(initial, initial_score, false)];
+        for _ in 0..rounds {
+            if beam.iter().all(|(_, _, complete)| *complete) {
+                break;
+            }
+            let mut candidates = Vec::new();
+            for (state, state_score, complete) in beam {
+                if complete {
+                    candidates.push((state, state_score, true));
+                    continue;
+                }
+                let mut extensions = extend(&state).into_iter().peekable();
+                if extensions.peek().is_none() {
+                    candidates.push((state, state_score, true));
+                }
+                for extension in extensions {
+                    let extension_score = score(&extension);
+                    candidates.push((extension, extension_score, false));
+                }
+            }
+            candidates.sort_by(|lhs, rhs| rhs.1.partial_cmp(&lhs.1).unwrap_or(Ordering::Equal));
+            candidates.truncate(self.width);
+            beam = candidates;
+        }
+        beam.into_iter().map(|(state, score, _)| (state, score)).collect()
+    }
+}
+
 mod tests {
     
     #[test]
@@ -100,7 +420,6 @@ mod tests {
 
         let result = [1, 2, -1, -2, 3, -3]
             .iter()
-            .map(|x| x)
             .minimize(|&x| (x * x + x) as f64);
         assert_eq!(result, (&-1, 0.0));
     }
@@ -111,8 +430,95 @@ mod tests {
 
         let result = [1.0, 2.0, -1.0, -2.0, 3.0, -3.0]
             .iter()
-            .map(|x| x)
             .maximize(|&x| (x * x + x) as u64);
         assert_eq!(result, (&3.0, 12));
     }
+
+    #[test]
+    fn top_k_selection() {
+        use super::{Maximize, Minimize};
+
+        let values = [3, 1, 4, 1, 5, 9, 2, 6];
+        assert_eq!(
+            values.iter().enumerate().k_smallest(3, |(_, &x)| x),
+            vec![((1, &1), 1), ((3, &1), 1), ((6, &2), 2)]
+        );
+        assert_eq!(values.iter().k_largest(2, |&x| *x), vec![(&9, 9), (&6, 6)]);
+        assert_eq!(values.iter().k_largest(100, |&x| *x).len(), values.len());
+    }
+
+    #[test]
+    fn optimize_by_cached_key() {
+        use super::OptimizeByCachedKey;
+
+        let result = (0u8..4).minimize_by_cached_key(|&x| vec![x; 2], |v| (v[0] as i32 - 2).abs());
+        assert_eq!(result, (2, vec![2, 2], 0));
+        let result = (0u8..4).maximize_by_cached_key(|&x| x as f64 / 2.0, |&y| y);
+        assert_eq!(result, (3, 1.5, 1.5));
+        assert_eq!((0u8..0).try_minimize_by_cached_key(|&x| x, |&x| x), None);
+    }
+
+    #[test]
+    fn hill_climbing() {
+        use super::HillClimbing;
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+
+        // Recover a 64-bit target where the score is the number of matching
+        // bits, rounded down to an even number. The rounding creates plateaus.
+        let target: u64 = 0x0123_4567_89ab_cdef;
+        let score = |&x: &u64| (((!(x ^ target)).count_ones() / 2) * 2) as f64;
+        let neighbour = |&x: &u64, random: &mut StdRng| x ^ (1 << random.gen_range(0, 64));
+        let initial = |random: &mut StdRng| random.gen::<u64>();
+
+        // Use a fixed seed to make the test reproducible.
+        let mut random = StdRng::seed_from_u64(0x5eed);
+        let optimizer = HillClimbing::new()
+            .with_restarts(2)
+            .with_max_steps(100_000)
+            .with_plateau_steps(10_000);
+        let (result, value) = optimizer.maximize(&mut random, initial, neighbour, score);
+        assert_eq!((result, value), (target, 64.0));
+
+        let (_, value) = optimizer.minimize(&mut random, initial, neighbour, score);
+        assert_eq!(value, 0.0);
+    }
+
+    #[test]
+    fn beam_search() {
+        use super::BeamSearch;
+
+        // Every prefix of 000 scores 0, while every other string scores the
+        // number of ones. This misleads a greedy search.
+        let score = |state: &Vec<u8>| {
+            if state.iter().all(|&x| x == 0) {
+                if state.len() == 3 { 10.0 } else { 0.0 }
+            } else {
+                state.iter().map(|&x| x as f64).sum()
+            }
+        };
+        let extend = |state: &Vec<u8>| {
+            if state.len() < 3 {
+                vec![[state.clone(), vec![0]].concat(), [state.clone(), vec![1]].concat()]
+            } else {
+                Vec::new()
+            }
+        };
+        let result = BeamSearch::new(1).search(Vec::new(), 10, extend, score);
+        assert_eq!(result, vec![(vec![1, 1, 1], 3.0)]);
+
+        let result = BeamSearch::new(4).search(Vec::new(), 10, extend, score);
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[0], (vec![0, 0, 0], 10.0));
+    }
+
+    #[test]
+    fn optimize_empty() {
+        use super::{Maximize, Minimize};
+
+        let empty: [i32; 0] = [];
+        assert_eq!(empty.iter().try_minimize(|&x| *x), None);
+        assert_eq!(empty.iter().try_maximize(|&x| *x), None);
+        assert_eq!([1, 2].iter().try_minimize(|&x| *x), Some((&1, 1)));
+    }
 }
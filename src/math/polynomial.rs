@@ -0,0 +1,227 @@
+//! This module implements polynomials over the two element field {0, 1}.
+
+use core::{fmt, ops};
+
+use crate::prelude::*;
+
+/// A polynomial over GF(2). Coefficient `i` is stored as bit `i & 63` of
+/// limb `i >> 6`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Polynomial {
+    limbs: Vec<u64>,
+}
+
+impl Polynomial {
+    /// Returns the zero polynomial.
+    pub fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    /// Returns the constant polynomial 1.
+    pub fn one() -> Self {
+        Self::monomial(0)
+    }
+
+    /// Returns the monomial `x^degree`.
+    pub fn monomial(degree: usize) -> Self {
+        let mut result = Self { limbs: vec![0; (degree >> 6) + 1] };
+        result.limbs[degree >> 6] = 1 << (degree & 63);
+        result
+    }
+
+    /// Returns the polynomial with the given coefficients, starting with the
+    /// constant coefficient.
+    pub fn from_coefficients(coefficients: &[u8]) -> Self {
+        let mut result = Self::zero();
+        for (index, &value) in coefficients.iter().enumerate() {
+            result.set_coefficient(index, value);
+        }
+        result
+    }
+
+    /// Returns the degree of the polynomial, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.limbs.last().map(|x| 64 * (self.limbs.len() - 1) + 63 - x.leading_zeros() as usize)
+    }
+
+    /// Returns true if this is the zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Gets the coefficient of `x^index`.
+    pub fn get_coefficient(&self, index: usize) -> u8 {
+        self.limbs.get(index >> 6).map_or(0, |x| ((x >> (index & 63)) & 1) as u8)
+    }
+
+    /// Sets the coefficient of `x^index`.
+    pub fn set_coefficient(&mut self, index: usize, value: u8) {
+        if self.limbs.len() <= index >> 6 {
+            self.limbs.resize((index >> 6) + 1, 0);
+        }
+        let mask = 0xffffffff_ffffffff ^ (1 << (index & 63));
+        let value = ((value & 1) as u64) << (index & 63);
+        self.limbs[index >> 6] = (self.limbs[index >> 6] & mask) ^ value;
+        self.normalize();
+    }
+
+    /// Evaluates the polynomial at the given element of GF(2).
+    pub fn evaluate(&self, value: u8) -> u8 {
+        if value & 1 == 0 {
+            self.get_coefficient(0)
+        } else {
+            (self.limbs.iter().map(|x| x.count_ones()).sum::<u32>() & 1) as u8
+        }
+    }
+
+    // Ensure that the last limb is always non-zero. Note: This is required
+    // to ensure that the derived implementation of PartialEq is correct.
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl fmt::Debug for Polynomial {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}", self)
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let degree = match self.degree() {
+            Some(degree) => degree,
+            None => return write!(formatter, "0"),
+        };
+        let mut first = true;
+        for index in (0..=degree).rev().filter(|&i| self.get_coefficient(i) == 1) {
+            if !first {
+                write!(formatter, " + ")?;
+            }
+            match index {
+                0 => write!(formatter, "1")?,
+                1 => write!(formatter, "x")?,
+                _ => write!(formatter, "x^{}", index)?,
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+/// Implements `p + q` for polynomial references `p` and `q`.
+impl ops::Add<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn add(self, other: &Polynomial) -> Polynomial {
+        let mut result = self.clone();
+        result += other;
+        result
+    }
+}
+
+/// Implements `p + q` for polynomials `p` and `q`.
+impl ops::Add<Polynomial> for Polynomial {
+    type Output = Polynomial;
+
+    fn add(mut self, other: Polynomial) -> Polynomial {
+        self += &other;
+        self
+    }
+}
+
+/// Implements `p += q` for polynomial `p` and polynomial reference `q`.
+impl ops::AddAssign<&Polynomial> for Polynomial {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn add_assign(&mut self, other: &Polynomial) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        self.limbs.iter_mut().zip(other.limbs.iter()).for_each(|(x, y)| *x ^= *y);
+        self.normalize();
+    }
+}
+
+/// Implements `p += q` for polynomials `p` and `q`.
+impl ops::AddAssign<Polynomial> for Polynomial {
+    fn add_assign(&mut self, other: Polynomial) {
+        *self += &other;
+    }
+}
+
+/// Implements `p * q` for polynomial references `p` and `q`.
+impl ops::Mul<&Polynomial> for &Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, other: &Polynomial) -> Polynomial {
+        let mut result = Polynomial::zero();
+        if let Some(degree) = other.degree() {
+            for index in (0..=degree).filter(|&i| other.get_coefficient(i) == 1) {
+                result += self << index;
+            }
+        }
+        result
+    }
+}
+
+/// Implements `p * q` for polynomials `p` and `q`.
+impl ops::Mul<Polynomial> for Polynomial {
+    type Output = Polynomial;
+
+    fn mul(self, other: Polynomial) -> Polynomial {
+        &self * &other
+    }
+}
+
+/// Multiplies the polynomial by `x^rhs`.
+impl ops::Shl<usize> for &Polynomial {
+    type Output = Polynomial;
+
+    fn shl(self, rhs: usize) -> Polynomial {
+        if self.is_zero() {
+            return Polynomial::zero();
+        }
+        let (offset, shift) = (rhs >> 6, rhs & 63);
+        let mut limbs = vec![0; self.limbs.len() + offset + 1];
+        for (i, x) in self.limbs.iter().enumerate() {
+            limbs[i + offset] |= x << shift;
+            if shift > 0 {
+                limbs[i + offset + 1] |= x >> (64 - shift);
+            }
+        }
+        let mut result = Polynomial { limbs };
+        result.normalize();
+        result
+    }
+}
+
+/// Multiplies the polynomial by `x^rhs`.
+impl ops::Shl<usize> for Polynomial {
+    type Output = Polynomial;
+
+    fn shl(self, rhs: usize) -> Polynomial {
+        &self << rhs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polynomial_arithmetic() {
+        // (x + 1)^2 = x^2 + 1 over GF(2).
+        let p = Polynomial::from_coefficients(&[1, 1]);
+        assert_eq!(&p * &p, Polynomial::from_coefficients(&[1, 0, 1]));
+        assert_eq!(&p + &p, Polynomial::zero());
+        assert_eq!((&p << 100).degree(), Some(101));
+        assert_eq!(Polynomial::zero().degree(), None);
+
+        let q = Polynomial::monomial(70) + Polynomial::one();
+        assert_eq!(q.to_string(), "x^70 + 1");
+        assert_eq!(q.evaluate(0), 1);
+        assert_eq!(q.evaluate(1), 0);
+    }
+}
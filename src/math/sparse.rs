@@ -0,0 +1,282 @@
+//! This module implements sparse matrices over the two element field {0, 1}, for linear
+//! systems which are too large to represent densely using `linear_algebra::Matrix`.
+
+use core::ops;
+
+use crate::prelude::*;
+use super::linear_algebra::{Error, Matrix, Vector};
+
+/// Returns the sum of two rows, given as sorted lists of column indices.
+fn add_rows(lhs: &[usize], rhs: &[usize]) -> Vec<usize> {
+    let mut result = Vec::with_capacity(lhs.len() + rhs.len());
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() && j < rhs.len() {
+        if lhs[i] < rhs[j] {
+            result.push(lhs[i]);
+            i += 1;
+        } else if lhs[i] > rhs[j] {
+            result.push(rhs[j]);
+            j += 1;
+        } else {
+            i += 1;
+            j += 1;
+        }
+    }
+    result.extend_from_slice(&lhs[i..]);
+    result.extend_from_slice(&rhs[j..]);
+    result
+}
+
+/// A sparse binary matrix type. Each row is stored as the sorted list of the columns where
+/// the row is 1, so memory use is proportional to the number of non-zero elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseMatrix {
+    pub dimensions: (usize, usize),
+    rows: Vec<Vec<usize>>
+}
+
+impl SparseMatrix {
+    /// Returns a new matrix with the given dimensions where each element is 0.
+    pub fn zeroes(rows: usize, columns: usize) -> Self {
+        Self { dimensions: (rows, columns), rows: vec![Vec::new(); rows] }
+    }
+
+    /// Returns a new identity matrix with the given dimension.
+    pub fn identity(dimension: usize) -> Self {
+        Self { dimensions: (dimension, dimension), rows: (0..dimension).map(|i| vec![i]).collect() }
+    }
+
+    /// Returns the matrix where row `i` is 1 exactly in the columns listed in `rows[i]`.
+    /// Columns may be given in any order, and columns listed twice cancel out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any column is not smaller than `columns`.
+    pub fn from_rows(columns: usize, rows: Vec<Vec<usize>>) -> Result<Self, Error> {
+        if rows.iter().flatten().any(|&column| column >= columns) {
+            return Err(Error::ConversionError);
+        }
+        let rows: Vec<Vec<usize>> = rows
+            .into_iter()
+            .map(|mut row| {
+                row.sort_unstable();
+                let mut result: Vec<usize> = Vec::with_capacity(row.len());
+                for column in row {
+                    if result.last() == Some(&column) {
+                        result.pop();
+                    } else {
+                        result.push(column);
+                    }
+                }
+                result
+            })
+            .collect();
+        Ok(Self { dimensions: (rows.len(), columns), rows })
+    }
+
+    /// Gets the element at `(row, column)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `row` or `column` is too large.
+    pub fn get_element(&self, row: usize, column: usize) -> u8 {
+        assert!(column < self.dimensions.1);
+        self.rows[row].binary_search(&column).is_ok() as u8
+    }
+
+    /// Sets the element at `(row, column)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `row` or `column` is too large.
+    pub fn set_element(&mut self, row: usize, column: usize, value: u8) {
+        assert!(column < self.dimensions.1);
+        let row = &mut self.rows[row];
+        match (row.binary_search(&column), value & 1) {
+            (Ok(index), 0) => { row.remove(index); },
+            (Err(index), 1) => row.insert(index, column),
+            _ => {}
+        }
+    }
+
+    /// Returns the sorted columns where the given row is 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is too large.
+    pub fn get_row(&self, row: usize) -> &[usize] {
+        &self.rows[row]
+    }
+
+    /// Returns the number of non-zero elements.
+    pub fn weight(&self) -> usize {
+        self.rows.iter().map(|row| row.len()).sum()
+    }
+
+    /// Returns the corresponding dense matrix.
+    pub fn to_dense(&self) -> Matrix {
+        Matrix::from_fn(self.dimensions.0, self.dimensions.1, |row, column| self.get_element(row, column))
+    }
+
+    /// Returns the unique solution `x` to `self * x = rhs`.
+    ///
+    /// The rows are reduced one at a time (sparsest first), by adding previously reduced rows
+    /// until the leading column of the row is not used as a pivot. The rows stay sparse as long
+    /// as the fill-in is small, which is the case for the structured systems produced by most
+    /// attacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system is inconsistent, or if the solution is not unique.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self.dimensions.0 != rhs.dimension`.
+    pub fn solve(&self, rhs: &Vector) -> Result<Vector, Error> {
+        assert_eq!(self.dimensions.0, rhs.dimension);
+        let mut order: Vec<usize> = (0..self.dimensions.0).collect();
+        order.sort_by_key(|&row| self.rows[row].len());
+
+        // `pivots[c]` is a reduced row with leading column `c`, together with its right-hand side.
+        let mut pivots: Vec<Option<(Vec<usize>, u8)>> = vec![None; self.dimensions.1];
+        for row in order {
+            let mut current = self.rows[row].clone();
+            let mut value = rhs.get_element(row);
+            while let Some(&column) = current.first() {
+                match &pivots[column] {
+                    Some((pivot, pivot_value)) => {
+                        current = add_rows(&current, pivot);
+                        value ^= pivot_value;
+                    },
+                    None => break
+                }
+            }
+            match current.first() {
+                Some(&column) => pivots[column] = Some((current, value)),
+                None if value != 0 => return Err(Error::InconsistentSystemError),
+                None => {}
+            }
+        }
+
+        // Back-substitute, starting with the last column.
+        let mut solution = Vector::zeroes(self.dimensions.1);
+        for column in (0..self.dimensions.1).rev() {
+            let (pivot, value) = pivots[column].as_ref().ok_or(Error::UnderDeterminedSystemError)?;
+            let result = pivot[1..].iter().fold(*value, |result, &other| result ^ solution.get_element(other));
+            solution.set_element(column, result);
+        }
+        Ok(solution)
+    }
+}
+
+impl From<&Matrix> for SparseMatrix {
+    fn from(matrix: &Matrix) -> Self {
+        let (rows, columns) = matrix.dimensions;
+        Self {
+            dimensions: matrix.dimensions,
+            rows: (0..rows)
+                .map(|row| (0..columns).filter(|&column| matrix.get_element(row, column) == 1).collect())
+                .collect()
+        }
+    }
+}
+
+impl From<&SparseMatrix> for Matrix {
+    fn from(matrix: &SparseMatrix) -> Self {
+        matrix.to_dense()
+    }
+}
+
+/// Implements `A * v` for sparse matrix reference `A` and vector reference `v`.
+///
+/// # Panics
+///
+/// Panics if `self.dimensions.1 != rhs.dimension`.
+impl ops::Mul<&Vector> for &SparseMatrix {
+    type Output = Vector;
+
+    fn mul(self, rhs: &Vector) -> Vector {
+        assert_eq!(self.dimensions.1, rhs.dimension);
+        let mut result = Vector::zeroes(self.dimensions.0);
+        for (i, row) in self.rows.iter().enumerate() {
+            let value = row.iter().fold(0, |value, &column| value ^ rhs.get_element(column));
+            result.set_element(i, value);
+        }
+        result
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+
+    use super::*;
+    use crate::math::linear_algebra::GaussElimination;
+
+    /// Returns a random sparse invertible matrix, obtained by permuting the rows of an upper
+    /// triangular matrix with the given number of additional elements in each row.
+    fn random_system(size: usize, weight: usize) -> SparseMatrix {
+        let mut rng = rand::thread_rng();
+        let mut rows: Vec<Vec<usize>> = (0..size)
+            .map(|i| {
+                let mut row = vec![i];
+                row.extend((0..weight).filter(|_| i + 1 < size).map(|_| rng.gen_range(i + 1, size)));
+                row
+            })
+            .collect();
+        rows.shuffle(&mut rng);
+        SparseMatrix::from_rows(size, rows).unwrap()
+    }
+
+    #[test]
+    fn sparse_matrix_creation() {
+        let matrix = SparseMatrix::from_rows(5, vec![vec![3, 1, 3, 3], vec![], vec![4, 0]]).unwrap();
+        assert_eq!(matrix.dimensions, (3, 5));
+        assert_eq!(matrix.get_row(0), [1, 3]);
+        assert_eq!(matrix.get_row(2), [0, 4]);
+        assert_eq!(matrix.weight(), 4);
+        assert!(SparseMatrix::from_rows(5, vec![vec![5]]).is_err());
+
+        let mut matrix = SparseMatrix::zeroes(17, 65);
+        for (i, j) in (0..17).zip((0..65).step_by(3)) {
+            matrix.set_element(i, j, 1);
+        }
+        matrix.set_element(0, 0, 0);
+        matrix.set_element(1, 3, 1);
+        assert_eq!(matrix.weight(), 16);
+        assert_eq!(matrix.get_element(0, 0), 0);
+        assert_eq!(matrix.get_element(1, 3), 1);
+        assert_eq!(SparseMatrix::identity(37).to_dense(), Matrix::identity(37));
+    }
+
+    #[test]
+    fn dense_conversion() {
+        let matrix = Matrix::random(17, 65);
+        let sparse = SparseMatrix::from(&matrix);
+        assert_eq!(Matrix::from(&sparse), matrix);
+        let vector = Vector::random(65);
+        assert_eq!(&sparse * &vector, &matrix * &vector);
+    }
+
+    #[test]
+    fn sparse_gauss_elimination() {
+        for _ in 0..10 {
+            let size = rand::thread_rng().gen_range(1, 512);
+            let matrix = random_system(size, 3);
+            let solution = Vector::random(size);
+            let rhs = &matrix * &solution;
+            assert_eq!(matrix.solve(&rhs).unwrap(), solution);
+            assert_eq!(GaussElimination::new(matrix.to_dense(), rhs).solve().unwrap(), solution);
+        }
+        // Additional consistent equations are allowed, but not inconsistent ones.
+        let matrix = SparseMatrix::from_rows(2, vec![vec![0, 1], vec![1], vec![0]]).unwrap();
+        let solution = Vector::from_bits(vec![true, false]);
+        assert_eq!(matrix.solve(&(&matrix * &solution)).unwrap(), solution);
+        let rhs = Vector::from_bits(vec![true, true, true]);
+        assert!(matches!(matrix.solve(&rhs), Err(Error::InconsistentSystemError)));
+
+        let matrix = SparseMatrix::from_rows(3, vec![vec![0, 1], vec![1, 2], vec![0, 2]]).unwrap();
+        assert!(matches!(matrix.solve(&Vector::zeroes(3)), Err(Error::UnderDeterminedSystemError)));
+    }
+}
@@ -3,6 +3,14 @@ use std::iter::FromIterator;
 use std::clone::Clone;
 use std::hash::Hash;
 use std::cmp::Eq;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use crate::crypto::random::RandomGenerator;
+
+pub mod english;
+pub mod quadgrams;
 
 
 /// A convenience type used for frequency counting.
@@ -21,8 +29,71 @@ impl<T> Frequencies<T> where T: Eq + Clone + Hash {
         self.sample_size += 1;
     }
 
-    pub fn iter(&self) -> std::collections::hash_map::Iter<T, usize> {
-        (&self).into_iter()
+    /// Returns the number of times the given value has been observed.
+    pub fn count_of(&self, value: &T) -> usize {
+        *self.counts.get(value).unwrap_or(&0)
+    }
+
+    /// Returns the `k` most common values together with their counts, in
+    /// decreasing order of frequency.
+    ///
+    /// # Note
+    ///
+    /// The order of values with equal counts is unspecified.
+    pub fn most_common(&self, k: usize) -> Vec<(T, usize)> {
+        let mut result: Vec<(T, usize)> = self.counts
+            .iter()
+            .map(|(value, &count)| (value.clone(), count))
+            .collect();
+        result.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        result.truncate(k);
+        result
+    }
+
+    /// Adds the observations from `other` to this set of frequencies.
+    pub fn merge(&mut self, other: &Frequencies<T>) {
+        for (value, &count) in other {
+            *self.counts.entry(value.clone()).or_insert(0) += count;
+        }
+        self.sample_size += other.sample_size;
+    }
+
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, T, usize> {
+        self.into_iter()
+    }
+}
+
+impl Frequencies<u8> {
+    /// The size of the chunks read by `from_reader`.
+    const CHUNK_SIZE: usize = 1 << 16;
+
+    /// Counts the bytes read from the given reader. The input is read in
+    /// fixed-size chunks, so arbitrarily large corpora may be processed in
+    /// constant memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the underlying reader fails.
+    pub fn from_reader<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut buffer = vec![0; Self::CHUNK_SIZE];
+        let mut counts = [0; 256];
+        loop {
+            let size = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(size) => size,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            for &byte in &buffer[..size] {
+                counts[byte as usize] += 1;
+            }
+        }
+        let mut frequencies = Frequencies::new();
+        for (byte, &count) in counts.iter().enumerate().filter(|(_, &count)| count > 0) {
+            frequencies.counts.insert(byte as u8, count);
+            frequencies.sample_size += count;
+        }
+        Ok(frequencies)
     }
 }
 
@@ -95,6 +166,41 @@ impl<T> Distribution<T> where T: Eq + Clone + Hash {
         Distribution { support, probabilities }
     }
 
+    /// Creates a discrete probability distribution from a set of frequencies
+    /// using add-`k` smoothing over the given alphabet. Each value `x` is
+    /// assigned the probability `(count(x) + k) / (N + k * A)`, where `N` is
+    /// the sample size and `A` the size of the alphabet. In particular, values
+    /// in the alphabet which were never observed are assigned a non-zero
+    /// probability. (Observed values outside the alphabet are added to it.)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is not positive.
+    pub fn smoothed<I>(frequencies: &Frequencies<T>, alphabet: I, k: f64) -> Self
+    where
+        I: IntoIterator<Item=T>
+    {
+        assert!(k > 0.0, "smoothing constant must be positive");
+        let mut counts: HashMap<T, usize> = alphabet.into_iter().map(|value| (value, 0)).collect();
+        for (value, &count) in frequencies {
+            counts.insert(value.clone(), count);
+        }
+        let total = (frequencies.sample_size as f64) + k * (counts.len() as f64);
+        Distribution::new(counts
+            .into_iter()
+            .map(|(value, count)| (value, (count as f64 + k) / total))
+            .collect())
+    }
+
+    /// Creates a discrete probability distribution from a set of frequencies
+    /// using Laplace (add-one) smoothing over the given alphabet.
+    pub fn laplace<I>(frequencies: &Frequencies<T>, alphabet: I) -> Self
+    where
+        I: IntoIterator<Item=T>
+    {
+        Distribution::smoothed(frequencies, alphabet, 1.0)
+    }
+
     /// Returns the entropy of the distribution.
     pub fn entropy(&self) -> f64 {
         let mut result = 0.0;
@@ -104,6 +210,22 @@ impl<T> Distribution<T> where T: Eq + Clone + Hash {
         result
     }
 
+    /// Returns the min-entropy of the distribution. This is the negated
+    /// logarithm of the probability of the most likely value, and is a lower
+    /// bound for both the Shannon and the collision entropy.
+    pub fn min_entropy(&self) -> f64 {
+        let maximum = self.probabilities.values().cloned().fold(0.0, f64::max);
+        -maximum.log2()
+    }
+
+    /// Returns the collision (Rényi order 2) entropy of the distribution. This
+    /// is the negated logarithm of the probability that two independent
+    /// samples are equal.
+    pub fn collision_entropy(&self) -> f64 {
+        let collision_probability: f64 = self.probabilities.values().map(|p| p * p).sum();
+        -collision_probability.log2()
+    }
+
     /// Returns the probability of the given observation.
     pub fn probability_of(&self, value: &T) -> f64 {
         *self.probabilities.get(value).unwrap_or(&0.0)
@@ -117,6 +239,150 @@ impl<T> Distribution<T> where T: Eq + Clone + Hash {
         }
         0.5 * result
     }
+
+    /// Returns the Kullback-Leibler divergence `D(self || other)` in bits.
+    ///
+    /// # Note
+    ///
+    /// The divergence is infinite if `other` assigns probability 0 to some
+    /// value in the support of `self`.
+    pub fn kl_divergence(&self, other: &Distribution<T>) -> f64 {
+        let mut result = 0.0;
+        for value in &self.support {
+            let (p, q) = (self.probability_of(value), other.probability_of(value));
+            if q == 0.0 {
+                return f64::INFINITY;
+            }
+            result += p * (p / q).log2();
+        }
+        result
+    }
+
+    /// Returns the cross-entropy `H(self, other)` in bits. This is the
+    /// average number of bits needed to encode values drawn from `self`
+    /// using an optimal code for `other`.
+    ///
+    /// # Note
+    ///
+    /// The cross-entropy is infinite if `other` assigns probability 0 to some
+    /// value in the support of `self`.
+    pub fn cross_entropy(&self, other: &Distribution<T>) -> f64 {
+        let mut result = 0.0;
+        for value in &self.support {
+            let (p, q) = (self.probability_of(value), other.probability_of(value));
+            if q == 0.0 {
+                return f64::INFINITY;
+            }
+            result -= p * q.log2();
+        }
+        result
+    }
+
+    /// Draws a value from the distribution using inverse transform sampling.
+    ///
+    /// # Note
+    ///
+    /// Values are visited in the (unspecified) iteration order of the support,
+    /// so the result depends on the state of `random` but is not guaranteed
+    /// to be reproducible across distinct `Distribution` instances.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the support of the distribution is empty.
+    pub fn sample<R: RandomGenerator>(&self, random: &mut R) -> T {
+        assert!(!self.support.is_empty(), "cannot sample from an empty distribution");
+        let total: f64 = self.support.iter().map(|value| self.probability_of(value)).sum();
+        // Use the top 53 bits to get a uniform value in [0, 1).
+        let mut target = ((random.next_u64() >> 11) as f64) / ((1u64 << 53) as f64) * total;
+        let mut result = None;
+        for value in &self.support {
+            result = Some(value);
+            target -= self.probability_of(value);
+            if target < 0.0 {
+                break;
+            }
+        }
+        // If rounding errors leave us past the end, we return the last value.
+        result.unwrap().clone()
+    }
+
+    /// Returns the divergence of `self` from `other` using the given measure.
+    pub fn divergence_from(&self, other: &Distribution<T>, divergence: Divergence) -> f64 {
+        match divergence {
+            Divergence::TotalVariation => self.distance_from(other),
+            Divergence::KullbackLeibler => self.kl_divergence(other),
+            Divergence::CrossEntropy => self.cross_entropy(other),
+        }
+    }
+}
+
+/// Measures of how much an observed distribution differs from a model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// The total variation distance (see `Distribution::distance_from`).
+    TotalVariation,
+    /// The Kullback-Leibler divergence (see `Distribution::kl_divergence`).
+    KullbackLeibler,
+    /// The cross-entropy (see `Distribution::cross_entropy`).
+    CrossEntropy,
+}
+
+impl Divergence {
+    /// Returns the largest value the measure can take.
+    pub fn maximum(&self) -> f64 {
+        match self {
+            Divergence::TotalVariation => 1.0,
+            Divergence::KullbackLeibler | Divergence::CrossEntropy => f64::INFINITY,
+        }
+    }
+}
+
+impl Distribution<u8> {
+    /// Creates the byte distribution of the given corpus.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the underlying reader fails.
+    pub fn from_reader<R: Read>(reader: R) -> io::Result<Self> {
+        Ok(Distribution::from(Frequencies::from_reader(reader)?))
+    }
+
+    /// Creates the byte distribution of the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Distribution::from_reader(File::open(path)?)
+    }
+}
+
+impl Distribution<String> {
+    /// Creates the word distribution of the given corpus, where words are
+    /// separated by whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the underlying reader fails, or if
+    /// the corpus is not valid UTF-8.
+    pub fn words_from_reader<R: Read>(reader: R) -> io::Result<Self> {
+        let mut frequencies = Frequencies::new();
+        for line in BufReader::new(reader).lines() {
+            for word in line?.split_whitespace() {
+                frequencies.add(&word.to_string());
+            }
+        }
+        Ok(Distribution::from(frequencies))
+    }
+
+    /// Creates the word distribution of the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read.
+    pub fn words_from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Distribution::words_from_reader(File::open(path)?)
+    }
 }
 
 /// Creates a discrete probability distribution from a set of frequencies.
@@ -161,6 +427,158 @@ impl<'a, T: 'a> FromIterator<&'a T> for Distribution<T> where T: Eq + Clone + Ha
     }
 }
 
+/// Returns the serial correlation coefficient of the buffer, that is, the
+/// correlation between each byte and the next (wrapping around at the end).
+/// This is close to 0 for random data, and close to 1 for data where each
+/// byte is close to the previous one.
+///
+/// # Note
+///
+/// Returns `None` if the buffer is constant (including the empty buffer),
+/// since the coefficient is undefined in this case.
+pub fn serial_correlation(buffer: &[u8]) -> Option<f64> {
+    let n = buffer.len() as f64;
+    let (mut sum, mut sum_of_squares, mut sum_of_products) = (0.0, 0.0, 0.0);
+    for (i, &x) in buffer.iter().enumerate() {
+        let (x, y) = (x as f64, buffer[(i + 1) % buffer.len()] as f64);
+        sum += x;
+        sum_of_squares += x * x;
+        sum_of_products += x * y;
+    }
+    let denominator = n * sum_of_squares - sum * sum;
+    if denominator == 0.0 {
+        return None;
+    }
+    Some((n * sum_of_products - sum * sum) / denominator)
+}
+
+/// Returns the index of coincidence of the given samples, that is, the
+/// probability that two samples drawn without replacement are equal.
+///
+/// # Note
+///
+/// Returns 0 if there are fewer than two samples.
+pub fn index_of_coincidence<T>(samples: &[T]) -> f64 where T: Eq + Clone + Hash {
+    if samples.len() < 2 {
+        return 0.0;
+    }
+    let frequencies: Frequencies<T> = samples.iter().collect();
+    let coincidences: usize = frequencies.iter().map(|(_, &count)| count * (count - 1)).sum();
+    (coincidences as f64) / ((samples.len() * (samples.len() - 1)) as f64)
+}
+
+/// Returns the average index of coincidence of the `period` columns obtained
+/// by taking every `period`-th sample. For data encrypted using a repeating
+/// key, this is maximized when `period` is a multiple of the key size.
+///
+/// # Panics
+///
+/// Panics if `period` is zero.
+pub fn periodic_index_of_coincidence<T>(samples: &[T], period: usize) -> f64 where T: Eq + Clone + Hash {
+    assert!(period > 0, "period must be non-zero");
+    let total: f64 = (0..period)
+        .map(|offset| {
+            let column: Vec<T> = samples.iter().skip(offset).step_by(period).cloned().collect();
+            index_of_coincidence(&column)
+        })
+        .sum();
+    total / (period as f64)
+}
+
+#[cfg(feature = "serde")]
+mod serialization {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::hash::Hash;
+    use std::io::{self, BufReader, BufWriter};
+    use std::path::Path;
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use serde::de::DeserializeOwned;
+
+    use super::{Distribution, Frequencies};
+
+    /// Distributions are serialized as a sequence of (value, probability) pairs.
+    impl<T> Serialize for Distribution<T> where T: Eq + Clone + Hash + Serialize {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.probabilities.iter())
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for Distribution<T> where T: Eq + Clone + Hash + Deserialize<'de> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let pairs = Vec::<(T, f64)>::deserialize(deserializer)?;
+            if pairs.iter().any(|(_, probability)| !probability.is_finite() || *probability < 0.0) {
+                return Err(de::Error::custom("invalid probability"));
+            }
+            Ok(Distribution::new(pairs.into_iter().collect::<HashMap<T, f64>>()))
+        }
+    }
+
+    /// Frequencies are serialized as a sequence of (value, count) pairs.
+    impl<T> Serialize for Frequencies<T> where T: Eq + Clone + Hash + Serialize {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(self.counts.iter())
+        }
+    }
+
+    impl<'de, T> Deserialize<'de> for Frequencies<T> where T: Eq + Clone + Hash + Deserialize<'de> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let mut frequencies = Frequencies::new();
+            for (value, count) in Vec::<(T, usize)>::deserialize(deserializer)? {
+                // The count of each value is bounded by the sample size.
+                frequencies.sample_size = frequencies.sample_size
+                    .checked_add(count)
+                    .ok_or_else(|| de::Error::custom("sample size overflow"))?;
+                *frequencies.counts.entry(value).or_insert(0) += count;
+            }
+            Ok(frequencies)
+        }
+    }
+
+    impl<T> Distribution<T> where T: Eq + Clone + Hash + Serialize + DeserializeOwned {
+        /// Saves the distribution as JSON to the file at the given path.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the file cannot be created or written.
+        pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+            serde_json::to_writer(BufWriter::new(File::create(path)?), self)?;
+            Ok(())
+        }
+
+        /// Loads a distribution saved using `save` from the file at the given path.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the file cannot be read, or does not contain a valid distribution.
+        pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+        }
+    }
+
+    impl<T> Frequencies<T> where T: Eq + Clone + Hash + Serialize + DeserializeOwned {
+        /// Saves the frequencies as JSON to the file at the given path.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the file cannot be created or written.
+        pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+            serde_json::to_writer(BufWriter::new(File::create(path)?), self)?;
+            Ok(())
+        }
+
+        /// Loads frequencies saved using `save` from the file at the given path.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the file cannot be read, or does not contain valid frequencies.
+        pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! dist {
     ( $( $value:expr => $probability:expr ),* ) => {{
@@ -208,6 +626,148 @@ mod tests {
         }
     }
 
+    #[test]
+    fn distribution_from_reader() {
+        use super::*;
+
+        let distribution = Distribution::from_reader(&b"abba"[..]).unwrap();
+        assert_eq!(distribution.probability_of(&b'a'), 0.5);
+        assert_eq!(distribution.probability_of(&b'b'), 0.5);
+
+        let distribution = Distribution::words_from_reader(&b"to be or\nnot to be"[..]).unwrap();
+        assert_eq!(distribution.probability_of(&"to".to_string()), 1.0 / 3.0);
+        assert_eq!(distribution.probability_of(&"not".to_string()), 1.0 / 6.0);
+    }
+
+    #[test]
+    fn divergences() {
+        use super::*;
+
+        let p = dist!(0 => 0.5, 1 => 0.5);
+        let q = dist!(0 => 0.25, 1 => 0.75);
+        let r = dist!(0 => 1.0);
+        assert_eq!(p.kl_divergence(&p), 0.0);
+        assert_eq!(p.cross_entropy(&p), p.entropy());
+        assert!((p.cross_entropy(&q) - p.entropy() - p.kl_divergence(&q)).abs() < 1e-12);
+        assert_eq!(p.kl_divergence(&r), f64::INFINITY);
+        assert_eq!(r.kl_divergence(&p), 1.0);
+        assert_eq!(p.divergence_from(&q, Divergence::TotalVariation), 0.25);
+    }
+
+    #[test]
+    fn frequency_accessors() {
+        use super::*;
+
+        let mut frequencies: Frequencies<u8> = b"abacab".iter().collect();
+        assert_eq!(frequencies.count_of(&b'a'), 3);
+        assert_eq!(frequencies.count_of(&b'd'), 0);
+        assert_eq!(frequencies.most_common(2), vec![(b'a', 3), (b'b', 2)]);
+
+        frequencies.merge(&b"ccdc".iter().collect());
+        assert_eq!(frequencies.sample_size, 10);
+        assert_eq!(frequencies.count_of(&b'c'), 4);
+        assert_eq!(frequencies.most_common(1), vec![(b'c', 4)]);
+        assert_eq!(frequencies.most_common(10).len(), 4);
+    }
+
+    #[test]
+    fn distribution_with_smoothing() {
+        use super::*;
+
+        let frequencies: Frequencies<u8> = b"aab".iter().collect();
+        let distribution = Distribution::laplace(&frequencies, b'a'..=b'c');
+        assert_eq!(distribution.probability_of(&b'a'), 0.5);
+        assert_eq!(distribution.probability_of(&b'b'), 1.0 / 3.0);
+        assert_eq!(distribution.probability_of(&b'c'), 1.0 / 6.0);
+
+        let distribution = Distribution::smoothed(&frequencies, 0..=255, 0.5);
+        assert!((0..=255).all(|x| distribution.probability_of(&x) > 0.0));
+        let observed: Distribution<u8> = b"xyz".iter().collect();
+        assert!(observed.kl_divergence(&distribution).is_finite());
+    }
+
+    #[test]
+    fn distribution_sampling() {
+        use super::*;
+        use crate::crypto::random::SeedableGenerator;
+        use crate::crypto::random::mersenne_twister::Mt19337;
+
+        let mut random = Mt19337::new(5489);
+        let distribution = dist!(b'a' => 0.75, b'b' => 0.25, b'c' => 0.0);
+        let frequencies: Frequencies<u8> = (0..10_000)
+            .map(|_| distribution.sample(&mut random))
+            .collect();
+        assert_eq!(frequencies.count_of(&b'c'), 0);
+        assert!((frequencies.count_of(&b'a') as f64 / 10_000.0 - 0.75).abs() < 0.02);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_encoding() {
+        use super::*;
+
+        let distribution = dist!(*b"th" => 0.75, *b"he" => 0.25);
+        let json = serde_json::to_string(&distribution).unwrap();
+        let result: Distribution<[u8; 2]> = serde_json::from_str(&json).unwrap();
+        assert_eq!(result.support, distribution.support);
+        assert_eq!(result.probability_of(b"th"), 0.75);
+        assert!(serde_json::from_str::<Distribution<u8>>("[[1, -0.5]]").is_err());
+
+        let frequencies: Frequencies<u8> = b"abacab".iter().collect();
+        let path = std::env::temp_dir().join("cryptopals-frequencies.json");
+        frequencies.save(&path).unwrap();
+        let result = Frequencies::<u8>::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.sample_size, 6);
+        assert_eq!(result.most_common(1), vec![(b'a', 3)]);
+
+        let json = format!("[[1, {}], [2, 1]]", usize::MAX);
+        assert!(serde_json::from_str::<Frequencies<u8>>(&json).is_err());
+    }
+
+    #[test]
+    fn entropy_estimators() {
+        use super::*;
+
+        let uniform: Distribution<u8> = (0..=255).collect();
+        assert_eq!(uniform.entropy(), 8.0);
+        assert!((uniform.min_entropy() - 8.0).abs() < 1e-12);
+        assert!((uniform.collision_entropy() - 8.0).abs() < 1e-12);
+
+        let skewed = dist!(0 => 0.5, 1 => 0.25, 2 => 0.25);
+        assert_eq!(skewed.min_entropy(), 1.0);
+        assert!(skewed.min_entropy() <= skewed.collision_entropy());
+        assert!(skewed.collision_entropy() <= skewed.entropy());
+
+        assert_eq!(serial_correlation(b"aaaa"), None);
+        assert!(serial_correlation(&(0..=255).collect::<Vec<u8>>()).unwrap() > 0.9);
+        assert!(serial_correlation(&[0, 255, 0, 255]).unwrap() < -0.9);
+    }
+
+    #[test]
+    fn frequencies_from_reader() {
+        use super::*;
+
+        // Use a buffer larger than a single chunk.
+        let buffer: Vec<u8> = (0..100_000).map(|x| (x % 3) as u8).collect();
+        let frequencies = Frequencies::from_reader(&buffer[..]).unwrap();
+        assert_eq!(frequencies.sample_size, 100_000);
+        assert_eq!(frequencies.count_of(&0), 33_334);
+        assert_eq!(frequencies.count_of(&2), 33_333);
+        assert_eq!(frequencies.count_of(&3), 0);
+    }
+
+    #[test]
+    fn index_of_coincidence() {
+        use super::*;
+
+        assert_eq!(super::index_of_coincidence(b"aaaa"), 1.0);
+        assert_eq!(super::index_of_coincidence(b"abcd"), 0.0);
+        assert_eq!(super::index_of_coincidence(b"aabb"), 1.0 / 3.0);
+        assert_eq!(periodic_index_of_coincidence(b"abababab", 2), 1.0);
+        assert_eq!(periodic_index_of_coincidence(b"abababab", 1), 3.0 / 7.0);
+    }
+
     #[test]
     fn distribution_from_macro() { 
         use super::*;
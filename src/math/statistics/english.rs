@@ -0,0 +1,192 @@
+//! Statistics of the English language, together with a scorer which may be
+//! used to rank candidate plaintexts.
+//!
+//! The monogram distribution is computed from the corpus in `data/english`
+//! and is intended to be matched against raw bytes. The bigram and trigram tables only contain
+//! lowercase letters and are matched against runs of ASCII letters, ignoring
+//! case.
+
+use super::{Distribution, Divergence};
+use super::quadgrams::QuadgramModel;
+use crate::dist;
+
+/// The corpus used to build the English monogram statistics.
+const CORPUS: &[u8] = include_bytes!("../../../data/english/corpus.txt");
+
+/// English monogram statistics, computed from a corpus of public domain texts.
+pub fn monograms() -> Distribution<u8> {
+    // Reading from a byte slice cannot fail.
+    Distribution::from_reader(CORPUS).unwrap()
+}
+
+/// English quadgram model trained on the same corpus as the monogram
+/// statistics.
+pub fn quadgrams() -> QuadgramModel {
+    QuadgramModel::train(CORPUS)
+}
+
+/// The 50 most common English bigrams, normalized to sum to 1.
+pub fn bigrams() -> Distribution<[u8; 2]> {
+    normalize(dist!(
+        *b"th" => 3.56, *b"he" => 3.07, *b"in" => 2.43, *b"er" => 2.05, *b"an" => 1.99,
+        *b"re" => 1.85, *b"on" => 1.76, *b"at" => 1.49, *b"en" => 1.45, *b"nd" => 1.35,
+        *b"ti" => 1.34, *b"es" => 1.34, *b"or" => 1.28, *b"te" => 1.20, *b"of" => 1.17,
+        *b"ed" => 1.17, *b"is" => 1.13, *b"it" => 1.12, *b"al" => 1.09, *b"ar" => 1.07,
+        *b"st" => 1.05, *b"to" => 1.04, *b"nt" => 1.04, *b"ng" => 0.95, *b"se" => 0.93,
+        *b"ha" => 0.93, *b"as" => 0.87, *b"ou" => 0.87, *b"io" => 0.83, *b"le" => 0.83,
+        *b"ve" => 0.83, *b"co" => 0.79, *b"me" => 0.79, *b"de" => 0.76, *b"hi" => 0.76,
+        *b"ri" => 0.73, *b"ro" => 0.73, *b"ic" => 0.70, *b"ne" => 0.69, *b"ea" => 0.69,
+        *b"ra" => 0.69, *b"ce" => 0.65, *b"li" => 0.62, *b"ch" => 0.60, *b"ll" => 0.58,
+        *b"be" => 0.58, *b"ma" => 0.57, *b"si" => 0.55, *b"om" => 0.55, *b"ur" => 0.54
+    ))
+}
+
+/// The 30 most common English trigrams, normalized to sum to 1.
+pub fn trigrams() -> Distribution<[u8; 3]> {
+    normalize(dist!(
+        *b"the" => 1.81, *b"and" => 0.73, *b"ing" => 0.72, *b"ent" => 0.42, *b"ion" => 0.42,
+        *b"her" => 0.36, *b"for" => 0.34, *b"tha" => 0.33, *b"nth" => 0.33, *b"int" => 0.32,
+        *b"ere" => 0.31, *b"tio" => 0.31, *b"ter" => 0.30, *b"est" => 0.28, *b"ers" => 0.28,
+        *b"ati" => 0.26, *b"hat" => 0.26, *b"ate" => 0.25, *b"all" => 0.25, *b"eth" => 0.24,
+        *b"hes" => 0.24, *b"ver" => 0.24, *b"his" => 0.24, *b"oft" => 0.22, *b"ith" => 0.21,
+        *b"fth" => 0.21, *b"sth" => 0.21, *b"oth" => 0.21, *b"res" => 0.21, *b"ont" => 0.20
+    ))
+}
+
+/// Returns the bigrams of the given text. Only bigrams consisting of two ASCII
+/// letters are returned, and these are converted to lowercase.
+pub fn bigrams_of(text: &[u8]) -> impl Iterator<Item=[u8; 2]> + '_ {
+    text.windows(2)
+        .filter(|window| window.iter().all(u8::is_ascii_alphabetic))
+        .map(|window| [window[0].to_ascii_lowercase(), window[1].to_ascii_lowercase()])
+}
+
+/// Returns the trigrams of the given text. Only trigrams consisting of three
+/// ASCII letters are returned, and these are converted to lowercase.
+pub fn trigrams_of(text: &[u8]) -> impl Iterator<Item=[u8; 3]> + '_ {
+    text.windows(3)
+        .filter(|window| window.iter().all(u8::is_ascii_alphabetic))
+        .map(|window| [
+            window[0].to_ascii_lowercase(),
+            window[1].to_ascii_lowercase(),
+            window[2].to_ascii_lowercase()
+        ])
+}
+
+fn normalize<T: Eq + Clone + std::hash::Hash>(distribution: Distribution<T>) -> Distribution<T> {
+    let total: f64 = distribution.support
+        .iter()
+        .map(|value| distribution.probability_of(value))
+        .sum();
+    Distribution::new(distribution.support
+        .iter()
+        .map(|value| (value.clone(), distribution.probability_of(value) / total))
+        .collect())
+}
+
+/// Scores candidate plaintexts by combining the divergence from the English
+/// monogram, bigram, and trigram distributions. Lower scores are better. By
+/// default, the total variation distance is used.
+///
+/// Monogram scoring alone is unreliable for short plaintexts, where a handful
+/// of characters decides the ranking. Bigrams and trigrams capture enough of
+/// the structure of English to break most of these ties.
+pub struct Scorer {
+    weights: (f64, f64, f64),
+    divergence: Divergence,
+    monograms: Distribution<u8>,
+    bigrams: Distribution<[u8; 2]>,
+    trigrams: Distribution<[u8; 3]>,
+}
+
+impl Scorer {
+    /// Creates a scorer using the default weights.
+    pub fn new() -> Self {
+        Scorer::with_weights(1.0, 0.5, 0.25)
+    }
+
+    /// Creates a scorer using the given monogram, bigram, and trigram weights.
+    pub fn with_weights(monogram_weight: f64, bigram_weight: f64, trigram_weight: f64) -> Self {
+        Scorer {
+            weights: (monogram_weight, bigram_weight, trigram_weight),
+            divergence: Divergence::TotalVariation,
+            monograms: monograms(),
+            bigrams: bigrams(),
+            trigrams: trigrams(),
+        }
+    }
+
+    /// Returns a scorer which uses the given divergence measure.
+    ///
+    /// # Note
+    ///
+    /// The Kullback-Leibler divergence and cross-entropy are infinite
+    /// whenever the plaintext contains a symbol which does not occur in the
+    /// model, so they are most useful together with smoothed models.
+    pub fn with_divergence(mut self, divergence: Divergence) -> Self {
+        self.divergence = divergence;
+        self
+    }
+
+    /// Returns the weighted sum of the monogram, bigram, and trigram divergences.
+    ///
+    /// # Note
+    ///
+    /// If the plaintext contains no bigrams (resp. trigrams) of letters, the
+    /// corresponding divergence is taken to be the maximum for the measure.
+    pub fn score(&self, plaintext: &[u8]) -> f64 {
+        let monogram_distance = plaintext
+            .iter()
+            .collect::<Distribution<u8>>()
+            .divergence_from(&self.monograms, self.divergence);
+        let bigram_distance = divergence_or_max(bigrams_of(plaintext), &self.bigrams, self.divergence);
+        let trigram_distance = divergence_or_max(trigrams_of(plaintext), &self.trigrams, self.divergence);
+
+        self.weights.0 * monogram_distance +
+        self.weights.1 * bigram_distance +
+        self.weights.2 * trigram_distance
+    }
+}
+
+impl Default for Scorer {
+    fn default() -> Self {
+        Scorer::new()
+    }
+}
+
+fn divergence_or_max<T, I>(observations: I, expected: &Distribution<T>, divergence: Divergence) -> f64
+where
+    T: Eq + Clone + std::hash::Hash,
+    I: Iterator<Item=T>
+{
+    let observed: Distribution<T> = observations.collect();
+    if observed.support.is_empty() {
+        divergence.maximum()
+    } else {
+        observed.divergence_from(expected, divergence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ngram_extraction() {
+        let text = b"The cat, 4 u";
+        assert_eq!(
+            bigrams_of(text).collect::<Vec<_>>(),
+            vec![*b"th", *b"he", *b"ca", *b"at"]
+        );
+        assert_eq!(trigrams_of(text).collect::<Vec<_>>(), vec![*b"the", *b"cat"]);
+    }
+
+    #[test]
+    fn combined_scorer() {
+        let scorer = Scorer::new();
+        // Both candidates have similar monogram statistics, but only one is English.
+        let english = scorer.score(b"the other one is there");
+        let garbled = scorer.score(b"teh otrhe oen si ethre");
+        assert!(english < garbled);
+    }
+}
@@ -0,0 +1,226 @@
+//! Quadgram language models with log-probability scoring.
+//!
+//! Quadgram statistics are the standard tool for ranking candidate solutions
+//! to classical ciphers. Text is scored by summing the log-probabilities of
+//! its quadgrams, after discarding everything except ASCII letters and
+//! converting to lowercase. Unseen quadgrams are assigned a floor probability
+//! smaller than that of any observed quadgram.
+//!
+//! Models may be stored using a compact binary format consisting of the
+//! sample size (u64), the number of distinct quadgrams (u32), and a table of
+//! (index, count) pairs (u32, u32) sorted by index. All integers are little
+//! endian, and the index of the quadgram `abcd` is `((a * 26 + b) * 26 + c) *
+//! 26 + d`.
+
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// The number of distinct quadgrams over the alphabet a-z.
+const SIZE: usize = 26 * 26 * 26 * 26;
+
+/// A quadgram model of a language.
+pub struct QuadgramModel {
+    sample_size: u64,
+    counts: Vec<u32>,
+    log_probabilities: Vec<f64>,
+    floor: f64,
+}
+
+impl QuadgramModel {
+    /// Trains a model on the given corpus.
+    pub fn train(corpus: &[u8]) -> Self {
+        let mut counts = vec![0; SIZE];
+        for index in quadgram_indices(corpus) {
+            counts[index] += 1;
+        }
+        QuadgramModel::from_counts(counts)
+    }
+
+    /// Trains a model on the corpus read from the given reader. The corpus is
+    /// processed in chunks, so arbitrarily large corpora may be used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from the underlying reader fails.
+    pub fn train_from_reader<R: Read>(reader: R) -> io::Result<Self> {
+        let mut counts = vec![0; SIZE];
+        // The index of the last three letters read, and the number of letters seen so far.
+        let (mut index, mut length) = (0, 0);
+        for byte in BufReader::new(reader).bytes() {
+            let byte = byte?;
+            if byte.is_ascii_alphabetic() {
+                index = (26 * index + (byte.to_ascii_lowercase() - b'a') as usize) % SIZE;
+                length += 1;
+                if length >= 4 {
+                    counts[index] += 1;
+                }
+            }
+        }
+        Ok(QuadgramModel::from_counts(counts))
+    }
+
+    /// Returns the number of quadgrams the model was trained on.
+    pub fn sample_size(&self) -> u64 {
+        self.sample_size
+    }
+
+    /// Returns the log (base 10) probability of the given quadgram, or the
+    /// floor value if the quadgram is unseen or not a quadgram of letters.
+    pub fn log_probability_of(&self, quadgram: &[u8; 4]) -> f64 {
+        quadgram_index(quadgram).map_or(self.floor, |index| self.log_probabilities[index])
+    }
+
+    /// Returns the sum of the log probabilities of the quadgrams of the text.
+    /// Higher scores are better.
+    pub fn log_likelihood(&self, text: &[u8]) -> f64 {
+        quadgram_indices(text).map(|index| self.log_probabilities[index]).sum()
+    }
+
+    /// Returns the average log probability per quadgram of the text, or the
+    /// floor value if the text contains fewer than four letters. Unlike the
+    /// log likelihood, this can be used to compare texts of different lengths.
+    pub fn average_log_likelihood(&self, text: &[u8]) -> f64 {
+        let (sum, count) = quadgram_indices(text)
+            .fold((0.0, 0), |(sum, count), index| (sum + self.log_probabilities[index], count + 1));
+        if count == 0 { self.floor } else { sum / (count as f64) }
+    }
+
+    /// Encodes the model using the binary format described in the module
+    /// documentation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let entries: Vec<(usize, u32)> = self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(index, &count)| (index, count))
+            .collect();
+        let mut result = Vec::with_capacity(12 + 8 * entries.len());
+        result.extend_from_slice(&self.sample_size.to_le_bytes());
+        result.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (index, count) in entries {
+            result.extend_from_slice(&(index as u32).to_le_bytes());
+            result.extend_from_slice(&count.to_le_bytes());
+        }
+        result
+    }
+
+    /// Decodes a model from the binary encoding produced by `to_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the buffer is not a valid
+    /// encoding of a model.
+    pub fn from_bytes(buffer: &[u8]) -> io::Result<Self> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid quadgram model");
+        if buffer.len() < 12 {
+            return Err(invalid());
+        }
+        let sample_size = u64::from_le_bytes(buffer[..8].try_into().unwrap());
+        let size = u32::from_le_bytes(buffer[8..12].try_into().unwrap()) as usize;
+        if buffer.len() != 12 + 8 * size {
+            return Err(invalid());
+        }
+        let mut counts = vec![0; SIZE];
+        let mut previous = None;
+        for entry in buffer[12..].chunks(8) {
+            let index = u32::from_le_bytes(entry[..4].try_into().unwrap()) as usize;
+            let count = u32::from_le_bytes(entry[4..].try_into().unwrap());
+            // Indices must be valid and strictly increasing.
+            if index >= SIZE || previous.is_some_and(|previous| index <= previous) {
+                return Err(invalid());
+            }
+            counts[index] = count;
+            previous = Some(index);
+        }
+        let result = QuadgramModel::from_counts(counts);
+        if result.sample_size != sample_size {
+            return Err(invalid());
+        }
+        Ok(result)
+    }
+
+    /// Saves the model to the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&self.to_bytes())?;
+        writer.flush()
+    }
+
+    /// Loads a model saved using `save` from the file at the given path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or does not contain a
+    /// valid model.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut buffer = Vec::new();
+        File::open(path)?.read_to_end(&mut buffer)?;
+        QuadgramModel::from_bytes(&buffer)
+    }
+
+    fn from_counts(counts: Vec<u32>) -> Self {
+        let sample_size: u64 = counts.iter().map(|&count| count as u64).sum();
+        // Use a total of 1 for empty models to keep the log probabilities finite.
+        let total = (sample_size.max(1)) as f64;
+        let floor = (0.01 / total).log10();
+        let log_probabilities = counts
+            .iter()
+            .map(|&count| if count > 0 { (count as f64 / total).log10() } else { floor })
+            .collect();
+        QuadgramModel { sample_size, counts, log_probabilities, floor }
+    }
+}
+
+/// Returns the index of the given quadgram if it consists of ASCII letters.
+fn quadgram_index(quadgram: &[u8]) -> Option<usize> {
+    quadgram.iter().try_fold(0, |index, byte| {
+        if byte.is_ascii_alphabetic() {
+            Some(26 * index + (byte.to_ascii_lowercase() - b'a') as usize)
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the indices of the quadgrams of the text, after discarding
+/// everything except ASCII letters.
+fn quadgram_indices(text: &[u8]) -> impl Iterator<Item=usize> {
+    let letters: Vec<u8> = text
+        .iter()
+        .filter(|byte| byte.is_ascii_alphabetic())
+        .map(|byte| byte.to_ascii_lowercase())
+        .collect();
+    (0..letters.len().saturating_sub(3))
+        .map(move |offset| quadgram_index(&letters[offset..offset + 4]).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quadgram_scoring() {
+        let model = QuadgramModel::train(b"The quick brown fox jumps over the lazy dog. The end.");
+        assert_eq!(model.sample_size(), 38);
+        assert!(model.log_probability_of(b"THEQ") > model.log_probability_of(b"qqqq"));
+        assert_eq!(model.log_probability_of(b"th e"), model.log_probability_of(b"qqqq"));
+        assert!(
+            model.average_log_likelihood(b"the lazy fox") >
+            model.average_log_likelihood(b"eht yzal xof")
+        );
+
+        let result = QuadgramModel::train_from_reader(&b"The quick brown fox jumps over the lazy dog. The end."[..]).unwrap();
+        assert_eq!(result.to_bytes(), model.to_bytes());
+
+        let result = QuadgramModel::from_bytes(&model.to_bytes()).unwrap();
+        assert_eq!(result.sample_size(), model.sample_size());
+        assert_eq!(result.log_likelihood(b"the lazy dog"), model.log_likelihood(b"the lazy dog"));
+        assert!(QuadgramModel::from_bytes(&model.to_bytes()[1..]).is_err());
+    }
+}
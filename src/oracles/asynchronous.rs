@@ -0,0 +1,40 @@
+//! Asynchronous oracles, allowing attacks to issue many queries concurrently.
+
+use std::future::Future;
+use std::sync::Mutex;
+
+use super::Oracle;
+
+/// The asynchronous version of `Oracle`. Queries take `&self` so that multiple queries may
+/// be in flight at the same time.
+pub trait AsyncOracle: Send + Sync {
+    type Output: Send;
+    type Error: Send;
+
+    fn query(&self, input: &[u8]) -> impl Future<Output=Result<Self::Output, Self::Error>> + Send;
+}
+
+/// Adapts a synchronous oracle to `AsyncOracle`. Queries are answered one at a time.
+pub struct Shared<O> {
+    oracle: Mutex<O>,
+}
+
+impl<O> Shared<O> {
+    pub fn new(oracle: O) -> Self {
+        Shared { oracle: Mutex::new(oracle) }
+    }
+
+    pub fn into_inner(self) -> O {
+        self.oracle.into_inner().unwrap()
+    }
+}
+
+impl<O> AsyncOracle for Shared<O> where O: Oracle + Send, O::Output: Send, O::Error: Send {
+    type Output = O::Output;
+    type Error = O::Error;
+
+    fn query(&self, input: &[u8]) -> impl Future<Output=Result<O::Output, O::Error>> + Send {
+        let result = self.oracle.lock().unwrap().query(input);
+        async move { result }
+    }
+}
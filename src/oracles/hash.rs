@@ -0,0 +1,86 @@
+pub mod length_extension {
+    use core::marker::PhantomData;
+    use rand::Rng;
+
+    use crate::oracles;
+    use crate::random_vec;
+    use crate::crypto::random::Random;
+    use crate::crypto::hash::{HashFunction, Mac, MessageDigest, NaiveMac, Sha1, Md4};
+
+    /// The maximum size of the random key.
+    pub const MAX_KEY_SIZE: usize = 64;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Error {
+        /// The tag does not authenticate the message.
+        MacError,
+        /// The buffer is too short to contain a tag.
+        LengthError,
+    }
+
+    /// Authenticates messages using `NaiveMac<H>` (that is, `H(key || message)`) under an
+    /// unknown key of unknown size.
+    pub struct Oracle<H: HashFunction> {
+        key: Vec<u8>,
+        hash: PhantomData<H>,
+    }
+
+    /// The oracle from challenge 29.
+    pub type Sha1Oracle = Oracle<Sha1>;
+
+    /// The oracle from challenge 30.
+    pub type Md4Oracle = Oracle<Md4>;
+
+    impl<H: HashFunction> Oracle<H> {
+        pub fn new(key: &[u8]) -> Self {
+            Oracle { key: key.to_vec(), hash: PhantomData }
+        }
+
+        /// Returns the message from challenges 29 and 30 together with its tag.
+        pub fn get_message(&self) -> (Vec<u8>, MessageDigest) {
+            let message = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon".to_vec();
+            let tag = self.get_tag_for(&message);
+            (message, tag)
+        }
+
+        pub fn get_tag_for(&self, message: &[u8]) -> MessageDigest {
+            NaiveMac::<H>::digest(&self.key, message)
+        }
+
+        /// Returns true if the tag authenticates the message.
+        pub fn verify_tag(&self, message: &[u8], tag: &[u8]) -> bool {
+            self.get_tag_for(message).as_ref() == tag
+        }
+
+        /// Returns true if the tag authenticates the message, and the message contains the
+        /// parameter `admin=true`.
+        pub fn is_admin_user(&self, message: &[u8], tag: &[u8]) -> bool {
+            self.verify_tag(message, tag) &&
+                message.split(|&x| x as char == ';').any(|param| param == b"admin=true")
+        }
+    }
+
+    impl<H: HashFunction> Random for Oracle<H> {
+        fn random() -> Self {
+            let key_size = rand::thread_rng().gen_range(1, MAX_KEY_SIZE + 1);
+            Oracle::new(&random_vec!(key_size))
+        }
+    }
+
+    /// Queries take a buffer on the form `message || tag`.
+    impl<H: HashFunction> oracles::Oracle for Oracle<H> {
+        type Output = ();
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<(), Error> {
+            if input.len() < H::DIGEST_SIZE {
+                return Err(Error::LengthError);
+            }
+            let (message, tag) = input.split_at(input.len() - H::DIGEST_SIZE);
+            if !self.verify_tag(message, tag) {
+                return Err(Error::MacError);
+            }
+            Ok(())
+        }
+    }
+}
@@ -1 +1,131 @@
 pub mod symmetric;
+pub mod remote;
+pub mod protocol;
+pub mod timing;
+pub mod hash;
+pub mod random;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+
+use crate::trace;
+use crate::crypto::symmetric::Error;
+
+/// A generic interface for the oracles used by the attacks. Each oracle exposes a single query
+/// taking an attacker-controlled buffer.
+pub trait Oracle {
+    type Output;
+    type Error;
+
+    fn query(&mut self, input: &[u8]) -> Result<Self::Output, Self::Error>;
+}
+
+/// Returned (converted to the error type of the wrapped oracle) by `Counted` when the query
+/// budget is exhausted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetExceeded;
+
+impl From<BudgetExceeded> for Error {
+    fn from(_: BudgetExceeded) -> Self {
+        Error::BudgetExceeded
+    }
+}
+
+/// Wraps an oracle and counts the number of queries made. This allows tests to verify the
+/// query complexity of an attack. If a budget is given, queries fail once the budget is
+/// exhausted.
+pub struct Counted<O> {
+    oracle: O,
+    queries: usize,
+    budget: Option<usize>,
+}
+
+impl<O> Counted<O> {
+    pub fn new(oracle: O) -> Self {
+        Counted { oracle, queries: 0, budget: None }
+    }
+
+    /// Returns a wrapper which allows at most `budget` queries.
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Returns the number of queries made so far, including rejected queries.
+    pub fn queries(&self) -> usize { self.queries }
+
+    /// Returns a reference to the wrapped oracle.
+    pub fn inner(&self) -> &O { &self.oracle }
+
+    /// Returns a mutable reference to the wrapped oracle. Queries made through this reference
+    /// are not counted.
+    pub fn inner_mut(&mut self) -> &mut O { &mut self.oracle }
+
+    pub fn into_inner(self) -> O { self.oracle }
+}
+
+impl<O> Oracle for Counted<O> where O: Oracle, O::Error: From<BudgetExceeded> {
+    type Output = O::Output;
+    type Error = O::Error;
+
+    fn query(&mut self, input: &[u8]) -> Result<Self::Output, Self::Error> {
+        self.queries += 1;
+        if self.budget.is_some_and(|budget| self.queries > budget) {
+            return Err(BudgetExceeded.into());
+        }
+        self.oracle.query(input)
+    }
+}
+
+/// Wraps an oracle and emits a `trace::Event::Query` for each query.
+pub struct Traced<O> {
+    oracle: O,
+    name: &'static str,
+}
+
+impl<O> Traced<O> {
+    /// Returns a wrapper which identifies the oracle by the given name in emitted events.
+    pub fn new(oracle: O, name: &'static str) -> Self {
+        Traced { oracle, name }
+    }
+
+    pub fn inner(&self) -> &O { &self.oracle }
+
+    pub fn inner_mut(&mut self) -> &mut O { &mut self.oracle }
+
+    pub fn into_inner(self) -> O { self.oracle }
+}
+
+impl<O: Oracle> Oracle for Traced<O> {
+    type Output = O::Output;
+    type Error = O::Error;
+
+    fn query(&mut self, input: &[u8]) -> Result<Self::Output, Self::Error> {
+        trace::emit(|| trace::Event::Query { oracle: self.name, size: input.len() });
+        self.oracle.query(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Identity;
+
+    impl Oracle for Identity {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            Ok(input.to_vec())
+        }
+    }
+
+    #[test]
+    fn query_budget() {
+        let mut oracle = Counted::new(Identity).with_budget(2);
+        assert_eq!(oracle.query(b"foo"), Ok(b"foo".to_vec()));
+        assert_eq!(oracle.query(b"bar"), Ok(b"bar".to_vec()));
+        assert_eq!(oracle.query(b"baz"), Err(Error::BudgetExceeded));
+        assert_eq!(oracle.queries(), 3);
+    }
+}
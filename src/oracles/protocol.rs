@@ -0,0 +1,393 @@
+//! A small framework for protocol simulations. Each `Party` reacts to incoming messages, and
+//! a `Session` delivers messages between two parties through a `Channel`. Since every message
+//! passes through the channel, man-in-the-middle attacks can be expressed as channels which
+//! intercept and rewrite messages in transit.
+
+use std::fmt;
+use std::error;
+
+/// A party in a two-party protocol.
+pub trait Party {
+    type Message;
+    type Error;
+
+    /// Returns the first message of the protocol, if sent by this party.
+    fn start(&mut self) -> Result<Option<Self::Message>, Self::Error> { Ok(None) }
+
+    /// Handles an incoming message and returns the reply, if any.
+    fn receive(&mut self, message: Self::Message) -> Result<Option<Self::Message>, Self::Error>;
+}
+
+/// The direction of a message in transit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// From the initiator to the responder.
+    Forward,
+    /// From the responder to the initiator.
+    Backward,
+}
+
+impl Direction {
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::Forward => Direction::Backward,
+            Direction::Backward => Direction::Forward,
+        }
+    }
+}
+
+/// A channel transporting messages between the parties of a session.
+pub trait Channel<M> {
+    /// Returns the message delivered to the recipient, or `None` if the message is dropped.
+    fn transmit(&mut self, direction: Direction, message: M) -> Option<M>;
+}
+
+/// A channel which delivers every message unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Direct;
+
+impl<M> Channel<M> for Direct {
+    fn transmit(&mut self, _: Direction, message: M) -> Option<M> {
+        Some(message)
+    }
+}
+
+impl<M, F> Channel<M> for F where F: FnMut(Direction, M) -> Option<M> {
+    fn transmit(&mut self, direction: Direction, message: M) -> Option<M> {
+        self(direction, message)
+    }
+}
+
+/// Wraps a channel and records every message delivered by it.
+pub struct Recorded<M, C> {
+    channel: C,
+    transcript: Vec<(Direction, M)>,
+}
+
+impl<M, C> Recorded<M, C> {
+    pub fn new(channel: C) -> Self {
+        Recorded { channel, transcript: Vec::new() }
+    }
+
+    /// Returns the delivered messages in order.
+    pub fn transcript(&self) -> &[(Direction, M)] { &self.transcript }
+
+    pub fn into_inner(self) -> C { self.channel }
+}
+
+impl<M: Clone, C: Channel<M>> Channel<M> for Recorded<M, C> {
+    fn transmit(&mut self, direction: Direction, message: M) -> Option<M> {
+        let message = self.channel.transmit(direction, message)?;
+        self.transcript.push((direction, message.clone()));
+        Some(message)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error<E> {
+    /// Returned when one of the parties fails.
+    PartyError(E),
+    /// Returned when the session exceeds the maximum number of messages.
+    MessageLimit,
+}
+
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:?}", self)
+    }
+}
+
+impl<E: fmt::Debug> error::Error for Error<E> {}
+
+/// Runs a protocol between two parties.
+pub struct Session<C> {
+    channel: C,
+    max_messages: usize,
+}
+
+impl<C> Session<C> {
+    const MAX_MESSAGES: usize = 1024;
+
+    pub fn new(channel: C) -> Self {
+        Session { channel, max_messages: Self::MAX_MESSAGES }
+    }
+
+    /// Returns a session which fails after delivering the given number of messages.
+    pub fn with_max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = max_messages;
+        self
+    }
+
+    pub fn channel(&self) -> &C { &self.channel }
+
+    pub fn channel_mut(&mut self) -> &mut C { &mut self.channel }
+
+    pub fn into_channel(self) -> C { self.channel }
+
+    /// Runs the protocol until a party does not reply or a message is dropped. The initiator
+    /// sends the first message. Returns the number of delivered messages.
+    pub fn run<A, B>(&mut self, initiator: &mut A, responder: &mut B) -> Result<usize, Error<A::Error>>
+        where
+            A: Party,
+            B: Party<Message = A::Message, Error = A::Error>,
+            C: Channel<A::Message>,
+    {
+        let mut direction = Direction::Forward;
+        let mut message = initiator
+            .start()
+            .map_err(Error::PartyError)?;
+        let mut nof_messages = 0;
+        while let Some(sent_message) = message {
+            let received_message = match self.channel.transmit(direction, sent_message) {
+                Some(received_message) => received_message,
+                None => break,
+            };
+            nof_messages += 1;
+            if nof_messages > self.max_messages {
+                return Err(Error::MessageLimit);
+            }
+            message = match direction {
+                Direction::Forward => responder.receive(received_message),
+                Direction::Backward => initiator.receive(received_message),
+            }.map_err(Error::PartyError)?;
+            direction = direction.reverse();
+        }
+        Ok(nof_messages)
+    }
+}
+
+/// The Diffie-Hellman echo protocol from set 5, problem 34. A sends the group parameters
+/// together with their public key, and B replies with their own public key. A then sends a message
+/// encrypted using AES-CBC with a key derived from the shared secret, and B echoes it back.
+///
+/// # Note
+///
+/// To avoid arbitrary precision arithmetic, we use a toy group modulo a 61-bit prime.
+pub mod diffie_hellman {
+    use std::fmt;
+    use std::error;
+    use rand;
+    use rand::Rng;
+
+    use crate::random_vec;
+    use crate::crypto::symmetric;
+    use crate::crypto::hash::HashFunction;
+    use crate::crypto::hash::sha::Sha1;
+    use crate::crypto::symmetric::{Cipher, Aes128, Aes128Cbc, BlockCipherMode};
+
+    #[derive(Debug)]
+    pub enum Error {
+        CipherError,
+        UnexpectedMessage,
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{:?}", self)
+        }
+    }
+
+    impl error::Error for Error {}
+
+    impl From<symmetric::Error> for Error {
+        fn from(_: symmetric::Error) -> Self {
+            Error::CipherError
+        }
+    }
+
+    /// A multiplicative group modulo a prime `p`, generated by `g`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Group {
+        pub p: u64,
+        pub g: u64,
+    }
+
+    impl Group {
+        pub fn pow(&self, base: u64, exponent: u64) -> u64 {
+            let modulus = self.p as u128;
+            let (mut base, mut exponent, mut result) = (base as u128 % modulus, exponent, 1);
+            while exponent > 0 {
+                if exponent & 1 == 1 {
+                    result = result * base % modulus;
+                }
+                base = base * base % modulus;
+                exponent >>= 1;
+            }
+            result as u64
+        }
+
+        fn private_key(&self) -> u64 {
+            rand::thread_rng().gen_range(2, self.p - 1)
+        }
+    }
+
+    impl Default for Group {
+        fn default() -> Self {
+            Group { p: (1 << 61) - 1, g: 3 }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Message {
+        /// Sent by A to initiate the protocol.
+        Parameters { group: Group, public_key: u64 },
+        /// Sent by B in response to the parameters.
+        PublicKey(u64),
+        /// An encrypted message, with the IV appended to the ciphertext.
+        Ciphertext(Vec<u8>),
+    }
+
+    /// Derives the AES key from the shared secret by truncating its SHA-1 digest.
+    pub fn derive_key(shared_secret: u64) -> Vec<u8> {
+        Sha1::digest(shared_secret.to_be_bytes()).as_ref()[..Aes128::KEY_SIZE].to_vec()
+    }
+
+    /// Encrypts the message using a random IV, which is appended to the ciphertext.
+    pub fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let iv = random_vec!(Aes128::BLOCK_SIZE);
+        let mut ciphertext = Aes128Cbc::new(key, &iv)?.encrypt_buffer(plaintext)?;
+        ciphertext.extend(iv);
+        Ok(ciphertext)
+    }
+
+    pub fn decrypt(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        if ciphertext.len() < Aes128::BLOCK_SIZE {
+            return Err(Error::CipherError);
+        }
+        let (ciphertext, iv) = ciphertext.split_at(ciphertext.len() - Aes128::BLOCK_SIZE);
+        Ok(Aes128Cbc::new(key, iv)?.decrypt_buffer(ciphertext)?)
+    }
+
+    /// The initiator, which sends a single message and expects it to be echoed back.
+    pub struct Alice {
+        group: Group,
+        private_key: u64,
+        key: Option<Vec<u8>>,
+        plaintext: Vec<u8>,
+        echo: Option<Vec<u8>>,
+    }
+
+    impl Alice {
+        pub fn new(group: Group, plaintext: &[u8]) -> Self {
+            Alice {
+                group,
+                private_key: group.private_key(),
+                key: None,
+                plaintext: plaintext.to_vec(),
+                echo: None
+            }
+        }
+
+        /// Returns the decrypted message echoed back by B.
+        pub fn echo(&self) -> Option<&[u8]> { self.echo.as_deref() }
+    }
+
+    impl super::Party for Alice {
+        type Message = Message;
+        type Error = Error;
+
+        fn start(&mut self) -> Result<Option<Message>, Error> {
+            let public_key = self.group.pow(self.group.g, self.private_key);
+            Ok(Some(Message::Parameters { group: self.group, public_key }))
+        }
+
+        fn receive(&mut self, message: Message) -> Result<Option<Message>, Error> {
+            match (message, &self.key) {
+                (Message::PublicKey(public_key), None) => {
+                    let key = derive_key(self.group.pow(public_key, self.private_key));
+                    let ciphertext = encrypt(&key, &self.plaintext)?;
+                    self.key = Some(key);
+                    Ok(Some(Message::Ciphertext(ciphertext)))
+                },
+                (Message::Ciphertext(ciphertext), Some(key)) => {
+                    self.echo = Some(decrypt(key, &ciphertext)?);
+                    Ok(None)
+                },
+                _ => Err(Error::UnexpectedMessage)
+            }
+        }
+    }
+
+    /// The responder, which echoes back every message it receives.
+    #[derive(Default)]
+    pub struct Bob {
+        key: Option<Vec<u8>>,
+        plaintexts: Vec<Vec<u8>>,
+    }
+
+    impl Bob {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Returns the decrypted messages received from A.
+        pub fn plaintexts(&self) -> &[Vec<u8>] { &self.plaintexts }
+    }
+
+    impl super::Party for Bob {
+        type Message = Message;
+        type Error = Error;
+
+        fn receive(&mut self, message: Message) -> Result<Option<Message>, Error> {
+            match (message, &self.key) {
+                (Message::Parameters { group, public_key }, None) => {
+                    let private_key = group.private_key();
+                    self.key = Some(derive_key(group.pow(public_key, private_key)));
+                    Ok(Some(Message::PublicKey(group.pow(group.g, private_key))))
+                },
+                (Message::Ciphertext(ciphertext), Some(key)) => {
+                    let plaintext = decrypt(key, &ciphertext)?;
+                    let ciphertext = encrypt(key, &plaintext)?;
+                    self.plaintexts.push(plaintext);
+                    Ok(Some(Message::Ciphertext(ciphertext)))
+                },
+                _ => Err(Error::UnexpectedMessage)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::diffie_hellman::{Alice, Bob, Group, Message};
+
+    #[test]
+    fn echo_protocol() {
+        let mut alice = Alice::new(Group::default(), b"YELLOW SUBMARINE");
+        let mut bob = Bob::new();
+        let mut session = Session::new(Recorded::new(Direct));
+        assert_eq!(session.run(&mut alice, &mut bob).unwrap(), 4);
+        assert_eq!(alice.echo(), Some(&b"YELLOW SUBMARINE"[..]));
+        assert_eq!(bob.plaintexts(), [b"YELLOW SUBMARINE".to_vec()]);
+
+        let directions: Vec<Direction> = session
+            .channel()
+            .transcript()
+            .iter()
+            .map(|(direction, _)| *direction)
+            .collect();
+        assert_eq!(directions, [
+            Direction::Forward,
+            Direction::Backward,
+            Direction::Forward,
+            Direction::Backward
+        ]);
+    }
+
+    #[test]
+    fn dropped_messages() {
+        let mut alice = Alice::new(Group::default(), b"YELLOW SUBMARINE");
+        let mut bob = Bob::new();
+        let mut session = Session::new(|_, message| match message {
+            Message::Ciphertext(_) => None,
+            message => Some(message),
+        });
+        assert_eq!(session.run(&mut alice, &mut bob).unwrap(), 2);
+        assert_eq!(alice.echo(), None);
+
+        let mut alice = Alice::new(Group::default(), b"YELLOW SUBMARINE");
+        let mut session = Session::new(Direct).with_max_messages(3);
+        assert!(matches!(session.run(&mut alice, &mut Bob::new()), Err(Error::MessageLimit)));
+    }
+}
@@ -0,0 +1,97 @@
+pub mod token {
+    #[cfg(feature = "system-time")]
+    use std::time::{SystemTime, SystemTimeError};
+    use std::convert::TryInto;
+    use rand;
+    use rand::Rng;
+
+    use crate::oracles;
+    use crate::crypto::random::{Random, RandomGenerator, SeedableGenerator, Mt19337, SecureRandom};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Error {
+        /// The query is not a big-endian `u64`.
+        LengthError,
+        /// The query budget of an oracle wrapped in `oracles::Counted` is exhausted.
+        BudgetExceeded,
+    }
+
+    impl From<oracles::BudgetExceeded> for Error {
+        fn from(_: oracles::BudgetExceeded) -> Self {
+            Error::BudgetExceeded
+        }
+    }
+
+    /// The generator used to produce tokens.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Source {
+        /// A `Mt19337` instance seeded with the current Unix time.
+        MersenneTwister,
+        /// The operating system generator.
+        SecureRandom,
+    }
+
+    /// Produces password reset tokens, as in the second half of set 3, problem 24.
+    pub struct Oracle {
+        source: Source,
+        token_size: usize,
+    }
+
+    impl Oracle {
+        const TOKEN_SIZE: usize = 16;
+
+        pub fn new(source: Source) -> Self {
+            Oracle { source, token_size: Self::TOKEN_SIZE }
+        }
+
+        pub fn with_token_size(mut self, token_size: usize) -> Self {
+            self.token_size = token_size;
+            self
+        }
+
+        pub fn source(&self) -> Source { self.source }
+
+        /// Returns a token generated at the given Unix time. This allows the attacker to
+        /// request tokens at chosen times.
+        pub fn get_token_at(&mut self, unix_time: u64) -> Vec<u8> {
+            match self.source {
+                Source::MersenneTwister => Self::get_token_from(Mt19337::new(unix_time as u32), self.token_size),
+                Source::SecureRandom => Self::get_token_from(SecureRandom, self.token_size),
+            }
+        }
+
+        /// Returns a token generated at the current time.
+        #[cfg(feature = "system-time")]
+        pub fn get_token(&mut self) -> Result<Vec<u8>, SystemTimeError> {
+            let unix_time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+            Ok(self.get_token_at(unix_time))
+        }
+
+        fn get_token_from<G: RandomGenerator>(mut random: G, token_size: usize) -> Vec<u8> {
+            (0..token_size).map(|_| random.next_u8()).collect()
+        }
+    }
+
+    /// Returns an oracle using a randomly chosen source.
+    impl Random for Oracle {
+        fn random() -> Self {
+            if rand::thread_rng().gen_bool(0.5) {
+                Self::new(Source::MersenneTwister)
+            } else {
+                Self::new(Source::SecureRandom)
+            }
+        }
+    }
+
+    /// Queries take the Unix time as a big-endian `u64`, and return the token generated at
+    /// that time.
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            let unix_time = input.try_into().map_err(|_| Error::LengthError)?;
+            Ok(self.get_token_at(u64::from_be_bytes(unix_time)))
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! Serves oracles over TCP, allowing attacks to be run against an out-of-process target.
+//!
+//! The protocol is a simple sequence of length-prefixed frames. Each request consists of
+//! the query input, and each response consists of a status byte (0 for success, 1 for an
+//! error) followed by the encoded output, or the formatted error. Lengths are encoded as 32
+//! bit little-endian integers, and frames larger than `MAX_FRAME_SIZE` are rejected.
+
+use std::fmt;
+use std::error;
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+use super::Oracle;
+
+const STATUS_OK: u8 = 0;
+const STATUS_ERROR: u8 = 1;
+
+/// The maximum size of a frame. This bounds the memory allocated for each frame read.
+pub const MAX_FRAME_SIZE: usize = 1 << 24;
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(io::Error),
+    RemoteError(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{:?}", self)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::IoError(error) => Some(error),
+            Error::RemoteError(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::IoError(error)
+    }
+}
+
+impl From<Error> for crate::crypto::symmetric::Error {
+    fn from(_: Error) -> Self {
+        crate::crypto::symmetric::Error::CipherError
+    }
+}
+
+/// Oracle outputs which can be sent over the wire.
+pub trait Message: Sized {
+    fn to_bytes(&self) -> Vec<u8>;
+
+    fn from_bytes(bytes: &[u8]) -> io::Result<Self>;
+}
+
+impl Message for Vec<u8> {
+    fn to_bytes(&self) -> Vec<u8> { self.clone() }
+
+    fn from_bytes(bytes: &[u8]) -> io::Result<Self> { Ok(bytes.to_vec()) }
+}
+
+impl Message for bool {
+    fn to_bytes(&self) -> Vec<u8> { vec![*self as u8] }
+
+    fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        match bytes {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData, "invalid boolean")),
+        }
+    }
+}
+
+/// Returns the size of the frame with the given size prefix, or an error if the frame is too large.
+fn frame_size(size: [u8; 4]) -> io::Result<usize> {
+    let size = u32::from_le_bytes(size) as usize;
+    if size > MAX_FRAME_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "frame too large"));
+    }
+    Ok(size)
+}
+
+fn write_frame<W: Write>(writer: &mut W, frame: &[u8]) -> io::Result<()> {
+    let size = u32::try_from(frame.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))?;
+    writer.write_all(&[&size.to_le_bytes()[..], frame].concat())
+}
+
+/// Reads a frame, returning `None` if the stream is closed before the first byte.
+fn read_frame<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut size = [0; 4];
+    match reader.read_exact(&mut size) {
+        Ok(()) => {},
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error),
+    }
+    let mut frame = vec![0; frame_size(size)?];
+    reader.read_exact(&mut frame)?;
+    Ok(Some(frame))
+}
+
+/// Answers queries from a single client until the connection is closed.
+pub fn serve_connection<O>(mut stream: TcpStream, oracle: &mut O) -> io::Result<()>
+    where O: Oracle, O::Output: Message, O::Error: fmt::Debug
+{
+    stream.set_nodelay(true)?;
+    while let Some(input) = read_frame(&mut stream)? {
+        // The response is buffered to avoid sending the status byte in a separate packet.
+        let (status, frame) = match oracle.query(&input) {
+            Ok(output) => (STATUS_OK, output.to_bytes()),
+            Err(error) => (STATUS_ERROR, format!("{:?}", error).into_bytes()),
+        };
+        let mut response = vec![status];
+        write_frame(&mut response, &frame)?;
+        stream.write_all(&response)?;
+    }
+    Ok(())
+}
+
+/// Answers queries from each incoming connection in turn. Errors on a single connection
+/// (e.g. a client disconnecting mid-frame, or sending an oversized frame) close that
+/// connection only. This only returns if accepting a connection fails.
+pub fn serve<O>(listener: TcpListener, mut oracle: O) -> io::Result<()>
+    where O: Oracle, O::Output: Message, O::Error: fmt::Debug
+{
+    for stream in listener.incoming() {
+        // The connection is dropped on error, and there is no one to report the error to.
+        let _ = serve_connection(stream?, &mut oracle);
+    }
+    Ok(())
+}
+
+/// A client for a remote oracle. The error type `E` is the error type expected by the
+/// attack, which allows attacks to be run unmodified against a remote oracle.
+pub struct Client<Output, E = Error> {
+    stream: TcpStream,
+    phantom: PhantomData<(Output, E)>,
+}
+
+impl<Output, E> Client<Output, E> {
+    pub fn connect<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(Client { stream, phantom: PhantomData })
+    }
+
+    fn request(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+        write_frame(&mut self.stream, input)?;
+        let mut status = [0; 1];
+        self.stream.read_exact(&mut status)?;
+        let frame = read_frame(&mut self.stream)?
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        match status[0] {
+            STATUS_OK => Ok(frame),
+            _ => Err(Error::RemoteError(String::from_utf8_lossy(&frame).into_owned())),
+        }
+    }
+}
+
+impl<Output: Message, E: From<Error>> Oracle for Client<Output, E> {
+    type Output = Output;
+    type Error = E;
+
+    fn query(&mut self, input: &[u8]) -> Result<Output, E> {
+        let frame = self.request(input)?;
+        Ok(Output::from_bytes(&frame).map_err(Error::from)?)
+    }
+}
+
+/// Asynchronous versions of the server and client. The client keeps a pool of connections
+/// to allow multiple queries to be in flight at the same time, and the server answers queries
+/// from all connections concurrently.
+#[cfg(feature = "async")]
+pub mod asynchronous {
+    use std::fmt;
+    use std::io;
+    use std::marker::PhantomData;
+    use std::net::SocketAddr;
+    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+    use crate::oracles::Oracle;
+    use crate::oracles::asynchronous::AsyncOracle;
+    use super::{Error, Message, STATUS_OK, STATUS_ERROR};
+
+    /// Reads a frame, returning `None` if the stream is closed before the first byte.
+    async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+        let mut size = [0; 4];
+        match reader.read_exact(&mut size).await {
+            Ok(_) => {},
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(error) => return Err(error),
+        }
+        let mut frame = vec![0; super::frame_size(size)?];
+        reader.read_exact(&mut frame).await?;
+        Ok(Some(frame))
+    }
+
+    async fn serve_connection<O>(mut stream: TcpStream, oracle: Arc<Mutex<O>>) -> io::Result<()>
+        where O: Oracle, O::Output: Message, O::Error: fmt::Debug
+    {
+        stream.set_nodelay(true)?;
+        while let Some(input) = read_frame(&mut stream).await? {
+            // A panic in the oracle only closes the current connection, so the oracle is still
+            // usable from other connections if the lock is poisoned.
+            let (status, frame) = {
+                let mut oracle = oracle.lock().unwrap_or_else(|error| error.into_inner());
+                match oracle.query(&input) {
+                    Ok(output) => (STATUS_OK, output.to_bytes()),
+                    Err(error) => (STATUS_ERROR, format!("{:?}", error).into_bytes()),
+                }
+            };
+            let mut response = vec![status];
+            super::write_frame(&mut response, &frame)?;
+            stream.write_all(&response).await?;
+        }
+        Ok(())
+    }
+
+    /// Answers queries from all incoming connections concurrently. Errors on a single
+    /// connection close that connection only. This only returns if accepting a connection
+    /// fails.
+    pub async fn serve<O>(listener: TcpListener, oracle: O) -> io::Result<()>
+        where O: Oracle + Send + 'static, O::Output: Message, O::Error: fmt::Debug
+    {
+        let oracle = Arc::new(Mutex::new(oracle));
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let oracle = oracle.clone();
+            tokio::spawn(async move {
+                // The connection is dropped on error, and there is no one to report the error to.
+                let _ = serve_connection(stream, oracle).await;
+            });
+        }
+    }
+
+    /// An asynchronous client for a remote oracle, using a pool of connections. Connections
+    /// are reopened if a request fails or is cancelled.
+    pub struct Client<Output, E = Error> {
+        address: SocketAddr,
+        connections: Vec<tokio::sync::Mutex<Option<TcpStream>>>,
+        next: AtomicUsize,
+        phantom: PhantomData<fn() -> (Output, E)>,
+    }
+
+    async fn open_stream(address: SocketAddr) -> io::Result<TcpStream> {
+        let stream = TcpStream::connect(address).await?;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+
+    impl<Output, E> Client<Output, E> {
+        /// Opens the given number of connections to the remote oracle.
+        pub async fn connect<A: ToSocketAddrs>(address: A, connections: usize) -> io::Result<Self> {
+            let stream = TcpStream::connect(address).await?;
+            stream.set_nodelay(true)?;
+            let address = stream.peer_addr()?;
+            let mut pool = vec![tokio::sync::Mutex::new(Some(stream))];
+            for _ in 1..connections.max(1) {
+                pool.push(tokio::sync::Mutex::new(Some(open_stream(address).await?)));
+            }
+            Ok(Client { address, connections: pool, next: AtomicUsize::new(0), phantom: PhantomData })
+        }
+
+        async fn request(&self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            let index = self.next.fetch_add(1, Ordering::Relaxed) % self.connections.len();
+            let mut connection = self.connections[index].lock().await;
+            // The stream is only returned to the pool once the full response has been read. If
+            // the request fails or the future is dropped, the stream (which may contain part of
+            // a response) is dropped, and the next request on this connection reconnects.
+            let mut stream = match connection.take() {
+                Some(stream) => stream,
+                None => open_stream(self.address).await?,
+            };
+            
+            let mut request = Vec::with_capacity(4 + input.len());
+            super::write_frame(&mut request, input)?;
+            stream.write_all(&request).await?;
+            let status = stream.read_u8().await?;
+            let frame = read_frame(&mut stream).await?
+                .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+            *connection = Some(stream);
+            match status {
+                STATUS_OK => Ok(frame),
+                _ => Err(Error::RemoteError(String::from_utf8_lossy(&frame).into_owned())),
+            }
+        }
+    }
+
+    impl<Output, E> AsyncOracle for Client<Output, E> 
+        where Output: Message + Send, E: From<Error> + Send
+    {
+        type Output = Output;
+        type Error = E;
+
+        async fn query(&self, input: &[u8]) -> Result<Output, E> {
+            let frame = self.request(input).await?;
+            Ok(Output::from_bytes(&frame).map_err(Error::from)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use super::*;
+
+    struct Reverse;
+
+    impl Oracle for Reverse {
+        type Output = Vec<u8>;
+        type Error = String;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, String> {
+            if input.is_empty() {
+                return Err("empty input".to_string());
+            }
+            Ok(input.iter().rev().cloned().collect())
+        }
+    }
+
+    #[test]
+    fn remote_queries() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve_connection(stream, &mut Reverse)
+        });
+
+        let mut client: Client<Vec<u8>> = Client::connect(address).unwrap();
+        assert_eq!(client.query(b"abc").unwrap(), b"cba");
+        assert!(matches!(client.query(b""), Err(Error::RemoteError(_))));
+        assert_eq!(client.query(b"de").unwrap(), b"ed");
+        
+        drop(client);
+        assert!(server.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn oversized_frame() {
+        let size = (MAX_FRAME_SIZE as u32 + 1).to_le_bytes();
+        let result = read_frame(&mut &size[..]);
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn serve_after_connection_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Reverse));
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        drop(stream);
+
+        let mut client: Client<Vec<u8>> = Client::connect(address).unwrap();
+        assert_eq!(client.query(b"abc").unwrap(), b"cba");
+    }
+
+    /// Panics on empty input.
+    #[cfg(feature = "async")]
+    struct Panicking;
+
+    #[cfg(feature = "async")]
+    impl Oracle for Panicking {
+        type Output = Vec<u8>;
+        type Error = String;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, String> {
+            assert!(!input.is_empty(), "empty input");
+            Reverse.query(input)
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_server_after_panic() {
+        use crate::oracles::asynchronous::AsyncOracle;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            tokio::spawn(asynchronous::serve(listener, Panicking));
+
+            // The panic closes the connection, but does not affect later connections.
+            let client: asynchronous::Client<Vec<u8>> = asynchronous::Client::connect(address, 1).await.unwrap();
+            assert!(client.query(b"").await.is_err());
+            assert_eq!(client.query(b"abc").await.unwrap(), b"cba");
+        });
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_client_after_cancellation() {
+        use std::time::Duration;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use crate::oracles::asynchronous::AsyncOracle;
+
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            // Reverses the input, and answers `slow` after a delay.
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            tokio::spawn(async move {
+                loop {
+                    let (mut stream, _) = listener.accept().await.unwrap();
+                    tokio::spawn(async move {
+                        let mut size = [0; 4];
+                        while stream.read_exact(&mut size).await.is_ok() {
+                            let mut input = vec![0; u32::from_le_bytes(size) as usize];
+                            stream.read_exact(&mut input).await.unwrap();
+                            if input == b"slow" {
+                                tokio::time::sleep(Duration::from_millis(200)).await;
+                            }
+                            let mut response = vec![STATUS_OK];
+                            write_frame(&mut response, &input.iter().rev().cloned().collect::<Vec<u8>>()).unwrap();
+                            stream.write_all(&response).await.unwrap();
+                        }
+                    });
+                }
+            });
+
+            // The response to the cancelled query is never read by later queries.
+            let client: asynchronous::Client<Vec<u8>> = asynchronous::Client::connect(address, 1).await.unwrap();
+            assert!(tokio::time::timeout(Duration::from_millis(50), client.query(b"slow")).await.is_err());
+            assert_eq!(client.query(b"abc").await.unwrap(), b"cba");
+        });
+    }
+}
@@ -1,4 +1,15 @@
+pub mod ecb_detection {
+    use crate::data;
+
+    /// Returns the hex-decoded ciphertexts from set 1, problem 8. Exactly one of these is
+    /// encrypted in ECB-mode.
+    pub fn get_ciphertexts() -> Result<Vec<Vec<u8>>, data::Error> {
+        data::Loader::bundled().read_hex_lines(1, 8)
+    }
+}
+
 pub mod ecb_cbc_detection {
+    use crate::oracles;
     use rand;
     use rand::Rng;
 
@@ -15,10 +26,13 @@ pub mod ecb_cbc_detection {
         Error,
     };
 
-    #[derive(Debug, Clone, Copy, PartialEq)]
+    /// The cipher modes used by the oracles in this module and in `mode_detection`. The oracle
+    /// in this module only uses ECB and CBC-mode.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub enum Mode {
         Ecb,
-        Cbc
+        Cbc,
+        Ctr
     }
 
     pub struct Oracle { 
@@ -64,7 +78,7 @@ pub mod ecb_cbc_detection {
 
         pub fn encrypt_buffer(&mut self, buffer: &[u8]) -> Result<Vec<u8>, Error> {
             // Encrypts the padded buffer inplace to avoid allocating a second vector for the result.
-            let mut output_buffer = Self::pad_buffer(&buffer);
+            let mut output_buffer = Self::pad_buffer(buffer);
             let output_size = output_buffer.len();
             let padding_size = Pkcs7::min_padding_size(Aes128::BLOCK_SIZE, output_size);
             output_buffer.resize(output_size + padding_size, 0);
@@ -89,9 +103,96 @@ pub mod ecb_cbc_detection {
             Self::new()
         }
     }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            self.encrypt_buffer(input)
+        }
+    }
+}
+
+pub mod mode_detection {
+    use crate::oracles;
+    use rand;
+    use rand::Rng;
+
+    use crate::crypto::random::Random;
+
+    use crate::crypto::symmetric::{
+        BlockCipherMode,
+        StreamCipherMode,
+        Aes128Ecb,
+        Aes128Cbc,
+        Aes128Ctr,
+        Error,
+    };
+
+    pub use super::ecb_cbc_detection::Mode;
+
+    /// Encrypts the given buffer surrounded by random data, using either ECB, CBC, or CTR-mode
+    /// chosen uniformly at random.
+    pub struct Oracle { 
+        cipher_mode: Option<Mode>
+    }
+
+    impl Oracle {
+        pub fn new() -> Self {
+            Self {
+                cipher_mode: None
+            }
+        }
+
+        fn pad_buffer(buffer: &[u8]) -> Vec<u8> {
+            let mut padded_buffer = Vec::with_capacity(10 + buffer.len() + 10);
+
+            let prefix_size = rand::thread_rng().gen_range(5, 11);
+            for _ in 0..prefix_size {
+                padded_buffer.push(rand::random());    
+            }
+            padded_buffer.extend(buffer);
+
+            let suffix_size = rand::thread_rng().gen_range(5, 11);
+            for _ in 0..suffix_size {
+                padded_buffer.push(rand::random());    
+            }
+            padded_buffer
+        }
+
+        pub fn encrypt_buffer(&mut self, buffer: &[u8]) -> Result<Vec<u8>, Error> {
+            let padded_buffer = Self::pad_buffer(buffer);
+            let (cipher_mode, output_buffer) = match rand::thread_rng().gen_range(0, 3) {
+                0 => (Mode::Ecb, Aes128Ecb::random().encrypt_buffer(&padded_buffer)?),
+                1 => (Mode::Cbc, Aes128Cbc::random().encrypt_buffer(&padded_buffer)?),
+                _ => (Mode::Ctr, Aes128Ctr::random().encrypt_buffer(&padded_buffer)?),
+            };
+            self.cipher_mode = Some(cipher_mode);
+            Ok(output_buffer)
+        }
+
+        pub fn cipher_mode(&self) -> Option<Mode> { self.cipher_mode }
+    }
+
+    impl Default for Oracle {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            self.encrypt_buffer(input)
+        }
+    }
 }
 
 pub mod simple_ecb_decryption {
+    use crate::oracles;
     use rand;
     use rand::Rng;
 
@@ -104,6 +205,7 @@ pub mod simple_ecb_decryption {
         Pkcs7,
         Error,
     };
+    use crate::data;
     use crate::random_vec;
     use crate::crypto::random::Random;
 
@@ -114,21 +216,31 @@ pub mod simple_ecb_decryption {
     }
 
     impl Oracle {
-        pub fn new(with_random_data: bool) -> Result<Self, Error> {
-            let cipher = Aes128Ecb::random();
-            
+        pub fn new(with_random_data: bool) -> Result<Self, data::Error> {
             let random_size = if with_random_data { 
                 rand::thread_rng().gen_range(0, Aes128::BLOCK_SIZE) 
             } else { 
                 0 
             };
+            Self::with_prefix_size(random_size)
+        }
+
+        /// Returns an oracle which prepends the given number of random bytes to the input.
+        pub fn with_prefix_size(random_size: usize) -> Result<Self, data::Error> {
+            let cipher = Aes128Ecb::random();
             let random_data: Vec<u8> = random_vec!(random_size);
 
-            let unknown_data = include_str!("../../data/set_2/problem_12.txt").replace("\n", "");
-            let unknown_data = base64::decode(&unknown_data).unwrap();
+            let unknown_data = data::Loader::bundled().read_base64(2, 12)?;
             
             Ok(Oracle { cipher, random_data, unknown_data })
         }
+
+        /// Returns an oracle which appends the given data to the input, rather than the data
+        /// from set 2, problem 12.
+        pub fn with_unknown_data(mut self, unknown_data: &[u8]) -> Self {
+            self.unknown_data = unknown_data.to_vec();
+            self
+        }
         
         fn build_plaintext(&self, known_data: &[u8]) -> Vec<u8> {
             // Ensure there is enough space for the random prefix, unknown suffix and PKCS7 padding.
@@ -147,7 +259,7 @@ pub mod simple_ecb_decryption {
         }
 
         pub fn encrypt_buffer(&mut self, buffer: &[u8]) -> Result<Vec<u8>, Error> {
-            let mut output_buffer = self.build_plaintext(&buffer);
+            let mut output_buffer = self.build_plaintext(buffer);
             let output_size = output_buffer.len();
             let padding_size = Pkcs7::min_padding_size(Aes128::BLOCK_SIZE, output_size);
             output_buffer.resize(output_size + padding_size, 0);
@@ -157,11 +269,23 @@ pub mod simple_ecb_decryption {
         }
     }
 
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            self.encrypt_buffer(input)
+        }
+    }
 }
 
 pub mod ecb_cut_and_paste {
+    use crate::oracles;
+    use std::fmt;
     use std::str::FromStr;
     
+    use crate::http;
+    use http::{ToParamStr, FromParamStr};
     use crate::crypto::random;
     use random::Random;
 
@@ -173,6 +297,8 @@ pub mod ecb_cut_and_paste {
         EncodingError,
         DecodingError,
         CipherError,
+        /// The query budget of an oracle wrapped in `oracles::Counted` is exhausted.
+        BudgetExceeded,
     }
 
     impl From<std::num::ParseIntError> for Error {
@@ -180,6 +306,12 @@ pub mod ecb_cut_and_paste {
             Error::DecodingError
         }
     }
+
+    impl From<http::Error> for Error {
+        fn from(_: http::Error) -> Self {
+            Error::DecodingError
+        }
+    }
     
     impl From<symmetric::Error> for Error {
         fn from(_: symmetric::Error) -> Self {
@@ -188,16 +320,18 @@ pub mod ecb_cut_and_paste {
     }
 
     #[derive(Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
     pub enum Role {
         User,
         Admin
     }
 
-    impl ToString for Role {
-        fn to_string(&self) -> String {
+    impl fmt::Display for Role {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
             match self {
-                Role::User => String::from("user"),
-                Role::Admin => String::from("admin"),
+                Role::User => write!(formatter, "user"),
+                Role::Admin => write!(formatter, "admin"),
             }
         }
     }
@@ -214,19 +348,26 @@ pub mod ecb_cut_and_paste {
         }
     }
 
+    /// A user profile. With the `serde` feature enabled, profiles may also be encoded using
+    /// `http::serde`, which produces the same parameter strings. The `Display` and `FromStr`
+    /// implementations are written by hand since the oracle has to work without `serde`.
+    /// Both paths reject unknown and duplicate keys.
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
     pub struct Profile {
         pub email: String,
         pub uid: u64,
         pub role: Role
     }
 
-    impl ToString for Profile {
-        fn to_string(&self) -> String {
-            vec![
-                format!("email={}", self.email.replace("=", "%3D").replace("&", "%26")),
-                format!("uid={}", self.uid),
-                format!("role={}", self.role.to_string())
-            ].join("&")
+    impl fmt::Display for Profile {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            let params = [
+                ("email", self.email.clone()),
+                ("uid", self.uid.to_string()),
+                ("role", self.role.to_string()),
+            ];
+            write!(formatter, "{}", params.to_param_str())
         }
     }
 
@@ -237,14 +378,16 @@ pub mod ecb_cut_and_paste {
             let mut email = None;
             let mut uid = None;
             let mut role = None;
-            for param in param_str.split('&') {
-                let mut tokens = param.split('=');
-                match (tokens.next(), tokens.next()) {
-                    (Some("email"), Some(value)) => email = Some(value.to_owned()),
-                    (Some("uid"), Some(value)) => uid = Some(value.parse()?),
-                    (Some("role"), Some(value)) => role = Some(value.parse()?),
+            for (key, value) in Vec::<(String, String)>::from_param_str(param_str)? {
+                let is_duplicate = match key.as_str() {
+                    "email" => email.replace(value).is_some(),
+                    "uid" => uid.replace(value.parse()?).is_some(),
+                    "role" => role.replace(value.parse()?).is_some(),
                     _ => return Err(Error::DecodingError)
                 };
+                if is_duplicate {
+                    return Err(Error::DecodingError);
+                }
             }
             if let (Some(email), Some(uid), Some(role)) = (email, uid, role) {
                 Ok(Profile { email, uid, role })
@@ -277,9 +420,357 @@ pub mod ecb_cut_and_paste {
             Oracle { cipher: Aes128Ecb::random() }
         }
     }
+
+    impl From<oracles::BudgetExceeded> for Error {
+        fn from(_: oracles::BudgetExceeded) -> Self {
+            Error::BudgetExceeded
+        }
+    }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            let email = std::str::from_utf8(input).map_err(|_| Error::EncodingError)?;
+            self.get_profile_for(email)
+        }
+    }
+}
+
+/// A hardened variant of the `ecb_cut_and_paste` oracle. The oracle may validate the email
+/// address before encoding the profile, and change how the profile is encoded and encrypted.
+pub mod hardened_cut_and_paste {
+    use crate::oracles;
+    use std::fmt;
+    use std::str::FromStr;
+    use rand::Rng;
+    use rand::seq::SliceRandom;
+
+    use crate::random_vec;
+    use crate::http::urlencoding;
+    use crate::crypto::random::Random;
+    use crate::crypto::symmetric::{BlockCipherMode, Aes128Ecb, Aes128Cbc, Aes128, Cipher};
+
+    pub use super::ecb_cbc_detection::Mode;
+    pub use super::ecb_cut_and_paste::{Error, Role, Profile};
+
+    /// Determines how structural characters in the email address are handled.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Encoding {
+        /// Email addresses containing `&`, `=` or `%` are rejected.
+        Reject,
+        /// The characters `&`, `=` and `%` are percent-encoded.
+        PercentEncode,
+        /// The email address is enclosed in double quotes. Email addresses containing `"` are
+        /// rejected, and `&`, `=` and `%` are percent-encoded.
+        Quote,
+    }
+
+    /// Determines which email addresses are accepted.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EmailFormat {
+        /// Any email address is accepted.
+        Any,
+        /// The email address must consist of a non-empty local part and a domain containing
+        /// a dot, separated by a single `@`.
+        Minimal,
+    }
+
+    /// Determines which characters are accepted in the email address.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Charset {
+        /// Any character is accepted.
+        Any,
+        /// Only printable ASCII characters are accepted.
+        Printable,
+    }
+
+    /// The fields of the encoded profile.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Field {
+        Email,
+        Uid,
+        Role,
+    }
+
+    impl fmt::Display for Field {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Field::Email => write!(formatter, "email"),
+                Field::Uid => write!(formatter, "uid"),
+                Field::Role => write!(formatter, "role"),
+            }
+        }
+    }
+
+    pub struct Oracle {
+        key: Vec<u8>,
+        mode: Mode,
+        encoding: Encoding,
+        email_format: EmailFormat,
+        charset: Charset,
+        uid: u64,
+        field_order: [Field; 3],
+    }
+
+    impl Oracle {
+        /// The maximum number of digits of a random uid.
+        const MAX_UID_DIGITS: u32 = 12;
+
+        pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+            self.encoding = encoding;
+            self
+        }
+
+        pub fn with_email_format(mut self, email_format: EmailFormat) -> Self {
+            self.email_format = email_format;
+            self
+        }
+
+        pub fn with_charset(mut self, charset: Charset) -> Self {
+            self.charset = charset;
+            self
+        }
+
+        /// Returns an oracle which encrypts the profile using the given cipher mode. In
+        /// CBC-mode, each message is encrypted using a random IV which is prepended to the
+        /// ciphertext. CTR-mode is not supported, and fails with `Error::CipherError`.
+        pub fn with_mode(mut self, mode: Mode) -> Self {
+            self.mode = mode;
+            self
+        }
+
+        /// Returns an oracle which assigns the given uid to each profile.
+        pub fn with_uid(mut self, uid: u64) -> Self {
+            self.uid = uid;
+            self
+        }
+
+        /// Returns an oracle which assigns a random uid of unknown size to each profile.
+        pub fn with_random_uid(self) -> Self {
+            let mut rng = rand::thread_rng();
+            let digits = rng.gen_range(1, Self::MAX_UID_DIGITS + 1);
+            let uid = rng.gen_range(10u64.pow(digits - 1), 10u64.pow(digits));
+            self.with_uid(uid)
+        }
+
+        /// Returns an oracle which encodes the fields of the profile in the given order.
+        pub fn with_field_order(mut self, field_order: [Field; 3]) -> Self {
+            self.field_order = field_order;
+            self
+        }
+
+        /// Returns an oracle which encodes the fields of the profile in a random order.
+        pub fn with_shuffled_fields(mut self) -> Self {
+            self.field_order.shuffle(&mut rand::thread_rng());
+            self
+        }
+
+        pub fn mode(&self) -> Mode { self.mode }
+
+        pub fn uid(&self) -> u64 { self.uid }
+
+        pub fn field_order(&self) -> [Field; 3] { self.field_order }
+
+        fn is_valid_format(email: &str) -> bool {
+            let mut parts = email.split('@');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(local), Some(domain), None) => {
+                    !local.is_empty() && 
+                    domain.split('.').count() > 1 && 
+                    domain.split('.').all(|label| !label.is_empty())
+                },
+                _ => false
+            }
+        }
+
+        fn encode_email(&self, email: &str) -> Result<String, Error> {
+            if self.email_format == EmailFormat::Minimal && !Self::is_valid_format(email) {
+                return Err(Error::EncodingError);
+            }
+            if self.charset == Charset::Printable && !email.bytes().all(|byte| (0x20..0x7f).contains(&byte)) {
+                return Err(Error::EncodingError);
+            }
+            match self.encoding {
+                Encoding::Reject if email.contains(['&', '=', '%']) => Err(Error::EncodingError),
+                Encoding::Reject => Ok(email.to_owned()),
+                Encoding::PercentEncode => Ok(urlencoding::encode_delimiters(email, &['&', '='])),
+                Encoding::Quote if email.contains('"') => Err(Error::EncodingError),
+                Encoding::Quote => Ok(format!("\"{}\"", urlencoding::encode_delimiters(email, &['&', '=']))),
+            }
+        }
+
+        fn encode_profile(&self, email: &str) -> Result<String, Error> {
+            let email = self.encode_email(email)?;
+            let params: Vec<String> = self.field_order
+                .iter()
+                .map(|field| match field {
+                    Field::Email => format!("{}={}", field, email),
+                    Field::Uid => format!("{}={}", field, self.uid),
+                    Field::Role => format!("{}={}", field, Role::User),
+                })
+                .collect();
+            Ok(params.join("&"))
+        }
+
+        pub fn get_profile_for(&mut self, email: &str) -> Result<Vec<u8>, Error> {
+            let param_str = self.encode_profile(email)?;
+            match self.mode {
+                Mode::Ecb => Ok(Aes128Ecb::new(&self.key)?.encrypt_str(&param_str)?),
+                Mode::Cbc => {
+                    let mut output_buffer = random_vec!(Aes128::BLOCK_SIZE);
+                    let mut cipher = Aes128Cbc::new(&self.key, &output_buffer)?;
+                    output_buffer.extend(cipher.encrypt_str(&param_str)?);
+                    Ok(output_buffer)
+                }
+                Mode::Ctr => Err(Error::CipherError),
+            }
+        }
+
+        pub fn get_role_from(&mut self, input_buffer: &[u8]) -> Result<Role, Error> {
+            let param_str = match self.mode {
+                Mode::Ecb => Aes128Ecb::new(&self.key)?.decrypt_str(input_buffer)?,
+                Mode::Cbc => {
+                    if input_buffer.len() < Aes128::BLOCK_SIZE {
+                        return Err(Error::CipherError);
+                    }
+                    let (iv, input_buffer) = input_buffer.split_at(Aes128::BLOCK_SIZE);
+                    Aes128Cbc::new(&self.key, iv)?.decrypt_str(input_buffer)?
+                }
+                Mode::Ctr => return Err(Error::CipherError),
+            };
+            Ok(Profile::from_str(&param_str)?.role)
+        }
+    }
+
+    /// Returns an oracle without any mitigations beyond percent-encoding, which is equivalent
+    /// to the `ecb_cut_and_paste` oracle.
+    impl Random for Oracle {
+        fn random() -> Self {
+            Oracle { 
+                key: random_vec!(Aes128::KEY_SIZE),
+                mode: Mode::Ecb,
+                encoding: Encoding::PercentEncode, 
+                email_format: EmailFormat::Any, 
+                charset: Charset::Any,
+                uid: 10,
+                field_order: [Field::Email, Field::Uid, Field::Role],
+            }
+        }
+    }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            let email = std::str::from_utf8(input).map_err(|_| Error::EncodingError)?;
+            self.get_profile_for(email)
+        }
+    }
+}
+
+/// A variant of the `ecb_cut_and_paste` oracle which encodes the profile as JSON.
+#[cfg(feature = "serde")]
+pub mod json_cut_and_paste {
+    use crate::oracles;
+    use serde::Serialize;
+    use serde_json::Value;
+
+    use crate::random_vec;
+    use crate::crypto::random::Random;
+    use crate::crypto::symmetric::{BlockCipherMode, Aes128Ecb, Aes128Cbc, Aes128, Cipher};
+
+    pub use super::ecb_cbc_detection::Mode;
+    pub use super::ecb_cut_and_paste::{Error, Role};
+
+    #[derive(Serialize)]
+    struct Profile<'a> {
+        email: &'a str,
+        uid: u64,
+        role: String,
+    }
+
+    pub struct Oracle {
+        key: Vec<u8>,
+        mode: Mode,
+    }
+
+    impl Oracle {
+        /// Returns an oracle using the given cipher mode. In CBC-mode, each message is
+        /// encrypted using a random IV which is prepended to the ciphertext. CTR-mode is not
+        /// supported, and fails with `Error::CipherError`.
+        pub fn with_mode(mut self, mode: Mode) -> Self {
+            self.mode = mode;
+            self
+        }
+
+        pub fn mode(&self) -> Mode { self.mode }
+
+        pub fn get_profile_for(&mut self, email: &str) -> Result<Vec<u8>, Error> {
+            let profile = Profile { email, uid: 10, role: Role::User.to_string() };
+            let json_str = serde_json::to_string(&profile).map_err(|_| Error::EncodingError)?;
+            match self.mode {
+                Mode::Ecb => Ok(Aes128Ecb::new(&self.key)?.encrypt_str(&json_str)?),
+                Mode::Cbc => {
+                    let mut output_buffer = random_vec!(Aes128::BLOCK_SIZE);
+                    let mut cipher = Aes128Cbc::new(&self.key, &output_buffer)?;
+                    output_buffer.extend(cipher.encrypt_str(&json_str)?);
+                    Ok(output_buffer)
+                }
+                Mode::Ctr => Err(Error::CipherError),
+            }
+        }
+
+        /// Returns the role of the encrypted profile.
+        ///
+        /// # Note
+        ///
+        /// Like most JSON parsers, we keep the last value if a key is repeated. Only the role
+        /// is required to be present.
+        pub fn get_role_from(&mut self, input_buffer: &[u8]) -> Result<Role, Error> {
+            let json_buffer = match self.mode {
+                Mode::Ecb => Aes128Ecb::new(&self.key)?.decrypt_buffer(input_buffer)?,
+                Mode::Cbc => {
+                    if input_buffer.len() < Aes128::BLOCK_SIZE {
+                        return Err(Error::CipherError);
+                    }
+                    let (iv, input_buffer) = input_buffer.split_at(Aes128::BLOCK_SIZE);
+                    Aes128Cbc::new(&self.key, iv)?.decrypt_buffer(input_buffer)?
+                }
+                Mode::Ctr => return Err(Error::CipherError),
+            };
+            let profile: Value = serde_json::from_slice(&json_buffer)
+                .map_err(|_| Error::DecodingError)?;
+            profile
+                .get("role")
+                .and_then(Value::as_str)
+                .ok_or(Error::DecodingError)?
+                .parse()
+        }
+    }
+
+    impl Random for Oracle {
+        fn random() -> Self {
+            Oracle { key: random_vec!(Aes128::KEY_SIZE), mode: Mode::Ecb }
+        }
+    }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            let email = std::str::from_utf8(input).map_err(|_| Error::EncodingError)?;
+            self.get_profile_for(email)
+        }
+    }
 }
 
 pub mod cbc_bitflipping_attacks {
+    use crate::oracles;
+    use crate::http::urlencoding;
     use crate::crypto::symmetric::{
         BlockCipherMode,
         Aes128Cbc,
@@ -299,10 +790,11 @@ pub mod cbc_bitflipping_attacks {
             let param_str = format!(
                 "{};userdata={};{}",
                 comment_1,
-                user_data.replace(";", "%3B").replace("=", "%3D"),
+                urlencoding::encode_delimiters(user_data, &[';', '=']),
                 comment_2
             );
-            self.cipher.encrypt_str(&param_str)
+            // Each message is encrypted using the same key and IV.
+            self.cipher.clone().encrypt_str(&param_str)
         }
 
         pub fn is_admin_user(&mut self, input_buffer: &[u8]) -> Result<bool, Error> {
@@ -310,7 +802,7 @@ pub mod cbc_bitflipping_attacks {
             // buffer which didn't decode to valid UTF-8, which in turn would prevent the attack
             // we are trying to implement.
             let target_buffer = b"admin=true";
-            let param_buffer = self.cipher.decrypt_buffer(input_buffer)?;
+            let param_buffer = self.cipher.clone().decrypt_buffer(input_buffer)?;
             for param_slice in param_buffer.split(|&x| x as char == ';') {
                 if param_slice == target_buffer {
                     return Ok(true)
@@ -325,66 +817,231 @@ pub mod cbc_bitflipping_attacks {
             Oracle { cipher: Aes128Cbc::random() }
         }
     }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            let user_data = String::from_utf8(input.to_vec())?;
+            self.encrypt_user_data(&user_data)
+        }
+    }
 }
 
 pub mod cbc_padding_oracle {
+    use crate::oracles;
     use crate::crypto::symmetric::{
         BlockCipherMode,
-        Aes128Cbc,
+        PaddingMode,
+        Pkcs7,
+        Cbc,
         Aes128,
         Cipher,
         Error,
     };
+    use crate::data;
     use crate::random_vec;
-    use crate::crypto::random::Random;
 
-    use base64;
     use rand;
     use rand::seq::SliceRandom;
 
-    pub struct Oracle {
-        cipher: Aes128Cbc,
+    /// The source of the plaintexts encrypted by the oracle.
+    enum Plaintexts {
+        Corpus(Vec<Vec<u8>>),
+        Generator(Box<dyn FnMut() -> Vec<u8> + Send>),
+    }
+
+    /// A CBC padding oracle using `Aes128` and the padding mode `P`.
+    pub struct Oracle<P: PaddingMode = Pkcs7> {
+        key: Vec<u8>,
+        cipher: Cbc<Aes128, P>,
         iv: Vec<u8>,
+        plaintexts: Plaintexts,
     }
 
     impl Oracle {
+        /// Returns an oracle with a random key and IV, which encrypts one of the strings from
+        /// set 3, problem 17.
+        pub fn new() -> Result<Self, data::Error> {
+            let key = random_vec!(Aes128::KEY_SIZE); 
+            let iv = random_vec!(Aes128::BLOCK_SIZE);
+            let plaintexts = data::Loader::bundled().read_base64_lines(3, 17)?;
+            // It is okay to unwrap here since the key size is known.
+            let cipher = Cbc::new(&key, &iv).unwrap();
+            Ok(Oracle { key, cipher, iv, plaintexts: Plaintexts::Corpus(plaintexts) })
+        }
+    }
+
+    impl<P: PaddingMode + Clone> Oracle<P> {
+        /// Returns an oracle which encrypts one of the given plaintexts, rather than the
+        /// strings from set 3, problem 17.
+        ///
+        /// # Panics
+        ///
+        /// Panics if no plaintexts are given.
+        pub fn with_plaintexts(mut self, plaintexts: Vec<Vec<u8>>) -> Self {
+            assert!(!plaintexts.is_empty());
+            self.plaintexts = Plaintexts::Corpus(plaintexts);
+            self
+        }
+
+        /// Returns an oracle which encrypts a plaintext returned by the given generator each
+        /// time an encrypted buffer is requested.
+        pub fn with_generator<F>(mut self, generator: F) -> Self 
+            where F: FnMut() -> Vec<u8> + Send + 'static
+        {
+            self.plaintexts = Plaintexts::Generator(Box::new(generator));
+            self
+        }
+
+        /// Returns an oracle using the padding mode `Q`, with the same key, IV and plaintexts.
+        pub fn with_padding<Q: PaddingMode + Clone>(self) -> Oracle<Q> {
+            // It is okay to unwrap here since the key and IV sizes are known.
+            let cipher = Cbc::new(&self.key, &self.iv).unwrap();
+            Oracle { key: self.key, cipher, iv: self.iv, plaintexts: self.plaintexts }
+        }
+
         /// This method encrypts a random string with a random key and IV, and returns the
         /// encrypted buffer prefixed by the IV. (This is just for convenience since we need
         /// to concatenate the two buffers before we start the attack anyway.)
         pub fn get_encrypted_buffer(&mut self) -> Result<Vec<u8>, Error> {
-            // It is safe to call unwrap here since the file is non-empty.
-            let random_str = include_str!("../../data/set_3/problem_17.txt")
-                .split('\n')
-                .collect::<Vec<&str>>()
-                .choose(&mut rand::thread_rng())
-                .unwrap()
-                .to_owned();
-            let random_buffer = base64::decode(random_str)
-                .unwrap();
+            let random_buffer = match &mut self.plaintexts {
+                // It is safe to call unwrap here since the list of plaintexts is non-empty.
+                Plaintexts::Corpus(plaintexts) => plaintexts
+                    .choose(&mut rand::thread_rng())
+                    .unwrap()
+                    .clone(),
+                Plaintexts::Generator(generator) => generator(),
+            };
 
+            // Each message is encrypted using the same IV.
             self.cipher
+                .clone()
                 .encrypt_buffer(&random_buffer)
                 .map(|buffer| [&self.iv[..], &buffer[..]].concat())
         }
 
+        /// Returns true if the buffer decrypts to a correctly padded plaintext. Buffers which
+        /// are empty or not a multiple of the block size are never valid.
         pub fn verify_padding(&mut self, buffer: &[u8]) -> bool {
-            // The only error returned by Aes128Cbc::decrypt_buffer is Error::PaddingError.
-            self.cipher.decrypt_buffer(buffer).is_ok()
+            if buffer.is_empty() || !buffer.len().is_multiple_of(Aes128::BLOCK_SIZE) {
+                return false;
+            }
+            // The only error returned by Cbc::decrypt_buffer is Error::PaddingError.
+            self.cipher.clone().decrypt_buffer(buffer).is_ok()
+        }
+    }
+
+
+    impl<P: PaddingMode + Clone> oracles::Oracle for Oracle<P> {
+        type Output = bool;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<bool, Error> {
+            Ok(self.verify_padding(input))
+        }
+    }
+}
+
+
+/// A variant of the CBC padding oracle from set 3, problem 17, where the decrypted message
+/// must also be valid UTF-8. Depending on the `Disclosure`, padding errors are either reported
+/// as such or masked by silently dropping the message.
+pub mod cbc_error_oracle {
+    use crate::oracles;
+    use crate::crypto::symmetric::{BlockCipherMode, Aes128Cbc, Aes128, Cipher};
+    use crate::crypto::symmetric;
+    use crate::random_vec;
+    use crate::crypto::random::Random;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Error {
+        /// The message is not correctly padded.
+        PaddingError,
+        /// The message is correctly padded, but is not valid UTF-8.
+        ContentError,
+        /// The message is too short, or its size is not a multiple of the block size.
+        LengthError,
+    }
+
+    /// Determines how the oracle responds to messages with invalid padding.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Disclosure {
+        /// Padding errors are reported as `Error::PaddingError`.
+        Distinct,
+        /// Messages with invalid padding are silently dropped, which makes them
+        /// indistinguishable from valid messages.
+        MaskedPadding,
+    }
+
+    pub struct Oracle {
+        key: Vec<u8>,
+        iv: Vec<u8>,
+        disclosure: Disclosure,
+    }
+
+    impl Oracle {
+        pub fn with_disclosure(mut self, disclosure: Disclosure) -> Self {
+            self.disclosure = disclosure;
+            self
+        }
+
+        pub fn disclosure(&self) -> Disclosure { self.disclosure }
+
+        /// Encrypts the given string and returns the encrypted buffer prefixed by the IV.
+        pub fn encrypt_str(&self, input_str: &str) -> Result<Vec<u8>, symmetric::Error> {
+            // It is okay to unwrap here since the key and IV sizes are known.
+            Aes128Cbc::new(&self.key, &self.iv)
+                .unwrap()
+                .encrypt_str(input_str)
+                .map(|buffer| [&self.iv[..], &buffer[..]].concat())
+        }
+
+        /// Decrypts the given buffer, which is expected to be prefixed by the IV, and
+        /// validates the padding and the content of the message.
+        pub fn submit_buffer(&self, buffer: &[u8]) -> Result<(), Error> {
+            if buffer.len() < 2 * Aes128::BLOCK_SIZE || !buffer.len().is_multiple_of(Aes128::BLOCK_SIZE) {
+                return Err(Error::LengthError);
+            }
+            let (iv, buffer) = buffer.split_at(Aes128::BLOCK_SIZE);
+            // It is okay to unwrap here since the key and IV sizes are known.
+            let mut cipher = Aes128Cbc::new(&self.key, iv).unwrap();
+            match cipher.decrypt_buffer(buffer) {
+                Ok(plaintext) => match String::from_utf8(plaintext) {
+                    Ok(_) => Ok(()),
+                    Err(_) => Err(Error::ContentError),
+                },
+                Err(_) => match self.disclosure {
+                    Disclosure::Distinct => Err(Error::PaddingError),
+                    Disclosure::MaskedPadding => Ok(()),
+                },
+            }
         }
     }
 
     impl Random for Oracle {
         fn random() -> Self {
-            let key = random_vec!(Aes128::KEY_SIZE); 
-            let iv = random_vec!(Aes128::BLOCK_SIZE);
-            // It is okay to unwrap here since the key size is known.
-            Oracle { cipher: Aes128Cbc::new(&key, &iv).unwrap(), iv }
+            Oracle {
+                key: random_vec!(Aes128::KEY_SIZE),
+                iv: random_vec!(Aes128::BLOCK_SIZE),
+                disclosure: Disclosure::Distinct,
+            }
         }
     }
-}
 
+    impl oracles::Oracle for Oracle {
+        type Output = ();
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<(), Error> {
+            self.submit_buffer(input)
+        }
+    }
+}
 
 pub mod random_access_read_write {
+    use crate::oracles;
     use crate::crypto::symmetric::{
         Error,
         Aes128Ctr,
@@ -394,10 +1051,18 @@ pub mod random_access_read_write {
     use crate::crypto::random::Random;
 
     pub struct Oracle {
-        cipher: Aes128Ctr
+        cipher: Aes128Ctr,
+        allow_edits: bool,
     }
 
     impl Oracle {
+        /// Returns an oracle which rejects direct edits, leaving only the append and truncate
+        /// operations.
+        pub fn without_edits(mut self) -> Self {
+            self.allow_edits = false;
+            self
+        }
+
         pub fn encrypt_buffer(&mut self, buffer: &[u8]) -> Result<Vec<u8>, Error> {
             self.cipher.seek(0);
             self.cipher.encrypt_buffer(buffer)
@@ -409,6 +1074,9 @@ pub mod random_access_read_write {
             offset: usize, 
             plaintext_buffer: &[u8]
         ) -> Result<(), Error> {
+            if !self.allow_edits {
+                return Err(Error::CipherError);
+            }
             let begin = offset;
             let end = offset + plaintext_buffer.len();
             self.cipher.seek(begin);
@@ -417,16 +1085,51 @@ pub mod random_access_read_write {
             self.cipher.encrypt_mut(&mut encrypted_buffer[begin..end])?;
             Ok(())
         }
+
+        /// Encrypts the plaintext and appends it to the encrypted buffer.
+        pub fn append_buffer(
+            &mut self, 
+            encrypted_buffer: &mut Vec<u8>, 
+            plaintext_buffer: &[u8]
+        ) -> Result<(), Error> {
+            let begin = encrypted_buffer.len();
+            self.cipher.seek(begin);
+
+            encrypted_buffer.extend_from_slice(plaintext_buffer);
+            self.cipher.encrypt_mut(&mut encrypted_buffer[begin..])?;
+            Ok(())
+        }
+
+        /// Truncates the encrypted buffer to the given size. Since each byte is encrypted
+        /// using the keystream at its offset, the remaining bytes are left unchanged.
+        pub fn truncate_buffer(&mut self, encrypted_buffer: &mut Vec<u8>, size: usize) -> Result<(), Error> {
+            if size > encrypted_buffer.len() {
+                return Err(Error::CipherError);
+            }
+            encrypted_buffer.truncate(size);
+            Ok(())
+        }
     }
 
     impl Random for Oracle {
         fn random() -> Self {
-            Oracle { cipher: Aes128Ctr::random() }
+            Oracle { cipher: Aes128Ctr::random(), allow_edits: true }
+        }
+    }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            self.encrypt_buffer(input)
         }
     }
 }
 
 pub mod ctr_bitflipping_attacks {
+    use crate::oracles;
+    use crate::http::urlencoding;
     use crate::crypto::symmetric::{
         Error,
         Aes128Ctr,
@@ -447,7 +1150,7 @@ pub mod ctr_bitflipping_attacks {
             let param_str = format!(
                 "{};userdata={};{}",
                 comment_1,
-                user_data.replace(";", "%3B").replace("=", "%3D"),
+                urlencoding::encode_delimiters(user_data, &[';', '=']),
                 comment_2
             );
             self.cipher.seek(0);
@@ -468,13 +1171,25 @@ pub mod ctr_bitflipping_attacks {
             Oracle { cipher: Aes128Ctr::random() }
         }
     }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            let user_data = String::from_utf8(input.to_vec())?;
+            self.encrypt_user_data(&user_data)
+        }
+    }
 }
 
 pub mod cbc_with_key_as_iv {
+    use crate::oracles;
     use std::fmt;
     use std::error;
 
     use crate::random_vec;
+    use crate::utils::bytes;
     use crate::crypto::symmetric;
     use crate::crypto::random::Random;
     use crate::crypto::symmetric::{Aes128, Cipher, Aes128Cbc, BlockCipherMode};
@@ -484,6 +1199,11 @@ pub mod cbc_with_key_as_iv {
         CipherError(symmetric::Error),
         PaddingError(Vec<u8>),
         DecodingError(Vec<u8>),
+        /// A padding error which does not disclose the plaintext.
+        OpaquePaddingError,
+        /// A decoding error which only discloses the offset of the first invalid byte.
+        OpaqueDecodingError(usize),
+        LengthError,
     }
 
     impl fmt::Display for Error {
@@ -498,6 +1218,9 @@ pub mod cbc_with_key_as_iv {
                 Error::CipherError(error) => Some(error),
                 Error::PaddingError(_) => None,
                 Error::DecodingError(_) => None,
+                Error::OpaquePaddingError => None,
+                Error::OpaqueDecodingError(_) => None,
+                Error::LengthError => None,
             }
         }
     }
@@ -508,39 +1231,77 @@ pub mod cbc_with_key_as_iv {
         }
     }
     
+    /// Determines what the oracle discloses when decryption fails.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Disclosure {
+        /// Invalid plaintexts are returned as part of the error.
+        Plaintext,
+        /// Only the type of error is returned, together with the offset of the first invalid
+        /// byte for decoding errors.
+        Offset,
+    }
+
     #[derive(Clone)]
     pub struct Oracle {
         key: Vec<u8>,
-        cipher: Aes128Cbc
+        cipher: Aes128Cbc,
+        disclosure: Disclosure,
+        max_blocks: Option<usize>,
     }
     
     impl Oracle {
+        /// Returns an oracle which discloses the given information when decryption fails.
+        pub fn with_disclosure(mut self, disclosure: Disclosure) -> Self {
+            self.disclosure = disclosure;
+            self
+        }
+
+        /// Returns an oracle which rejects messages longer than the given number of blocks.
+        pub fn with_max_blocks(mut self, max_blocks: usize) -> Self {
+            self.max_blocks = Some(max_blocks);
+            self
+        }
+
+        fn verify_length(&self, size: usize) -> Result<(), Error> {
+            if size == 0 || !size.is_multiple_of(Aes128::BLOCK_SIZE) {
+                return Err(Error::LengthError);
+            }
+            match self.max_blocks {
+                Some(max_blocks) if size > max_blocks * Aes128::BLOCK_SIZE => Err(Error::LengthError),
+                _ => Ok(())
+            }
+        }
+
         pub fn encrypt_str(&mut self, input_string: &str) -> Result<Vec<u8>, Error> {
-            self.cipher
-                .encrypt_str(input_string)
-                .map_err(Error::from)
+            // Each message is encrypted using the key as IV.
+            let output_buffer = self.cipher.clone().encrypt_str(input_string)?;
+            self.verify_length(output_buffer.len())?;
+            Ok(output_buffer)
         }
 
         pub fn decrypt_str(&mut self, input_buffer: &[u8]) -> Result<Vec<u8>, Error> {
-            // We have to handle the case when unpadding fails explicitly here
-            // since we want to return the ciphertext together with the error.
-            let mut output_buffer = input_buffer.to_vec();
-            match self.cipher.decrypt_mut(&mut output_buffer) {
-                Ok(output_size) => { output_buffer.truncate(output_size) },
-                Err(_) => { return Err(Error::PaddingError(output_buffer)) },
+            self.verify_length(input_buffer.len())?;
+            // The plaintext is returned together with the error, so we cannot use
+            // `decrypt_str` here.
+            let (output_buffer, report) = self.cipher.clone().decrypt_with_report(input_buffer)?;
+            if !report.valid_padding {
+                return Err(match self.disclosure {
+                    Disclosure::Plaintext => Error::PaddingError(output_buffer),
+                    Disclosure::Offset => Error::OpaquePaddingError,
+                });
             }
-            if output_buffer.iter().any(|&byte| byte < 0x20 || 0x7f < byte) {
-                    return Err(Error::DecodingError(output_buffer));
+            // Only printable ASCII is accepted, which is stricter than UTF-8.
+            if let Some(offset) = output_buffer.iter().position(|&byte| !(0x20..0x7f).contains(&byte)) {
+                return Err(match self.disclosure {
+                    Disclosure::Plaintext => Error::DecodingError(output_buffer),
+                    Disclosure::Offset => Error::OpaqueDecodingError(offset),
+                });
             }
             Ok(output_buffer)
         }
 
         pub fn verify_key(&self, key: &[u8]) -> bool {
-            let mut result = 0;
-            for i in 0..Aes128::KEY_SIZE {
-                result |= key[i] ^ self.key[i];
-            }
-            result == 0
+            key.len() == Aes128::KEY_SIZE && bytes::constant_time_eq(key, &self.key)
         }
     }
     
@@ -548,8 +1309,281 @@ pub mod cbc_with_key_as_iv {
         fn random() -> Self {
             let key = random_vec!(Aes128::KEY_SIZE);
             let cipher = Aes128Cbc::new(&key, &key).unwrap();
-            Oracle { key, cipher }
+            Oracle { key, cipher, disclosure: Disclosure::Plaintext, max_blocks: None }
+        }
+    }
+
+    impl From<oracles::BudgetExceeded> for Error {
+        fn from(error: oracles::BudgetExceeded) -> Self {
+            Error::CipherError(error.into())
+        }
+    }
+
+    /// The decryption oracle is the one used by the attack.
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            self.decrypt_str(input)
         }
     }
 }
 
+
+/// A verify-only oracle for AEAD ciphers which records every accepted forgery, i.e. every
+/// accepted message which was not produced by the oracle itself.
+pub mod aead_forgery {
+    use crate::oracles;
+    use crate::random_vec;
+    use crate::crypto::aead::Aead;
+    use crate::crypto::symmetric::Error;
+    use crate::crypto::random::Random;
+
+    /// A (nonce, ciphertext, tag) triple.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Message {
+        pub nonce: Vec<u8>,
+        pub ciphertext: Vec<u8>,
+        pub tag: Vec<u8>,
+    }
+
+    impl Message {
+        /// Returns the message on the form nonce || ciphertext || tag.
+        pub fn to_bytes(&self) -> Vec<u8> {
+            [&self.nonce[..], &self.ciphertext[..], &self.tag[..]].concat()
+        }
+    }
+
+    pub struct Oracle<A: Aead> {
+        cipher: A,
+        tag_size: usize,
+        messages: Vec<Message>,
+        forgeries: Vec<Message>,
+        queries: usize,
+    }
+
+    impl<A: Aead> Oracle<A> {
+        pub fn new(cipher: A) -> Self {
+            Oracle {
+                cipher,
+                tag_size: A::TAG_SIZE,
+                messages: Vec::new(),
+                forgeries: Vec::new(),
+                queries: 0,
+            }
+        }
+
+        /// Returns an oracle which truncates tags to the given number of bytes, and only
+        /// accepts tags of this size.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the tag size is zero or larger than `A::TAG_SIZE`.
+        pub fn with_tag_size(mut self, tag_size: usize) -> Self {
+            assert!(tag_size > 0 && tag_size <= A::TAG_SIZE);
+            self.tag_size = tag_size;
+            self
+        }
+
+        pub fn tag_size(&self) -> usize { self.tag_size }
+
+        /// Encrypts the plaintext using a random nonce.
+        pub fn encrypt_buffer(&mut self, plaintext: &[u8]) -> Result<Message, Error> {
+            self.encrypt_buffer_with_nonce(&random_vec!(A::NONCE_SIZE), plaintext)
+        }
+
+        /// Encrypts the plaintext using the given nonce. Nonces are not checked for reuse.
+        pub fn encrypt_buffer_with_nonce(&mut self, nonce: &[u8], plaintext: &[u8]) -> Result<Message, Error> {
+            let (ciphertext, mut tag) = self.cipher.seal(nonce, &[], plaintext)?;
+            tag.truncate(self.tag_size);
+            let message = Message { nonce: nonce.to_vec(), ciphertext, tag };
+            self.messages.push(message.clone());
+            Ok(message)
+        }
+
+        /// Returns true if the tag is valid. Accepted messages which were not returned by
+        /// the oracle are recorded as forgeries.
+        pub fn verify_message(&mut self, message: &Message) -> bool {
+            self.queries += 1;
+            if message.tag.len() != self.tag_size {
+                return false;
+            }
+            // The oracle truncates tags itself, so it compares against a prefix of the full tag.
+            let valid = match self.cipher.compute_tag(&message.nonce, &[], &message.ciphertext) {
+                Ok(tag) => tag[..self.tag_size] == message.tag[..],
+                Err(_) => false,
+            };
+            if valid && !self.messages.contains(message) && !self.forgeries.contains(message) {
+                self.forgeries.push(message.clone());
+            }
+            valid
+        }
+
+        /// Returns the number of verification queries made so far.
+        pub fn queries(&self) -> usize { self.queries }
+
+        /// Returns the distinct forgeries accepted so far.
+        pub fn forgeries(&self) -> &[Message] { &self.forgeries }
+    }
+
+    impl<A: Aead + Random> Random for Oracle<A> {
+        fn random() -> Self {
+            Oracle::new(A::random())
+        }
+    }
+
+    /// The oracle takes a buffer on the form nonce || ciphertext || tag.
+    impl<A: Aead> oracles::Oracle for Oracle<A> {
+        type Output = bool;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<bool, Error> {
+            if input.len() < A::NONCE_SIZE + self.tag_size {
+                return Err(Error::CipherError);
+            }
+            let (nonce, input) = input.split_at(A::NONCE_SIZE);
+            let (ciphertext, tag) = input.split_at(input.len() - self.tag_size);
+            Ok(self.verify_message(&Message {
+                nonce: nonce.to_vec(),
+                ciphertext: ciphertext.to_vec(),
+                tag: tag.to_vec()
+            }))
+        }
+    }
+}
+
+pub mod rc4_single_byte_biases {
+    use crate::oracles;
+    use crate::encoding::base64;
+    use crate::crypto::random::Random;
+    use crate::crypto::symmetric::{StreamCipherMode, Rc4, Error};
+
+    /// The secret cookie from challenge 56.
+    pub const COOKIE: &str = "QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F";
+
+    /// Encrypts `request || cookie` using RC4 with a fresh random 128-bit key for each request.
+    pub struct Oracle {
+        cookie: Vec<u8>,
+        queries: usize,
+    }
+
+    impl Oracle {
+        pub fn new(cookie: &[u8]) -> Self {
+            Oracle { cookie: cookie.to_vec(), queries: 0 }
+        }
+
+        pub fn encrypt_request(&mut self, request: &[u8]) -> Result<Vec<u8>, Error> {
+            self.queries += 1;
+            Rc4::random().encrypt_buffer(&[request, &self.cookie[..]].concat())
+        }
+
+        /// Returns the number of encryption queries made so far.
+        pub fn queries(&self) -> usize { self.queries }
+
+        /// Returns the size of the cookie, which is leaked by the ciphertext size.
+        pub fn cookie_size(&self) -> usize { self.cookie.len() }
+    }
+
+    /// Returns an oracle using the cookie from challenge 56.
+    impl Default for Oracle {
+        fn default() -> Self {
+            // It is safe to unwrap here since the cookie is valid base64.
+            Oracle::new(&base64::decode(COOKIE).unwrap())
+        }
+    }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            self.encrypt_request(input)
+        }
+    }
+}
+
+pub mod mac_then_encrypt {
+    use crate::oracles;
+    use crate::crypto::hash::{Mac, HashFunction, Sha1, Sha1NaiveMac};
+    use crate::crypto::symmetric::{BlockCipherMode, Aes128Cbc, Aes128, Cipher};
+    use crate::crypto::symmetric;
+    use crate::random_vec;
+    use crate::crypto::random::Random;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Error {
+        /// The message is not correctly padded.
+        PaddingError,
+        /// The message is correctly padded, but the tag is invalid.
+        MacError,
+        /// The message is too short, or its size is not a multiple of the block size.
+        LengthError,
+    }
+
+    /// Authenticates messages using MAC-then-encrypt: the plaintext is authenticated using
+    /// `Sha1NaiveMac`, and `plaintext || tag` is encrypted using AES-128 in CBC-mode. Since the
+    /// padding is checked before the tag, the oracle leaks which of the two checks failed.
+    pub struct Oracle {
+        cipher_key: Vec<u8>,
+        mac_key: Vec<u8>,
+        iv: Vec<u8>,
+    }
+
+    impl Oracle {
+        /// The size of the tag appended to each plaintext.
+        pub const TAG_SIZE: usize = Sha1::DIGEST_SIZE;
+
+        /// Encrypts the plaintext together with its tag, and returns the encrypted buffer
+        /// prefixed by the IV.
+        pub fn encrypt_buffer(&self, plaintext: &[u8]) -> Result<Vec<u8>, symmetric::Error> {
+            let tag = Sha1NaiveMac::digest(&self.mac_key, plaintext);
+            // It is okay to unwrap here since the key and IV sizes are known.
+            Aes128Cbc::new(&self.cipher_key, &self.iv)
+                .unwrap()
+                .encrypt_buffer(&[plaintext, tag.as_ref()].concat())
+                .map(|buffer| [&self.iv[..], &buffer[..]].concat())
+        }
+
+        /// Decrypts the given buffer, which is expected to be prefixed by the IV, and verifies
+        /// the padding and then the tag. Returns the authenticated plaintext.
+        pub fn decrypt_and_verify(&self, buffer: &[u8]) -> Result<Vec<u8>, Error> {
+            if buffer.len() < 2 * Aes128::BLOCK_SIZE || !buffer.len().is_multiple_of(Aes128::BLOCK_SIZE) {
+                return Err(Error::LengthError);
+            }
+            let (iv, buffer) = buffer.split_at(Aes128::BLOCK_SIZE);
+            // It is okay to unwrap here since the key and IV sizes are known.
+            let mut plaintext = Aes128Cbc::new(&self.cipher_key, iv)
+                .unwrap()
+                .decrypt_buffer(buffer)
+                .map_err(|_| Error::PaddingError)?;
+            if plaintext.len() < Self::TAG_SIZE {
+                return Err(Error::MacError);
+            }
+            let tag = plaintext.split_off(plaintext.len() - Self::TAG_SIZE);
+            if Sha1NaiveMac::digest(&self.mac_key, &plaintext).as_ref() != &tag[..] {
+                return Err(Error::MacError);
+            }
+            Ok(plaintext)
+        }
+    }
+
+    impl Random for Oracle {
+        fn random() -> Self {
+            Oracle {
+                cipher_key: random_vec!(Aes128::KEY_SIZE),
+                mac_key: random_vec!(Aes128::KEY_SIZE),
+                iv: random_vec!(Aes128::BLOCK_SIZE),
+            }
+        }
+    }
+
+    impl oracles::Oracle for Oracle {
+        type Output = Vec<u8>;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
+            self.decrypt_and_verify(input)
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Timing-sensitive oracles. Time is measured and spent through a `Clock`, which allows the
+//! same oracle to run against the wall clock or against a simulated clock which advances
+//! instantly when sleeping.
+//!
+//! Attacks which depend on the current Unix time (like recovering a time-based seed) also read
+//! the time from a `Clock`. These expect the clock to measure time since the Unix epoch, like
+//! `SystemClock` or a `SimulatedClock` created using `SimulatedClock::starting_at`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+#[cfg(feature = "system-time")]
+use std::time::SystemTime;
+use std::thread;
+
+/// A source of time used by timing-sensitive oracles and the attacks against them.
+pub trait Clock {
+    /// Returns the time elapsed since an arbitrary, fixed point in time.
+    fn now(&self) -> Duration;
+
+    /// Blocks for the given duration.
+    fn sleep(&self, duration: Duration);
+}
+
+/// The wall clock.
+#[derive(Debug, Clone, Copy)]
+pub struct RealClock {
+    origin: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        RealClock { origin: Instant::now() }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+/// The system clock, which measures time since the Unix epoch. Unlike `RealClock`, this is
+/// not monotonic.
+#[cfg(feature = "system-time")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "system-time")]
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock
+    }
+}
+
+#[cfg(feature = "system-time")]
+impl Clock for SystemClock {
+    /// Returns the time elapsed since the Unix epoch. If the system time is before the epoch,
+    /// this returns zero.
+    fn now(&self) -> Duration {
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration)
+    }
+}
+
+/// A simulated clock which only advances when sleeping. Clones share the same time, so an
+/// oracle and an attack can each hold a copy of the clock.
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl SimulatedClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a clock which starts at the given time. This is used to simulate the Unix
+    /// time.
+    pub fn starting_at(time: Duration) -> Self {
+        let clock = Self::new();
+        clock.advance(time);
+        clock
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.nanos.fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
+
+pub mod hmac_timing_leak {
+    use std::time::Duration;
+    use rand;
+    use rand::Rng;
+
+    use crate::oracles;
+    use crate::random_vec;
+    use crate::crypto::hash::{Mac, Sha1Hmac};
+
+    use super::Clock;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Error {
+        /// The buffer is too short to contain a signature.
+        LengthError,
+        /// The query budget of an oracle wrapped in `oracles::Counted` is exhausted.
+        BudgetExceeded,
+    }
+
+    impl From<oracles::BudgetExceeded> for Error {
+        fn from(_: oracles::BudgetExceeded) -> Self {
+            Error::BudgetExceeded
+        }
+    }
+
+    /// Verifies HMAC-SHA1 signatures using a byte-at-a-time comparison which sleeps after
+    /// each matching byte, as in set 4, problems 31 and 32.
+    pub struct Oracle<C: Clock> {
+        key: Vec<u8>,
+        clock: C,
+        delay: Duration,
+        jitter: Duration,
+    }
+
+    impl<C: Clock> Oracle<C> {
+        const KEY_SIZE: usize = 16;
+
+        /// Returns an oracle with a random key, which sleeps for 50 ms after each matching
+        /// byte.
+        pub fn new(clock: C) -> Self {
+            Oracle {
+                key: random_vec!(Self::KEY_SIZE),
+                clock,
+                delay: Duration::from_millis(50),
+                jitter: Duration::from_secs(0),
+            }
+        }
+
+        /// Returns an oracle which sleeps for the given duration after each matching byte.
+        pub fn with_delay(mut self, delay: Duration) -> Self {
+            self.delay = delay;
+            self
+        }
+
+        /// Returns an oracle which sleeps for an additional random duration, uniformly
+        /// distributed below `jitter`, on each verification.
+        pub fn with_jitter(mut self, jitter: Duration) -> Self {
+            self.jitter = jitter;
+            self
+        }
+
+        pub fn clock(&self) -> &C { &self.clock }
+
+        pub fn get_signature_for(&self, file: &[u8]) -> Vec<u8> {
+            Sha1Hmac::digest(&self.key, file).as_ref().to_vec()
+        }
+
+        fn insecure_compare(&self, lhs: &[u8], rhs: &[u8]) -> bool {
+            if lhs.len() != rhs.len() {
+                return false;
+            }
+            for (x, y) in lhs.iter().zip(rhs) {
+                if x != y {
+                    return false;
+                }
+                self.clock.sleep(self.delay);
+            }
+            true
+        }
+
+        pub fn verify_signature(&mut self, file: &[u8], signature: &[u8]) -> bool {
+            if self.jitter > Duration::from_secs(0) {
+                let jitter = rand::thread_rng().gen_range(0, self.jitter.as_nanos() as u64);
+                self.clock.sleep(Duration::from_nanos(jitter));
+            }
+            self.insecure_compare(&self.get_signature_for(file), signature)
+        }
+    }
+
+    /// Queries take a buffer on the form `file || signature`.
+    impl<C: Clock> oracles::Oracle for Oracle<C> {
+        type Output = bool;
+        type Error = Error;
+
+        fn query(&mut self, input: &[u8]) -> Result<bool, Error> {
+            if input.len() < Sha1Hmac::TAG_SIZE {
+                return Err(Error::LengthError);
+            }
+            let (file, signature) = input.split_at(input.len() - Sha1Hmac::TAG_SIZE);
+            Ok(self.verify_signature(file, signature))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulated_clock() {
+        let clock = SimulatedClock::new();
+        let other_clock = clock.clone();
+        clock.sleep(Duration::from_secs(3600));
+        other_clock.advance(Duration::from_millis(5));
+        assert_eq!(clock.now(), Duration::from_millis(3_600_005));
+        assert_eq!(other_clock.now(), clock.now());
+    }
+
+    #[test]
+    fn simulated_unix_time() {
+        let clock = SimulatedClock::starting_at(Duration::from_secs(1_500_000_000));
+        clock.sleep(Duration::from_secs(60));
+        assert_eq!(clock.now().as_secs(), 1_500_000_060);
+    }
+
+    #[test]
+    fn real_clock() {
+        let clock = RealClock::new();
+        let start = clock.now();
+        clock.sleep(Duration::from_millis(5));
+        assert!(clock.now() - start >= Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,7 @@
+//! The `alloc` items which are part of the standard library prelude. Modules which are
+//! available without the `std` feature import these using `use crate::prelude::*`.
+
+pub use alloc::{vec, format};
+pub use alloc::vec::Vec;
+pub use alloc::string::String;
+pub use alloc::borrow::ToOwned;
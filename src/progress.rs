@@ -0,0 +1,107 @@
+//! Progress reporting and cancellation for long-running attacks. Unlike the events in `trace`,
+//! reporters are passed to the attack explicitly, so progress may be observed (and the attack
+//! cancelled) from a different thread.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// A progress event emitted by an attack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The attack has recovered `completed` out of `total` bytes.
+    BytesRecovered { completed: usize, total: usize },
+    /// The attack has queried the oracle `count` times so far.
+    OracleCalls { count: usize },
+}
+
+/// Receives progress events from an attack.
+pub trait Reporter {
+    fn report(&self, event: Event);
+}
+
+/// A reporter which ignores all events.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Silent;
+
+impl Reporter for Silent {
+    fn report(&self, _: Event) {}
+}
+
+/// A reporter which sends each event over a channel. Events are dropped once the receiver
+/// has been dropped.
+#[derive(Debug, Clone)]
+pub struct Channel {
+    sender: Sender<Event>,
+}
+
+impl Channel {
+    /// Returns a reporter together with the receiving end of the channel.
+    pub fn new() -> (Self, Receiver<Event>) {
+        let (sender, receiver) = mpsc::channel();
+        (Channel { sender }, receiver)
+    }
+}
+
+impl Reporter for Channel {
+    fn report(&self, event: Event) {
+        let _ = self.sender.send(event);
+    }
+}
+
+/// A token used to cancel an attack. Attacks check the token between oracle queries, so
+/// cancellation takes effect at the next check rather than immediately. Clones share the
+/// same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn channel_reporter() {
+        let (reporter, receiver) = Channel::new();
+        let worker = thread::spawn(move || {
+            for completed in 1..=3 {
+                reporter.report(Event::BytesRecovered { completed, total: 3 });
+            }
+        });
+        worker.join().unwrap();
+        let events: Vec<Event> = receiver.iter().collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events.last(), Some(&Event::BytesRecovered { completed: 3, total: 3 }));
+
+        // Reporting to a closed channel is not an error.
+        let (reporter, receiver) = Channel::new();
+        drop(receiver);
+        reporter.report(Event::OracleCalls { count: 1 });
+    }
+
+    #[test]
+    fn cancellation_token() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        thread::spawn(move || token.cancel()).join().unwrap();
+        assert!(clone.is_cancelled());
+    }
+}
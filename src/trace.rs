@@ -0,0 +1,124 @@
+//! A minimal event log for oracles and attacks. Events are delivered to the subscriber
+//! installed on the current thread using `with_subscriber`, and are not constructed at all
+//! when no subscriber is installed.
+//!
+//! # Note
+//!
+//! Subscribers are thread-local, so events emitted by worker threads (as in
+//! `get_unknown_data_parallel`) are not delivered.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+/// An event emitted by an oracle or an attack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The named oracle was queried with a buffer of the given size.
+    Query { oracle: &'static str, size: usize },
+    /// The named attack accepted the value at the given position of the data it recovers.
+    Candidate { attack: &'static str, position: usize, value: u8 },
+    /// The named attack has recovered `completed` out of `total` units (blocks or bytes).
+    Progress { attack: &'static str, completed: usize, total: usize },
+}
+
+/// Receives events emitted on the current thread.
+pub trait Subscriber {
+    fn event(&self, event: &Event);
+}
+
+/// A subscriber which writes each event to stderr.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stderr;
+
+impl Subscriber for Stderr {
+    fn event(&self, event: &Event) {
+        eprintln!("{:?}", event);
+    }
+}
+
+/// A subscriber which records all events. Clones share the same log.
+#[derive(Debug, Clone, Default)]
+pub struct Log {
+    events: Arc<Mutex<Vec<Event>>>,
+}
+
+impl Log {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the events recorded so far.
+    pub fn events(&self) -> Vec<Event> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl Subscriber for Log {
+    fn event(&self, event: &Event) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+thread_local! {
+    static SUBSCRIBER: RefCell<Option<Rc<dyn Subscriber>>> = RefCell::new(None);
+}
+
+/// Calls `f` with the given subscriber installed on the current thread. The previous
+/// subscriber is restored afterwards.
+pub fn with_subscriber<S, F, R>(subscriber: S, f: F) -> R
+    where
+        S: Subscriber + 'static,
+        F: FnOnce() -> R
+{
+    // Restores the previous subscriber, even if `f` panics.
+    struct Guard(Option<Rc<dyn Subscriber>>);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SUBSCRIBER.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+
+    let previous = SUBSCRIBER.with(|current| current.replace(Some(Rc::new(subscriber))));
+    let _guard = Guard(previous);
+    f()
+}
+
+/// Emits the event returned by `event` if a subscriber is installed on the current thread.
+pub fn emit<F>(event: F) where F: FnOnce() -> Event {
+    // The subscriber is cloned to allow subscribers to emit events themselves.
+    if let Some(subscriber) = SUBSCRIBER.with(|current| current.borrow().clone()) {
+        subscriber.event(&event());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(completed: usize) -> Event {
+        Event::Progress { attack: "test", completed, total: 2 }
+    }
+
+    #[test]
+    fn scoped_subscribers() {
+        let outer_log = Log::new();
+        let inner_log = Log::new();
+        emit(|| progress(0));
+        with_subscriber(outer_log.clone(), || {
+            emit(|| progress(1));
+            with_subscriber(inner_log.clone(), || emit(|| progress(2)));
+            emit(|| progress(3));
+        });
+        emit(|| progress(4));
+        assert_eq!(outer_log.events(), [progress(1), progress(3)]);
+        assert_eq!(inner_log.events(), [progress(2)]);
+    }
+
+    #[test]
+    fn lazy_events() {
+        emit(|| panic!("no subscriber is installed"));
+    }
+}
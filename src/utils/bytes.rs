@@ -0,0 +1,200 @@
+//! Operations on byte buffers which are shared by the ciphers and the attacks.
+//!
+//! Binary operations on two buffers follow the semantics of `Iterator::zip`, and only consider
+//! the first `min(lhs.len(), rhs.len())` bytes. XOR and Hamming distance process the buffers in
+//! 64-bit lanes, falling back to single bytes for the remainder.
+
+use alloc::collections::BTreeSet;
+use core::convert::TryInto;
+
+use crate::prelude::*;
+
+const LANE_SIZE: usize = 8;
+
+#[inline(always)]
+fn to_lane(bytes: &[u8]) -> u64 {
+    // It is okay to unwrap here since lanes are created using `chunks_exact(LANE_SIZE)`.
+    u64::from_ne_bytes(bytes.try_into().unwrap())
+}
+
+/// Returns `chunk_size` buffers, where the `i`th buffer contains the `i`th byte of each chunk of
+/// size `chunk_size`. If the final chunk is partial, only the first buffers contain a byte from
+/// it.
+///
+/// # Panics
+///
+/// Panics if the chunk size is zero.
+pub fn transpose_chunks(buffer: &[u8], chunk_size: usize) -> Vec<Vec<u8>> {
+    assert!(chunk_size > 0, "chunk size must be non-zero");
+    (0..chunk_size)
+        .map(|offset| buffer.iter().skip(offset).step_by(chunk_size).cloned().collect())
+        .collect()
+}
+
+/// Returns the XOR of the two buffers.
+pub fn xor_slices(lhs: &[u8], rhs: &[u8]) -> Vec<u8> {
+    let mut result = lhs[..lhs.len().min(rhs.len())].to_vec();
+    xor_in_place(&mut result, rhs);
+    result
+}
+
+/// XORs `rhs` into `lhs`.
+pub fn xor_in_place(lhs: &mut [u8], rhs: &[u8]) {
+    let size = lhs.len().min(rhs.len());
+    let (lhs, rhs) = (&mut lhs[..size], &rhs[..size]);
+    let mut lhs_lanes = lhs.chunks_exact_mut(LANE_SIZE);
+    let mut rhs_lanes = rhs.chunks_exact(LANE_SIZE);
+    for (x, y) in (&mut lhs_lanes).zip(&mut rhs_lanes) {
+        x.copy_from_slice(&(to_lane(x) ^ to_lane(y)).to_ne_bytes());
+    }
+    lhs_lanes.into_remainder().iter_mut().zip(rhs_lanes.remainder()).for_each(|(x, y)| *x ^= y);
+}
+
+/// Returns the number of differing bits between the two buffers.
+pub fn hamming_distance(lhs: &[u8], rhs: &[u8]) -> u32 {
+    let size = lhs.len().min(rhs.len());
+    let lhs_lanes = lhs[..size].chunks_exact(LANE_SIZE);
+    let rhs_lanes = rhs[..size].chunks_exact(LANE_SIZE);
+    let remainder: u32 = lhs_lanes.remainder().iter()
+        .zip(rhs_lanes.remainder())
+        .map(|(x, y)| (x ^ y).count_ones())
+        .sum();
+    lhs_lanes.zip(rhs_lanes)
+        .map(|(x, y)| (to_lane(x) ^ to_lane(y)).count_ones())
+        .sum::<u32>() + remainder
+}
+
+/// Returns true if the buffers are equal. Unlike the other binary operations, buffers of
+/// different sizes are never equal. The running time only depends on the sizes of the buffers.
+pub fn constant_time_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    if lhs.len() != rhs.len() {
+        return false;
+    }
+    let difference = lhs.iter().zip(rhs).fold(0, |difference, (x, y)| difference | (x ^ y));
+    core::hint::black_box(difference) == 0
+}
+
+/// Returns the block with the given index, or `None` if the buffer does not contain a complete
+/// block at the index.
+pub fn get_block(buffer: &[u8], block_size: usize, index: usize) -> Option<&[u8]> {
+    buffer.get(index * block_size..(index + 1) * block_size)
+}
+
+/// Returns true if both buffers contain the same complete block at the given index.
+pub fn blocks_equal(lhs: &[u8], rhs: &[u8], block_size: usize, index: usize) -> bool {
+    match (get_block(lhs, block_size, index), get_block(rhs, block_size, index)) {
+        (Some(lhs), Some(rhs)) => lhs == rhs,
+        _ => false,
+    }
+}
+
+/// Returns the index of the first block which differs between the two buffers. A partial or
+/// missing block is considered different from a complete block.
+///
+/// # Panics
+///
+/// Panics if the block size is zero.
+pub fn first_differing_block(lhs: &[u8], rhs: &[u8], block_size: usize) -> Option<usize> {
+    assert!(block_size > 0, "block size must be non-zero");
+    let mut lhs_blocks = lhs.chunks(block_size);
+    let mut rhs_blocks = rhs.chunks(block_size);
+    let mut index = 0;
+    loop {
+        match (lhs_blocks.next(), rhs_blocks.next()) {
+            (None, None) => return None,
+            (lhs, rhs) if lhs != rhs => return Some(index),
+            _ => index += 1,
+        }
+    }
+}
+
+/// Returns the index of the first pair of identical consecutive blocks.
+///
+/// # Panics
+///
+/// Panics if the block size is zero.
+pub fn find_identical_consecutive_blocks(buffer: &[u8], block_size: usize) -> Option<usize> {
+    assert!(block_size > 0, "block size must be non-zero");
+    let blocks: Vec<&[u8]> = buffer.chunks_exact(block_size).collect();
+    blocks.windows(2).position(|pair| pair[0] == pair[1])
+}
+
+/// Returns the number of blocks which are identical to some earlier block in the buffer. A
+/// trailing partial block is ignored.
+///
+/// # Panics
+///
+/// Panics if the block size is zero.
+pub fn count_repeated_blocks(buffer: &[u8], block_size: usize) -> usize {
+    assert!(block_size > 0, "block size must be non-zero");
+    let blocks = buffer.chunks_exact(block_size);
+    let total = blocks.len();
+    total - blocks.collect::<BTreeSet<_>>().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transpose() {
+        let columns = transpose_chunks(b"abcdefgh", 3);
+        assert_eq!(columns, vec![b"adg".to_vec(), b"beh".to_vec(), b"cf".to_vec()]);
+        assert_eq!(transpose_chunks(b"", 2), vec![Vec::<u8>::new(), Vec::new()]);
+    }
+
+    #[test]
+    fn xor() {
+        assert_eq!(xor_slices(&[0x0f, 0xf0, 0xff], &[0xff, 0xff]), vec![0xf0, 0x0f]);
+        let mut buffer = vec![1, 2, 3];
+        xor_in_place(&mut buffer, &[1, 2, 3, 4]);
+        assert_eq!(buffer, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn constant_time_equality() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+    }
+
+    #[test]
+    fn lanes_and_remainder() {
+        // Compare against the scalar implementation for sizes around the lane size.
+        for size in 0..3 * LANE_SIZE {
+            let lhs: Vec<u8> = (0..size as u8).map(|x| x.wrapping_mul(37)).collect();
+            let rhs: Vec<u8> = (0..size as u8 + 1).map(|x| x.wrapping_mul(101) ^ 0x5a).collect();
+            let expected: Vec<u8> = lhs.iter().zip(&rhs).map(|(x, y)| x ^ y).collect();
+            assert_eq!(xor_slices(&lhs, &rhs), expected);
+            assert_eq!(xor_slices(&rhs, &lhs), expected);
+            assert_eq!(
+                hamming_distance(&lhs, &rhs),
+                expected.iter().map(|x| x.count_ones()).sum::<u32>()
+            );
+        }
+        assert_eq!(hamming_distance(b"this is a test", b"wokka wokka!!!"), 37);
+    }
+
+    #[test]
+    fn compare_blocks() {
+        let lhs = b"aaaabbbbbbbbcc";
+        let rhs = b"aaaaccccbbbbcc";
+        assert_eq!(get_block(lhs, 4, 1), Some(&b"bbbb"[..]));
+        assert_eq!(get_block(lhs, 4, 3), None);
+        assert!(blocks_equal(lhs, rhs, 4, 2));
+        assert!(!blocks_equal(lhs, rhs, 4, 1));
+        assert_eq!(first_differing_block(lhs, rhs, 4), Some(1));
+        assert_eq!(first_differing_block(lhs, lhs, 4), None);
+        assert_eq!(first_differing_block(lhs, &lhs[..12], 4), Some(3));
+        assert_eq!(find_identical_consecutive_blocks(lhs, 4), Some(1));
+        assert_eq!(find_identical_consecutive_blocks(rhs, 4), None);
+        assert_eq!(count_repeated_blocks(b"aaaabbbbaaaaaaaa", 4), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "block size must be non-zero")]
+    fn zero_block_size() {
+        count_repeated_blocks(b"aaaa", 0);
+    }
+}
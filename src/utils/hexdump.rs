@@ -0,0 +1,127 @@
+//! Block-aligned hexdumps for debugging attacks on block ciphers.
+//!
+//! Each row contains a single block, followed by its printable characters. Blocks which are
+//! identical to an earlier block are marked with the offset of the first occurrence. When the
+//! buffer is compared against another buffer, differing bytes are marked with `^^` on the
+//! following line. For example, dumping `YELLOW SUBMARINEYELLOW SUBMARINE` using a block size of
+//! 16 gives
+//!
+//! ```text
+//! 00000000  59 45 4c 4c 4f 57 20 53 55 42 4d 41 52 49 4e 45  |YELLOW SUBMARINE|
+//! 00000010  59 45 4c 4c 4f 57 20 53 55 42 4d 41 52 49 4e 45  |YELLOW SUBMARINE|  == 00000000
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Hexdump<'a> {
+    buffer: &'a [u8],
+    block_size: usize,
+    other: Option<&'a [u8]>,
+}
+
+impl<'a> Hexdump<'a> {
+    /// Returns a hexdump of the buffer with one block per row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block size is zero.
+    pub fn new(buffer: &'a [u8], block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must be non-zero");
+        Hexdump { buffer, block_size, other: None }
+    }
+
+    /// Returns a hexdump which marks bytes that differ from the corresponding bytes in `other`.
+    /// Bytes past the end of `other` are considered different.
+    pub fn with_diff(mut self, other: &'a [u8]) -> Self {
+        self.other = Some(other);
+        self
+    }
+
+    fn is_different(&self, offset: usize) -> bool {
+        match self.other {
+            Some(other) => other.get(offset) != Some(&self.buffer[offset]),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for Hexdump<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let mut first_offsets = HashMap::new();
+        for (index, block) in self.buffer.chunks(self.block_size).enumerate() {
+            let offset = index * self.block_size;
+            write!(formatter, "{:08x} ", offset)?;
+            for byte in block {
+                write!(formatter, " {:02x}", byte)?;
+            }
+            // Pad the final block so that the printable characters are aligned.
+            let padding = 3 * (self.block_size - block.len());
+            write!(formatter, "{:padding$}  |", "", padding = padding)?;
+            for &byte in block {
+                let c = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+                write!(formatter, "{}", c)?;
+            }
+            write!(formatter, "|")?;
+
+            // Only complete blocks are considered identical.
+            if block.len() == self.block_size {
+                let first_offset = *first_offsets.entry(block).or_insert(offset);
+                if first_offset != offset {
+                    write!(formatter, "  == {:08x}", first_offset)?;
+                }
+            }
+            writeln!(formatter)?;
+
+            if (offset..offset + block.len()).any(|offset| self.is_different(offset)) {
+                let markers: Vec<&str> = (offset..offset + block.len())
+                    .map(|offset| if self.is_different(offset) { "^^" } else { "  " })
+                    .collect();
+                writeln!(formatter, "{:9} {}", "", markers.join(" ").trim_end())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns a hexdump of the buffer with one block per row.
+pub fn hexdump(buffer: &[u8], block_size: usize) -> String {
+    Hexdump::new(buffer, block_size).to_string()
+}
+
+/// Returns a hexdump of `buffer` with one block per row, marking the bytes which differ from
+/// `other`.
+pub fn diff(buffer: &[u8], other: &[u8], block_size: usize) -> String {
+    Hexdump::new(buffer, block_size).with_diff(other).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_blocks() {
+        let output = hexdump(b"abcdabcdxy\x00zabcdq", 4);
+        assert_eq!(output, concat!(
+            "00000000  61 62 63 64  |abcd|\n",
+            "00000004  61 62 63 64  |abcd|  == 00000000\n",
+            "00000008  78 79 00 7a  |xy.z|\n",
+            "0000000c  61 62 63 64  |abcd|  == 00000000\n",
+            "00000010  71           |q|\n",
+        ));
+        assert_eq!(hexdump(b"", 16), "");
+    }
+
+    #[test]
+    fn differing_bytes() {
+        let output = diff(b"abcdefghij", b"abXdefghI", 4);
+        assert_eq!(output, concat!(
+            "00000000  61 62 63 64  |abcd|\n",
+            "                ^^\n",
+            "00000004  65 66 67 68  |efgh|\n",
+            "00000008  69 6a        |ij|\n",
+            "          ^^ ^^\n",
+        ));
+    }
+}
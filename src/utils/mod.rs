@@ -0,0 +1,5 @@
+pub mod bytes;
+#[cfg(feature = "std")]
+pub mod hexdump;
+#[cfg(feature = "std")]
+pub mod search;
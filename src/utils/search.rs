@@ -0,0 +1,73 @@
+//! Exhaustive search primitives. When the `parallel` feature is enabled, the search is split
+//! between threads using rayon. Results are always identical to the sequential versions in
+//! `sequential`, which are used when the feature is disabled.
+
+use std::ops::Range;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Returns the first value in the range (in order) which satisfies the predicate.
+#[cfg(feature = "parallel")]
+pub fn find_first<F>(range: Range<u64>, predicate: F) -> Option<u64>
+    where F: Fn(u64) -> bool + Sync + Send
+{
+    range.into_par_iter().find_first(|&value| predicate(value))
+}
+
+/// Returns the first value in the range (in order) which satisfies the predicate.
+#[cfg(not(feature = "parallel"))]
+pub fn find_first<F>(range: Range<u64>, predicate: F) -> Option<u64>
+    where F: Fn(u64) -> bool + Sync + Send
+{
+    sequential::find_first(range, predicate)
+}
+
+/// Applies the function to each item, and returns the results in the same order as the items.
+#[cfg(feature = "parallel")]
+pub fn map<T, U, F>(items: &[T], function: F) -> Vec<U>
+    where T: Sync, U: Send, F: Fn(&T) -> U + Sync + Send
+{
+    items.par_iter().map(function).collect()
+}
+
+/// Applies the function to each item, and returns the results in the same order as the items.
+#[cfg(not(feature = "parallel"))]
+pub fn map<T, U, F>(items: &[T], function: F) -> Vec<U>
+    where T: Sync, U: Send, F: Fn(&T) -> U + Sync + Send
+{
+    sequential::map(items, function)
+}
+
+pub mod sequential {
+    use std::ops::Range;
+
+    /// Returns the first value in the range which satisfies the predicate.
+    pub fn find_first<F: Fn(u64) -> bool>(mut range: Range<u64>, predicate: F) -> Option<u64> {
+        range.find(|&value| predicate(value))
+    }
+
+    /// Applies the function to each item.
+    pub fn map<T, U, F: Fn(&T) -> U>(items: &[T], function: F) -> Vec<U> {
+        items.iter().map(function).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_to_sequential() {
+        // Several values satisfy the predicate, so the first one must be chosen.
+        let predicate = |value: u64| value.wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 56 == 0x42;
+        let expected = sequential::find_first(0..1 << 20, predicate);
+        assert!(expected.is_some());
+        assert_eq!(find_first(0..1 << 20, predicate), expected);
+        assert_eq!(find_first(0..1 << 10, |_| false), None);
+
+        let items: Vec<u64> = (0..1000).collect();
+        let function = |&item: &u64| item.count_ones() * 3;
+        assert_eq!(map(&items, function), sequential::map(&items, function));
+    }
+}
@@ -1,15 +1,14 @@
 mod set_1 {
     
     mod problem_1 {
-        use hex;
-        use base64;
+        use cryptopals::encoding::{hex, base64};
 
         #[test]
         fn solution() {
             let hex_str = 
                 "49276d206b696c6c696e6720796f757220627261696e206c\
                  696b65206120706f69736f6e6f7573206d757368726f6f6d".to_owned();
-            let base64_str = base64::encode(&hex::decode(hex_str).unwrap());
+            let base64_str = base64::encode(hex::decode(hex_str).unwrap());
             assert_eq!(
                 base64_str,
                 "SSdtIGtpbGxpbmcgeW91ciBicmFpbiBsaWtlIGEgcG9pc29ub3VzIG11c2hyb29t"
@@ -18,23 +17,21 @@ mod set_1 {
     }
 
     mod problem_2 {
-        use hex;
+        use cryptopals::encoding::hex;
+        use cryptopals::utils::bytes;
 
         #[test]
         fn solution() {
             let lhs = hex::decode("1c0111001f010100061a024b53535009181c").unwrap();
             let rhs = hex::decode("686974207468652062756c6c277320657965").unwrap();
-            let result: Vec<u8> = lhs.iter()
-                .zip(rhs.iter())
-                .map(|(x, y)| x ^ y)
-                .collect();
+            let result = bytes::xor_slices(&lhs, &rhs);
             assert_eq!(hex::encode(result), "746865206b696420646f6e277420706c6179");
         }
     }
 
     mod problem_3 {
-        use hex;
         use cryptopals::attacks::statistics::single_byte_xor;
+        use cryptopals::encoding::hex;
 
         #[test]
         fn solution() {
@@ -42,30 +39,46 @@ mod set_1 {
                 "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736"
             ).unwrap();
             
-            let result = single_byte_xor::recover_plaintext(&ciphertext);
-            assert_eq!(result.unwrap(), "Cooking MC's like a pound of bacon");
+            let result = single_byte_xor::recover_plaintext(&ciphertext).unwrap();
+            assert_eq!(result.plaintext, "Cooking MC's like a pound of bacon");
+            assert_eq!(result.key, b'X');
+            assert_eq!(result.candidates.len(), single_byte_xor::CANDIDATES);
+            assert_eq!(result.candidates[0].key, b'X');
+        }
+
+        #[test]
+        fn solution_with_scorers() {
+            use cryptopals::attacks::statistics::scoring::MonogramScorer;
+
+            let ciphertext = hex::decode(
+                "1b37373331363f78151b7f2b783431333d78397828372d363c78373e783a393b3736"
+            ).unwrap();
+
+            let result = single_byte_xor::recover_plaintext_with(&ciphertext, 1, &MonogramScorer::default());
+            assert_eq!(result.unwrap().plaintext, "Cooking MC's like a pound of bacon");
+
+            let keys = single_byte_xor::rank_keys(&ciphertext, 3, &MonogramScorer::default());
+            assert_eq!(keys.len(), 3);
+            assert_eq!(keys[0].0, b'X');
+            assert!(keys[0].1 <= keys[1].1 && keys[1].1 <= keys[2].1);
         }
     }
     
     mod problem_4 {
-        use hex;
         use cryptopals::attacks::statistics::detect_single_byte_xor;
 
         #[test]
         fn solution() {
-            let ciphertexts: Vec<Vec<u8>> = include_str!("../data/set_1/problem_4.txt")
-                .split('\n')
-                .map(|string| hex::decode(string).unwrap())
-                .collect();
+            let ciphertexts = cryptopals::data::read_hex_lines(1, 4).unwrap();
             
             let result = detect_single_byte_xor::recover_plaintext(&ciphertexts);
-            assert_eq!(result.unwrap(), "Now that the party is jumping\n");
+            assert_eq!(result.unwrap().plaintext, "Now that the party is jumping\n");
         }
     }
 
     mod problem_5 {
-        use hex;
         use cryptopals::crypto::symmetric::{StreamCipherMode, RepeatingKeyXor};
+        use cryptopals::encoding::hex;
         
         #[test]
         fn solution() {
@@ -83,33 +96,105 @@ mod set_1 {
     }
 
     mod problem_6 {
-        use base64;
         use cryptopals::attacks::statistics::repeating_key_xor;
 
         #[test]
         fn solution() {
-            let ciphertext = base64::decode(
-                &include_str!("../data/set_1/problem_6.txt").replace("\n", "")
-            ).unwrap();
+            let ciphertext = cryptopals::data::read_base64(1, 6).unwrap();
             
             // This decodes the plaintext as UTF-8.
             let result = repeating_key_xor::recover_plaintext(&ciphertext, None);
             assert!(result.is_ok());
         }
+
+        #[test]
+        fn solution_using_index_of_coincidence() {
+            use repeating_key_xor::KeySizeDetector;
+
+            let ciphertext = cryptopals::data::read_base64(1, 6).unwrap();
+
+            assert_eq!(KeySizeDetector::IndexOfCoincidence.detect(&ciphertext, repeating_key_xor::MAX_KEY_SIZE), 29);
+            let result = repeating_key_xor::recover_plaintext_using(
+                &ciphertext,
+                KeySizeDetector::IndexOfCoincidence,
+                repeating_key_xor::KeySizeSearch::new()
+            );
+            assert_eq!(result.ok(), repeating_key_xor::recover_plaintext(&ciphertext, None).ok());
+        }
+
+        #[test]
+        fn detection_of_long_keys() {
+            use cryptopals::crypto::symmetric::{RepeatingKeyXor, StreamCipherMode};
+            use repeating_key_xor::{KeySizeDetector, KeySizeSearch};
+
+            let ciphertext = cryptopals::data::read_base64(1, 6).unwrap();
+            let plaintext = repeating_key_xor::recover_plaintext(&ciphertext, None).unwrap().plaintext;
+
+            // Keys longer than the default maximum are only detected if the maximum is raised.
+            let key: Vec<u8> = (0..47).map(|index| (31 * index + 7) as u8).collect();
+            let ciphertext = RepeatingKeyXor::new(&key).encrypt_buffer(plaintext.as_bytes()).unwrap();
+            assert_eq!(KeySizeDetector::IndexOfCoincidence.detect(&ciphertext, 60), 47);
+            let result = repeating_key_xor::recover_plaintext_using(
+                &ciphertext,
+                KeySizeDetector::IndexOfCoincidence,
+                KeySizeSearch::new().with_max_key_size(60)
+            );
+            assert_eq!(result.unwrap().key, key);
+        }
+
+        #[test]
+        fn solution_using_kasiski_examination() {
+            use repeating_key_xor::KeySizeDetector;
+
+            let ciphertext = cryptopals::data::read_base64(1, 6).unwrap();
+
+            assert_eq!(repeating_key_xor::kasiski_examination(&ciphertext, 3, repeating_key_xor::MAX_KEY_SIZE)[0].0, 29);
+            assert_eq!(KeySizeDetector::Kasiski.detect(&ciphertext, repeating_key_xor::MAX_KEY_SIZE), 29);
+        }
+
+        #[test]
+        fn zero_sizes() {
+            use repeating_key_xor::{KeySizeDetector, KeySizeSearch};
+
+            let ciphertext = cryptopals::data::read_base64(1, 6).unwrap();
+
+            assert!(repeating_key_xor::kasiski_examination(&ciphertext, 0, repeating_key_xor::MAX_KEY_SIZE).is_empty());
+            assert!(repeating_key_xor::recover_plaintext(&ciphertext, Some(0)).is_err());
+            let search = KeySizeSearch::new().with_max_key_size(0);
+            assert!(repeating_key_xor::recover_plaintext_using(&ciphertext, KeySizeDetector::Kasiski, search).is_err());
+        }
+
+        #[test]
+        fn ranked_key_sizes() {
+            let ciphertext = cryptopals::data::read_base64(1, 6).unwrap();
+
+            let key_sizes = repeating_key_xor::rank_key_sizes(&ciphertext, 40, 3);
+            assert_eq!(key_sizes[0].0, 29);
+            let result = repeating_key_xor::recover_plaintext(&ciphertext, None).unwrap();
+            assert_eq!(result.key, b"Terminator X: Bring the noise");
+            assert!(result.plaintext.starts_with("I'm back and I'm ringin' the bell"));
+
+            // Restricting the search bounds the size of the recovered key.
+            let search = repeating_key_xor::KeySizeSearch::new().with_max_key_size(20).with_candidates(1);
+            let result = repeating_key_xor::recover_plaintext_with(
+                &ciphertext,
+                None,
+                search,
+                &cryptopals::attacks::statistics::scoring::MonogramScorer::default()
+            );
+            let result = result.unwrap();
+            assert!(result.key.len() <= 20);
+        }
     }
 
     mod problem_7 {
-        use base64;
-
         use cryptopals::crypto::symmetric::{BlockCipherMode, Aes128Ecb};
 
         #[test]
         fn solution() {
             let key = "YELLOW SUBMARINE".as_bytes();
-            let mut cipher = Aes128Ecb::new(&key).unwrap();
-            let ciphertext = base64::decode(
-                &include_str!("../data/set_1/problem_7.txt").replace("\n", "")
-            ).unwrap();
+            let mut cipher = Aes128Ecb::new(key).unwrap();
+            let ciphertext = cryptopals::data::read_base64(1, 7).unwrap();
             
             // This decodes the plaintext as UTF-8.
             let result = cipher.decrypt_str(&ciphertext);
@@ -118,21 +203,39 @@ mod set_1 {
     }
 
     mod problem_8 {
-        use hex;
-
         use cryptopals::attacks::symmetric::ecb_detection;
 
         #[test]
         fn solution() {
-            let ciphertexts: Vec<Vec<u8>> =include_str!("../data/set_1/problem_8.txt")
-                .split('\n')
-                .map(|string| hex::decode(string).unwrap())
-                .collect();
+            let ciphertexts = cryptopals::data::read_hex_lines(1, 8).unwrap();
             
             let result = ciphertexts.iter().any(|ciphertext|
-                ecb_detection::detect_ecb_mode(&ciphertext)
+                ecb_detection::detect_ecb_mode(ciphertext)
             );
             assert!(result);
         }
+
+        #[test]
+        fn solution_in_set() {
+            use cryptopals::oracles::symmetric::ecb_detection::get_ciphertexts;
+
+            let ciphertexts = get_ciphertexts().unwrap();
+            let index = ecb_detection::detect_ecb_in_set(&ciphertexts).unwrap();
+            assert_eq!(index, 132);
+            assert!(ecb_detection::detect_ecb_mode(&ciphertexts[index]));
+        }
+
+        #[test]
+        fn repeated_blocks() {
+            let buffer = b"YELLOW SUBMARINEyellow submarineYELLOW SUBMARINEYELLOW SUBMARINE!";
+            assert_eq!(
+                ecb_detection::find_repeated_blocks(buffer, 16), 
+                vec![(0, 2), (0, 3), (2, 3)]
+            );
+            assert_eq!(ecb_detection::get_repeated_block_score(buffer, 16), 2);
+            
+            // Blocks which only agree on the first 8 bytes are not repeated.
+            assert!(!ecb_detection::detect_ecb_mode(b"YELLOW SUBMARINEYELLOW Submarine"));
+        }
     }
 }
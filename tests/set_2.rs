@@ -17,7 +17,6 @@ mod set_2 {
     }
 
     mod problem_10 {
-        use base64;
         use cryptopals::crypto::symmetric::{
             BlockCipherMode, Aes128Cbc, 
             Cipher, Aes128
@@ -29,8 +28,7 @@ mod set_2 {
            let iv = [0; Aes128::BLOCK_SIZE];
            let mut cipher = Aes128Cbc::new(key, &iv).unwrap();
 
-           let buffer = include_str!("../data/set_2/problem_10.txt").replace("\n", "");
-           let buffer = base64::decode(&buffer).unwrap().to_owned();
+           let buffer = cryptopals::data::read_base64(2, 10).unwrap();
 
            // This decodes the plaintext as UTF-8.
            let result = cipher.decrypt_str(&buffer);
@@ -54,6 +52,32 @@ mod set_2 {
         }
     }
 
+    mod mode_detection {
+        use cryptopals::{oracles, attacks};
+        use oracles::symmetric::mode_detection::{Oracle, Mode};
+        use attacks::symmetric::mode_detection::get_cipher_mode;
+
+        #[test]
+        fn solution() {
+            let mut oracle: Oracle = Default::default(); 
+            let mut correct = 0;
+            for _ in 0..200 {
+                let confidence = get_cipher_mode(|buffer| oracle.encrypt_buffer(buffer)).unwrap(); 
+                let mode = oracle.cipher_mode().unwrap();
+                
+                // The actual mode is never ruled out, and ECB is always detected.
+                assert!(confidence.of(mode) > 0.0);
+                if mode == Mode::Ecb {
+                    assert_eq!(confidence.mode(), Mode::Ecb);
+                }
+                if confidence.mode() == mode {
+                    correct += 1;
+                }
+            }
+            assert!(correct >= 170);
+        }
+    }
+
     mod problem_12 {
         use cryptopals::{oracles, attacks};
         use oracles::symmetric::simple_ecb_decryption::Oracle;
@@ -68,6 +92,63 @@ mod set_2 {
             assert!(result.is_ok());
             assert_eq!(result.unwrap(), oracle.unknown_data); 
         }
+
+        #[test]
+        fn query_count() {
+            use cryptopals::oracles::{Oracle as _, Counted};
+
+            // One query per recovered byte, together with a few queries for the block size.
+            let mut oracle = Counted::new(Oracle::new(false).unwrap()).with_budget(160);
+            let result = get_unknown_data(|buffer| { oracle.query(buffer) });
+            assert_eq!(result.unwrap(), oracle.inner().unknown_data);
+        }
+
+        #[cfg(feature = "async")]
+        #[test]
+        fn solution_using_async_remote_oracle() {
+            use std::sync::Arc;
+            use cryptopals::crypto::symmetric::Error;
+            use cryptopals::oracles::remote::asynchronous::{serve, Client};
+            use attacks::asynchronous::simple_ecb_decryption;
+
+            let oracle = Oracle::new(false).unwrap();
+            let unknown_data = oracle.unknown_data.clone();
+            let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+            let result = runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                let address = listener.local_addr().unwrap();
+                tokio::spawn(serve(listener, oracle));
+
+                let client: Client<Vec<u8>, Error> = Client::connect(address, 4).await.unwrap();
+                simple_ecb_decryption::get_unknown_data(Arc::new(client), 4).await
+            });
+            assert_eq!(result.unwrap(), unknown_data);
+        }
+
+        #[test]
+        fn solution_with_arbitrary_data() {
+            use cryptopals::random_vec;
+
+            for size in 0..=48 {
+                let unknown_data = random_vec!(size);
+                let mut oracle = Oracle::new(false).unwrap().with_unknown_data(&unknown_data);
+                let result = get_unknown_data(|buffer| { oracle.encrypt_buffer(buffer) });
+                assert_eq!(result.unwrap(), unknown_data);
+            }
+        }
+
+        #[test]
+        fn solution_in_parallel() {
+            use std::sync::Mutex;
+            use attacks::symmetric::simple_ecb_decryption::get_unknown_data_parallel;
+
+            let oracle = Mutex::new(Oracle::new(false).unwrap());
+            let result = get_unknown_data_parallel(
+                |buffer| { oracle.lock().unwrap().encrypt_buffer(buffer) },
+                4
+            );
+            assert_eq!(result.unwrap(), oracle.lock().unwrap().unknown_data);
+        }
     }
 
     mod problem_13 {
@@ -83,6 +164,87 @@ mod set_2 {
             
             assert_eq!(oracle.get_role_from(&profile).unwrap(), Role::Admin);
         }
+
+        #[test]
+        fn profile_round_trip() {
+            use std::str::FromStr;
+            use oracles::symmetric::ecb_cut_and_paste::Profile;
+
+            let email = "foo%26bar&role=admin@bar.com";
+            let profile = Profile { email: email.to_owned(), uid: 10, role: Role::User };
+            let profile = Profile::from_str(&profile.to_string()).unwrap();
+            assert_eq!(profile.email, email);
+            assert_eq!(profile.role, Role::User);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn profile_serde_encoding() {
+            use std::str::FromStr;
+            use cryptopals::http;
+            use oracles::symmetric::ecb_cut_and_paste::Profile;
+
+            let profile = Profile { email: "foo&role=admin".to_owned(), uid: 10, role: Role::User };
+            let param_str = http::serde::to_string(&profile).unwrap();
+            assert_eq!(param_str, profile.to_string());
+            
+            let profile: Profile = http::serde::from_str("email=foo%40bar.com&uid=10&role=admin").unwrap();
+            let expected = Profile::from_str("email=foo%40bar.com&uid=10&role=admin").unwrap();
+            assert_eq!((profile.email, profile.uid, profile.role), (expected.email, expected.uid, expected.role));
+
+            // Duplicate and unknown keys are rejected by both implementations.
+            for param_str in &["email=foo&uid=10&role=user&role=admin", "email=foo&uid=10&role=user&x=1"] {
+                assert!(http::serde::from_str::<Profile>(param_str).is_err());
+                assert!(Profile::from_str(param_str).is_err());
+            }
+        }
+
+        #[test]
+        fn any_uid_size() {
+            use oracles::symmetric::hardened_cut_and_paste;
+
+            for digits in 1..=12 {
+                let mut oracle = hardened_cut_and_paste::Oracle::random().with_uid(10u64.pow(digits - 1));
+                let profile = get_admin_profile(|email| oracle.get_profile_for(email)).unwrap();
+                assert_eq!(oracle.get_role_from(&profile).unwrap(), Role::Admin);
+            }
+        }
+
+        #[test]
+        fn any_target_value() {
+            use cryptopals::http::urlencoding;
+            use crypto::symmetric::{BlockCipherMode, Aes128Ecb};
+            use attacks::symmetric::ecb_cut_and_paste::inject_value;
+
+            let key = [0x2b; 16];
+            for uid in [1, 10, 1234, 12345678] {
+                let mut get_profile_for = |email: &str| {
+                    let email = urlencoding::encode_delimiters(email, &['&', '=']);
+                    let param_str = format!("email={}&uid={}&role=user", email, uid);
+                    Ok(Aes128Ecb::new(&key).unwrap().encrypt_str(&param_str)?)
+                };
+                // Values may span several blocks.
+                for value in ["admin", "moderator", "0123456789abcdef", "superuser-with-a-long-role-name"] {
+                    let profile = inject_value(&mut get_profile_for, value).unwrap();
+                    let param_str = Aes128Ecb::new(&key).unwrap().decrypt_str(&profile).unwrap();
+                    assert!(param_str.ends_with(&format!("&uid={}&role={}", uid, value)));
+                }
+            }
+        }
+
+        #[test]
+        fn profile_layout() {
+            use attacks::symmetric::ecb_cut_and_paste::{get_layout, Layout};
+
+            let mut oracle = Oracle::random();
+            let layout = get_layout(&mut |email| oracle.get_profile_for(email)).unwrap();
+            assert_eq!(layout, Layout {
+                block_size: 16,
+                prefix_size: "email=".len(),
+                suffix_size: "&uid=10&role=user".len(),
+                value_size: "user".len(),
+            });
+        }
     }
 
     mod problem_14 {
@@ -101,6 +263,150 @@ mod set_2 {
                 assert_eq!(result.unwrap(), oracle.unknown_data); 
             }    
         }
+
+        #[test]
+        fn solution_for_all_prefix_sizes() {
+            use attacks::symmetric::harder_ecb_decryption::get_prefix_size;
+
+            for prefix_size in 0..4 * 16 {
+                let mut oracle = Oracle::with_prefix_size(prefix_size).unwrap();
+                let result = get_prefix_size(|buffer| { oracle.encrypt_buffer(buffer) });
+                assert_eq!(result.unwrap(), prefix_size);
+
+                let result = get_unknown_data(|buffer| { oracle.encrypt_buffer(buffer) });
+                assert_eq!(result.unwrap(), oracle.unknown_data); 
+            }
+        }
+    }
+
+    mod hardened_cut_and_paste {
+        use cryptopals::{oracles, attacks, crypto};
+        use oracles::symmetric::hardened_cut_and_paste::{Oracle, Encoding, EmailFormat, Charset};
+        use attacks::symmetric::hardened_cut_and_paste::{analyze, Outcome};
+        use crypto::random::Random;
+
+        #[test]
+        fn structural_characters() {
+            let mut oracle = Oracle::random().with_encoding(Encoding::PercentEncode);
+            assert_eq!(analyze(&mut oracle), Outcome::Ineffective);
+            
+            let mut oracle = Oracle::random().with_encoding(Encoding::Reject);
+            assert_eq!(analyze(&mut oracle), Outcome::Ineffective);
+        }
+
+        #[test]
+        fn email_format() {
+            let mut oracle = Oracle::random().with_email_format(EmailFormat::Minimal);
+            assert_eq!(analyze(&mut oracle), Outcome::Complicates);
+            
+            let mut oracle = Oracle::random()
+                .with_encoding(Encoding::Reject)
+                .with_email_format(EmailFormat::Minimal);
+            assert_eq!(analyze(&mut oracle), Outcome::Complicates);
+        }
+
+        #[test]
+        fn printable_charset() {
+            let mut oracle = Oracle::random().with_charset(Charset::Printable);
+            assert_eq!(analyze(&mut oracle), Outcome::Blocks);
+            
+            let mut oracle = Oracle::random()
+                .with_email_format(EmailFormat::Minimal)
+                .with_charset(Charset::Printable);
+            assert_eq!(analyze(&mut oracle), Outcome::Blocks);
+        }
+
+        #[test]
+        fn quoted_email() {
+            let mut oracle = Oracle::random().with_encoding(Encoding::Quote);
+            assert_eq!(analyze(&mut oracle), Outcome::Ineffective);
+            assert!(oracle.get_profile_for("\"&role=admin").is_err());
+        }
+
+        #[test]
+        fn uid_size() {
+            // The layout of the profile is discovered by the attack, so the size of the uid
+            // does not matter.
+            for digits in 1..=12 {
+                let mut oracle = Oracle::random().with_uid(10u64.pow(digits - 1));
+                assert_eq!(analyze(&mut oracle), Outcome::Ineffective);
+            }
+            let mut oracle = Oracle::random().with_random_uid();
+            assert_eq!(analyze(&mut oracle), Outcome::Ineffective);
+        }
+
+        #[test]
+        fn field_order() {
+            use oracles::symmetric::hardened_cut_and_paste::Field;
+
+            // The attack only requires the role to be the last field.
+            let mut oracle = Oracle::random().with_field_order([Field::Uid, Field::Email, Field::Role]);
+            assert_eq!(analyze(&mut oracle), Outcome::Ineffective);
+
+            let mut oracle = Oracle::random().with_field_order([Field::Email, Field::Role, Field::Uid]);
+            assert_eq!(analyze(&mut oracle), Outcome::Blocks);
+
+            let mut oracle = Oracle::random().with_field_order([Field::Role, Field::Email, Field::Uid]);
+            assert_eq!(analyze(&mut oracle), Outcome::Blocks);
+
+            let mut oracle = Oracle::random().with_shuffled_fields();
+            let expected = if oracle.field_order()[2] == Field::Role {
+                Outcome::Ineffective
+            } else {
+                Outcome::Blocks
+            };
+            assert_eq!(analyze(&mut oracle), expected);
+        }
+
+        #[test]
+        fn cbc_mode() {
+            use oracles::symmetric::hardened_cut_and_paste::Mode;
+
+            let mut oracle = Oracle::random().with_mode(Mode::Cbc);
+            assert_eq!(analyze(&mut oracle), Outcome::Blocks);
+        }
+
+        #[test]
+        fn ctr_mode_is_unsupported() {
+            use oracles::symmetric::hardened_cut_and_paste::Mode;
+
+            let mut oracle = Oracle::random().with_mode(Mode::Ctr);
+            assert!(oracle.get_profile_for("user@example.com").is_err());
+            assert!(oracle.get_role_from(&[0; 32]).is_err());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod json_cut_and_paste {
+        use cryptopals::{oracles, attacks, crypto};
+        use oracles::symmetric::json_cut_and_paste::{Role, Mode, Oracle};
+        use attacks::symmetric::json_cut_and_paste::{get_admin_profile_ecb, get_admin_profile_cbc};
+        use crypto::random::Random;
+
+        #[test]
+        fn solution_using_ecb() {
+            let mut oracle = Oracle::random().with_mode(Mode::Ecb);
+            let profile = get_admin_profile_ecb(|email| oracle.get_profile_for(email)).unwrap();
+
+            assert_eq!(oracle.get_role_from(&profile).unwrap(), Role::Admin);
+        }
+
+        #[test]
+        fn solution_using_cbc() {
+            let mut oracle = Oracle::random().with_mode(Mode::Cbc);
+            let profile = get_admin_profile_cbc(|email| oracle.get_profile_for(email)).unwrap();
+
+            assert_eq!(oracle.get_role_from(&profile).unwrap(), Role::Admin);
+        }
+
+        #[test]
+        fn quotes_are_escaped() {
+            for mode in [Mode::Ecb, Mode::Cbc] {
+                let mut oracle = Oracle::random().with_mode(mode);
+                let profile = oracle.get_profile_for("\\\",\"role\":\"admin").unwrap();
+                assert_eq!(oracle.get_role_from(&profile).unwrap(), Role::User);
+            }
+        }
     }
 
     mod problem_15 {
@@ -127,21 +433,36 @@ mod set_2 {
     mod problem_16 {
         use cryptopals::{oracles, attacks, crypto};
         use oracles::symmetric::cbc_bitflipping_attacks::Oracle;
-        use attacks::symmetric::cbc_bitflipping_attacks::get_admin_profile;
+        use attacks::symmetric::cbc_bitflipping_attacks::{get_admin_profile, get_prefix_size};
         use crypto::random::Random;
     
         #[test]
         fn solution() {
             let mut oracle = Oracle::random();
-            // We assume that we know the size of the prefix. Alternatively, we could 
-            // guess the size of the prefix and query the oracle once for verification.
-            let comment_1 = "comment1=cooking%20MCs";
-            let result = get_admin_profile(
-                comment_1.len(), 
-                &mut |buffer| { oracle.encrypt_user_data(buffer) }
-            );
+            let result = get_admin_profile(&mut |buffer| { oracle.encrypt_user_data(buffer) });
             assert!(result.is_ok());
             assert_eq!(oracle.is_admin_user(&result.unwrap()), Ok(true));
         }
+
+        #[test]
+        fn prefix_size() {
+            let mut oracle = Oracle::random();
+            let result = get_prefix_size(&mut |buffer| { oracle.encrypt_user_data(buffer) });
+            assert_eq!(result.unwrap(), "comment1=cooking%20MCs;userdata=".len());
+        }
+
+        #[test]
+        fn known_prefix_size() {
+            use attacks::symmetric::cbc_bitflipping_attacks::get_admin_profile_with_prefix_size;
+
+            let mut oracle = Oracle::random();
+            let mut queries = 0;
+            let result = get_admin_profile_with_prefix_size(
+                &mut |buffer| { queries += 1; oracle.encrypt_user_data(buffer) },
+                "comment1=cooking%20MCs;userdata=".len()
+            );
+            assert_eq!(queries, 1);
+            assert_eq!(oracle.is_admin_user(&result.unwrap()), Ok(true));
+        }
     }
 }
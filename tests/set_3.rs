@@ -1,14 +1,13 @@
 mod set_3 {
 
     mod problem_17 {
-        use cryptopals::{oracles, attacks, crypto};
+        use cryptopals::{oracles, attacks};
         use oracles::symmetric::cbc_padding_oracle::Oracle;
         use attacks::symmetric::cbc_padding_oracle::get_plaintext_buffer;
-        use crypto::random::Random;
 
         #[test]
         fn solution() {
-            let mut oracle = Oracle::random();
+            let mut oracle = Oracle::new().unwrap();
             let buffer = oracle.get_encrypted_buffer().unwrap();
             let result = get_plaintext_buffer(
                 &buffer,
@@ -18,12 +17,335 @@ mod set_3 {
             // Check that the result is correct by attempting to decode the buffer as UTF-8.
             assert!(String::from_utf8(result.unwrap()).is_ok());
         }
+
+        #[test]
+        fn query_count() {
+            use cryptopals::oracles::{Oracle as _, Counted};
+
+            let mut oracle = Counted::new(Oracle::new().unwrap());
+            let buffer = oracle.inner_mut().get_encrypted_buffer().unwrap();
+            let result = get_plaintext_buffer(&buffer, &mut |buffer| oracle.query(buffer).unwrap());
+            assert!(result.is_ok());
+            
+            // At most 256 guesses and one verification query per recovered byte.
+            assert!(oracle.queries() <= 257 * (buffer.len() - 16));
+        }
+
+        #[test]
+        fn invalid_lengths() {
+            use cryptopals::oracles::Oracle as _;
+
+            let mut oracle = Oracle::new().unwrap();
+            assert_eq!(oracle.query(&[]), Ok(false));
+            assert_eq!(oracle.query(&[0; 5]), Ok(false));
+            assert_eq!(oracle.query(&[0; 33]), Ok(false));
+        }
+
+        #[test]
+        fn solution_using_remote_oracle() {
+            use std::net::TcpListener;
+            use std::thread;
+            use cryptopals::crypto::symmetric::Error;
+            use cryptopals::oracles::Oracle as _;
+            use cryptopals::oracles::remote::{serve_connection, Client};
+
+            let mut oracle = Oracle::new().unwrap();
+            let buffer = oracle.get_encrypted_buffer().unwrap();
+
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let address = listener.local_addr().unwrap();
+            let server = thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                serve_connection(stream, &mut oracle)
+            });
+
+            let mut client: Client<bool, Error> = Client::connect(address).unwrap();
+            let result = get_plaintext_buffer(&buffer, &mut |buffer| client.query(buffer).unwrap());
+            assert!(String::from_utf8(result.unwrap()).is_ok());
+
+            drop(client);
+            assert!(server.join().unwrap().is_ok());
+        }
+
+        #[cfg(feature = "async")]
+        #[test]
+        fn solution_using_async_remote_oracle() {
+            use std::sync::Arc;
+            use cryptopals::crypto::symmetric::{Error, Aes128, Pkcs7};
+            use cryptopals::oracles::remote::asynchronous::{serve, Client};
+            use attacks::asynchronous::cbc_padding_oracle;
+
+            let mut oracle = Oracle::new().unwrap();
+            let buffer = oracle.get_encrypted_buffer().unwrap();
+            let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+            let result = runtime.block_on(async move {
+                let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                let address = listener.local_addr().unwrap();
+                tokio::spawn(serve(listener, oracle));
+
+                let client: Client<bool, Error> = Client::connect(address, 8).await.unwrap();
+                cbc_padding_oracle::get_plaintext_buffer::<Aes128, Pkcs7, _>(&buffer, Arc::new(client)).await
+            });
+            assert!(String::from_utf8(result.unwrap()).is_ok());
+        }
+
+        #[test]
+        fn solution_with_arbitrary_plaintexts() {
+            use cryptopals::random_vec;
+
+            for size in 0..=48 {
+                let plaintext = random_vec!(size);
+                let mut oracle = Oracle::new().unwrap().with_plaintexts(vec![plaintext.clone()]);
+                let buffer = oracle.get_encrypted_buffer().unwrap();
+                let result = get_plaintext_buffer(
+                    &buffer,
+                    &mut |buffer| oracle.verify_padding(buffer)
+                );
+                assert_eq!(result.unwrap(), plaintext);
+            }
+        }
+
+        #[test]
+        fn solution_for_all_padding_sizes() {
+            use cryptopals::crypto::symmetric::{Aes128Cbc, BlockCipherMode};
+
+            let iv = [0; 16];
+            let cipher = Aes128Cbc::new(&[1; 16], &iv).unwrap();
+            for size in 0..=32 {
+                // Plaintexts ending with e.g. `02 02` give false positives for the last byte.
+                let plaintext = vec![2; size];
+                let encrypted_buffer = [&iv[..], &cipher.clone().encrypt_buffer(&plaintext).unwrap()].concat();
+                let result = get_plaintext_buffer(
+                    &encrypted_buffer,
+                    &mut |buffer| cipher.clone().decrypt_buffer(buffer).is_ok()
+                );
+                assert_eq!(result.unwrap(), plaintext);
+            }
+        }
+
+        #[test]
+        fn solution_with_other_padding() {
+            use cryptopals::crypto::symmetric::{Aes128, Cbc, BlockCipherMode, AnsiX923};
+            use attacks::symmetric::cbc_padding_oracle::get_plaintext_buffer_with;
+
+            let iv = [0; 16];
+            let cipher: Cbc<Aes128, AnsiX923> = Cbc::new(&[1; 16], &iv).unwrap();
+            let plaintext = b"Cooking MC's like a pound of bacon";
+            let encrypted_buffer = [&iv[..], &cipher.clone().encrypt_buffer(plaintext).unwrap()].concat();
+            let result = get_plaintext_buffer_with::<Aes128, AnsiX923, _>(
+                &encrypted_buffer,
+                &mut |buffer| cipher.clone().decrypt_buffer(buffer).is_ok()
+            );
+            assert_eq!(result.unwrap(), plaintext);
+        }
+
+        #[test]
+        fn trace_events() {
+            use cryptopals::oracles::{Oracle as _, Traced};
+            use cryptopals::trace::{self, Event, Log};
+
+            let plaintext = b"Cooking MC's like a pound of bacon".to_vec();
+            let mut oracle = Traced::new(
+                Oracle::new().unwrap().with_plaintexts(vec![plaintext.clone()]),
+                "padding"
+            );
+            let buffer = oracle.inner_mut().get_encrypted_buffer().unwrap();
+            let log = Log::new();
+            let result = trace::with_subscriber(log.clone(), || {
+                get_plaintext_buffer(&buffer, &mut |buffer| oracle.query(buffer).unwrap())
+            });
+            assert_eq!(result.unwrap(), plaintext);
+
+            let events = log.events();
+            let queries = events.iter().filter(|event| matches!(event, Event::Query { .. })).count();
+            let candidates = events.iter().filter(|event| matches!(event, Event::Candidate { .. })).count();
+            let progress: Vec<&Event> = events
+                .iter()
+                .filter(|event| matches!(event, Event::Progress { .. }))
+                .collect();
+            assert!(queries >= candidates);
+            assert_eq!(candidates, 3 * 16);
+            assert_eq!(progress.last(), Some(&&Event::Progress { 
+                attack: "cbc_padding_oracle", 
+                completed: 3, 
+                total: 3 
+            }));
+        }
+
+        #[test]
+        fn progress_and_cancellation() {
+            use cryptopals::crypto::symmetric::{Aes128, Pkcs7};
+            use cryptopals::progress::{self, CancellationToken, Event, Reporter};
+            use attacks::symmetric::cbc_padding_oracle::{get_plaintext_buffer_with_progress, Error};
+
+            let plaintext = b"Cooking MC's like a pound of bacon".to_vec();
+            let mut oracle = Oracle::new().unwrap().with_plaintexts(vec![plaintext.clone()]);
+            let buffer = oracle.get_encrypted_buffer().unwrap();
+            let (reporter, receiver) = progress::Channel::new();
+            let token = CancellationToken::new();
+            let result = get_plaintext_buffer_with_progress::<Aes128, Pkcs7, _, _>(
+                &buffer,
+                &mut |buffer| oracle.verify_padding(buffer),
+                &reporter,
+                &token
+            );
+            assert_eq!(result.unwrap(), plaintext);
+            let events: Vec<Event> = receiver.try_iter().collect();
+            let recovered: Vec<usize> = events
+                .iter()
+                .filter_map(|event| match event {
+                    Event::BytesRecovered { completed, total: 48 } => Some(*completed),
+                    _ => None
+                })
+                .collect();
+            assert_eq!(recovered, [16, 32, 48]);
+            assert!(events.iter().any(|event| matches!(event, Event::OracleCalls { count } if *count >= 48)));
+
+            // Cancel the attack once the first block has been recovered.
+            struct CancelOnProgress(CancellationToken);
+
+            impl Reporter for CancelOnProgress {
+                fn report(&self, event: Event) {
+                    if let Event::BytesRecovered { .. } = event {
+                        self.0.cancel();
+                    }
+                }
+            }
+
+            let token = CancellationToken::new();
+            let mut queries = 0;
+            let result = get_plaintext_buffer_with_progress::<Aes128, Pkcs7, _, _>(
+                &buffer,
+                &mut |buffer| {
+                    queries += 1;
+                    oracle.verify_padding(buffer)
+                },
+                &CancelOnProgress(token.clone()),
+                &token
+            );
+            assert!(matches!(result, Err(Error::Cancelled)));
+            assert!(queries < 2 * 16 * 256);
+        }
+
+        #[test]
+        fn solution_for_all_padding_modes() {
+            use std::sync::{Arc, Mutex};
+            use cryptopals::crypto::symmetric::{Aes128, PaddingMode, Pkcs7, AnsiX923, Iso7816};
+            use attacks::symmetric::cbc_padding_oracle::get_plaintext_buffer_with;
+
+            fn solve<P: PaddingMode + Clone>(mut oracle: Oracle<P>, plaintexts: &Mutex<Vec<Vec<u8>>>) {
+                for _ in 0..16 {
+                    let buffer = oracle.get_encrypted_buffer().unwrap();
+                    let result = get_plaintext_buffer_with::<Aes128, P, _>(
+                        &buffer,
+                        &mut |buffer| oracle.verify_padding(buffer)
+                    );
+                    assert_eq!(Some(result.unwrap()), plaintexts.lock().unwrap().pop());
+                }
+            }
+
+            // Plaintexts of varying sizes, ending with bytes which resemble padding.
+            let plaintexts = Arc::new(Mutex::new(Vec::new()));
+            let generator = |plaintexts: Arc<Mutex<Vec<Vec<u8>>>>| {
+                let mut size = 0;
+                move || {
+                    size += 3;
+                    let plaintext: Vec<u8> = (0..size).map(|index| [0, 1, 2, 0x80][index % 4]).collect();
+                    plaintexts.lock().unwrap().push(plaintext.clone());
+                    plaintext
+                }
+            };
+            solve(
+                Oracle::new().unwrap().with_generator(generator(plaintexts.clone())).with_padding::<Pkcs7>(),
+                &plaintexts
+            );
+            solve(
+                Oracle::new().unwrap().with_generator(generator(plaintexts.clone())).with_padding::<AnsiX923>(),
+                &plaintexts
+            );
+            solve(
+                Oracle::new().unwrap().with_generator(generator(plaintexts.clone())).with_padding::<Iso7816>(),
+                &plaintexts
+            );
+        }
+    }
+
+    mod cbc_error_oracle {
+        use cryptopals::{oracles, attacks, crypto};
+        use oracles::symmetric::cbc_error_oracle::{Oracle, Disclosure, Error};
+        use attacks::symmetric::cbc_error_oracle::get_plaintext_buffer;
+        use crypto::random::Random;
+
+        const PLAINTEXT: &str = "Cooking MC's like a pound of bacon, naïvely";
+
+        #[test]
+        fn distinct_errors() {
+            let oracle = Oracle::random();
+            let mut buffer = oracle.encrypt_str(PLAINTEXT).unwrap();
+            assert_eq!(oracle.submit_buffer(&buffer), Ok(()));
+
+            let size = buffer.len();
+            buffer[size - 17] ^= 0x01;
+            assert_eq!(oracle.submit_buffer(&buffer), Err(Error::PaddingError));
+            
+            let oracle = oracle.with_disclosure(Disclosure::MaskedPadding);
+            assert_eq!(oracle.submit_buffer(&buffer), Ok(()));
+        }
+
+        #[test]
+        fn solution() {
+            let oracle = Oracle::random();
+            let buffer = oracle.encrypt_str(PLAINTEXT).unwrap();
+            let result = get_plaintext_buffer(&buffer, &mut |buffer| oracle.submit_buffer(buffer));
+            assert_eq!(result.unwrap(), PLAINTEXT.as_bytes());
+        }
+
+        #[test]
+        fn solution_with_masked_padding_errors() {
+            let oracle = Oracle::random().with_disclosure(Disclosure::MaskedPadding);
+            let buffer = oracle.encrypt_str(PLAINTEXT).unwrap();
+            let result = get_plaintext_buffer(&buffer, &mut |buffer| oracle.submit_buffer(buffer));
+            assert_eq!(result.unwrap(), PLAINTEXT.as_bytes());
+        }
+    }
+
+    mod mac_then_encrypt {
+        use cryptopals::{oracles, attacks, crypto};
+        use oracles::symmetric::mac_then_encrypt::{Oracle, Error};
+        use attacks::symmetric::mac_then_encrypt::get_plaintext_buffer;
+        use crypto::random::Random;
+
+        const PLAINTEXT: &[u8] = b"comment1=cooking%20MCs;userdata=bob;admin=false";
+
+        #[test]
+        fn distinct_errors() {
+            let oracle = Oracle::random();
+            let mut buffer = oracle.encrypt_buffer(PLAINTEXT).unwrap();
+            assert_eq!(oracle.decrypt_and_verify(&buffer).unwrap(), PLAINTEXT);
+
+            // Flipping a bit in the last block breaks the padding, while flipping a bit in
+            // the first block only breaks the tag.
+            let size = buffer.len();
+            buffer[size - 17] ^= 0x01;
+            assert_eq!(oracle.decrypt_and_verify(&buffer), Err(Error::PaddingError));
+            buffer[size - 17] ^= 0x01;
+            buffer[0] ^= 0x01;
+            assert_eq!(oracle.decrypt_and_verify(&buffer), Err(Error::MacError));
+            assert_eq!(oracle.decrypt_and_verify(&buffer[1..]), Err(Error::LengthError));
+        }
+
+        #[test]
+        fn solution() {
+            let oracle = Oracle::random();
+            let buffer = oracle.encrypt_buffer(PLAINTEXT).unwrap();
+            let result = get_plaintext_buffer(&buffer, &mut |buffer| oracle.decrypt_and_verify(buffer));
+            assert_eq!(result.unwrap(), PLAINTEXT);
+        }
     }
 
     mod problem_18 {
-        use base64;
-        
         use cryptopals::crypto::symmetric::{Aes128Ctr, StreamCipherMode};
+        use cryptopals::encoding::base64;
 
         const INPUT: &str = 
             "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==";
@@ -39,6 +361,93 @@ mod set_3 {
         }
     }
 
+    mod problem_19 {
+        use cryptopals::crypto::symmetric::{
+            StreamCipherMode,
+            Aes128Ctr,
+            Cipher,
+            Aes128,
+        };
+        use cryptopals::random_vec;
+
+        use cryptopals::attacks::statistics;
+        use statistics::fixed_nonce_ctr::using_substitutions;
+
+        fn get_plaintexts() -> Vec<Vec<u8>> {
+            cryptopals::data::read_base64_lines(3, 19).unwrap()
+        }
+
+        #[test]
+        fn solution() {
+            let plaintexts = get_plaintexts();
+            let key = random_vec!(Aes128::KEY_SIZE);
+            let nonce = random_vec!(Aes128::BLOCK_SIZE / 2);
+            let ciphertexts: Vec<Vec<u8>> = plaintexts
+                .iter()
+                .map(|buffer| Aes128Ctr::new(&key, &nonce).unwrap().encrypt_buffer(buffer).unwrap())
+                .collect();
+
+            let result = using_substitutions::recover_plaintexts(&ciphertexts).unwrap();
+
+            // The tails of the longest lines are only covered by a single ciphertext, so we only
+            // require most of the plaintext to be recovered correctly.
+            let total: usize = plaintexts.iter().map(|buffer| buffer.len()).sum();
+            let correct: usize = plaintexts
+                .iter()
+                .zip(&result)
+                .map(|(expected, actual)| {
+                    assert_eq!(expected.len(), actual.len());
+                    expected.iter().zip(actual.as_bytes()).filter(|(x, y)| x == y).count()
+                })
+                .sum();
+            assert!(correct as f64 > 0.9 * total as f64, "{} of {} bytes", correct, total);
+
+            // Fix up the first line using a crib.
+            let mut keystream = using_substitutions::most_frequent_byte_keystream(&ciphertexts);
+            using_substitutions::apply_crib(&mut keystream, &ciphertexts[0], 0, b"I have met them");
+            let result = using_substitutions::decrypt(&ciphertexts, &keystream);
+            assert!(result[0].starts_with(b"I have met them"));
+            assert!(result[1].starts_with(b"Coming with viv"));
+        }
+
+        #[test]
+        fn solution_with_annealing() {
+            use rand::{rngs::StdRng, SeedableRng};
+
+            let plaintexts = get_plaintexts();
+            let key = random_vec!(Aes128::KEY_SIZE);
+            let nonce = random_vec!(Aes128::BLOCK_SIZE / 2);
+            let ciphertexts: Vec<Vec<u8>> = plaintexts
+                .iter()
+                .map(|buffer| Aes128Ctr::new(&key, &nonce).unwrap().encrypt_buffer(buffer).unwrap())
+                .collect();
+
+            // Annealing is reproducible given a seeded generator.
+            let result = using_substitutions::recover_plaintexts_with_annealing(
+                &ciphertexts,
+                300,
+                &mut StdRng::seed_from_u64(0x5eed)
+            ).unwrap();
+            let expected = using_substitutions::recover_plaintexts_with_annealing(
+                &ciphertexts,
+                300,
+                &mut StdRng::seed_from_u64(0x5eed)
+            ).unwrap();
+            assert_eq!(result, expected);
+
+            let total: usize = plaintexts.iter().map(|buffer| buffer.len()).sum();
+            let correct: usize = plaintexts
+                .iter()
+                .zip(&result)
+                .map(|(expected, actual)| {
+                    assert_eq!(expected.len(), actual.len());
+                    expected.iter().zip(actual.as_bytes()).filter(|(x, y)| x == y).count()
+                })
+                .sum();
+            assert!(correct as f64 > 0.9 * total as f64, "{} of {} bytes", correct, total);
+        }
+    }
+
     mod problem_20 {
         use cryptopals::crypto::symmetric::{
             StreamCipherMode,
@@ -53,12 +462,7 @@ mod set_3 {
         use statistics::fixed_nonce_ctr::using_statistics;
 
         pub fn get_ciphertexts() -> Result<Vec<Vec<u8>>, Error> {
-            // It is safe to call unwrap here since each line is valid base64.
-            let mut buffers = include_str!("../data/set_3/problem_20.txt")
-                .split('\n')
-                .filter(|string| string.len() > 0)
-                .map(|string| base64::decode(&string).unwrap())
-                .collect::<Vec<Vec<u8>>>();
+            let mut buffers = cryptopals::data::read_base64_lines(3, 20).unwrap();
 
             let key = random_vec!(Aes128::KEY_SIZE);
             let nonce = random_vec!(Aes128::BLOCK_SIZE / 2);
@@ -71,16 +475,27 @@ mod set_3 {
         #[test]
         fn solution() {
             let ciphertexts = get_ciphertexts().unwrap();
-            
-            // This decodes the plaintext as UTF-8.
-            let result = using_statistics::recover_plaintexts(&ciphertexts);
-            assert!(result.is_ok());
+            let expected = cryptopals::data::read_base64_lines(3, 20).unwrap();
+            let min_length = ciphertexts.iter().map(Vec::len).min().unwrap();
+
+            let result = using_statistics::recover_plaintexts(&ciphertexts).unwrap();
+            assert_eq!(result.len(), expected.len());
+
+            // The bytes covered by all ciphertexts are recovered correctly, except the first
+            // (where capital letters are not recognized by the monogram scorer). The plaintexts
+            // are no longer truncated, and most of the tails are recovered as well.
+            let mut correct = 0;
+            for (plaintext, expected) in result.iter().zip(&expected) {
+                assert_eq!(plaintext.as_bytes()[1..min_length], expected[1..min_length]);
+                correct += plaintext.bytes().zip(expected).filter(|(x, y)| x == *y).count();
+            }
+            let total: usize = expected.iter().map(Vec::len).sum();
+            assert!(correct as f64 / total as f64 > 0.95);
         }
         
     }
 
     mod problem_21 {
-        
         use cryptopals::crypto;
         use crypto::random::{
             SeedableGenerator, 
@@ -93,49 +508,65 @@ mod set_3 {
             let mut random = Mt19337::new(1);
             let output = [
                 0x6ac1f425, 0xff4780eb, 0xb8672f8c, 0xeebc1448, 
-                0x00077EFF, 0x20CCC389, 0x4D65aacb, 0xffc11E85
+                0x00077eff, 0x20ccc389, 0x4d65aacb, 0xffc11e85
             ];
-            for i in 0..output.len() {
-                assert_eq!(random.next_u32(), output[i]);
+            for &value in output.iter() {
+                assert_eq!(random.next_u32(), value);
             }
         }
     }
 
     mod problem_22 {
-        use rand;
         use rand::Rng;
 
-        use std::time::{Duration, SystemTime};
-        
+        use std::time::Duration;
+
         use cryptopals::crypto;
         use crypto::random::{
-            SeedableGenerator, 
-            RandomGenerator, 
+            SeedableGenerator,
+            RandomGenerator,
             Mt19337
         };
-        
+
+        use cryptopals::oracles::timing::{Clock, SimulatedClock};
         use cryptopals::attacks;
         use attacks::random::mersenne_twister::{
-            recover_timestamp_from, 
+            recover_timestamp_from,
             MAXIMUM_DELTA,
-            Error
         };
 
-        fn get_unix_time() -> Result<u64, Error> {
-            // Simulate the passage of [0, MAXIMUM_DELTA) seconds.
-            let delta = Duration::from_secs(rand::thread_rng().gen_range(0, MAXIMUM_DELTA));
-            match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.checked_sub(delta) {
-                Some(duration) => { Ok(duration.as_secs()) }
-                None => { Err(Error::UnixTimeError) }
-            } 
+        #[test]
+        fn solution() {
+            let clock = SimulatedClock::starting_at(Duration::from_secs(1_500_000_000));
+            let seed = clock.now().as_secs();
+            let output = Mt19337::new(seed as u32).next_u32();
+
+            // Simulate the passage of [0, MAXIMUM_DELTA] seconds.
+            clock.sleep(Duration::from_secs(rand::thread_rng().gen_range(0, MAXIMUM_DELTA + 1)));
+            let result = recover_timestamp_from(&clock, output, MAXIMUM_DELTA);
+            assert_eq!(result.unwrap(), seed);
         }
 
         #[test]
-        fn solution() {
-            let seed = get_unix_time().unwrap();
+        fn search_window() {
+            let clock = SimulatedClock::starting_at(Duration::from_secs(1_500_000_000));
+            let seed = clock.now().as_secs();
+            let output = Mt19337::new(seed as u32).next_u32();
+
+            clock.sleep(Duration::from_secs(5000));
+            assert!(recover_timestamp_from(&clock, output, MAXIMUM_DELTA).is_err());
+            assert_eq!(recover_timestamp_from(&clock, output, 5000).unwrap(), seed);
+        }
+
+        #[cfg(feature = "system-time")]
+        #[test]
+        fn system_clock() {
+            use cryptopals::oracles::timing::SystemClock;
+
+            let clock = SystemClock::new();
+            let seed = clock.now().as_secs();
             let output = Mt19337::new(seed as u32).next_u32();
-            let result = recover_timestamp_from(output);
-            assert_eq!(result.unwrap(), seed); 
+            assert_eq!(recover_timestamp_from(&clock, output, MAXIMUM_DELTA).unwrap(), seed);
         }
     }
 
@@ -143,21 +574,32 @@ mod set_3 {
         use cryptopals::crypto;
         use crypto::random::{Random, Mt19337, RandomGenerator};
         
-        use cryptopals::attacks::random::mersenne_twister::recover_state_from;
+        use cryptopals::attacks::random::mersenne_twister::{clone_generator, recover_state_from};
 
         #[test]
         fn solution() {
+            let mut random = Mt19337::random();
+            let mut clone = clone_generator(&mut random);
+            assert_eq!(random, clone);
+            for _ in 0..1000 {
+                assert_eq!(clone.next_u32(), random.next_u32());
+            }
+        }
+
+        #[test]
+        fn linear_algebra() {
             let mut random = Mt19337::random();
             let mut state = [0; 624];
-            for i in 0..624 {
-                state[i] = recover_state_from(random.next_u32()).unwrap();
+            for word in state.iter_mut() {
+                let output = random.next_u32();
+                *word = recover_state_from(output).unwrap();
+                assert_eq!(*word, Mt19337::untemper(output));
             }
             assert_eq!(random, Mt19337::from_state(state, 624));
         }
     }
 
     mod problem_24 {
-        use rand;
         use rand::Rng;
         use std::iter;
 
@@ -171,10 +613,57 @@ mod set_3 {
         fn solution() {
             let key = rand::thread_rng().gen::<u16>();
             let mut random = Mt19337::new(key as u32);
-            let input = iter::repeat(b'A').take(14).collect::<Vec<u8>>();
+            let input = iter::repeat_n(b'A', 14).collect::<Vec<u8>>();
             let output = random.encrypt_buffer(&input).unwrap();
             let result = recover_key_from(&input, &output);
             assert_eq!(result.unwrap(), key);
         }
+
+        #[test]
+        fn password_reset_tokens() {
+            use cryptopals::oracles::random::token::{Oracle, Source};
+            use cryptopals::attacks::random::mersenne_twister::{is_time_seeded_token, recover_token_seed};
+
+            let unix_time = 1_500_000_000;
+            for _ in 0..10 {
+                let mut oracle = Oracle::new(Source::MersenneTwister);
+                let delta = rand::thread_rng().gen_range(0, 100);
+                let token = oracle.get_token_at(unix_time - delta);
+                assert_eq!(recover_token_seed(&token, unix_time).unwrap(), unix_time - delta);
+
+                let mut oracle = Oracle::new(Source::SecureRandom);
+                let token = oracle.get_token_at(unix_time - delta);
+                assert!(!is_time_seeded_token(&token, unix_time));
+            }
+        }
+
+        #[test]
+        fn counted_token_queries() {
+            use cryptopals::oracles::{Oracle as _, Counted};
+            use cryptopals::oracles::random::token::{Oracle, Source, Error};
+
+            let unix_time: u64 = 1_500_000_000;
+            let mut oracle = Counted::new(Oracle::new(Source::MersenneTwister)).with_budget(2);
+            let token = oracle.query(&unix_time.to_be_bytes()).unwrap();
+            assert_eq!(token, oracle.inner_mut().get_token_at(unix_time));
+            assert_eq!(oracle.query(&[0; 4]), Err(Error::LengthError));
+            assert_eq!(oracle.query(&unix_time.to_be_bytes()), Err(Error::BudgetExceeded));
+        }
+
+        #[cfg(feature = "system-time")]
+        #[test]
+        fn current_time_tokens() {
+            use cryptopals::crypto::random::Random;
+            use cryptopals::oracles::random::token::{Oracle, Source};
+            use cryptopals::attacks::random::mersenne_twister::is_time_seeded_token;
+
+            let mut oracle = Oracle::random();
+            let token = oracle.get_token().unwrap();
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            assert_eq!(is_time_seeded_token(&token, now), oracle.source() == Source::MersenneTwister);
+        }
     }
 }
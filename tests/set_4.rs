@@ -3,48 +3,96 @@ mod set_4 {
     mod problem_25 {
         use cryptopals::crypto::random::Random;
         use cryptopals::oracles::symmetric::random_access_read_write::Oracle;
-        
+        use cryptopals::attacks::symmetric::random_access_read_write::{
+            recover_plaintext,
+            recover_plaintext_by_block
+        };
 
         #[test]
         fn solution() {
-            let plaintext = include_str!("../data/set_4/problem_25.txt")
-                .as_bytes();
+            let plaintext = cryptopals::data::read_string(4, 25).unwrap().into_bytes();
         
             let mut oracle = Oracle::random();
             let ciphertext = oracle.encrypt_buffer(&plaintext).unwrap();
 
-            // Replacing the entire ciphertext with zeroes will return the keystream.
-            let mut keystream = ciphertext.clone();
-            oracle.edit_buffer(&mut keystream,0, &vec![0; ciphertext.len()]).unwrap();
+            let result = recover_plaintext(
+                &ciphertext, 
+                &mut |buffer, offset, edit| oracle.edit_buffer(buffer, offset, edit)
+            );
+            assert_eq!(result.unwrap(), plaintext);
+        }
+
+        #[test]
+        fn solution_by_block() {
+            let plaintext = cryptopals::data::read_string(4, 25).unwrap().into_bytes();
+        
+            let mut oracle = Oracle::random();
+            let ciphertext = oracle.encrypt_buffer(&plaintext).unwrap();
 
-            let result: Vec<u8> = ciphertext
-                .iter()
-                .zip(keystream.iter())
-                .map(|(c, k)| c ^ k)
-                .collect();
-            assert_eq!(result, plaintext);
+            let result = recover_plaintext_by_block(
+                &ciphertext, 
+                16,
+                &mut |buffer, offset, edit| {
+                    assert!(edit.len() <= 16);
+                    oracle.edit_buffer(buffer, offset, edit)
+                }
+            );
+            assert_eq!(result.unwrap(), plaintext);
+        }
+
+        #[test]
+        fn zero_block_size() {
+            let mut oracle = Oracle::random();
+            let ciphertext = oracle.encrypt_buffer(b"YELLOW SUBMARINE").unwrap();
+
+            let result = recover_plaintext_by_block(
+                &ciphertext,
+                0,
+                &mut |buffer, offset, edit| oracle.edit_buffer(buffer, offset, edit)
+            );
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn solution_by_append() {
+            use std::cell::RefCell;
+            use cryptopals::attacks::symmetric::random_access_read_write::recover_plaintext_by_append;
+
+            let plaintext = cryptopals::data::read_string(4, 25).unwrap().into_bytes();
+        
+            let oracle = RefCell::new(Oracle::random().without_edits());
+            let mut ciphertext = oracle.borrow_mut().encrypt_buffer(&plaintext).unwrap();
+            assert!(oracle.borrow_mut().edit_buffer(&mut ciphertext, 0, &[0]).is_err());
+
+            let result = recover_plaintext_by_append(
+                &ciphertext, 
+                &mut |buffer, size| oracle.borrow_mut().truncate_buffer(buffer, size),
+                &mut |buffer, edit| oracle.borrow_mut().append_buffer(buffer, edit)
+            );
+            assert_eq!(result.unwrap(), plaintext);
         }
     }
 
     mod problem_26 {
         use cryptopals::{oracles, attacks, crypto};
         use oracles::symmetric::ctr_bitflipping_attacks::Oracle;
-        use attacks::symmetric::ctr_bitflipping_attacks::get_admin_profile;
+        use attacks::symmetric::ctr_bitflipping_attacks::{get_admin_profile, get_prefix_size};
         use crypto::random::Random;
     
         #[test]
         fn solution() {
             let mut oracle = Oracle::random();
-            // We assume that we know the size of the prefix. Alternatively, we could 
-            // guess the size of the prefix and query the oracle once for verification.
-            let comment_1 = "comment1=cooking%20MCs";
-            let result = get_admin_profile(
-                comment_1.len(), 
-                &mut |buffer| { oracle.encrypt_user_data(buffer) }
-            );
+            let result = get_admin_profile(&mut |buffer| { oracle.encrypt_user_data(buffer) });
             assert!(result.is_ok());
             assert_eq!(oracle.is_admin_user(&result.unwrap()), Ok(true));
         }
+
+        #[test]
+        fn prefix_size() {
+            let mut oracle = Oracle::random();
+            let result = get_prefix_size(&mut |buffer| { oracle.encrypt_user_data(buffer) });
+            assert_eq!(result.unwrap(), "comment1=cooking%20MCs;userdata=".len());
+        }
     }
 
     mod problem_27 {
@@ -61,7 +109,57 @@ mod set_4 {
                 &mut |string| { sender.encrypt_str(string) },
                 &mut |buffer| { receiver.decrypt_str(buffer) }
             );
-            assert!(sender.verify_key(&key.unwrap()), true);
+            assert!(sender.verify_key(&key.unwrap()));
+        }
+
+        #[test]
+        fn solution_from_errors() {
+            use cryptopals::oracles::symmetric::cbc_with_key_as_iv::Disclosure;
+            use cryptopals::attacks::symmetric::cbc_with_key_as_iv::get_key_from_errors;
+
+            let mut sender = Oracle::random()
+                .with_disclosure(Disclosure::Offset)
+                .with_max_blocks(2);
+            let mut receiver = sender.clone();
+
+            let key = get_key_from_errors(
+                &mut |string| { sender.encrypt_str(string) },
+                &mut |buffer| { receiver.decrypt_str(buffer) }
+            );
+            assert!(sender.verify_key(&key.unwrap()));
+        }
+
+        #[test]
+        fn truncated_keys() {
+            let mut sender = Oracle::random();
+            let mut receiver = sender.clone();
+
+            let key = get_key(
+                &mut |string| { sender.encrypt_str(string) },
+                &mut |buffer| { receiver.decrypt_str(buffer) }
+            ).unwrap();
+            assert!(!sender.verify_key(&[]));
+            assert!(!sender.verify_key(&key[..15]));
+        }
+
+        #[test]
+        fn invalid_lengths() {
+            use cryptopals::oracles::Oracle as _;
+            use cryptopals::oracles::symmetric::cbc_with_key_as_iv::Error;
+
+            let mut oracle = Oracle::random();
+            assert!(matches!(oracle.query(&[0; 5]), Err(Error::LengthError)));
+            assert!(matches!(oracle.query(&[0; 33]), Err(Error::LengthError)));
+            assert!(matches!(oracle.query(&[]), Err(Error::LengthError)));
+        }
+
+        #[test]
+        fn short_responses() {
+            use cryptopals::attacks::symmetric::cbc_with_key_as_iv::get_key_from_errors;
+
+            assert!(get_key(&mut |_| Ok(Vec::new()), &mut |_| Ok(Vec::new())).is_err());
+            assert!(get_key(&mut |_| Ok(vec![0; 48]), &mut |_| Ok(vec![0; 32])).is_err());
+            assert!(get_key_from_errors(&mut |_| Ok(vec![0; 15]), &mut |_| Ok(Vec::new())).is_err());
         }
     }
 
@@ -81,4 +179,185 @@ mod set_4 {
             assert_ne!(first_mac, second_mac);
         }
     }
+
+    mod problem_29 {
+        use cryptopals::crypto::random::Random;
+        use cryptopals::crypto::hash::{HashFunction, Sha1, Sha256};
+        use cryptopals::oracles::hash::length_extension::{Oracle, Sha1Oracle, MAX_KEY_SIZE};
+        use cryptopals::attacks::hash::length_extension::{get_glue_padding, forge_tag};
+
+        #[test]
+        fn glue_padding() {
+            for message_size in 0..256 {
+                let padding = get_glue_padding::<Sha1>(message_size);
+                assert_eq!((message_size + padding.len()) % Sha1::BLOCK_SIZE, 0);
+                assert!(padding.len() >= 9 && padding.len() <= 72);
+            }
+        }
+
+        #[test]
+        fn solution() {
+            let oracle = Sha1Oracle::random();
+            let (message, tag) = oracle.get_message();
+            assert!(oracle.verify_tag(&message, tag.as_ref()));
+
+            let (forged_message, forged_tag) = forge_tag::<Sha1, _>(
+                &message,
+                &tag,
+                b";admin=true",
+                MAX_KEY_SIZE,
+                &mut |message, tag| oracle.verify_tag(message, tag)
+            ).unwrap();
+            assert!(forged_message.starts_with(&message));
+            assert!(oracle.is_admin_user(&forged_message, forged_tag.as_ref()));
+        }
+
+        #[test]
+        fn sha256_solution() {
+            let oracle = Oracle::<Sha256>::random();
+            let (message, tag) = oracle.get_message();
+
+            let (forged_message, forged_tag) = forge_tag::<Sha256, _>(
+                &message,
+                &tag,
+                b";admin=true",
+                MAX_KEY_SIZE,
+                &mut |message, tag| oracle.verify_tag(message, tag)
+            ).unwrap();
+            assert!(oracle.is_admin_user(&forged_message, forged_tag.as_ref()));
+        }
+    }
+
+    mod problem_30 {
+        use cryptopals::crypto::random::Random;
+        use cryptopals::crypto::hash::Md4;
+        use cryptopals::oracles::hash::length_extension::{Md4Oracle, MAX_KEY_SIZE};
+        use cryptopals::attacks::hash::length_extension::forge_tag;
+
+        #[test]
+        fn solution() {
+            let oracle = Md4Oracle::random();
+            let (message, tag) = oracle.get_message();
+            assert!(oracle.verify_tag(&message, tag.as_ref()));
+
+            let (forged_message, forged_tag) = forge_tag::<Md4, _>(
+                &message,
+                &tag,
+                b";admin=true",
+                MAX_KEY_SIZE,
+                &mut |message, tag| oracle.verify_tag(message, tag)
+            ).unwrap();
+            assert!(forged_message.starts_with(&message));
+            assert!(oracle.is_admin_user(&forged_message, forged_tag.as_ref()));
+        }
+    }
+
+    mod problem_31 {
+        use cryptopals::{oracles, attacks};
+        use oracles::timing::SimulatedClock;
+        use oracles::timing::hmac_timing_leak::Oracle;
+        use attacks::timing::hmac_timing_leak::get_signature;
+
+        #[test]
+        fn solution() {
+            let clock = SimulatedClock::new();
+            let mut oracle = Oracle::new(clock.clone());
+            let result = get_signature(
+                |file, signature| oracle.verify_signature(file, signature),
+                &clock,
+                b"foo",
+                1
+            );
+            assert_eq!(result.unwrap(), oracle.get_signature_for(b"foo"));
+        }
+
+        #[test]
+        fn progress_and_cancellation() {
+            use cryptopals::progress::{self, CancellationToken, Event};
+            use attacks::timing::hmac_timing_leak::{get_signature_with_progress, Error};
+
+            let clock = SimulatedClock::new();
+            let mut oracle = Oracle::new(clock.clone());
+            let (reporter, receiver) = progress::Channel::new();
+            let token = CancellationToken::new();
+            let result = get_signature_with_progress(
+                |file, signature| oracle.verify_signature(file, signature),
+                &clock,
+                b"foo",
+                1,
+                &reporter,
+                &token
+            );
+            assert_eq!(result.unwrap(), oracle.get_signature_for(b"foo"));
+            let recovered = receiver
+                .try_iter()
+                .filter(|event| matches!(event, Event::BytesRecovered { .. }))
+                .count();
+            assert_eq!(recovered, 20);
+
+            token.cancel();
+            let result = get_signature_with_progress(
+                |file, signature| oracle.verify_signature(file, signature),
+                &clock,
+                b"foo",
+                1,
+                &progress::Silent,
+                &token
+            );
+            assert_eq!(result, Err(Error::Cancelled));
+        }
+
+        #[cfg(feature = "async")]
+        #[test]
+        fn solution_using_async_oracle() {
+            use cryptopals::oracles::asynchronous::Shared;
+            use attacks::asynchronous::hmac_timing_leak;
+
+            let clock = SimulatedClock::new();
+            let oracle = Shared::new(Oracle::new(clock.clone()));
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            let result = runtime.block_on(hmac_timing_leak::get_signature(&oracle, &clock, b"foo", 1));
+            assert_eq!(result.unwrap(), oracle.into_inner().get_signature_for(b"foo"));
+        }
+
+        #[test]
+        fn counted_queries() {
+            use cryptopals::oracles::{Oracle as _, Counted};
+
+            let clock = SimulatedClock::new();
+            let mut oracle = Counted::new(Oracle::new(clock.clone()));
+            let result = get_signature(
+                |file, signature| oracle.query(&[file, signature].concat()).unwrap(),
+                &clock,
+                b"foo",
+                1
+            );
+            assert_eq!(result.unwrap(), oracle.inner().get_signature_for(b"foo"));
+            assert!(oracle.queries() > 0);
+            assert!(oracle.query(&[0; 19]).is_err());
+        }
+    }
+
+    mod problem_32 {
+        use std::time::Duration;
+        use cryptopals::{oracles, attacks};
+        use oracles::timing::SimulatedClock;
+        use oracles::timing::hmac_timing_leak::Oracle;
+        use attacks::timing::hmac_timing_leak::get_signature;
+
+        #[test]
+        fn solution() {
+            let clock = SimulatedClock::new();
+            let mut oracle = Oracle::new(clock.clone())
+                .with_delay(Duration::from_millis(5))
+                .with_jitter(Duration::from_millis(5));
+            let result = get_signature(
+                |file, signature| oracle.verify_signature(file, signature),
+                &clock,
+                b"foo",
+                10
+            );
+            assert_eq!(result.unwrap(), oracle.get_signature_for(b"foo"));
+        }
+    }
 }
@@ -0,0 +1,27 @@
+mod set_5 {
+
+    mod problem_34 {
+        use cryptopals::{oracles, attacks};
+        use oracles::protocol::{Session, Direction};
+        use oracles::protocol::diffie_hellman::{Alice, Bob, Group};
+        use attacks::protocol::parameter_injection::Mallory;
+
+        #[test]
+        fn solution() {
+            let mut alice = Alice::new(Group::default(), b"YELLOW SUBMARINE");
+            let mut bob = Bob::new();
+            let mut session = Session::new(Mallory::new());
+            assert!(session.run(&mut alice, &mut bob).is_ok());
+
+            // The protocol completes as usual.
+            assert_eq!(alice.echo(), Some(&b"YELLOW SUBMARINE"[..]));
+            assert_eq!(bob.plaintexts(), [b"YELLOW SUBMARINE".to_vec()]);
+
+            // Mallory recovers both the message and the echo.
+            assert_eq!(session.channel().plaintexts(), [
+                (Direction::Forward, b"YELLOW SUBMARINE".to_vec()),
+                (Direction::Backward, b"YELLOW SUBMARINE".to_vec()),
+            ]);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+mod set_7 {
+
+    mod problem_56 {
+        use cryptopals::{oracles, attacks};
+        use oracles::symmetric::rc4_single_byte_biases::Oracle;
+        use attacks::symmetric::rc4_single_byte_biases::{get_bias_table, get_most_likely_bytes};
+
+        #[test]
+        fn oracle() {
+            let mut oracle = Oracle::default();
+            assert_eq!(oracle.cookie_size(), 30);
+            let first = oracle.encrypt_request(b"/").unwrap();
+            let second = oracle.encrypt_request(b"/").unwrap();
+            assert_eq!(first.len(), 1 + oracle.cookie_size());
+            // Each request is encrypted under a fresh key.
+            assert_ne!(first, second);
+            assert_eq!(oracle.queries(), 2);
+        }
+
+        #[test]
+        fn single_byte_biases() {
+            // The second keystream byte is biased towards 0 with probability 2/256.
+            let table = get_bias_table(2, 1 << 16);
+            assert_eq!(table[1].iter().sum::<usize>(), 1 << 16);
+            assert_eq!(get_most_likely_bytes(&table)[1], 0);
+        }
+    }
+}
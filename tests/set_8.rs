@@ -0,0 +1,57 @@
+mod set_8 {
+
+    mod aead_forgery {
+        use cryptopals::{oracles, crypto};
+        use oracles::symmetric::aead_forgery::{Oracle, Message};
+        use crypto::aead::{Aead, Gcm, ChaCha20Poly1305};
+        use crypto::random::Random;
+
+        fn forge_with_truncated_tag<A: Aead + Random>() {
+            let mut oracle: Oracle<A> = Oracle::random().with_tag_size(1);
+            let message = oracle.encrypt_buffer(b"YELLOW SUBMARINE").unwrap();
+            assert!(oracle.verify_message(&message));
+            assert!(oracle.forgeries().is_empty());
+
+            // A one-byte tag is forged in at most 256 attempts.
+            let mut ciphertext = message.ciphertext.clone();
+            ciphertext[0] ^= 1;
+            let forgery = (0..=255)
+                .map(|byte| Message { nonce: message.nonce.clone(), ciphertext: ciphertext.clone(), tag: vec![byte] })
+                .find(|forgery| oracle.verify_message(forgery))
+                .unwrap();
+            assert_eq!(oracle.forgeries(), std::slice::from_ref(&forgery));
+            assert_eq!(oracle.queries(), 2 + forgery.tag[0] as usize);
+
+            // Replaying the forgery does not count as a new forgery.
+            assert!(oracle.verify_message(&forgery));
+            assert_eq!(oracle.forgeries().len(), 1);
+        }
+
+        #[test]
+        fn truncated_tags() {
+            forge_with_truncated_tag::<Gcm>();
+            forge_with_truncated_tag::<ChaCha20Poly1305>();
+        }
+
+        #[test]
+        fn tag_size() {
+            let mut oracle: Oracle<Gcm> = Oracle::random().with_tag_size(4);
+            let mut message = oracle.encrypt_buffer(b"YELLOW SUBMARINE").unwrap();
+            assert_eq!(message.tag.len(), 4);
+            
+            message.tag.push(0);
+            assert!(!oracle.verify_message(&message));
+        }
+
+        #[test]
+        fn query_interface() {
+            use cryptopals::oracles::{Oracle as _, Counted};
+
+            let mut oracle = Counted::new(Oracle::<ChaCha20Poly1305>::random()).with_budget(2);
+            let message = oracle.inner_mut().encrypt_buffer(b"YELLOW SUBMARINE").unwrap();
+            assert_eq!(oracle.query(&message.to_bytes()), Ok(true));
+            assert_eq!(oracle.query(&message.to_bytes()[1..]), Ok(false));
+            assert!(oracle.query(&message.to_bytes()).is_err());
+        }
+    }
+}